@@ -0,0 +1,179 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_lite::future;
+use pr070c01::{Handshake, Packet, Protocol};
+use std::collections::VecDeque;
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+// ========================================== Constants ========================================= \\
+
+const PACKETS: u64 = 1024;
+const LARGE_LEN: usize = 1024 * 1024;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Default)]
+struct Pipe {
+    buf: VecDeque<u8>,
+    waker: Option<Waker>,
+}
+
+struct Duplex {
+    read: Arc<Mutex<Pipe>>,
+    write: Arc<Mutex<Pipe>>,
+}
+
+// ========================================= impl Duplex ======================================== \\
+
+impl Duplex {
+    // ==================================== Constructors ==================================== \\
+
+    fn pair() -> (Self, Self) {
+        let a = Arc::new(Mutex::new(Pipe::default()));
+        let b = Arc::new(Mutex::new(Pipe::default()));
+
+        (
+            Duplex {
+                read: a.clone(),
+                write: b.clone(),
+            },
+            Duplex { read: b, write: a },
+        )
+    }
+}
+
+// ======================================= impl AsyncRead ======================================= \\
+
+impl AsyncRead for Duplex {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        ctx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut pipe = self.read.lock().unwrap();
+        if pipe.buf.is_empty() {
+            pipe.waker = Some(ctx.waker().clone());
+            return Poll::Pending;
+        }
+
+        let len = buf.len().min(pipe.buf.len());
+        for (dst, src) in buf[..len].iter_mut().zip(pipe.buf.drain(..len)) {
+            *dst = src;
+        }
+
+        Poll::Ready(Ok(len))
+    }
+}
+
+// ======================================= impl AsyncWrite ====================================== \\
+
+impl AsyncWrite for Duplex {
+    fn poll_write(self: Pin<&mut Self>, _: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let mut pipe = self.write.lock().unwrap();
+        pipe.buf.extend(buf);
+        if let Some(waker) = pipe.waker.take() {
+            waker.wake();
+        }
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+// ========================================= handshake() ======================================== \\
+
+fn handshake(c: &mut Criterion) {
+    c.bench_function("handshake", |bencher| {
+        bencher.iter(|| {
+            let (left, right) = Duplex::pair();
+            future::block_on(connect(left, right))
+        })
+    });
+}
+
+// ========================================== packets() ========================================= \\
+
+fn packets(c: &mut Criterion) {
+    let mut group = c.benchmark_group("packets");
+    group.throughput(Throughput::Elements(PACKETS));
+    group.bench_function("heartbeat", |bencher| {
+        let (mut left, mut right) = Duplex::pair();
+        let (mut initiator, mut responder) = future::block_on(connect(&mut left, &mut right));
+
+        bencher.iter(|| {
+            future::block_on(async {
+                for _ in 0..PACKETS {
+                    initiator
+                        .send(&mut left, Packet::heartbeat())
+                        .await
+                        .unwrap();
+                }
+
+                for _ in 0..PACKETS {
+                    assert!(responder.recv(&mut right).await.unwrap().is_heartbeat());
+                }
+            })
+        })
+    });
+    group.finish();
+}
+
+// =========================================== large() ========================================== \\
+
+fn large(c: &mut Criterion) {
+    let data = vec![0x5a; LARGE_LEN];
+
+    let mut group = c.benchmark_group("large");
+    group.throughput(Throughput::Bytes(LARGE_LEN as u64));
+    group.bench_function("send_large", |bencher| {
+        let (mut left, mut right) = Duplex::pair();
+        let (mut initiator, mut responder) = future::block_on(connect(&mut left, &mut right));
+
+        bencher.iter(|| {
+            future::block_on(async {
+                initiator.send_large(&mut left, &data).await.unwrap();
+                responder.recv_large(&mut right).await.unwrap()
+            })
+        })
+    });
+    group.finish();
+}
+
+// ========================================== connect() ========================================= \\
+
+async fn connect<IO>(a: IO, b: IO) -> (Protocol, Protocol)
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    let (initiator, responder) = future::zip(Handshake::initiate(a), Handshake::respond(b)).await;
+
+    (
+        initiator.unwrap().done().unwrap(),
+        responder.unwrap().done().unwrap(),
+    )
+}
+
+// ============================================ main ============================================ \\
+
+criterion_group!(benches, handshake, packets, large);
+criterion_main!(benches);
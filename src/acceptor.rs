@@ -0,0 +1,123 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Config, HandshakeLimiter, HandshakeParams, Respond, Timeout, Timer};
+use core::fmt::{self, Debug, Formatter};
+use core::time::Duration;
+use futures_io::{AsyncRead, AsyncWrite};
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone)]
+pub struct Acceptor {
+    config: Config,
+    timeout: Duration,
+}
+
+// ======================================== impl Acceptor ======================================= \\
+
+impl Acceptor {
+    // ====================================== Constants ===================================== \\
+
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    // ==================================== Constructors ==================================== \\
+
+    pub fn new(config: Config) -> Self {
+        let config = if config.params().is_some() {
+            config
+        } else {
+            config.with_params(HandshakeParams::shared().clone())
+        };
+
+        Acceptor {
+            config,
+            timeout: Self::DEFAULT_TIMEOUT,
+        }
+    }
+
+    #[inline]
+    pub fn with_pow(mut self, difficulty: u8) -> Self {
+        self.config = self.config.with_pow(difficulty);
+        self
+    }
+
+    #[inline]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    // Responders over the limit fail right away instead of waiting for a slot; a `Config` with its
+    // own `HandshakeLimiter` can be used to let them queue instead.
+    #[inline]
+    pub fn with_max_handshakes(mut self, max: usize) -> Self {
+        self.config = self.config.with_limiter(HandshakeLimiter::new(max));
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    #[inline]
+    pub fn pow(&self) -> u8 {
+        self.config.pow()
+    }
+
+    #[inline]
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    #[inline]
+    pub fn max_handshakes(&self) -> Option<usize> {
+        self.config.limiter().map(HandshakeLimiter::max)
+    }
+
+    // Handshakes only get counted while they are limited.
+    #[inline]
+    pub fn active(&self) -> usize {
+        self.config.limiter().map_or(0, HandshakeLimiter::active)
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub fn accept<IO>(&self, io: IO) -> Respond<IO>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        Respond::new(io, self.config.clone())
+    }
+
+    #[inline]
+    pub fn accept_with_timer<IO, Tmr>(&self, io: IO) -> Timeout<Respond<IO>, Tmr>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+        Tmr: Timer,
+    {
+        self.accept(io).timeout(self.timeout)
+    }
+}
+
+// ========================================= impl Debug ========================================= \\
+
+impl Debug for Acceptor {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Acceptor")
+            .field("config", &self.config)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
@@ -0,0 +1,464 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// Acknowledgments are sets of sequence numbers, encoded as ascending ranges of varints so that
+// long runs stay small:
+//
+// ACK  ;; count(v) + (gap(v) + len(v)) * count
+// NACK ;; count(v) + (gap(v) + len(v)) * count
+//
+// The first gap is the start of the first range, and every following gap is the distance from
+// the end of the previous range. Lengths are never zero.
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Error, Result};
+use core::ops::Range;
+use std::collections::BTreeSet;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Ack {
+    ranges: Ranges,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Nack {
+    ranges: Ranges,
+}
+
+#[derive(Clone, Debug)]
+pub struct RecvWindow {
+    window: u64,
+    received: Ranges,
+}
+
+#[derive(Clone, Debug)]
+pub struct SendWindow {
+    next: u64,
+    capacity: usize,
+    in_flight: BTreeSet<u64>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct Ranges(Vec<Range<u64>>);
+
+// ========================================== impl Ack ========================================== \\
+
+impl Ack {
+    // ====================================== Constants ===================================== \\
+
+    pub const MAX_RANGES: usize = 64;
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new<Iter: IntoIterator<Item = Range<u64>>>(ranges: Iter) -> Self {
+        Ack {
+            ranges: Ranges::new(ranges),
+        }
+    }
+
+    #[inline]
+    pub fn decode(buf: &[u8]) -> Result<Self> {
+        Ok(Ack {
+            ranges: Ranges::decode(buf)?,
+        })
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn ranges(&self) -> &[Range<u64>] {
+        &self.ranges.0
+    }
+
+    #[inline]
+    pub fn contains(&self, seq: u64) -> bool {
+        self.ranges.contains(seq)
+    }
+
+    #[inline]
+    pub fn largest(&self) -> Option<u64> {
+        self.ranges.largest()
+    }
+
+    #[inline]
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        self.ranges.encode(buf)
+    }
+}
+
+// ========================================== impl Nack ========================================= \\
+
+impl Nack {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new<Iter: IntoIterator<Item = Range<u64>>>(ranges: Iter) -> Self {
+        Nack {
+            ranges: Ranges::new(ranges),
+        }
+    }
+
+    #[inline]
+    pub fn decode(buf: &[u8]) -> Result<Self> {
+        Ok(Nack {
+            ranges: Ranges::decode(buf)?,
+        })
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn ranges(&self) -> &[Range<u64>] {
+        &self.ranges.0
+    }
+
+    #[inline]
+    pub fn contains(&self, seq: u64) -> bool {
+        self.ranges.contains(seq)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.0.is_empty()
+    }
+
+    #[inline]
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        self.ranges.encode(buf)
+    }
+}
+
+// ======================================= impl RecvWindow ====================================== \\
+
+impl RecvWindow {
+    // ====================================== Constants ===================================== \\
+
+    pub const DEFAULT_WINDOW: usize = 64;
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new() -> Self {
+        RecvWindow {
+            window: Self::DEFAULT_WINDOW as u64,
+            received: Ranges::default(),
+        }
+    }
+
+    #[inline]
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.set_window(window);
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn window(&self) -> usize {
+        self.window as usize
+    }
+
+    #[inline]
+    pub fn contains(&self, seq: u64) -> bool {
+        self.received.contains(seq)
+    }
+
+    // Every sequence number below this one has been received.
+    #[inline]
+    pub fn contiguous(&self) -> u64 {
+        match self.received.0.first() {
+            Some(range) if range.start == 0 => range.end,
+            _ => 0,
+        }
+    }
+
+    #[inline]
+    pub fn largest(&self) -> Option<u64> {
+        self.received.largest()
+    }
+
+    // Only the most recent ranges are acknowledged, as older ones have most likely already been
+    // acknowledged before.
+    pub fn ack(&self) -> Ack {
+        let skip = self.received.0.len().saturating_sub(Ack::MAX_RANGES);
+
+        Ack {
+            ranges: Ranges(self.received.0[skip..].to_vec()),
+        }
+    }
+
+    pub fn nack(&self) -> Nack {
+        let mut gaps = Vec::new();
+        let mut end = 0;
+        for range in &self.received.0 {
+            if range.start > end {
+                gaps.push(end..range.start);
+            }
+
+            end = range.end;
+        }
+
+        Nack {
+            ranges: Ranges(gaps),
+        }
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub fn set_window(&mut self, window: usize) {
+        self.window = window.max(1) as u64;
+    }
+
+    // Returns whether the sequence number hadn't been received before. Sequence numbers that are
+    // `window` or more past the contiguous ones are dropped, which bounds the ranges kept.
+    #[inline]
+    pub fn receive(&mut self, seq: u64) -> bool {
+        if seq >= self.contiguous().saturating_add(self.window) {
+            return false;
+        }
+
+        self.received.insert(seq)
+    }
+}
+
+// ======================================= impl SendWindow ====================================== \\
+
+impl SendWindow {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new(capacity: usize) -> Self {
+        SendWindow {
+            next: 0,
+            capacity,
+            in_flight: BTreeSet::new(),
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    #[inline]
+    pub fn next_seq(&self) -> u64 {
+        self.next
+    }
+
+    #[inline]
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    // The window slides from the lowest unacknowledged sequence number, so that the peer never has
+    // to track more than `capacity` sequence numbers past the ones it received in order.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        match self.lowest_unacked() {
+            Some(lowest) => self.next - lowest >= self.capacity as u64,
+            None => self.capacity == 0,
+        }
+    }
+
+    #[inline]
+    pub fn is_in_flight(&self, seq: u64) -> bool {
+        self.in_flight.contains(&seq)
+    }
+
+    #[inline]
+    pub fn lowest_unacked(&self) -> Option<u64> {
+        self.in_flight.iter().next().copied()
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+    }
+
+    #[inline]
+    pub fn push(&mut self) -> Option<u64> {
+        if self.is_full() {
+            return None;
+        }
+
+        let seq = self.next;
+        self.next += 1;
+        self.in_flight.insert(seq);
+
+        Some(seq)
+    }
+
+    // Returns the sequence numbers that were in flight and are now acknowledged.
+    pub fn ack(&mut self, ack: &Ack) -> Vec<u64> {
+        let mut acked = Vec::new();
+        for range in ack.ranges() {
+            acked.extend(self.in_flight.range(range.clone()).copied());
+        }
+
+        for seq in &acked {
+            self.in_flight.remove(seq);
+        }
+
+        acked
+    }
+
+    // Returns the sequence numbers that are still in flight but were reported missing.
+    pub fn nack(&self, nack: &Nack) -> Vec<u64> {
+        nack.ranges()
+            .iter()
+            .flat_map(|range| self.in_flight.range(range.clone()).copied())
+            .collect()
+    }
+}
+
+// ========================================= impl Ranges ======================================== \\
+
+impl Ranges {
+    // ==================================== Constructors ==================================== \\
+
+    fn new<Iter: IntoIterator<Item = Range<u64>>>(ranges: Iter) -> Self {
+        let mut ranges = ranges
+            .into_iter()
+            .filter(|range| range.start < range.end)
+            .collect::<Vec<_>>();
+        ranges.sort_by_key(|range| range.start);
+
+        let mut merged: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+
+        Ranges(merged)
+    }
+
+    fn decode(mut buf: &[u8]) -> Result<Self> {
+        let count = varint(&mut buf)?;
+
+        let mut ranges = Vec::new();
+        let mut end = 0u64;
+        for idx in 0..count {
+            let gap = varint(&mut buf)?;
+            let len = varint(&mut buf)?;
+            if len == 0 || (idx > 0 && gap == 0) {
+                return Err(Error::InvalidAck);
+            }
+
+            let start = end.checked_add(gap).ok_or(Error::InvalidAck)?;
+            end = start.checked_add(len).ok_or(Error::InvalidAck)?;
+            ranges.push(start..end);
+        }
+
+        if !buf.is_empty() {
+            return Err(Error::InvalidAck);
+        }
+
+        Ok(Ranges(ranges))
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    fn contains(&self, seq: u64) -> bool {
+        match self.0.binary_search_by_key(&seq, |range| range.start) {
+            Ok(_) => true,
+            Err(0) => false,
+            Err(idx) => self.0[idx - 1].contains(&seq),
+        }
+    }
+
+    #[inline]
+    fn largest(&self) -> Option<u64> {
+        self.0.last().map(|range| range.end - 1)
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) {
+        put_varint(buf, self.0.len() as u64);
+
+        let mut end = 0;
+        for range in &self.0 {
+            put_varint(buf, range.start - end);
+            put_varint(buf, range.end - range.start);
+            end = range.end;
+        }
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    fn insert(&mut self, seq: u64) -> bool {
+        let idx = match self.0.binary_search_by_key(&seq, |range| range.start) {
+            Ok(_) => return false,
+            Err(idx) => idx,
+        };
+
+        if idx > 0 && self.0[idx - 1].contains(&seq) {
+            return false;
+        }
+
+        let extends_prev = idx > 0 && self.0[idx - 1].end == seq;
+        let extends_next = idx < self.0.len() && self.0[idx].start == seq + 1;
+        match (extends_prev, extends_next) {
+            (true, true) => {
+                self.0[idx - 1].end = self.0[idx].end;
+                self.0.remove(idx);
+            }
+            (true, false) => self.0[idx - 1].end += 1,
+            (false, true) => self.0[idx].start = seq,
+            (false, false) => self.0.insert(idx, seq..seq + 1),
+        }
+
+        true
+    }
+}
+
+// ========================================== varint() ========================================== \\
+
+fn varint(buf: &mut &[u8]) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = buf.split_first().ok_or(Error::InvalidAck)?;
+        *buf = rest;
+
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(Error::InvalidAck)
+}
+
+// ======================================== put_varint() ======================================== \\
+
+fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+
+    buf.push(value as u8);
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl Default for RecvWindow {
+    #[inline]
+    fn default() -> Self {
+        RecvWindow::new()
+    }
+}
@@ -0,0 +1,133 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{send, Result};
+use core::mem;
+use core::time::Duration;
+use packets::{Packet, MSG_OVERHEAD};
+use std::time::Instant;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BatchPolicy {
+    max_bytes: usize,
+    max_delay: Duration,
+}
+
+pub struct Batcher {
+    policy: BatchPolicy,
+    packets: Vec<Packet>,
+    bytes: usize,
+    first: Option<Instant>,
+    scratch: Vec<u8>,
+}
+
+// ====================================== impl BatchPolicy ====================================== \\
+
+impl BatchPolicy {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub const fn new(max_bytes: usize, max_delay: Duration) -> Self {
+        BatchPolicy {
+            max_bytes,
+            max_delay,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    #[inline]
+    pub fn max_delay(&self) -> Duration {
+        self.max_delay
+    }
+}
+
+// ======================================== impl Batcher ======================================== \\
+
+impl Batcher {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new(policy: BatchPolicy) -> Self {
+        Batcher {
+            policy,
+            packets: Vec::new(),
+            bytes: 0,
+            first: None,
+            scratch: Vec::new(),
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn policy(&self) -> BatchPolicy {
+        self.policy
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.packets.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.packets.is_empty()
+    }
+
+    #[inline]
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    #[inline]
+    pub fn deadline(&self) -> Option<Instant> {
+        self.first.map(|first| first + self.policy.max_delay)
+    }
+
+    #[inline]
+    pub fn is_due(&self) -> bool {
+        self.bytes >= self.policy.max_bytes
+            || self
+                .deadline()
+                .map_or(false, |deadline| Instant::now() >= deadline)
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub fn set_policy(&mut self, policy: BatchPolicy) {
+        self.policy = policy;
+    }
+
+    pub fn push(&mut self, packet: Packet) -> Result<bool> {
+        let bytes = send::encode(&packet, &mut self.scratch)?;
+        self.bytes += bytes + MSG_OVERHEAD;
+        self.packets.push(packet);
+        self.first.get_or_insert_with(Instant::now);
+
+        Ok(self.is_due())
+    }
+
+    #[inline]
+    pub fn take(&mut self) -> Vec<Packet> {
+        self.bytes = 0;
+        self.first = None;
+
+        mem::take(&mut self.packets)
+    }
+}
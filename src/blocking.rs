@@ -0,0 +1,239 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Config, Engine, HandshakeInfo, PublicKey, Result, Suite};
+use core::future::Future;
+use core::pin::Pin;
+use core::ptr;
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use futures_io::{AsyncRead, AsyncWrite};
+use packets::Packet;
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+
+// ========================================== Constants ========================================= \\
+
+const VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+// ============================================ Types =========================================== \\
+
+pub struct Handshake<IO> {
+    io: IO,
+    handshake: crate::Handshake,
+}
+
+pub struct Protocol<IO> {
+    io: IO,
+    engine: Engine,
+    packets: VecDeque<Packet>,
+    buf: Vec<u8>,
+    out: Vec<u8>,
+}
+
+struct Blocking<IO>(IO);
+
+// ======================================= impl Handshake ======================================= \\
+
+impl<IO> Handshake<IO>
+where
+    IO: Read + Write + Unpin,
+{
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn initiate(io: IO) -> Result<Self> {
+        Self::initiate_with(io, Config::default())
+    }
+
+    pub fn initiate_with(io: IO, config: Config) -> Result<Self> {
+        let mut initiate = crate::Handshake::initiate_with(Blocking(io), config);
+        let handshake = run(&mut initiate)?;
+
+        Ok(Handshake {
+            io: initiate.done().0,
+            handshake,
+        })
+    }
+
+    #[inline]
+    pub fn respond(io: IO) -> Result<Self> {
+        Self::respond_with(io, Config::default())
+    }
+
+    pub fn respond_with(io: IO, config: Config) -> Result<Self> {
+        let mut respond = crate::Handshake::respond_with(Blocking(io), config);
+        let handshake = run(&mut respond)?;
+
+        Ok(Handshake {
+            io: respond.done().0,
+            handshake,
+        })
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn suite(&self) -> Suite {
+        self.handshake.suite()
+    }
+
+    #[inline]
+    pub fn remote_payload(&self) -> &[u8] {
+        self.handshake.remote_payload()
+    }
+
+    #[inline]
+    pub fn remote_identity(&self) -> Option<&PublicKey> {
+        self.handshake.remote_identity()
+    }
+
+    #[inline]
+    pub fn info(&self) -> HandshakeInfo {
+        self.handshake.info()
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn done(self) -> Result<Protocol<IO>> {
+        Ok(Protocol::new(self.io, self.handshake.done()?.into_engine()))
+    }
+}
+
+// ======================================== impl Protocol ======================================= \\
+
+impl<IO> Protocol<IO>
+where
+    IO: Read + Write,
+{
+    // ====================================== Constants ===================================== \\
+
+    pub const READ_LEN: usize = 16 * 1024;
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    fn new(io: IO, engine: Engine) -> Self {
+        Protocol {
+            io,
+            engine,
+            packets: VecDeque::new(),
+            buf: vec![0; Self::READ_LEN],
+            out: Vec::new(),
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn get_ref(&self) -> &IO {
+        &self.io
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn into_inner(self) -> IO {
+        self.io
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut IO {
+        &mut self.io
+    }
+
+    pub fn send(&mut self, packet: Packet) -> Result<usize> {
+        self.out.clear();
+
+        let len = self.engine.write_packet(packet, &mut self.out)?;
+        self.io.write_all(&self.out)?;
+        self.io.flush()?;
+
+        Ok(len)
+    }
+
+    pub fn recv(&mut self) -> Result<Packet> {
+        loop {
+            if let Some(packet) = self.packets.pop_front() {
+                return Ok(packet);
+            }
+
+            let read = self.io.read(&mut self.buf)?;
+            if read == 0 {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+            }
+
+            self.packets.extend(self.engine.read(&self.buf[..read])?);
+        }
+    }
+}
+
+// ======================================= impl AsyncRead ======================================= \\
+
+impl<IO: Read + Unpin> AsyncRead for Blocking<IO> {
+    #[inline]
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(self.0.read(buf))
+    }
+}
+
+// ======================================= impl AsyncWrite ====================================== \\
+
+impl<IO: Write + Unpin> AsyncWrite for Blocking<IO> {
+    #[inline]
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _: &mut Context,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(self.0.write(buf))
+    }
+
+    #[inline]
+    fn poll_flush(mut self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(self.0.flush())
+    }
+
+    #[inline]
+    fn poll_close(mut self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(self.0.flush())
+    }
+}
+
+// ============================================ run() =========================================== \\
+
+fn run<Fut, Output>(fut: &mut Fut) -> Result<Output>
+where
+    Fut: Future<Output = Result<Output>> + Unpin,
+{
+    let waker = unsafe { Waker::from_raw(RawWaker::new(ptr::null(), &VTABLE)) };
+    let mut ctx = Context::from_waker(&waker);
+
+    match Pin::new(fut).poll(&mut ctx) {
+        Poll::Ready(output) => output,
+        Poll::Pending => Err(io::Error::from(io::ErrorKind::WouldBlock).into()),
+    }
+}
+
+// =========================================== clone() ========================================== \\
+
+fn clone(_: *const ()) -> RawWaker {
+    RawWaker::new(ptr::null(), &VTABLE)
+}
+
+// =========================================== noop() =========================================== \\
+
+fn noop(_: *const ()) {}
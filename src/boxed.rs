@@ -0,0 +1,111 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// A `BoxedIo` erases the type of the underlying IO, so that connections over different transports
+// can be stored in the same collection and driven by the same (`Send`) futures.
+
+// =========================================== Imports ========================================== \\
+
+use core::fmt::{self, Debug, Formatter};
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::{AsyncRead, AsyncWrite, IoSlice, IoSliceMut};
+use std::io;
+
+// ============================================ Types =========================================== \\
+
+pub struct BoxedIo {
+    io: Box<dyn AsyncIo>,
+}
+
+// ========================================= Interfaces ========================================= \\
+
+pub trait AsyncIo: AsyncRead + AsyncWrite + Unpin + Send {}
+
+// ======================================== impl AsyncIo ======================================== \\
+
+impl<IO> AsyncIo for IO where IO: AsyncRead + AsyncWrite + Unpin + Send {}
+
+// ======================================== impl BoxedIo ======================================== \\
+
+impl BoxedIo {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new<IO>(io: IO) -> Self
+    where
+        IO: AsyncIo + 'static,
+    {
+        BoxedIo { io: Box::new(io) }
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn into_inner(self) -> Box<dyn AsyncIo> {
+        self.io
+    }
+}
+
+// ======================================= impl AsyncRead ======================================= \\
+
+impl AsyncRead for BoxedIo {
+    #[inline]
+    fn poll_read(
+        self: Pin<&mut Self>,
+        ctx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_read(ctx, buf)
+    }
+
+    #[inline]
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        ctx: &mut Context,
+        bufs: &mut [IoSliceMut],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_read_vectored(ctx, bufs)
+    }
+}
+
+// ======================================= impl AsyncWrite ====================================== \\
+
+impl AsyncWrite for BoxedIo {
+    #[inline]
+    fn poll_write(self: Pin<&mut Self>, ctx: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write(ctx, buf)
+    }
+
+    #[inline]
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        ctx: &mut Context,
+        bufs: &[IoSlice],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write_vectored(ctx, bufs)
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_flush(ctx)
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_close(ctx)
+    }
+}
+
+// ========================================= impl Debug ========================================= \\
+
+impl Debug for BoxedIo {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("BoxedIo").finish()
+    }
+}
@@ -0,0 +1,126 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// A read budget limits how many bytes (or how many reads from the underlying IO) the receiving
+// futures consume within a single poll. Once it is exhausted, the future wakes itself up and
+// returns `Poll::Pending`, so that it doesn't starve the other tasks of a single-threaded executor
+// while the peer keeps sending. The budget is carried over from one message to the next, and is
+// refilled whenever the future yields.
+
+// =========================================== Imports ========================================== \\
+
+use core::task::Context;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ReadBudget {
+    max_bytes: Option<usize>,
+    max_reads: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Budget {
+    limit: ReadBudget,
+    bytes: usize,
+    reads: usize,
+}
+
+// ======================================= impl ReadBudget ====================================== \\
+
+impl ReadBudget {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub const fn unlimited() -> Self {
+        ReadBudget {
+            max_bytes: None,
+            max_reads: None,
+        }
+    }
+
+    #[inline]
+    pub const fn with_max_bytes(mut self, max: usize) -> Self {
+        self.max_bytes = Some(max);
+        self
+    }
+
+    #[inline]
+    pub const fn with_max_reads(mut self, max: usize) -> Self {
+        self.max_reads = Some(max);
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn max_bytes(&self) -> Option<usize> {
+        self.max_bytes
+    }
+
+    #[inline]
+    pub fn max_reads(&self) -> Option<usize> {
+        self.max_reads
+    }
+
+    #[inline]
+    pub fn is_unlimited(&self) -> bool {
+        self.max_bytes.is_none() && self.max_reads.is_none()
+    }
+}
+
+// ========================================= impl Budget ======================================== \\
+
+impl Budget {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new(limit: ReadBudget) -> Self {
+        Budget {
+            limit,
+            bytes: 0,
+            reads: 0,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub(crate) fn is_exhausted(&self) -> bool {
+        matches!(self.limit.max_bytes, Some(max) if self.bytes >= max)
+            || matches!(self.limit.max_reads, Some(max) if self.reads >= max)
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub(crate) fn consume(&mut self, bytes: usize) {
+        self.bytes += bytes;
+        self.reads += 1;
+    }
+
+    #[inline]
+    pub(crate) fn refill(&mut self) {
+        self.bytes = 0;
+        self.reads = 0;
+    }
+
+    // Refills the budget and schedules the task to be polled again if it is exhausted, in which
+    // case the caller must return `Poll::Pending`.
+    #[inline]
+    pub(crate) fn poll_yield(&mut self, ctx: &mut Context) -> bool {
+        if self.is_exhausted() {
+            self.refill();
+            ctx.waker().wake_by_ref();
+
+            true
+        } else {
+            false
+        }
+    }
+}
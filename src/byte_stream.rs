@@ -0,0 +1,146 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Error, ErrorKind, Framed, Protocol};
+use core::cmp;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::{AsyncRead, AsyncWrite};
+use std::io;
+
+// ============================================ Types =========================================== \\
+
+pub struct ByteStream<IO> {
+    framed: Framed<IO>,
+    msg_len: usize,
+    msg_off: usize,
+}
+
+// ======================================= impl ByteStream ====================================== \\
+
+impl<IO> ByteStream<IO> {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(super) fn new(proto: Protocol, io: IO) -> Self {
+        ByteStream {
+            framed: Framed::new(proto, io),
+            msg_len: 0,
+            msg_off: 0,
+        }
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn done(self) -> (Protocol, IO) {
+        self.framed.done()
+    }
+}
+
+// ======================================= impl AsyncRead ======================================= \\
+
+impl<IO> AsyncRead for ByteStream<IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        ctx: &mut Context,
+        out: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if this.msg_off >= this.msg_len {
+            match this.framed.poll_read_msg(ctx) {
+                Poll::Ready(Ok(Some(len))) => {
+                    this.msg_len = len;
+                    this.msg_off = 0;
+                }
+                Poll::Ready(Ok(None)) => return Poll::Ready(Ok(0)),
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(into_io(err))),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        let len = cmp::min(out.len(), this.msg_len - this.msg_off);
+        out[..len].copy_from_slice(&this.framed.msg()[this.msg_off..this.msg_off + len]);
+        this.msg_off += len;
+
+        Poll::Ready(Ok(len))
+    }
+}
+
+// ======================================= impl AsyncWrite ====================================== \\
+
+impl<IO> AsyncWrite for ByteStream<IO>
+where
+    IO: AsyncWrite + Unpin,
+{
+    fn poll_write(self: Pin<&mut Self>, ctx: &mut Context, data: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if data.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        match this.framed.poll_drain(ctx) {
+            Poll::Ready(Ok(())) => (),
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(into_io(err))),
+            Poll::Pending => return Poll::Pending,
+        }
+
+        match this.framed.poll_pace(ctx) {
+            Poll::Ready(Ok(())) => (),
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(into_io(err))),
+            Poll::Pending => return Poll::Pending,
+        }
+
+        // Only as much as the peer's window allows gets written, and a full one is reported as
+        // `WouldBlock` by `write_msg`.
+        let mut len = cmp::min(data.len(), this.framed.max_msg_len());
+        if let Some(credit) = this.framed.credit().filter(|&credit| credit > 0) {
+            len = cmp::min(len as u64, credit) as usize;
+        }
+
+        this.framed.write_msg(&data[..len]).map_err(into_io)?;
+
+        Poll::Ready(Ok(len))
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<io::Result<()>> {
+        self.get_mut().framed.poll_flush(ctx).map_err(into_io)
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<io::Result<()>> {
+        self.get_mut().framed.poll_close(ctx).map_err(into_io)
+    }
+}
+
+// ========================================== into_io() ========================================= \\
+
+pub(crate) fn into_io(error: Error) -> io::Error {
+    match error {
+        Error::Io(error) => error,
+        Error::Context { source, .. } if matches!(*source, Error::Io(_)) => into_io(*source),
+        error @ Error::WindowExhausted { .. } => {
+            io::Error::new(io::ErrorKind::WouldBlock, format!("{:?}", error))
+        }
+        error => {
+            let kind = match error.kind() {
+                ErrorKind::PeerClosed => io::ErrorKind::ConnectionAborted,
+                ErrorKind::Transient => io::ErrorKind::TimedOut,
+                ErrorKind::Fatal | ErrorKind::ProtocolViolation => io::ErrorKind::InvalidData,
+            };
+
+            io::Error::new(kind, format!("{:?}", error))
+        }
+    }
+}
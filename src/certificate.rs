@@ -0,0 +1,213 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// A certificate lets a long-term identity key delegate to a subkey for a limited time:
+//
+// CERTIFICATE ;; issuer(32) + subkey(32) + not_before(8) + not_after(8) + capabilities(8)
+//              + signature(64)
+//
+// Times are seconds since the unix epoch. The issuer signs `CONTEXT` followed by every field
+// before the signature. Capabilities are opaque to this crate.
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Error, Result};
+use core::convert::TryFrom;
+use core::time::Duration;
+use ed25519_dalek::{Keypair, PublicKey, Signature, Signer, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// ========================================== Constants ========================================= \\
+
+const CONTEXT: &[u8] = b"pr070c01 certificate";
+const SIGNED_LEN: usize = 2 * PUBLIC_KEY_LENGTH + 24;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Certificate {
+    issuer: PublicKey,
+    subkey: PublicKey,
+    not_before: u64,
+    not_after: u64,
+    capabilities: u64,
+    signature: Signature,
+}
+
+// ====================================== impl Certificate ====================================== \\
+
+impl Certificate {
+    // ====================================== Constants ===================================== \\
+
+    pub const LEN: usize = SIGNED_LEN + SIGNATURE_LENGTH;
+
+    // ==================================== Constructors ==================================== \\
+
+    pub fn issue(
+        issuer: &Keypair,
+        subkey: PublicKey,
+        not_before: SystemTime,
+        not_after: SystemTime,
+        capabilities: u64,
+    ) -> Self {
+        let not_before = secs(not_before);
+        let not_after = secs(not_after);
+        let signed = signed(&issuer.public, &subkey, not_before, not_after, capabilities);
+
+        Certificate {
+            issuer: issuer.public,
+            subkey,
+            not_before,
+            not_after,
+            capabilities,
+            signature: issuer.sign(&message(&signed)),
+        }
+    }
+
+    #[inline]
+    pub fn issue_for(issuer: &Keypair, subkey: PublicKey, validity: Duration) -> Self {
+        let now = SystemTime::now();
+        Self::issue(issuer, subkey, now, now + validity, 0)
+    }
+
+    pub fn decode(buf: &[u8; Self::LEN]) -> Result<Self> {
+        let issuer = PublicKey::from_bytes(&buf[..32]).map_err(|_| Error::InvalidCertificate)?;
+        let subkey = PublicKey::from_bytes(&buf[32..64]).map_err(|_| Error::InvalidCertificate)?;
+        let signature =
+            Signature::try_from(&buf[SIGNED_LEN..]).map_err(|_| Error::InvalidCertificate)?;
+
+        Ok(Certificate {
+            issuer,
+            subkey,
+            not_before: u64_at(buf, 64),
+            not_after: u64_at(buf, 72),
+            capabilities: u64_at(buf, 80),
+            signature,
+        })
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn issuer(&self) -> &PublicKey {
+        &self.issuer
+    }
+
+    #[inline]
+    pub fn subkey(&self) -> &PublicKey {
+        &self.subkey
+    }
+
+    #[inline]
+    pub fn not_before(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.not_before)
+    }
+
+    #[inline]
+    pub fn not_after(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.not_after)
+    }
+
+    #[inline]
+    pub fn capabilities(&self) -> u64 {
+        self.capabilities
+    }
+
+    #[inline]
+    pub fn has_capabilities(&self, capabilities: u64) -> bool {
+        self.capabilities & capabilities == capabilities
+    }
+
+    #[inline]
+    pub fn is_valid_at(&self, time: SystemTime) -> bool {
+        let time = secs(time);
+        self.not_before <= time && time <= self.not_after
+    }
+
+    pub fn encode(&self) -> [u8; Self::LEN] {
+        let mut buf = [0; Self::LEN];
+        buf[..SIGNED_LEN].copy_from_slice(&self.signed());
+        buf[SIGNED_LEN..].copy_from_slice(&self.signature.to_bytes());
+
+        buf
+    }
+
+    #[inline]
+    pub fn verify(&self) -> Result<()> {
+        self.verify_at(SystemTime::now())
+    }
+
+    pub fn verify_at(&self, time: SystemTime) -> Result<()> {
+        if !self.is_valid_at(time) {
+            return Err(Error::InvalidCertificate);
+        }
+
+        self.issuer
+            .verify_strict(&message(&self.signed()), &self.signature)
+            .map_err(|_| Error::InvalidCertificate)
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    #[inline]
+    fn signed(&self) -> [u8; SIGNED_LEN] {
+        signed(
+            &self.issuer,
+            &self.subkey,
+            self.not_before,
+            self.not_after,
+            self.capabilities,
+        )
+    }
+}
+
+// ========================================== signed() ========================================== \\
+
+fn signed(
+    issuer: &PublicKey,
+    subkey: &PublicKey,
+    not_before: u64,
+    not_after: u64,
+    capabilities: u64,
+) -> [u8; SIGNED_LEN] {
+    let mut buf = [0; SIGNED_LEN];
+    buf[..32].copy_from_slice(issuer.as_bytes());
+    buf[32..64].copy_from_slice(subkey.as_bytes());
+    buf[64..72].copy_from_slice(&not_before.to_le_bytes());
+    buf[72..80].copy_from_slice(&not_after.to_le_bytes());
+    buf[80..88].copy_from_slice(&capabilities.to_le_bytes());
+
+    buf
+}
+
+// ========================================== message() ========================================= \\
+
+fn message(signed: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(CONTEXT.len() + signed.len());
+    message.extend_from_slice(CONTEXT);
+    message.extend_from_slice(signed);
+
+    message
+}
+
+// =========================================== secs() =========================================== \\
+
+#[inline]
+fn secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs())
+}
+
+// ========================================== u64_at() ========================================== \\
+
+#[inline]
+fn u64_at(buf: &[u8], off: usize) -> u64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&buf[off..off + 8]);
+
+    u64::from_le_bytes(bytes)
+}
@@ -0,0 +1,181 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::control::Control;
+use crate::{Protocol, Result, Session, Write};
+use core::fmt::{self, Display, Formatter};
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::AsyncWrite;
+use snow::TransportState;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reason {
+    code: u16,
+    message: Option<String>,
+}
+
+pub struct Close<'proto, Output> {
+    inner: CloseInner<'proto, Output>,
+}
+
+enum CloseInner<'proto, Output> {
+    Empty,
+    Write {
+        write: Write<Output, &'proto mut TransportState, &'proto mut Vec<u8>>,
+        session: &'proto mut Session,
+    },
+    Close {
+        out: Output,
+    },
+}
+
+// ========================================= impl Reason ======================================== \\
+
+impl Reason {
+    // ====================================== Constants ===================================== \\
+
+    pub const NORMAL: u16 = 0;
+    pub const GOING_AWAY: u16 = 1;
+    pub const PROTOCOL_ERROR: u16 = 2;
+    pub const TIMEOUT: u16 = 3;
+    pub const IDLE_TIMEOUT: u16 = 4;
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new(code: u16) -> Self {
+        Reason {
+            code,
+            message: None,
+        }
+    }
+
+    #[inline]
+    pub fn with_message<Message: Into<String>>(code: u16, message: Message) -> Self {
+        Reason {
+            code,
+            message: Some(message.into()),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn from_parts(code: u16, message: &str) -> Self {
+        Reason {
+            code,
+            message: Some(message)
+                .filter(|message| !message.is_empty())
+                .map(Into::into),
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn code(&self) -> u16 {
+        self.code
+    }
+
+    #[inline]
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+}
+
+// ========================================= impl Close ========================================= \\
+
+impl<'proto, Output> Close<'proto, Output> {
+    // ==================================== Constructors ==================================== \\
+
+    pub(super) fn new(reason: &Reason, proto: &'proto mut Protocol, out: Output) -> Self
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        Control::Close(reason.code, reason.message().unwrap_or("")).encode(&mut proto.msg);
+
+        Close {
+            inner: CloseInner::Write {
+                write: Write::control(
+                    &mut proto.msg,
+                    &mut proto.buf,
+                    out,
+                    &mut proto.state,
+                    proto.session.padding,
+                    proto.session.wire,
+                ),
+                session: &mut proto.session,
+            },
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Output> Future for Close<'_, Output>
+where
+    Output: AsyncWrite + Unpin,
+{
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let inner = &mut self.get_mut().inner;
+        loop {
+            match mem::take(inner) {
+                CloseInner::Empty => panic!(),
+                CloseInner::Write { mut write, session } => {
+                    if Pin::new(&mut write).poll(ctx)?.is_ready() {
+                        let (_, _, out, _) = write.done();
+                        session.liveness.sent();
+
+                        *inner = CloseInner::Close { out };
+                    } else {
+                        *inner = CloseInner::Write { write, session };
+
+                        return Poll::Pending;
+                    }
+                }
+                CloseInner::Close { mut out } => {
+                    if Pin::new(&mut out).poll_close(ctx)?.is_ready() {
+                        return Poll::Ready(Ok(()));
+                    } else {
+                        *inner = CloseInner::Close { out };
+
+                        return Poll::Pending;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ======================================== impl Display ======================================== \\
+
+impl Display for Reason {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        if let Some(message) = &self.message {
+            write!(fmt, "{}: {}", self.code, message)
+        } else {
+            write!(fmt, "{}", self.code)
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<Output> Default for CloseInner<'_, Output> {
+    #[inline]
+    fn default() -> Self {
+        CloseInner::Empty
+    }
+}
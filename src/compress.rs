@@ -0,0 +1,134 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// The compression algorithm is negotiated by prefixing the handshake payloads with one byte:
+//
+// -> offer(1) + payload  ;; bitmask of (1 << id) for each algorithm offered by the initiator
+// <- choice(1) + payload ;; id of the algorithm picked by the responder
+//
+// Compressed packets are then sent as COMPRESSED control messages (see control.rs).
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Error, Result};
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compression {
+    None,
+    Lz4,
+    Zstd,
+}
+
+// ====================================== impl Compression ====================================== \\
+
+impl Compression {
+    // ====================================== Constants ===================================== \\
+
+    pub const ALL: [Compression; 3] = [Compression::None, Compression::Lz4, Compression::Zstd];
+
+    pub const ZSTD_LEVEL: i32 = 3;
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn from_id(id: u8) -> Option<Self> {
+        Compression::ALL.get(id as usize).copied()
+    }
+
+    pub fn available() -> Vec<Self> {
+        [Compression::Zstd, Compression::Lz4, Compression::None]
+            .iter()
+            .copied()
+            .filter(|compression| compression.is_available())
+            .collect()
+    }
+
+    pub(crate) fn negotiate(preferred: &[Compression], offer: u8) -> Self {
+        preferred
+            .iter()
+            .copied()
+            .find(|compression| compression.is_available() && offer & compression.bit() != 0)
+            .unwrap_or_default()
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+
+    #[inline]
+    pub fn is_available(self) -> bool {
+        match self {
+            Compression::None => true,
+            Compression::Lz4 => cfg!(feature = "lz4"),
+            Compression::Zstd => cfg!(feature = "zstd"),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn bit(self) -> u8 {
+        1 << self.id()
+    }
+
+    #[inline]
+    pub(crate) fn mask(compressions: &[Compression]) -> u8 {
+        compressions
+            .iter()
+            .filter(|compression| compression.is_available())
+            .fold(Compression::None.bit(), |mask, compression| {
+                mask | compression.bit()
+            })
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[cfg_attr(not(any(feature = "lz4", feature = "zstd")), allow(unused_variables))]
+    pub(crate) fn compress(self, bytes: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            #[cfg(feature = "lz4")]
+            Compression::Lz4 => Ok(lz4_flex::compress_prepend_size(bytes)),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => {
+                zstd::bulk::compress(bytes, Self::ZSTD_LEVEL).map_err(|_| Error::Compression)
+            }
+            _ => Err(Error::Compression),
+        }
+    }
+
+    #[cfg_attr(not(any(feature = "lz4", feature = "zstd")), allow(unused_variables))]
+    pub(crate) fn decompress(self, bytes: &[u8], max: usize) -> Result<Vec<u8>> {
+        match self {
+            #[cfg(feature = "lz4")]
+            Compression::Lz4 => {
+                if bytes.len() < 4
+                    || u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize > max
+                {
+                    return Err(Error::Compression);
+                }
+
+                lz4_flex::decompress_size_prepended(bytes).map_err(|_| Error::Compression)
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => zstd::bulk::decompress(bytes, max).map_err(|_| Error::Compression),
+            _ => Err(Error::Compression),
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl Default for Compression {
+    #[inline]
+    fn default() -> Self {
+        Compression::None
+    }
+}
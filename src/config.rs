@@ -0,0 +1,567 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{
+    send, Certificate, Compression, Error, HandshakeLimiter, HandshakeParams, Keypair, Metrics,
+    PublicKey, Result, StaticKey, WireFormat,
+};
+use core::fmt::{self, Debug, Formatter};
+use packets::Packet;
+use snow::params::NoiseParams;
+use snow::{Builder, HandshakeState};
+use std::sync::Arc;
+
+#[cfg(feature = "metrics")]
+use crate::ProtocolMetrics;
+
+// ========================================== Constants ========================================= \\
+
+pub(crate) const HYBRID_DECLINED: u8 = 0;
+pub(crate) const HYBRID_ACCEPTED: u8 = 1;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone)]
+pub struct Config {
+    suites: Vec<Suite>,
+    compressions: Vec<Compression>,
+    wire: WireFormat,
+    payload: Vec<u8>,
+    verifier: Option<Verifier>,
+    identity: Option<Arc<Keypair>>,
+    static_key: Option<Arc<StaticKey>>,
+    certificate: Option<Certificate>,
+    remote_identity: Option<PublicKey>,
+    hybrid: bool,
+    params: Option<HandshakeParams>,
+    pow: u8,
+    max_pow: u8,
+    limiter: Option<HandshakeLimiter>,
+    metrics: Metrics,
+}
+
+type Verifier = Arc<dyn Fn(&[u8]) -> bool + Send + Sync>;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Suite {
+    ChaChaPolyBlake2b,
+    ChaChaPolySha256,
+    AesGcmBlake2b,
+    AesGcmSha256,
+}
+
+// ========================================= impl Config ======================================== \\
+
+impl Config {
+    // ====================================== Constants ===================================== \\
+
+    pub const MAX_POW: u8 = 24;
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new() -> Self {
+        Config {
+            suites: Suite::ALL.to_vec(),
+            compressions: Compression::available(),
+            wire: WireFormat::default(),
+            payload: Vec::new(),
+            verifier: None,
+            identity: None,
+            static_key: None,
+            certificate: None,
+            remote_identity: None,
+            hybrid: false,
+            params: None,
+            pow: 0,
+            max_pow: Self::MAX_POW,
+            limiter: None,
+            metrics: Metrics::default(),
+        }
+    }
+
+    #[inline]
+    pub fn with_suites<Suites>(mut self, suites: Suites) -> Self
+    where
+        Suites: IntoIterator<Item = Suite>,
+    {
+        self.suites = suites.into_iter().collect();
+        self
+    }
+
+    #[inline]
+    pub fn with_compressions<Compressions>(mut self, compressions: Compressions) -> Self
+    where
+        Compressions: IntoIterator<Item = Compression>,
+    {
+        self.compressions = compressions.into_iter().collect();
+        self
+    }
+
+    #[inline]
+    pub fn with_wire_format(mut self, wire: WireFormat) -> Self {
+        self.wire = wire;
+        self
+    }
+
+    // An initiator's payload is only ever sent encrypted, which requires it to either target the
+    // responder's identity (IK) or have one of its own (XN).
+    #[inline]
+    pub fn with_payload<Payload>(mut self, payload: Payload) -> Self
+    where
+        Payload: Into<Vec<u8>>,
+    {
+        self.payload = payload.into();
+        self
+    }
+
+    pub fn with_packet(mut self, packet: Packet) -> Result<Self> {
+        send::encode(&packet, &mut self.payload)?;
+
+        Ok(self)
+    }
+
+    #[inline]
+    pub fn with_verifier<Verify>(mut self, verify: Verify) -> Self
+    where
+        Verify: Fn(&[u8]) -> bool + Send + Sync + 'static,
+    {
+        self.verifier = Some(Arc::new(verify));
+        self
+    }
+
+    #[inline]
+    pub fn with_identity(mut self, keypair: Keypair) -> Self {
+        self.static_key = Some(Arc::new(StaticKey::from_identity(&keypair)));
+        self.identity = Some(Arc::new(keypair));
+        self.certificate = None;
+        self
+    }
+
+    #[inline]
+    pub fn with_delegation(mut self, subkey: Keypair, certificate: Certificate) -> Self {
+        self.static_key = Some(Arc::new(StaticKey::from_identity(&subkey)));
+        self.identity = Some(Arc::new(subkey));
+        self.certificate = Some(certificate);
+        self
+    }
+
+    #[inline]
+    pub fn with_remote_identity(mut self, identity: PublicKey) -> Self {
+        self.remote_identity = Some(identity);
+        self
+    }
+
+    #[cfg(feature = "pq")]
+    #[inline]
+    pub fn with_hybrid(mut self, hybrid: bool) -> Self {
+        self.hybrid = hybrid;
+        self
+    }
+
+    #[inline]
+    pub fn with_params(mut self, params: HandshakeParams) -> Self {
+        self.params = Some(params);
+        self
+    }
+
+    #[inline]
+    pub fn with_pow(mut self, difficulty: u8) -> Self {
+        self.pow = difficulty;
+        self
+    }
+
+    #[inline]
+    pub fn with_max_pow(mut self, difficulty: u8) -> Self {
+        self.max_pow = difficulty;
+        self
+    }
+
+    #[inline]
+    pub fn with_limiter(mut self, limiter: HandshakeLimiter) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub fn with_metrics(mut self, metrics: Arc<dyn ProtocolMetrics>) -> Self {
+        self.metrics = Metrics::new(metrics);
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn suites(&self) -> &[Suite] {
+        &self.suites
+    }
+
+    #[inline]
+    pub fn compressions(&self) -> &[Compression] {
+        &self.compressions
+    }
+
+    #[inline]
+    pub fn wire_format(&self) -> WireFormat {
+        self.wire
+    }
+
+    #[inline]
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    #[inline]
+    pub fn identity(&self) -> Option<&Keypair> {
+        self.identity.as_deref()
+    }
+
+    #[inline]
+    pub fn certificate(&self) -> Option<&Certificate> {
+        self.certificate.as_ref()
+    }
+
+    #[inline]
+    pub fn remote_identity(&self) -> Option<&PublicKey> {
+        self.remote_identity.as_ref()
+    }
+
+    #[inline]
+    pub fn is_hybrid(&self) -> bool {
+        self.hybrid
+    }
+
+    #[inline]
+    pub fn params(&self) -> Option<&HandshakeParams> {
+        self.params.as_ref()
+    }
+
+    #[inline]
+    pub fn pow(&self) -> u8 {
+        self.pow
+    }
+
+    #[inline]
+    pub fn max_pow(&self) -> u8 {
+        self.max_pow
+    }
+
+    #[inline]
+    pub fn limiter(&self) -> Option<&HandshakeLimiter> {
+        self.limiter.as_ref()
+    }
+
+    #[inline]
+    pub(crate) fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    // The prologue is the initiator's offer as it sent it, followed by the responder's choice and
+    // its answer if a hybrid handshake was offered, so that tampering with any of them makes the
+    // handshake fail. Static keys are derived from identities: both sides have one when targeted
+    // (IK), and only the initiator when it announced an identity otherwise (XN).
+    pub(crate) fn build_variant(
+        &self,
+        suite: Suite,
+        offer: &[u8],
+        id: u8,
+        hybrid: bool,
+        initiator: bool,
+    ) -> Result<(HandshakeState, NoiseParams)> {
+        let targeted = id & Suite::TARGETED != 0;
+        let identified = id & Suite::IDENTIFIED != 0;
+        let params = match &self.params {
+            Some(params) => params.variant(suite, targeted, identified, hybrid).clone(),
+            None => HandshakeParams::shared()
+                .variant(suite, targeted, identified, hybrid)
+                .clone(),
+        };
+
+        let mut prologue = [0; 3 + Suite::MAX_OFFERED];
+        prologue[..offer.len()].copy_from_slice(offer);
+        prologue[offer.len()] = id;
+
+        let mut len = offer.len() + 1;
+        if id & Suite::HYBRID != 0 {
+            prologue[len] = if hybrid {
+                HYBRID_ACCEPTED
+            } else {
+                HYBRID_DECLINED
+            };
+
+            len += 1;
+        }
+
+        let prologue = &prologue[..len];
+        let builder = Builder::new(params.clone()).prologue(prologue);
+        if !targeted && !(identified && initiator) {
+            if initiator {
+                return Ok((builder.build_initiator()?, params));
+            } else {
+                return Ok((builder.build_responder()?, params));
+            }
+        }
+
+        let local = self
+            .static_key
+            .as_ref()
+            .ok_or(Error::UnsupportedSuite(id))?;
+        let builder = builder.local_private_key(local.secret());
+        let state = if targeted && initiator {
+            let remote = self.remote_static()?;
+            builder.remote_public_key(&remote).build_initiator()?
+        } else if initiator {
+            builder.build_initiator()?
+        } else {
+            builder.build_responder()?
+        };
+
+        Ok((state, params))
+    }
+
+    #[inline]
+    pub(crate) fn is_targeted(&self) -> bool {
+        self.remote_identity.is_some()
+    }
+
+    pub(crate) fn suite_id(&self, suite: Suite) -> u8 {
+        let mut id = suite.id();
+        if self.is_targeted() {
+            id |= Suite::TARGETED;
+        }
+
+        if self.identity.is_some() {
+            id |= Suite::IDENTIFIED;
+        }
+
+        if self.hybrid {
+            id |= Suite::HYBRID;
+        }
+
+        id
+    }
+
+    // The initiator offers its suites in order of preference, and the responder answers with the
+    // first one it accepts:
+    //
+    // OFFER  ;; count(1) + id(1) * count
+    // CHOICE ;; id(1)
+    pub(crate) fn suite_offer(&self) -> ([u8; 1 + Suite::MAX_OFFERED], usize) {
+        let mut offer = [0; 1 + Suite::MAX_OFFERED];
+        let mut len = 1;
+        for &suite in self.suites.iter().take(Suite::MAX_OFFERED) {
+            offer[len] = self.suite_id(suite);
+            len += 1;
+        }
+
+        if len == 1 {
+            offer[len] = self.suite_id(Suite::default());
+            len += 1;
+        }
+
+        offer[0] = (len - 1) as u8;
+        (offer, len)
+    }
+
+    // Picks the first suite of an offer that is accepted, along with its id as it was offered.
+    pub(crate) fn choose_suite(&self, ids: &[u8]) -> Option<(Suite, u8)> {
+        let flags = Suite::TARGETED | Suite::HYBRID | Suite::IDENTIFIED;
+        ids.iter()
+            .find_map(|&id| match Suite::from_id(id & !flags) {
+                Some(suite) if self.accepts(suite) => Some((suite, id)),
+                _ => None,
+            })
+    }
+
+    #[inline]
+    pub(crate) fn accepts(&self, suite: Suite) -> bool {
+        self.suites.contains(&suite)
+    }
+
+    #[inline]
+    pub(crate) fn compression_offer(&self) -> u8 {
+        Compression::mask(&self.compressions)
+    }
+
+    #[inline]
+    pub(crate) fn offers(&self, compression: Compression) -> bool {
+        self.compression_offer() & compression.bit() != 0
+    }
+
+    #[inline]
+    pub(crate) fn offer(&self) -> u8 {
+        self.compression_offer() | self.wire.bit()
+    }
+
+    #[inline]
+    pub(crate) fn negotiate(&self, offer: u8) -> (Compression, WireFormat) {
+        (
+            Compression::negotiate(&self.compressions, offer),
+            self.wire.negotiate(offer),
+        )
+    }
+
+    pub(crate) fn accepts_choice(&self, choice: u8) -> Option<(Compression, WireFormat)> {
+        let compression = Compression::from_id(choice & !WireFormat::MASK)?;
+        let wire = self.wire.negotiate(choice);
+        if self.offers(compression) && choice & WireFormat::MASK == wire.bit() {
+            Some((compression, wire))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub(crate) fn verify(&self, payload: &[u8]) -> bool {
+        self.verifier
+            .as_ref()
+            .map_or(true, |verify| verify(payload))
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    #[inline]
+    fn remote_static(&self) -> Result<[u8; 32]> {
+        let identity = self.remote_identity.as_ref().ok_or(Error::InvalidIdentity)?;
+        StaticKey::remote(identity)
+    }
+}
+
+// ========================================= impl Suite ========================================= \\
+
+impl Suite {
+    // ====================================== Constants ===================================== \\
+
+    pub const ALL: [Suite; 4] = [
+        Suite::ChaChaPolyBlake2b,
+        Suite::ChaChaPolySha256,
+        Suite::AesGcmBlake2b,
+        Suite::AesGcmSha256,
+    ];
+
+    // Offers can list more suites than there currently are, but the responder only ever looks at
+    // this many of them.
+    pub(crate) const MAX_OFFERED: usize = 8;
+
+    pub(crate) const IDENTIFIED: u8 = 0x10;
+    pub(crate) const HYBRID: u8 = 0x40;
+    pub(crate) const TARGETED: u8 = 0x80;
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn from_id(id: u8) -> Option<Self> {
+        Suite::ALL.get(id as usize).copied()
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+
+    #[inline]
+    pub fn pattern(self) -> &'static str {
+        match self {
+            Suite::ChaChaPolyBlake2b => "Noise_NN_25519_ChaChaPoly_BLAKE2b",
+            Suite::ChaChaPolySha256 => "Noise_NN_25519_ChaChaPoly_SHA256",
+            Suite::AesGcmBlake2b => "Noise_NN_25519_AESGCM_BLAKE2b",
+            Suite::AesGcmSha256 => "Noise_NN_25519_AESGCM_SHA256",
+        }
+    }
+
+    #[inline]
+    pub fn params(self) -> NoiseParams {
+        HandshakeParams::shared().get(self).clone()
+    }
+
+    #[inline]
+    pub fn targeted_params(self) -> NoiseParams {
+        HandshakeParams::shared()
+            .variant(self, true, true, false)
+            .clone()
+    }
+
+    #[cfg(feature = "pq")]
+    #[inline]
+    pub fn hybrid_params(self) -> NoiseParams {
+        HandshakeParams::shared()
+            .variant(self, false, false, true)
+            .clone()
+    }
+
+    pub(crate) fn variant_params(
+        self,
+        targeted: bool,
+        identified: bool,
+        hybrid: bool,
+    ) -> NoiseParams {
+        let mut pattern = self.pattern().to_owned();
+        if targeted {
+            pattern = pattern.replacen("_NN_", "_IK_", 1);
+        } else if identified {
+            pattern = pattern.replacen("_NN_", "_XN_", 1);
+        }
+
+        if hybrid {
+            pattern = pattern.replacen("_25519_", "hfs_25519+Kyber1024_", 1);
+        }
+
+        pattern.parse().unwrap()
+    }
+}
+
+// ========================================= impl Debug ========================================= \\
+
+impl Debug for Config {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Config")
+            .field("suites", &self.suites)
+            .field("compressions", &self.compressions)
+            .field("wire", &self.wire)
+            .field("payload", &self.payload)
+            .field("verifier", &self.verifier.is_some())
+            .field(
+                "identity",
+                &self.identity.as_ref().map(|keypair| keypair.public),
+            )
+            .field(
+                "static_key",
+                &self.static_key.as_ref().map(|key| *key.public()),
+            )
+            .field("certificate", &self.certificate)
+            .field("remote_identity", &self.remote_identity)
+            .field("hybrid", &self.hybrid)
+            .field("params", &self.params.is_some())
+            .field("pow", &self.pow)
+            .field("max_pow", &self.max_pow)
+            .field("limiter", &self.limiter)
+            .field("metrics", &self.metrics.is_enabled())
+            .finish()
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl Default for Config {
+    #[inline]
+    fn default() -> Self {
+        Config::new()
+    }
+}
+
+impl Default for Suite {
+    #[inline]
+    fn default() -> Self {
+        Suite::ChaChaPolyBlake2b
+    }
+}
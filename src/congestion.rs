@@ -0,0 +1,197 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// A congestion controller decides how many bytes a `ReliableChannel` can have in flight, and how
+// long it should wait before sending its next message.
+
+// =========================================== Imports ========================================== \\
+
+use core::time::Duration;
+use std::time::Instant;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug)]
+pub struct NewReno {
+    mss: usize,
+    cwnd: usize,
+    ssthresh: usize,
+    recovery: Option<Instant>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct FixedRate {
+    bytes_per_sec: u64,
+    next: Instant,
+}
+
+// ========================================= Interfaces ========================================= \\
+
+pub trait CongestionControl: Send {
+    fn window(&self) -> usize;
+
+    fn delay(&self) -> Option<Duration> {
+        None
+    }
+
+    fn on_sent(&mut self, _bytes: usize) {}
+
+    fn on_ack(&mut self, _bytes: usize, _sent: Instant) {}
+
+    fn on_loss(&mut self, _bytes: usize, _sent: Instant) {}
+
+    fn on_timeout(&mut self) {}
+}
+
+// ======================================== impl NewReno ======================================== \\
+
+impl NewReno {
+    // ====================================== Constants ===================================== \\
+
+    pub const MSS: usize = 1200;
+    pub const INITIAL_WINDOW: usize = 10;
+    pub const MIN_WINDOW: usize = 2;
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new() -> Self {
+        NewReno::with_mss(Self::MSS)
+    }
+
+    #[inline]
+    pub fn with_mss(mss: usize) -> Self {
+        NewReno {
+            mss,
+            cwnd: Self::INITIAL_WINDOW * mss,
+            ssthresh: usize::MAX,
+            recovery: None,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn mss(&self) -> usize {
+        self.mss
+    }
+
+    #[inline]
+    pub fn ssthresh(&self) -> usize {
+        self.ssthresh
+    }
+
+    #[inline]
+    pub fn is_recovering(&self) -> bool {
+        self.recovery.is_some()
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    // Messages sent before the current recovery period started were already accounted for by
+    // the reduction that started it.
+    #[inline]
+    fn before_recovery(&self, sent: Instant) -> bool {
+        matches!(self.recovery, Some(start) if sent <= start)
+    }
+
+    #[inline]
+    fn reduce(&mut self) {
+        self.ssthresh = (self.cwnd / 2).max(Self::MIN_WINDOW * self.mss);
+        self.recovery = Some(Instant::now());
+    }
+}
+
+// ======================================= impl FixedRate ======================================= \\
+
+impl FixedRate {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new(bytes_per_sec: u64) -> Self {
+        FixedRate {
+            bytes_per_sec: bytes_per_sec.max(1),
+            next: Instant::now(),
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn bytes_per_sec(&self) -> u64 {
+        self.bytes_per_sec
+    }
+}
+
+// =================================== impl CongestionControl =================================== \\
+
+impl CongestionControl for NewReno {
+    #[inline]
+    fn window(&self) -> usize {
+        self.cwnd
+    }
+
+    fn on_ack(&mut self, bytes: usize, sent: Instant) {
+        if self.before_recovery(sent) {
+            return;
+        }
+
+        self.recovery = None;
+        if self.cwnd < self.ssthresh {
+            self.cwnd += bytes;
+        } else {
+            self.cwnd += (self.mss * bytes / self.cwnd).max(1);
+        }
+    }
+
+    fn on_loss(&mut self, _: usize, sent: Instant) {
+        if self.before_recovery(sent) {
+            return;
+        }
+
+        self.reduce();
+        self.cwnd = self.ssthresh;
+    }
+
+    fn on_timeout(&mut self) {
+        self.reduce();
+        self.cwnd = Self::MIN_WINDOW * self.mss;
+    }
+}
+
+impl CongestionControl for FixedRate {
+    #[inline]
+    fn window(&self) -> usize {
+        usize::MAX
+    }
+
+    #[inline]
+    fn delay(&self) -> Option<Duration> {
+        let now = Instant::now();
+        if self.next > now {
+            Some(self.next - now)
+        } else {
+            None
+        }
+    }
+
+    fn on_sent(&mut self, bytes: usize) {
+        let now = Instant::now();
+        let secs = bytes as f64 / self.bytes_per_sec as f64;
+        self.next = self.next.max(now) + Duration::from_secs_f64(secs);
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl Default for NewReno {
+    #[inline]
+    fn default() -> Self {
+        NewReno::new()
+    }
+}
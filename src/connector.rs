@@ -0,0 +1,359 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::socks::Socks5;
+use crate::{Config, Error, Handshake, HappyEyeballs, Initiate, Protocol, Race, Result};
+use crate::{SocksAddr, Timer, Transport};
+use core::cmp;
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use futures_io::{AsyncRead, AsyncWrite};
+use std::io;
+use std::net::SocketAddr;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Debug)]
+pub struct Connector {
+    config: Config,
+    attempts: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+    timeout: Duration,
+    stagger: Duration,
+    socks5: Option<SocksAddr>,
+}
+
+pub struct Connect<'conn, Dial, Fut, IO, Tmr> {
+    connector: &'conn Connector,
+    dial: Dial,
+    attempt: u32,
+    inner: ConnectInner<Fut, IO, Tmr>,
+}
+
+enum ConnectInner<Fut, IO, Tmr> {
+    Empty,
+    Dial { fut: Pin<Box<Fut>>, timer: Tmr },
+    Socks { socks: Socks5<IO>, timer: Tmr },
+    Handshake { initiate: Initiate<IO>, timer: Tmr },
+    Backoff { timer: Tmr },
+    Done,
+}
+
+// ======================================= impl Connector ======================================= \\
+
+impl Connector {
+    // ====================================== Constants ===================================== \\
+
+    pub const DEFAULT_ATTEMPTS: u32 = 5;
+    pub const DEFAULT_BACKOFF: Duration = Duration::from_millis(100);
+    pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(10);
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+    pub const DEFAULT_STAGGER: Duration = Duration::from_millis(250);
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new(config: Config) -> Self {
+        Connector {
+            config,
+            attempts: Self::DEFAULT_ATTEMPTS,
+            backoff: Self::DEFAULT_BACKOFF,
+            max_backoff: Self::DEFAULT_MAX_BACKOFF,
+            timeout: Self::DEFAULT_TIMEOUT,
+            stagger: Self::DEFAULT_STAGGER,
+            socks5: None,
+        }
+    }
+
+    #[inline]
+    pub fn with_attempts(mut self, attempts: u32) -> Self {
+        self.attempts = cmp::max(attempts, 1);
+        self
+    }
+
+    #[inline]
+    pub fn with_backoff(mut self, backoff: Duration, max_backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    #[inline]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    #[inline]
+    pub fn with_stagger(mut self, stagger: Duration) -> Self {
+        self.stagger = stagger;
+        self
+    }
+
+    #[inline]
+    pub fn via_socks5<Target: Into<SocksAddr>>(mut self, target: Target) -> Self {
+        self.socks5 = Some(target.into());
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    #[inline]
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    #[inline]
+    pub fn backoff(&self) -> Duration {
+        self.backoff
+    }
+
+    #[inline]
+    pub fn max_backoff(&self) -> Duration {
+        self.max_backoff
+    }
+
+    #[inline]
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    #[inline]
+    pub fn stagger(&self) -> Duration {
+        self.stagger
+    }
+
+    #[inline]
+    pub fn socks5(&self) -> Option<&SocksAddr> {
+        self.socks5.as_ref()
+    }
+
+    #[inline]
+    pub fn backoff_for(&self, attempt: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        let backoff = self.backoff.checked_mul(factor).unwrap_or(self.max_backoff);
+
+        cmp::min(backoff, self.max_backoff)
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub fn connect<Dial, Fut, IO, Tmr>(&self, dial: Dial) -> Connect<Dial, Fut, IO, Tmr>
+    where
+        Dial: FnMut() -> Fut,
+        Fut: Future<Output = io::Result<IO>>,
+        IO: AsyncRead + AsyncWrite + Unpin,
+        Tmr: Timer,
+    {
+        Connect::new(self, dial)
+    }
+
+    #[inline]
+    pub fn connect_via<Trn, Tmr>(&self, transport: Trn) -> Connect<Trn, Trn::Dial, Trn::Io, Tmr>
+    where
+        Trn: Transport,
+        Tmr: Timer,
+    {
+        Connect::new(self, transport)
+    }
+
+    #[inline]
+    pub fn connect_any<Dial, Fut, IO, Tmr>(
+        &self,
+        addrs: &[SocketAddr],
+        dial: Dial,
+    ) -> Connect<HappyEyeballs<Dial, Tmr>, Race<Fut, Tmr>, IO, Tmr>
+    where
+        Dial: FnMut(SocketAddr) -> Fut,
+        Fut: Future<Output = io::Result<IO>>,
+        IO: AsyncRead + AsyncWrite + Unpin,
+        Tmr: Timer,
+    {
+        Connect::new(self, HappyEyeballs::new(addrs, dial, self.stagger))
+    }
+}
+
+// ======================================== impl Connect ======================================== \\
+
+impl<'conn, Dial, Fut, IO, Tmr> Connect<'conn, Dial, Fut, IO, Tmr> {
+    // ==================================== Constructors ==================================== \\
+
+    fn new(connector: &'conn Connector, mut dial: Dial) -> Self
+    where
+        Dial: Transport<Io = IO, Dial = Fut>,
+        Tmr: Timer,
+    {
+        let inner = ConnectInner::Dial {
+            fut: Box::pin(dial.dial()),
+            timer: Tmr::after(connector.timeout),
+        };
+
+        Connect {
+            connector,
+            dial,
+            attempt: 1,
+            inner,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    fn retry(&mut self, error: Error) -> Result<()>
+    where
+        Tmr: Timer,
+    {
+        if self.attempt >= self.connector.attempts {
+            self.inner = ConnectInner::Done;
+
+            return Err(error);
+        }
+
+        self.inner = ConnectInner::Backoff {
+            timer: Tmr::after(self.connector.backoff_for(self.attempt)),
+        };
+
+        Ok(())
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Dial, Fut, IO, Tmr> Future for Connect<'_, Dial, Fut, IO, Tmr>
+where
+    Dial: Transport<Io = IO, Dial = Fut> + Unpin,
+    Fut: Future<Output = io::Result<IO>>,
+    IO: AsyncRead + AsyncWrite + Unpin,
+    Tmr: Timer,
+{
+    type Output = Result<(IO, Protocol)>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            match mem::take(&mut this.inner) {
+                ConnectInner::Empty | ConnectInner::Done => panic!(),
+                ConnectInner::Dial { mut fut, mut timer } => match fut.as_mut().poll(ctx) {
+                    Poll::Ready(Ok(io)) => {
+                        if let Some(target) = &this.connector.socks5 {
+                            this.inner = ConnectInner::Socks {
+                                socks: Socks5::new(io, target.clone()),
+                                timer,
+                            };
+                        } else {
+                            this.inner = ConnectInner::Handshake {
+                                initiate: Handshake::initiate_with(
+                                    io,
+                                    this.connector.config.clone(),
+                                ),
+                                timer,
+                            };
+                        }
+                    }
+                    Poll::Ready(Err(error)) => this.retry(error.into())?,
+                    Poll::Pending => {
+                        if Pin::new(&mut timer).poll(ctx).is_ready() {
+                            this.retry(Error::Timeout)?;
+                        } else {
+                            this.inner = ConnectInner::Dial { fut, timer };
+
+                            return Poll::Pending;
+                        }
+                    }
+                },
+                ConnectInner::Socks {
+                    mut socks,
+                    mut timer,
+                } => match Pin::new(&mut socks).poll(ctx) {
+                    Poll::Ready(Ok(())) => {
+                        this.inner = ConnectInner::Handshake {
+                            initiate: Handshake::initiate_with(
+                                socks.done(),
+                                this.connector.config.clone(),
+                            ),
+                            timer,
+                        };
+                    }
+                    Poll::Ready(Err(error)) => this.retry(error)?,
+                    Poll::Pending => {
+                        if Pin::new(&mut timer).poll(ctx).is_ready() {
+                            this.retry(Error::Timeout)?;
+                        } else {
+                            this.inner = ConnectInner::Socks { socks, timer };
+
+                            return Poll::Pending;
+                        }
+                    }
+                },
+                ConnectInner::Handshake {
+                    mut initiate,
+                    mut timer,
+                } => match Pin::new(&mut initiate).poll(ctx) {
+                    Poll::Ready(Ok(handshake)) => {
+                        let io = initiate.done();
+                        this.inner = ConnectInner::Done;
+
+                        return Poll::Ready(Ok((io, handshake.done()?)));
+                    }
+                    Poll::Ready(Err(error)) => this.retry(error)?,
+                    Poll::Pending => {
+                        if Pin::new(&mut timer).poll(ctx).is_ready() {
+                            this.retry(Error::Timeout)?;
+                        } else {
+                            this.inner = ConnectInner::Handshake { initiate, timer };
+
+                            return Poll::Pending;
+                        }
+                    }
+                },
+                ConnectInner::Backoff { mut timer } => {
+                    if Pin::new(&mut timer).poll(ctx).is_pending() {
+                        this.inner = ConnectInner::Backoff { timer };
+
+                        return Poll::Pending;
+                    }
+
+                    this.attempt += 1;
+                    this.inner = ConnectInner::Dial {
+                        fut: Box::pin(this.dial.dial()),
+                        timer: Tmr::after(this.connector.timeout),
+                    };
+                }
+            }
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<Fut, IO, Tmr> Default for ConnectInner<Fut, IO, Tmr> {
+    #[inline]
+    fn default() -> Self {
+        ConnectInner::Empty
+    }
+}
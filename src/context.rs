@@ -0,0 +1,131 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Direction, HandshakePhase};
+use core::fmt::{self, Display, Formatter};
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ErrorContext {
+    phase: Option<HandshakePhase>,
+    direction: Option<Direction>,
+    packet: Option<u16>,
+    offset: Option<usize>,
+}
+
+// ====================================== impl ErrorContext ===================================== \\
+
+impl ErrorContext {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new(direction: Direction) -> Self {
+        ErrorContext {
+            direction: Some(direction),
+            ..ErrorContext::default()
+        }
+    }
+
+    #[inline]
+    pub(crate) fn read(offset: usize) -> Self {
+        Self::new(Direction::Read).with_offset(offset)
+    }
+
+    #[inline]
+    pub(crate) fn write(offset: usize) -> Self {
+        Self::new(Direction::Write).with_offset(offset)
+    }
+
+    #[inline]
+    pub(crate) fn handshake(phase: HandshakePhase) -> Self {
+        ErrorContext {
+            phase: Some(phase),
+            ..ErrorContext::default()
+        }
+    }
+
+    #[inline]
+    pub(crate) fn packet(packet: u16) -> Self {
+        ErrorContext {
+            packet: Some(packet),
+            ..ErrorContext::default()
+        }
+    }
+
+    #[inline]
+    pub(crate) fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn phase(&self) -> Option<HandshakePhase> {
+        self.phase
+    }
+
+    #[inline]
+    pub fn direction(&self) -> Option<Direction> {
+        self.direction
+    }
+
+    #[inline]
+    pub fn packet_id(&self) -> Option<u16> {
+        self.packet
+    }
+
+    #[inline]
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    // Keeps the fields which are already set, as they were set closer to where the error happened.
+    #[inline]
+    pub(crate) fn merge(self, other: Self) -> Self {
+        ErrorContext {
+            phase: self.phase.or(other.phase),
+            direction: self.direction.or(other.direction),
+            packet: self.packet.or(other.packet),
+            offset: self.offset.or(other.offset),
+        }
+    }
+}
+
+// ======================================== impl Display ======================================== \\
+
+impl Display for ErrorContext {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let mut sep = "";
+        if let Some(phase) = self.phase {
+            write!(fmt, "{}phase={:?}", sep, phase)?;
+            sep = ", ";
+        }
+
+        if let Some(direction) = self.direction {
+            write!(fmt, "{}direction={:?}", sep, direction)?;
+            sep = ", ";
+        }
+
+        if let Some(packet) = self.packet {
+            write!(fmt, "{}packet={:#06x}", sep, packet)?;
+            sep = ", ";
+        }
+
+        if let Some(offset) = self.offset {
+            write!(fmt, "{}offset={}", sep, offset)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,451 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// A control message is sent as an empty message (which can never be a valid packet), followed by
+// a message starting with the control's kind:
+//
+// REKEY      ;; kind(1)
+// FRAGMENT   ;; kind(1) + stream(4) + seq(4) + last(1) + payload
+// PING       ;; kind(1) + nonce(8)
+// PONG       ;; kind(1) + nonce(8)
+// CLOSE      ;; kind(1) + code(2) + message
+// CUSTOM     ;; kind(1) + id(2) + payload
+// WINDOW     ;; kind(1) + credit(4)
+// COMPRESSED ;; kind(1) + algorithm(1) + payload
+// RESIZE     ;; kind(1) + window(4)
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Compression, Custom, Error, Flow, Reason, Result};
+use core::convert::TryInto;
+use core::str;
+use core::time::Duration;
+use packets::MSG_MAX_LEN;
+use snow::TransportState;
+use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
+
+// ========================================== Constants ========================================= \\
+
+const REKEY: u8 = 0;
+const FRAGMENT: u8 = 1;
+const PING: u8 = 2;
+const PONG: u8 = 3;
+const CLOSE: u8 = 4;
+const CUSTOM: u8 = 5;
+const WINDOW: u8 = 6;
+const COMPRESSED: u8 = 7;
+const RESIZE: u8 = 8;
+
+// At most this many large messages get buffered at once, whether they are complete or not, and
+// together they can't be larger than the maximum length of a single one.
+const MAX_STREAMS: usize = 8;
+// How many packets can be received while waiting for a large message, a custom one or a pong,
+// before they stop being queued for the next receive.
+const MAX_BACKLOG: usize = 32;
+// Pings received past this many unanswered ones are dropped instead of answered.
+const MAX_PONGS: usize = 16;
+
+pub(crate) const FRAGMENT_OVERHEAD: usize = 10;
+pub(crate) const COMPRESSED_OVERHEAD: usize = 2;
+
+// ============================================ Types =========================================== \\
+
+pub(crate) enum Control<'msg> {
+    Rekey,
+    Fragment(Fragment<'msg>),
+    Ping(u64),
+    Pong(u64),
+    Close(u16, &'msg str),
+    Custom(u16, &'msg [u8]),
+    Window(u32),
+    Compressed(u8, &'msg [u8]),
+    Resize(u32),
+}
+
+pub(crate) struct Fragment<'msg> {
+    pub(crate) stream: u32,
+    pub(crate) seq: u32,
+    pub(crate) last: bool,
+    pub(crate) payload: &'msg [u8],
+}
+
+pub(crate) struct Reassembly {
+    max: usize,
+    partial: HashMap<u32, (u32, Vec<u8>)>,
+    done: VecDeque<Vec<u8>>,
+    buffered: usize,
+}
+
+pub(crate) struct Inbox {
+    pub(crate) reassembly: Reassembly,
+    pub(crate) flow: Flow,
+    pongs: VecDeque<u64>,
+    nonce: u64,
+    ping: Option<(u64, Instant)>,
+    rtt: Option<Duration>,
+    customs: VecDeque<Custom>,
+    pub(crate) compression: Compression,
+    inflated: Option<Vec<u8>>,
+    backlog: VecDeque<Vec<u8>>,
+    rekeys: u64,
+}
+
+// ======================================== impl Control ======================================== \\
+
+impl<'msg> Control<'msg> {
+    // ====================================== Encoding ====================================== \\
+
+    pub(crate) fn encode(&self, msg: &mut Vec<u8>) {
+        msg.clear();
+
+        match self {
+            Control::Rekey => msg.push(REKEY),
+            Control::Fragment(fragment) => {
+                msg.push(FRAGMENT);
+                msg.extend_from_slice(&fragment.stream.to_le_bytes());
+                msg.extend_from_slice(&fragment.seq.to_le_bytes());
+                msg.push(fragment.last as u8);
+                msg.extend_from_slice(fragment.payload);
+            }
+            Control::Ping(nonce) => {
+                msg.push(PING);
+                msg.extend_from_slice(&nonce.to_le_bytes());
+            }
+            Control::Pong(nonce) => {
+                msg.push(PONG);
+                msg.extend_from_slice(&nonce.to_le_bytes());
+            }
+            Control::Close(code, message) => {
+                msg.push(CLOSE);
+                msg.extend_from_slice(&code.to_le_bytes());
+                msg.extend_from_slice(message.as_bytes());
+            }
+            Control::Custom(id, payload) => {
+                msg.push(CUSTOM);
+                msg.extend_from_slice(&id.to_le_bytes());
+                msg.extend_from_slice(payload);
+            }
+            Control::Window(credit) => {
+                msg.push(WINDOW);
+                msg.extend_from_slice(&credit.to_le_bytes());
+            }
+            Control::Compressed(algorithm, payload) => {
+                msg.push(COMPRESSED);
+                msg.push(*algorithm);
+                msg.extend_from_slice(payload);
+            }
+            Control::Resize(window) => {
+                msg.push(RESIZE);
+                msg.extend_from_slice(&window.to_le_bytes());
+            }
+        }
+    }
+
+    // ====================================== Decoding ====================================== \\
+
+    pub(crate) fn decode(msg: &'msg [u8]) -> Result<Self> {
+        match msg.first() {
+            Some(&REKEY) if msg.len() == 1 => Ok(Control::Rekey),
+            Some(&FRAGMENT) if msg.len() >= FRAGMENT_OVERHEAD => Ok(Control::Fragment(Fragment {
+                stream: u32::from_le_bytes([msg[1], msg[2], msg[3], msg[4]]),
+                seq: u32::from_le_bytes([msg[5], msg[6], msg[7], msg[8]]),
+                last: msg[9] != 0,
+                payload: &msg[FRAGMENT_OVERHEAD..],
+            })),
+            Some(&PING) if msg.len() == 9 => Ok(Control::Ping(u64::from_le_bytes(
+                msg[1..].try_into().unwrap(),
+            ))),
+            Some(&PONG) if msg.len() == 9 => Ok(Control::Pong(u64::from_le_bytes(
+                msg[1..].try_into().unwrap(),
+            ))),
+            Some(&CLOSE) if msg.len() >= 3 => match str::from_utf8(&msg[3..]) {
+                Ok(message) => Ok(Control::Close(
+                    u16::from_le_bytes([msg[1], msg[2]]),
+                    message,
+                )),
+                Err(_) => Err(Error::InvalidControl),
+            },
+            Some(&CUSTOM) if msg.len() >= 3 => Ok(Control::Custom(
+                u16::from_le_bytes([msg[1], msg[2]]),
+                &msg[3..],
+            )),
+            Some(&WINDOW) if msg.len() == 5 => Ok(Control::Window(u32::from_le_bytes(
+                msg[1..].try_into().unwrap(),
+            ))),
+            Some(&COMPRESSED) if msg.len() >= COMPRESSED_OVERHEAD => {
+                Ok(Control::Compressed(msg[1], &msg[COMPRESSED_OVERHEAD..]))
+            }
+            Some(&RESIZE) if msg.len() == 5 => Ok(Control::Resize(u32::from_le_bytes(
+                msg[1..].try_into().unwrap(),
+            ))),
+            _ => Err(Error::InvalidControl),
+        }
+    }
+}
+
+// ======================================= impl Reassembly ====================================== \\
+
+impl Reassembly {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new(max: usize) -> Self {
+        Reassembly {
+            max,
+            partial: HashMap::new(),
+            done: VecDeque::new(),
+            buffered: 0,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub(crate) fn max(&self) -> usize {
+        self.max
+    }
+
+    #[inline]
+    pub(crate) fn has_done(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub(crate) fn set_max(&mut self, max: usize) {
+        self.max = max;
+    }
+
+    #[inline]
+    pub(crate) fn pop(&mut self) -> Option<Vec<u8>> {
+        let data = self.done.pop_front()?;
+        self.buffered -= data.len();
+
+        Some(data)
+    }
+
+    pub(crate) fn feed(&mut self, fragment: Fragment) -> Result<()> {
+        if !self.partial.contains_key(&fragment.stream)
+            && self.partial.len() + self.done.len() >= MAX_STREAMS
+        {
+            return Err(self.full());
+        }
+
+        let (seq, data) = self
+            .partial
+            .entry(fragment.stream)
+            .or_insert_with(|| (0, Vec::new()));
+
+        if fragment.seq != *seq {
+            self.discard(fragment.stream);
+            return Err(Error::InvalidControl);
+        }
+
+        let len = data.len() + fragment.payload.len();
+        if len > self.max {
+            self.discard(fragment.stream);
+            return Err(Error::MessageSize {
+                max: self.max,
+                actual: len,
+            });
+        } else if self.buffered + fragment.payload.len() > self.max {
+            self.discard(fragment.stream);
+            return Err(self.full());
+        }
+
+        data.extend_from_slice(fragment.payload);
+        *seq += 1;
+        self.buffered += fragment.payload.len();
+
+        if fragment.last {
+            if let Some((_, data)) = self.partial.remove(&fragment.stream) {
+                self.done.push_back(data);
+            }
+        }
+
+        Ok(())
+    }
+
+    // ======================================= Helpers ====================================== \
+
+    #[inline]
+    fn full(&self) -> Error {
+        Error::ReassemblyFull {
+            streams: self.partial.len() + self.done.len(),
+            bytes: self.buffered,
+        }
+    }
+
+    #[inline]
+    fn discard(&mut self, stream: u32) {
+        if let Some((_, data)) = self.partial.remove(&stream) {
+            self.buffered -= data.len();
+        }
+    }
+}
+
+// ========================================= impl Inbox ========================================= \\
+
+impl Inbox {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new(max: usize) -> Self {
+        Inbox {
+            reassembly: Reassembly::new(max),
+            flow: Flow::new(),
+            pongs: VecDeque::new(),
+            nonce: 0,
+            ping: None,
+            rtt: None,
+            customs: VecDeque::new(),
+            compression: Compression::None,
+            inflated: None,
+            backlog: VecDeque::new(),
+            rekeys: 0,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub(crate) fn rtt(&self) -> Option<Duration> {
+        self.rtt
+    }
+
+    #[inline]
+    pub(crate) fn rekeys(&self) -> u64 {
+        self.rekeys
+    }
+
+    #[inline]
+    pub(crate) fn has_pongs(&self) -> bool {
+        !self.pongs.is_empty()
+    }
+
+    #[inline]
+    pub(crate) fn has_customs(&self) -> bool {
+        !self.customs.is_empty()
+    }
+
+    #[inline]
+    pub(crate) fn has_inflated(&self) -> bool {
+        self.inflated.is_some()
+    }
+
+    #[inline]
+    pub(crate) fn has_backlog(&self) -> bool {
+        !self.backlog.is_empty()
+    }
+
+    #[inline]
+    pub(crate) fn is_pinging(&self, nonce: u64) -> bool {
+        matches!(self.ping, Some((ping, _)) if ping == nonce)
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub(crate) fn next_nonce(&mut self) -> u64 {
+        self.nonce = self.nonce.wrapping_add(1);
+        self.nonce
+    }
+
+    #[inline]
+    pub(crate) fn start_ping(&mut self, nonce: u64) {
+        self.ping = Some((nonce, Instant::now()));
+    }
+
+    #[inline]
+    pub(crate) fn pop_pong(&mut self) -> Option<u64> {
+        self.pongs.pop_front()
+    }
+
+    #[inline]
+    pub(crate) fn pop_custom(&mut self) -> Option<Custom> {
+        self.customs.pop_front()
+    }
+
+    #[inline]
+    pub(crate) fn take_inflated(&mut self) -> Option<Vec<u8>> {
+        self.inflated.take()
+    }
+
+    // Queues a packet which was received while waiting for something else, for the next receive
+    // to return. It gets counted as received once it is.
+    pub(crate) fn defer(&mut self, msg: &[u8]) -> Result<()> {
+        if self.backlog.len() >= MAX_BACKLOG {
+            return Err(Error::BacklogFull(MAX_BACKLOG));
+        }
+
+        self.backlog.push_back(msg.to_vec());
+
+        Ok(())
+    }
+
+    #[inline]
+    pub(crate) fn pop_backlog(&mut self) -> Option<Vec<u8>> {
+        self.backlog.pop_front()
+    }
+
+    // Returns whether `msg` carried data for the application, so that it gets counted as received.
+    pub(crate) fn handle(&mut self, msg: &[u8], state: &mut TransportState) -> Result<bool> {
+        match Control::decode(msg)? {
+            Control::Rekey => {
+                state.rekey_incoming();
+                self.rekeys += 1;
+            }
+            Control::Fragment(fragment) => {
+                self.flow.received(msg.len());
+                self.reassembly.feed(fragment)?;
+
+                return Ok(true);
+            }
+            Control::Ping(nonce) => {
+                if self.pongs.len() < MAX_PONGS {
+                    self.pongs.push_back(nonce);
+                }
+            }
+            Control::Pong(nonce) => {
+                if let Some((ping, sent)) = self.ping {
+                    if ping == nonce {
+                        self.rtt = Some(sent.elapsed());
+                        self.ping = None;
+                    }
+                }
+            }
+            Control::Close(code, message) => {
+                return Err(Error::Closed(Reason::from_parts(code, message)));
+            }
+            Control::Custom(id, payload) => match Custom::new(id, payload) {
+                Ok(custom) => {
+                    self.flow.received(msg.len());
+                    self.customs.push_back(custom);
+
+                    return Ok(true);
+                }
+                Err(_) => return Err(Error::InvalidControl),
+            },
+            Control::Window(credit) => self.flow.granted(credit),
+            Control::Compressed(algorithm, payload) => match Compression::from_id(algorithm) {
+                Some(compression)
+                    if compression == self.compression && compression != Compression::None =>
+                {
+                    self.flow.received(msg.len());
+                    self.inflated = Some(compression.decompress(payload, MSG_MAX_LEN)?);
+                }
+                _ => return Err(Error::InvalidControl),
+            },
+            Control::Resize(window) => self.flow.resized(window),
+        }
+
+        Ok(false)
+    }
+}
@@ -0,0 +1,170 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::control::Control;
+use crate::{Error, Protocol, Result, Session, Write};
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::AsyncWrite;
+use snow::TransportState;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Custom {
+    id: u16,
+    payload: Vec<u8>,
+}
+
+pub struct SendCustom<'proto, Output> {
+    inner: SendCustomInner<'proto, Output>,
+}
+
+enum SendCustomInner<'proto, Output> {
+    Empty,
+    Credit {
+        buf: &'proto mut Vec<u8>,
+        msg: &'proto mut Vec<u8>,
+        state: &'proto mut TransportState,
+        session: &'proto mut Session,
+        out: Output,
+    },
+    Write {
+        write: Write<Output, &'proto mut TransportState, &'proto mut Vec<u8>>,
+        session: &'proto mut Session,
+    },
+}
+
+// ========================================= impl Custom ======================================== \\
+
+impl Custom {
+    // ====================================== Constants ===================================== \\
+
+    pub const RESERVED: u16 = 0x0100;
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new<Payload: Into<Vec<u8>>>(id: u16, payload: Payload) -> Result<Self> {
+        if Self::is_reserved(id) {
+            return Err(Error::ReservedPacketId(id));
+        }
+
+        Ok(Custom {
+            id,
+            payload: payload.into(),
+        })
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn is_reserved(id: u16) -> bool {
+        id < Self::RESERVED
+    }
+
+    #[inline]
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+
+    #[inline]
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn into_payload(self) -> Vec<u8> {
+        self.payload
+    }
+}
+
+// ======================================= impl SendCustom ====================================== \\
+
+impl<'proto, Output> SendCustom<'proto, Output> {
+    // ==================================== Constructors ==================================== \\
+
+    pub(super) fn new(custom: &Custom, proto: &'proto mut Protocol, out: Output) -> Self
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        Control::Custom(custom.id, &custom.payload).encode(&mut proto.msg);
+
+        SendCustom {
+            inner: SendCustomInner::Credit {
+                buf: &mut proto.buf,
+                msg: &mut proto.msg,
+                state: &mut proto.state,
+                session: &mut proto.session,
+                out,
+            },
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Output> Future for SendCustom<'_, Output>
+where
+    Output: AsyncWrite + Unpin,
+{
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let inner = &mut self.get_mut().inner;
+        loop {
+            match mem::take(inner) {
+                SendCustomInner::Empty => panic!(),
+                SendCustomInner::Credit {
+                    buf,
+                    msg,
+                    state,
+                    session,
+                    out,
+                } => {
+                    session.inbox.flow.check(msg.len())?;
+
+                    *inner = SendCustomInner::Write {
+                        write: Write::control(msg, buf, out, state, session.padding, session.wire),
+                        session,
+                    };
+                }
+                SendCustomInner::Write { mut write, session } => {
+                    if let Poll::Ready(wrote) = Pin::new(&mut write).poll(ctx)? {
+                        let (msg, _, _, _) = write.done();
+                        session.stats.sent(msg.len());
+                        session.inbox.flow.sent(msg.len());
+                        session.rekeyer.record(msg.len());
+                        session.liveness.sent();
+
+                        return Poll::Ready(Ok(wrote));
+                    } else {
+                        *inner = SendCustomInner::Write { write, session };
+
+                        return Poll::Pending;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<Output> Default for SendCustomInner<'_, Output> {
+    #[inline]
+    fn default() -> Self {
+        SendCustomInner::Empty
+    }
+}
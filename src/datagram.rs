@@ -0,0 +1,249 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// Each datagram carries exactly one packet, without any length prefix. The nonce is sent
+// explicitly so that reordered datagrams can still be decrypted, and a sliding window of recently
+// seen nonces is used to drop replays:
+//
+// DATAGRAM ;; nonce(8) + ciphertext
+//
+// Datagrams are never fragmented: a packet whose datagram would exceed the configured MTU is
+// rejected before anything is sent.
+
+// =========================================== Imports ========================================== \\
+
+use crate::unknown;
+use crate::{send, Error, RecvFrom, Result, SendTo};
+use core::task::{Context, Poll};
+use packets::{Packet, MSG_MAX_LEN, NOISE_MAX_LEN, NOISE_OVERHEAD};
+use snow::StatelessTransportState;
+use std::io;
+use std::net::SocketAddr;
+
+// ============================================ Types =========================================== \\
+
+pub struct DatagramProtocol {
+    buf: Vec<u8>,
+    msg: Vec<u8>,
+    state: StatelessTransportState,
+    nonce: u64,
+    mtu: usize,
+    replay: Replay,
+}
+
+struct Replay {
+    max: Option<u64>,
+    seen: u64,
+}
+
+// ========================================= Interfaces ========================================= \\
+
+pub trait DatagramSocket {
+    fn poll_send_to(
+        &self,
+        ctx: &mut Context,
+        buf: &[u8],
+        addr: SocketAddr,
+    ) -> Poll<io::Result<usize>>;
+
+    fn poll_recv_from(
+        &self,
+        ctx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<(usize, SocketAddr)>>;
+}
+
+// ==================================== impl DatagramProtocol =================================== \\
+
+impl DatagramProtocol {
+    // ====================================== Constants ===================================== \\
+
+    pub const NONCE_LEN: usize = 8;
+    pub const MAX_LEN: usize = Self::NONCE_LEN + NOISE_MAX_LEN;
+    pub const REPLAY_WINDOW: u64 = 64;
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new(state: StatelessTransportState) -> Self {
+        DatagramProtocol {
+            buf: vec![0; Self::MAX_LEN],
+            msg: vec![0; MSG_MAX_LEN],
+            state,
+            nonce: 0,
+            mtu: Self::MAX_LEN,
+            replay: Replay::new(),
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn next_nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    #[inline]
+    pub fn max_received_nonce(&self) -> Option<u64> {
+        self.replay.max
+    }
+
+    #[inline]
+    pub fn mtu(&self) -> usize {
+        self.mtu
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub fn set_mtu(&mut self, mtu: usize) {
+        self.mtu = mtu.min(Self::MAX_LEN);
+    }
+
+    #[inline]
+    pub fn send_to<'sock, Socket>(
+        &mut self,
+        socket: &'sock Socket,
+        addr: SocketAddr,
+        packet: Packet,
+    ) -> SendTo<'_, 'sock, Socket>
+    where
+        Socket: DatagramSocket,
+    {
+        SendTo::new(packet, addr, self, socket)
+    }
+
+    #[inline]
+    pub fn recv_from<'sock, Socket>(&mut self, socket: &'sock Socket) -> RecvFrom<'_, 'sock, Socket>
+    where
+        Socket: DatagramSocket,
+    {
+        RecvFrom::new(self, socket)
+    }
+
+    pub(crate) fn encrypt(&mut self, packet: Packet) -> Result<usize> {
+        self.buf.resize(Self::MAX_LEN, 0);
+
+        let bytes = send::encode(&packet, &mut self.msg)?;
+        let nonce = self.nonce;
+        let len = self.state.write_message(
+            nonce,
+            &self.msg[..bytes],
+            &mut self.buf[Self::NONCE_LEN..],
+        )?;
+
+        let len = Self::NONCE_LEN + len;
+        if len > self.mtu {
+            return Err(Error::ExceedsMtu { mtu: self.mtu, len });
+        }
+
+        #[cfg(feature = "audit")]
+        debug_assert!(self.nonce < u64::MAX, "sending nonce overflowed");
+
+        self.nonce += 1;
+        Self::encode_nonce(&mut self.buf, nonce);
+
+        Ok(len)
+    }
+
+    pub(crate) fn decrypt(&mut self, len: usize) -> Result<Option<Packet>> {
+        if len < Self::NONCE_LEN + NOISE_OVERHEAD || len > Self::MAX_LEN {
+            return Ok(None);
+        }
+
+        let nonce = Self::decode_nonce(&self.buf);
+        if !self.replay.check(nonce) {
+            return Ok(None);
+        }
+
+        self.msg.resize(MSG_MAX_LEN, 0);
+        let buf = &self.buf[Self::NONCE_LEN..len];
+        let len = match self.state.read_message(nonce, buf, &mut self.msg) {
+            Ok(len) => len,
+            Err(_) => return Ok(None),
+        };
+
+        self.replay.accept(nonce);
+
+        Ok(Some(unknown::decode(&self.msg[..len])?))
+    }
+
+    #[inline]
+    pub(crate) fn buf(&self) -> &[u8] {
+        &self.buf
+    }
+
+    #[inline]
+    pub(crate) fn buf_mut(&mut self) -> &mut [u8] {
+        self.buf.resize(Self::MAX_LEN, 0);
+        &mut self.buf
+    }
+
+    // ======================================= Helpers ====================================== \
+
+    #[inline]
+    pub(crate) fn encode_nonce(buf: &mut [u8], nonce: u64) {
+        buf[..Self::NONCE_LEN].copy_from_slice(&nonce.to_le_bytes());
+    }
+
+    #[inline]
+    pub(crate) fn decode_nonce(buf: &[u8]) -> u64 {
+        let mut nonce = [0; Self::NONCE_LEN];
+        nonce.copy_from_slice(&buf[..Self::NONCE_LEN]);
+
+        u64::from_le_bytes(nonce)
+    }
+}
+
+// ========================================= impl Replay ======================================== \\
+
+impl Replay {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    fn new() -> Self {
+        Replay { max: None, seen: 0 }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    fn check(&self, nonce: u64) -> bool {
+        match self.max {
+            None => true,
+            Some(max) if nonce > max => true,
+            Some(max) if max - nonce >= DatagramProtocol::REPLAY_WINDOW => false,
+            Some(max) => self.seen & (1 << (max - nonce)) == 0,
+        }
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    fn accept(&mut self, nonce: u64) {
+        #[cfg(feature = "audit")]
+        let max = self.max;
+        #[cfg(feature = "audit")]
+        debug_assert!(self.check(nonce), "accepted a replayed nonce");
+
+        match self.max {
+            Some(max) if nonce <= max => self.seen |= 1 << (max - nonce),
+            Some(max) if nonce - max < DatagramProtocol::REPLAY_WINDOW => {
+                self.seen = (self.seen << (nonce - max)) | 1;
+                self.max = Some(nonce);
+            }
+            _ => {
+                self.seen = 1;
+                self.max = Some(nonce);
+            }
+        }
+
+        #[cfg(feature = "audit")]
+        debug_assert!(self.max >= max, "received nonce went backwards");
+    }
+}
@@ -0,0 +1,247 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// A dispatcher routes received packets by id, either to a handler or to a subscription. Packets
+// without a route are handed back to the caller. A subscription whose receiving half was dropped
+// is unrouted the next time a packet is dispatched to it.
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Protocol, Recv, Result};
+use core::fmt::{self, Debug, Formatter};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use futures_core::Stream;
+use futures_io::AsyncRead;
+use packets::Packet;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+// ============================================ Types =========================================== \\
+
+#[derive(Default)]
+pub struct Dispatcher {
+    routes: HashMap<u16, Route>,
+}
+
+enum Route {
+    Handler(Box<dyn FnMut(Packet) + Send>),
+    Channel(Arc<Mutex<Channel>>),
+}
+
+pub struct Subscription {
+    id: u16,
+    channel: Arc<Mutex<Channel>>,
+}
+
+#[derive(Default)]
+struct Channel {
+    queue: VecDeque<Packet>,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+pub struct Dispatch<'proto, 'disp, Input> {
+    recv: Recv<'proto, Input>,
+    dispatcher: &'disp mut Dispatcher,
+}
+
+// ======================================= impl Dispatcher ====================================== \\
+
+impl Dispatcher {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new() -> Self {
+        Dispatcher::default()
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn is_routed(&self, id: u16) -> bool {
+        self.routes.contains_key(&id)
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub fn on<Handle>(&mut self, id: u16, handle: Handle)
+    where
+        Handle: FnMut(Packet) + Send + 'static,
+    {
+        self.insert(id, Route::Handler(Box::new(handle)));
+    }
+
+    pub fn subscribe(&mut self, id: u16) -> Subscription {
+        let channel = Arc::new(Mutex::new(Channel::default()));
+        self.insert(id, Route::Channel(channel.clone()));
+
+        Subscription { id, channel }
+    }
+
+    #[inline]
+    pub fn remove(&mut self, id: u16) -> bool {
+        match self.routes.remove(&id) {
+            Some(route) => {
+                route.close();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn dispatch(&mut self, id: u16, packet: Packet) -> Option<Packet> {
+        let route = match self.routes.get_mut(&id) {
+            Some(route) => route,
+            None => return Some(packet),
+        };
+
+        match route {
+            Route::Handler(handle) => handle(packet),
+            Route::Channel(channel) if Arc::strong_count(channel) == 1 => {
+                self.routes.remove(&id);
+                return Some(packet);
+            }
+            Route::Channel(channel) => {
+                let mut channel = channel.lock().unwrap();
+                channel.queue.push_back(packet);
+
+                if let Some(waker) = channel.waker.take() {
+                    waker.wake();
+                }
+            }
+        }
+
+        None
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    #[inline]
+    fn insert(&mut self, id: u16, route: Route) {
+        if let Some(route) = self.routes.insert(id, route) {
+            route.close();
+        }
+    }
+}
+
+// ========================================= impl Route ========================================= \\
+
+impl Route {
+    // ===================================== Destructors ==================================== \\
+
+    fn close(self) {
+        if let Route::Channel(channel) = self {
+            let mut channel = channel.lock().unwrap();
+            channel.closed = true;
+
+            if let Some(waker) = channel.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+// ====================================== impl Subscription ===================================== \\
+
+impl Subscription {
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub fn try_recv(&self) -> Option<Packet> {
+        self.channel.lock().unwrap().queue.pop_front()
+    }
+}
+
+// ======================================== impl Dispatch ======================================= \\
+
+impl<'proto, 'disp, Input> Dispatch<'proto, 'disp, Input> {
+    // ==================================== Constructors ==================================== \\
+
+    pub(super) fn new(
+        dispatcher: &'disp mut Dispatcher,
+        proto: &'proto mut Protocol,
+        inp: Input,
+    ) -> Self
+    where
+        Input: AsyncRead + Unpin,
+    {
+        Dispatch {
+            recv: Recv::new(proto, inp),
+            dispatcher,
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Input> Future for Dispatch<'_, '_, Input>
+where
+    Input: AsyncRead + Unpin,
+{
+    type Output = Result<Option<Packet>>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match this.recv.poll_with_id(ctx)? {
+            Poll::Ready((id, packet)) => Poll::Ready(Ok(this.dispatcher.dispatch(id, packet))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+// ========================================= impl Stream ======================================== \\
+
+impl Stream for Subscription {
+    type Item = Packet;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<Self::Item>> {
+        let mut channel = self.channel.lock().unwrap();
+        if let Some(packet) = channel.queue.pop_front() {
+            Poll::Ready(Some(packet))
+        } else if channel.closed {
+            Poll::Ready(None)
+        } else {
+            channel.waker = Some(ctx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+// ========================================== impl Drop ========================================= \\
+
+impl Drop for Dispatcher {
+    fn drop(&mut self) {
+        for (_, route) in self.routes.drain() {
+            route.close();
+        }
+    }
+}
+
+// ========================================= impl Debug ========================================= \\
+
+impl Debug for Dispatcher {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_set().entries(self.routes.keys()).finish()
+    }
+}
+
+impl Debug for Subscription {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Subscription").field("id", &self.id).finish()
+    }
+}
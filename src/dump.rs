@@ -0,0 +1,110 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// A `StateDump` is a snapshot of the state machine of a session or of one of its futures, meant
+// to debug stuck futures. It never contains any key material, only the name of the current state,
+// the number of bytes buffered by it and, once the handshake is done, the nonce counters.
+
+// =========================================== Imports ========================================== \\
+
+use core::fmt::{self, Debug, Display, Formatter};
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct StateDump {
+    kind: &'static str,
+    state: &'static str,
+    buffered: usize,
+    nonces: Option<(u64, u64)>,
+}
+
+// ======================================= impl StateDump ======================================= \\
+
+impl StateDump {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new(kind: &'static str, state: &'static str) -> Self {
+        StateDump {
+            kind,
+            state,
+            buffered: 0,
+            nonces: None,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn with_buffered(mut self, buffered: usize) -> Self {
+        self.buffered = buffered;
+        self
+    }
+
+    #[inline]
+    pub(crate) fn with_nonces(mut self, nonces: Option<(u64, u64)>) -> Self {
+        self.nonces = nonces;
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    #[inline]
+    pub fn state(&self) -> &'static str {
+        self.state
+    }
+
+    #[inline]
+    pub fn buffered(&self) -> usize {
+        self.buffered
+    }
+
+    #[inline]
+    pub fn sending_nonce(&self) -> Option<u64> {
+        self.nonces.map(|(sending, _)| sending)
+    }
+
+    #[inline]
+    pub fn receiving_nonce(&self) -> Option<u64> {
+        self.nonces.map(|(_, receiving)| receiving)
+    }
+}
+
+// ========================================= impl Debug ========================================= \\
+
+impl Debug for StateDump {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct(self.kind)
+            .field("state", &self.state)
+            .field("buffered", &self.buffered)
+            .field("sending_nonce", &self.sending_nonce())
+            .field("receiving_nonce", &self.receiving_nonce())
+            .finish()
+    }
+}
+
+// ======================================== impl Display ======================================== \\
+
+impl Display for StateDump {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "{}({}, buffered={}",
+            self.kind, self.state, self.buffered
+        )?;
+        if let Some((sending, receiving)) = self.nonces {
+            write!(fmt, ", nonces={}/{}", sending, receiving)?;
+        }
+
+        write!(fmt, ")")
+    }
+}
@@ -0,0 +1,97 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::{AsyncRead, AsyncWrite};
+use std::io;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Debug)]
+pub struct Duplex<Input, Output> {
+    inp: Input,
+    out: Output,
+}
+
+#[cfg(feature = "quic")]
+pub type QuicStream = Duplex<quinn::RecvStream, quinn::SendStream>;
+
+// ========================================= impl Duplex ======================================== \\
+
+impl<Input, Output> Duplex<Input, Output> {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new(inp: Input, out: Output) -> Self {
+        Duplex { inp, out }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn input(&self) -> &Input {
+        &self.inp
+    }
+
+    #[inline]
+    pub fn output(&self) -> &Output {
+        &self.out
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn into_inner(self) -> (Input, Output) {
+        (self.inp, self.out)
+    }
+}
+
+// ========================================== impl From ========================================= \\
+
+#[cfg(feature = "quic")]
+impl From<(quinn::SendStream, quinn::RecvStream)> for QuicStream {
+    #[inline]
+    fn from((out, inp): (quinn::SendStream, quinn::RecvStream)) -> Self {
+        Duplex::new(inp, out)
+    }
+}
+
+// ======================================= impl AsyncRead ======================================= \\
+
+impl<Input: AsyncRead + Unpin, Output: Unpin> AsyncRead for Duplex<Input, Output> {
+    #[inline]
+    fn poll_read(
+        self: Pin<&mut Self>,
+        ctx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inp).poll_read(ctx, buf)
+    }
+}
+
+// ======================================= impl AsyncWrite ====================================== \\
+
+impl<Input: Unpin, Output: AsyncWrite + Unpin> AsyncWrite for Duplex<Input, Output> {
+    #[inline]
+    fn poll_write(self: Pin<&mut Self>, ctx: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().out).poll_write(ctx, buf)
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().out).poll_flush(ctx)
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().out).poll_close(ctx)
+    }
+}
@@ -0,0 +1,229 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::control::{Control, COMPRESSED_OVERHEAD};
+use crate::{send, wipe, write, Error, Protocol, Result, WireFormat};
+use packets::{Packet, MSG_MAX_LEN, RAW_MAX_LEN};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+// ============================================ Types =========================================== \\
+
+pub struct Engine {
+    pending: Vec<u8>,
+    msg: Vec<u8>,
+    escaped: bool,
+    proto: Protocol,
+}
+
+// ========================================= impl Engine ======================================== \\
+
+impl Engine {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new(proto: Protocol) -> Self {
+        Engine {
+            pending: Vec::new(),
+            msg: vec![0; MSG_MAX_LEN],
+            escaped: false,
+            proto,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn is_initiator(&self) -> bool {
+        self.proto.state.is_initiator()
+    }
+
+    #[inline]
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    pub fn write_packet(&mut self, packet: Packet, out: &mut Vec<u8>) -> Result<usize> {
+        let bytes = send::encode(&packet, &mut self.msg)?;
+
+        // Nothing gets written if the packet doesn't fit in the peer's window.
+        let compressed = self.proto.session.compress(&self.msg)?;
+        let len = compressed
+            .as_ref()
+            .map_or(bytes, |payload| payload.len() + COMPRESSED_OVERHEAD);
+        self.proto.session.inbox.flow.check(len)?;
+
+        let start = out.len();
+        let mut off = start;
+        let mut ctl = Vec::new();
+
+        while let Some(nonce) = self.proto.session.inbox.pop_pong() {
+            Control::Pong(nonce).encode(&mut ctl);
+            off = self.control(&ctl, out, off)?;
+        }
+
+        if self.proto.session.rekeyer.is_due() {
+            Control::Rekey.encode(&mut ctl);
+            off = self.control(&ctl, out, off)?;
+
+            self.proto.state.rekey_outgoing();
+            self.proto.session.rekeyer.reset();
+        }
+
+        if let Some(window) = self.proto.session.inbox.flow.take_update() {
+            Control::Resize(window).encode(&mut ctl);
+            off = self.control(&ctl, out, off)?;
+        }
+
+        if let Some(grant) = self.proto.session.inbox.flow.take_grant() {
+            Control::Window(grant).encode(&mut ctl);
+            off = self.control(&ctl, out, off)?;
+        }
+
+        if let Some(payload) = compressed {
+            let id = self.proto.session.inbox.compression.id();
+            Control::Compressed(id, &payload).encode(&mut ctl);
+            off = self.control(&ctl, out, off)?;
+        } else {
+            off = write::append(
+                &mut self.proto.state,
+                &self.msg,
+                out,
+                off,
+                self.proto.session.padding,
+                self.proto.session.wire,
+            )?;
+        }
+
+        out.truncate(off);
+
+        self.proto.session.packet_sent(&self.msg);
+        self.proto.session.inbox.flow.sent(len);
+        self.proto.session.rekeyer.record(bytes);
+        self.proto.session.liveness.sent();
+
+        Ok(off - start)
+    }
+
+    pub fn read(&mut self, bytes: &[u8]) -> Result<Vec<Packet>> {
+        self.pending.extend_from_slice(bytes);
+
+        let wire = self.proto.session.wire;
+        let hdr = wire.header_len();
+
+        let mut packets = Vec::new();
+        while let Some(mut bytes) = self.proto.session.inbox.pop_backlog() {
+            self.proto.session.packet_received(&bytes)?;
+            if let Some(packet) = self.proto.session.unknown.decode(&bytes)? {
+                packets.push(packet);
+            }
+
+            wipe(&mut bytes);
+        }
+
+        let mut off = 0;
+        while self.pending.len() - off >= hdr {
+            let (len, _) = wire.decode_header(&self.pending[off..]);
+            let checksum = wire.decode_checksum(&self.pending[off..]);
+            if len > RAW_MAX_LEN {
+                return Err(Error::MessageSize {
+                    max: RAW_MAX_LEN,
+                    actual: len,
+                });
+            } else if self.pending.len() - off - hdr < len {
+                break;
+            }
+
+            self.msg.resize(MSG_MAX_LEN, 0);
+
+            let buf = &self.pending[off + hdr..off + hdr + len];
+            WireFormat::verify(checksum, buf)?;
+
+            let len = self.proto.state.read_message(buf, &mut self.msg)?;
+            let len = self.proto.session.padding.unpad(&self.msg[..len])?;
+            off += hdr + buf.len();
+
+            self.proto.session.liveness.received();
+            if self.escaped {
+                self.escaped = false;
+                self.proto
+                    .session
+                    .handle(&self.msg[..len], &mut self.proto.state)?;
+
+                if let Some(mut bytes) = self.proto.session.inbox.take_inflated() {
+                    self.proto.session.packet_received(&bytes)?;
+                    if let Some(packet) = self.proto.session.unknown.decode(&bytes)? {
+                        packets.push(packet);
+                    }
+
+                    wipe(&mut bytes);
+                }
+            } else if len == 0 {
+                self.escaped = true;
+            } else {
+                self.proto.session.inbox.flow.received(len);
+                self.proto.session.packet_received(&self.msg[..len])?;
+                if let Some(packet) = self.proto.session.unknown.decode(&self.msg[..len])? {
+                    packets.push(packet);
+                }
+
+                wipe(&mut self.msg[..len]);
+            }
+        }
+
+        self.pending.drain(..off);
+
+        Ok(packets)
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    fn control(&mut self, ctl: &[u8], out: &mut Vec<u8>, len: usize) -> Result<usize> {
+        let len = write::append(
+            &mut self.proto.state,
+            &[],
+            out,
+            len,
+            self.proto.session.padding,
+            self.proto.session.wire,
+        )?;
+
+        write::append(
+            &mut self.proto.state,
+            ctl,
+            out,
+            len,
+            self.proto.session.padding,
+            self.proto.session.wire,
+        )
+    }
+}
+
+// ========================================== impl From ========================================= \\
+
+impl From<Protocol> for Engine {
+    #[inline]
+    fn from(proto: Protocol) -> Self {
+        proto.into_engine()
+    }
+}
+
+// ========================================== impl Drop ========================================= \\
+
+#[cfg(feature = "zeroize")]
+impl Drop for Engine {
+    #[inline]
+    fn drop(&mut self) {
+        self.pending.zeroize();
+        self.msg.zeroize();
+    }
+}
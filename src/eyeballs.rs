@@ -0,0 +1,177 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Timer, Transport};
+use core::future::Future;
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use futures_io::{AsyncRead, AsyncWrite};
+use std::collections::VecDeque;
+use std::io;
+use std::net::SocketAddr;
+
+// ============================================ Types =========================================== \\
+
+pub struct HappyEyeballs<Dial, Tmr> {
+    addrs: Vec<SocketAddr>,
+    dial: Dial,
+    stagger: Duration,
+    _timer: PhantomData<fn() -> Tmr>,
+}
+
+pub struct Race<Fut, Tmr> {
+    pending: VecDeque<Pin<Box<Fut>>>,
+    running: Vec<Pin<Box<Fut>>>,
+    stagger: Duration,
+    timer: Option<Tmr>,
+    error: Option<io::Error>,
+}
+
+// ===================================== impl HappyEyeballs ===================================== \\
+
+impl<Dial, Tmr> HappyEyeballs<Dial, Tmr> {
+    // ==================================== Constructors ==================================== \\
+
+    pub(crate) fn new(addrs: &[SocketAddr], dial: Dial, stagger: Duration) -> Self {
+        // Alternates between address families, starting with the family of the first address,
+        // as recommended by RFC 8305.
+        let first = addrs.first().map(SocketAddr::is_ipv6).unwrap_or(true);
+        let mut preferred = addrs.iter().filter(|addr| addr.is_ipv6() == first);
+        let mut fallback = addrs.iter().filter(|addr| addr.is_ipv6() != first);
+
+        let mut sorted = Vec::with_capacity(addrs.len());
+        loop {
+            match (preferred.next(), fallback.next()) {
+                (None, None) => break,
+                (Some(addr), None) | (None, Some(addr)) => sorted.push(*addr),
+                (Some(preferred), Some(fallback)) => {
+                    sorted.push(*preferred);
+                    sorted.push(*fallback);
+                }
+            }
+        }
+
+        HappyEyeballs {
+            addrs: sorted,
+            dial,
+            stagger,
+            _timer: PhantomData,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn addrs(&self) -> &[SocketAddr] {
+        &self.addrs
+    }
+
+    #[inline]
+    pub fn stagger(&self) -> Duration {
+        self.stagger
+    }
+}
+
+// ======================================= impl Transport ======================================= \\
+
+impl<Dial, Fut, IO, Tmr> Transport for HappyEyeballs<Dial, Tmr>
+where
+    Dial: FnMut(SocketAddr) -> Fut,
+    Fut: Future<Output = io::Result<IO>>,
+    IO: AsyncRead + AsyncWrite + Unpin,
+    Tmr: Timer,
+{
+    type Io = IO;
+    type Dial = Race<Fut, Tmr>;
+
+    fn dial(&mut self) -> Self::Dial {
+        // Futures are lazy, so creating all of them upfront doesn't start any connection attempt
+        // before it is first polled.
+        let dial = &mut self.dial;
+        let pending = self
+            .addrs
+            .iter()
+            .map(|addr| Box::pin(dial(*addr)))
+            .collect();
+
+        Race {
+            pending,
+            running: Vec::new(),
+            stagger: self.stagger,
+            timer: None,
+            error: None,
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Fut, IO, Tmr> Future for Race<Fut, Tmr>
+where
+    Fut: Future<Output = io::Result<IO>>,
+    Tmr: Timer,
+{
+    type Output = io::Result<IO>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            let mut started = false;
+            let start = match &mut this.timer {
+                Some(timer) => Pin::new(timer).poll(ctx).is_ready(),
+                None => true,
+            };
+
+            if start {
+                if let Some(fut) = this.pending.pop_front() {
+                    this.running.push(fut);
+                    this.timer = Some(Tmr::after(this.stagger));
+                    started = true;
+                } else if this.running.is_empty() {
+                    let error = this.error.take();
+                    let error = error.unwrap_or_else(|| io::ErrorKind::InvalidInput.into());
+
+                    return Poll::Ready(Err(error));
+                } else {
+                    this.timer = None;
+                }
+            }
+
+            let mut failed = false;
+            let mut idx = 0;
+            while idx < this.running.len() {
+                match this.running[idx].as_mut().poll(ctx) {
+                    Poll::Ready(Ok(io)) => {
+                        this.pending.clear();
+                        this.running.clear();
+
+                        return Poll::Ready(Ok(io));
+                    }
+                    Poll::Ready(Err(error)) => {
+                        this.running.swap_remove(idx);
+                        this.error = Some(error);
+                        failed = true;
+                    }
+                    Poll::Pending => idx += 1,
+                }
+            }
+
+            // A failed attempt immediately starts the next one, and a new attempt comes with a new
+            // timer which needs to be polled at least once to be woken up later.
+            if failed {
+                this.timer = None;
+            } else if !started {
+                return Poll::Pending;
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// Each side announces how many bytes it is willing to buffer (its window, 0 meaning unlimited)
+// and grants them back as it consumes them. The peer's window bounds how many bytes can be in
+// flight: a message larger than it can only be sent once every byte of it was granted back.
+// Bytes which were in flight when the window changed get granted back on top of the new one.
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Error, Result};
+use core::cmp;
+use core::mem;
+
+// ============================================ Types =========================================== \\
+
+pub(crate) struct Flow {
+    window: Option<u32>,
+    update: bool,
+    consumed: u32,
+    grant: u32,
+    limit: Option<u32>,
+    credit: u64,
+}
+
+// ========================================== impl Flow ========================================= \\
+
+impl Flow {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Flow {
+            window: None,
+            update: false,
+            consumed: 0,
+            grant: 0,
+            limit: None,
+            credit: 0,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub(crate) fn window(&self) -> Option<u32> {
+        self.window
+    }
+
+    #[inline]
+    pub(crate) fn limit(&self) -> Option<u32> {
+        self.limit
+    }
+
+    #[inline]
+    pub(crate) fn credit(&self) -> Option<u64> {
+        self.limit.map(|_| self.credit)
+    }
+
+    #[inline]
+    pub(crate) fn can_send(&self, len: usize) -> bool {
+        match self.limit {
+            Some(limit) => self.credit >= cmp::min(len as u64, limit as u64),
+            None => true,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn check(&self, len: usize) -> Result<()> {
+        if self.can_send(len) {
+            Ok(())
+        } else {
+            Err(Error::WindowExhausted {
+                credit: self.credit,
+                len,
+            })
+        }
+    }
+
+    #[inline]
+    pub(crate) fn has_update(&self) -> bool {
+        self.update
+    }
+
+    #[inline]
+    pub(crate) fn has_grant(&self) -> bool {
+        self.grant > 0
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub(crate) fn set_window(&mut self, window: Option<u32>) {
+        self.window = window.filter(|&window| window > 0);
+        self.update = true;
+        self.consumed = 0;
+        self.grant = 0;
+    }
+
+    #[inline]
+    pub(crate) fn take_update(&mut self) -> Option<u32> {
+        if mem::take(&mut self.update) {
+            Some(self.window.unwrap_or(0))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub(crate) fn sent(&mut self, len: usize) {
+        if self.limit.is_some() {
+            self.credit = self.credit.saturating_sub(len as u64);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn received(&mut self, len: usize) {
+        if let Some(window) = self.window {
+            self.consumed = self.consumed.saturating_add(len as u32);
+            if self.consumed >= window / 2 {
+                self.grant = self.grant.saturating_add(self.consumed);
+                self.consumed = 0;
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn take_grant(&mut self) -> Option<u32> {
+        Some(mem::take(&mut self.grant)).filter(|&grant| grant > 0)
+    }
+
+    #[inline]
+    pub(crate) fn resized(&mut self, limit: u32) {
+        self.limit = Some(limit).filter(|&limit| limit > 0);
+        self.credit = limit as u64;
+    }
+
+    #[inline]
+    pub(crate) fn granted(&mut self, credit: u32) {
+        if self.limit.is_some() {
+            self.credit = self.credit.saturating_add(credit as u64);
+        }
+    }
+}
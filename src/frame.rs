@@ -0,0 +1,158 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// With the V2 and V3 wire formats (see wire.rs), the flags of a frame header are laid out as follows:
+//
+// bit 0     ;; COMPRESSED, set when the frame carries a compressed packet
+// bit 1     ;; FRAGMENT, set when the frame carries a fragment of a large message
+// bits 2..5 ;; priority, from 0 (lowest, the default) to 7
+// bits 5..  ;; reserved, always zero
+//
+// Flags are hints for the transport and the peer; they are not sent with the V1 wire format.
+
+// =========================================== Imports ========================================== \\
+
+use packets::Packet;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FrameFlags(u16);
+
+pub struct Frame {
+    packet: Packet,
+    flags: FrameFlags,
+}
+
+// ======================================= impl FrameFlags ====================================== \\
+
+impl FrameFlags {
+    // ====================================== Constants ===================================== \\
+
+    pub const COMPRESSED: u16 = 1 << 0;
+    pub const FRAGMENT: u16 = 1 << 1;
+    pub const PRIORITY: u16 = 0b111 << Self::PRIORITY_SHIFT;
+
+    pub const MAX_PRIORITY: u8 = 7;
+
+    const PRIORITY_SHIFT: u16 = 2;
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn from_bits(bits: u16) -> Self {
+        FrameFlags(bits)
+    }
+
+    #[inline]
+    pub fn with_compressed(self, compressed: bool) -> Self {
+        self.with(Self::COMPRESSED, compressed)
+    }
+
+    #[inline]
+    pub fn with_fragment(self, fragment: bool) -> Self {
+        self.with(Self::FRAGMENT, fragment)
+    }
+
+    #[inline]
+    pub fn with_priority(self, priority: u8) -> Self {
+        let priority = priority.min(Self::MAX_PRIORITY) as u16;
+        FrameFlags((self.0 & !Self::PRIORITY) | (priority << Self::PRIORITY_SHIFT))
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    #[inline]
+    pub fn is_compressed(self) -> bool {
+        self.0 & Self::COMPRESSED != 0
+    }
+
+    #[inline]
+    pub fn is_fragment(self) -> bool {
+        self.0 & Self::FRAGMENT != 0
+    }
+
+    #[inline]
+    pub fn priority(self) -> u8 {
+        ((self.0 & Self::PRIORITY) >> Self::PRIORITY_SHIFT) as u8
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    #[inline]
+    fn with(self, flag: u16, set: bool) -> Self {
+        if set {
+            FrameFlags(self.0 | flag)
+        } else {
+            FrameFlags(self.0 & !flag)
+        }
+    }
+}
+
+// ========================================= impl Frame ========================================= \\
+
+impl Frame {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new(packet: Packet) -> Self {
+        Frame {
+            packet,
+            flags: FrameFlags::default(),
+        }
+    }
+
+    #[inline]
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.flags = self.flags.with_priority(priority);
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn packet(&self) -> &Packet {
+        &self.packet
+    }
+
+    #[inline]
+    pub fn flags(&self) -> FrameFlags {
+        self.flags
+    }
+
+    #[inline]
+    pub fn priority(&self) -> u8 {
+        self.flags.priority()
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn into_packet(self) -> Packet {
+        self.packet
+    }
+
+    #[inline]
+    pub(crate) fn into_parts(self) -> (Packet, FrameFlags) {
+        (self.packet, self.flags)
+    }
+}
+
+// ========================================== impl From ========================================= \\
+
+impl From<Packet> for Frame {
+    #[inline]
+    fn from(packet: Packet) -> Self {
+        Frame::new(packet)
+    }
+}
@@ -0,0 +1,426 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::control::{Control, COMPRESSED_OVERHEAD};
+use crate::unknown;
+use crate::write;
+use crate::{
+    wipe, Error, ErrorContext, Protocol, Result, Throttle, UnknownPacketPolicy, WireFormat,
+};
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::{AsyncRead, AsyncWrite};
+use packets::RAW_MAX_LEN;
+use std::io;
+
+// ============================================ Types =========================================== \\
+
+pub(crate) struct Framed<IO> {
+    io: IO,
+    proto: Protocol,
+    hdr: [u8; WireFormat::MAX_HEADER_LEN],
+    hdr_off: usize,
+    inp: Vec<u8>,
+    inp_len: usize,
+    inp_off: usize,
+    escaped: bool,
+    out_len: usize,
+    out_off: usize,
+    sent: Vec<(u16, usize)>,
+    throttle: Throttle,
+}
+
+// ========================================= impl Framed ======================================== \\
+
+impl<IO> Framed<IO> {
+    // ==================================== Constructors ==================================== \\
+
+    // Whatever a dropped send left unwritten goes out before anything else, and whatever is left
+    // unwritten once done goes back to the protocol for its next send to write, with the packets
+    // it holds counting as sent.
+    pub(crate) fn new(mut proto: Protocol, io: IO) -> Self {
+        let pending = proto.session.pending.take();
+        if pending.len() > proto.buf.len() {
+            proto.buf.resize(pending.len(), 0);
+        }
+
+        proto.buf[..pending.len()].copy_from_slice(&pending);
+
+        Framed {
+            io,
+            proto,
+            hdr: [0; WireFormat::MAX_HEADER_LEN],
+            hdr_off: 0,
+            inp: Vec::new(),
+            inp_len: 0,
+            inp_off: 0,
+            escaped: false,
+            out_len: pending.len(),
+            out_off: 0,
+            sent: Vec::new(),
+            throttle: Throttle::default(),
+        }
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn done(mut self) -> (Protocol, IO) {
+        let unwritten = &self.proto.buf[self.out_off..self.out_len];
+        self.proto.session.pending.save(unwritten);
+
+        for (id, bytes) in self.sent.drain(..) {
+            self.proto.session.packet_written(id, bytes);
+        }
+
+        (self.proto, self.io)
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub(crate) fn msg(&self) -> &[u8] {
+        &self.proto.msg
+    }
+
+    #[inline]
+    pub(crate) fn is_initiator(&self) -> bool {
+        self.proto.state.is_initiator()
+    }
+
+    #[inline]
+    pub(crate) fn unknown_packet_policy(&self) -> UnknownPacketPolicy {
+        self.proto.session.unknown
+    }
+
+    #[inline]
+    pub(crate) fn max_msg_len(&self) -> usize {
+        self.proto.session.padding.max_len()
+    }
+
+    #[inline]
+    pub(crate) fn credit(&self) -> Option<u64> {
+        self.proto.session.inbox.flow.credit()
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    // Packets only count as sent once their frames have been written out.
+    #[inline]
+    pub(crate) fn packet_sent(&mut self, msg: &[u8]) {
+        self.sent.push((unknown::id(msg), msg.len()));
+    }
+
+    #[inline]
+    pub(crate) fn packet_received(&mut self, len: usize) -> Result<()> {
+        self.proto.session.packet_received(&self.proto.msg[..len])
+    }
+
+    // Waits until the send limit and pacing let another message be written, which they then
+    // only account for once it is.
+    #[inline]
+    pub(crate) fn poll_pace(&mut self, ctx: &mut Context) -> Poll<Result<()>> {
+        self.throttle.poll(ctx, &mut self.proto.session, 0)
+    }
+
+    pub(crate) fn write_msg(&mut self, msg: &[u8]) -> Result<()> {
+        if msg.len() > self.max_msg_len() {
+            return Err(Error::MessageSize {
+                max: self.max_msg_len(),
+                actual: msg.len(),
+            });
+        }
+
+        let compressed = self.proto.session.compress(msg)?;
+        let len = match &compressed {
+            Some(payload) => payload.len() + COMPRESSED_OVERHEAD,
+            None => msg.len(),
+        };
+
+        self.proto.session.inbox.flow.check(len)?;
+
+        let start = self.out_len;
+        self.encrypt_pongs()?;
+
+        if self.proto.session.rekeyer.is_due() {
+            let mut ctl = Vec::new();
+            Control::Rekey.encode(&mut ctl);
+
+            self.encrypt(&[])?;
+            self.encrypt(&ctl)?;
+            self.proto.state.rekey_outgoing();
+            self.proto.session.rekeyer.reset();
+        }
+
+        if let Some(window) = self.proto.session.inbox.flow.take_update() {
+            let mut ctl = Vec::new();
+            Control::Resize(window).encode(&mut ctl);
+
+            self.encrypt(&[])?;
+            self.encrypt(&ctl)?;
+        }
+
+        if let Some(grant) = self.proto.session.inbox.flow.take_grant() {
+            let mut ctl = Vec::new();
+            Control::Window(grant).encode(&mut ctl);
+
+            self.encrypt(&[])?;
+            self.encrypt(&ctl)?;
+        }
+
+        if let Some(payload) = compressed {
+            let mut ctl = Vec::new();
+            let id = self.proto.session.inbox.compression.id();
+            Control::Compressed(id, &payload).encode(&mut ctl);
+
+            self.encrypt(&[])?;
+            self.encrypt(&ctl)?;
+        } else {
+            self.encrypt(msg)?;
+        }
+
+        self.proto.session.send_limit.consume(len);
+        self.proto.session.pacer.sent(self.out_len - start);
+        self.proto.session.inbox.flow.sent(len);
+        self.proto.session.rekeyer.record(len);
+        self.proto.session.liveness.sent();
+
+        Ok(())
+    }
+
+    // Pings get answered as soon as they're received, with the pongs being written out as far as
+    // the IO lets them, and by the next read or write otherwise.
+    pub(crate) fn poll_read_msg(&mut self, ctx: &mut Context) -> Poll<Result<Option<usize>>>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        let poll = self.poll_read(ctx);
+        if self.out_off < self.out_len {
+            if let Poll::Ready(Err(err)) = self.poll_flush(ctx) {
+                return Poll::Ready(Err(err));
+            }
+        }
+
+        poll
+    }
+
+    pub(crate) fn poll_drain(&mut self, ctx: &mut Context) -> Poll<Result<()>>
+    where
+        IO: AsyncWrite + Unpin,
+    {
+        while self.out_off < self.out_len {
+            match Pin::new(&mut self.io)
+                .poll_write(ctx, &self.proto.buf[self.out_off..self.out_len])
+            {
+                Poll::Ready(Ok(0)) => {
+                    let err = Error::from(io::Error::from(io::ErrorKind::WriteZero));
+
+                    return Poll::Ready(Err(err.with_context(ErrorContext::write(self.out_off))));
+                }
+                Poll::Ready(Ok(wrote)) => self.out_off += wrote,
+                Poll::Ready(Err(err)) => {
+                    let context = ErrorContext::write(self.out_off);
+
+                    return Poll::Ready(Err(Error::from(err).with_context(context)));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        self.out_len = 0;
+        self.out_off = 0;
+
+        for (id, bytes) in self.sent.drain(..) {
+            self.proto.session.packet_written(id, bytes);
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    pub(crate) fn poll_flush(&mut self, ctx: &mut Context) -> Poll<Result<()>>
+    where
+        IO: AsyncWrite + Unpin,
+    {
+        if self.poll_drain(ctx)?.is_pending() {
+            return Poll::Pending;
+        }
+
+        Pin::new(&mut self.io).poll_flush(ctx).map_err(Error::from)
+    }
+
+    pub(crate) fn poll_close(&mut self, ctx: &mut Context) -> Poll<Result<()>>
+    where
+        IO: AsyncWrite + Unpin,
+    {
+        if self.poll_drain(ctx)?.is_pending() {
+            return Poll::Pending;
+        }
+
+        Pin::new(&mut self.io).poll_close(ctx).map_err(Error::from)
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    fn poll_read(&mut self, ctx: &mut Context) -> Poll<Result<Option<usize>>>
+    where
+        IO: AsyncRead + Unpin,
+    {
+        // Packets queued by a previous receive come before anything still unread.
+        if self.hdr_off == 0 {
+            if let Some(mut bytes) = self.proto.session.inbox.pop_backlog() {
+                self.proto.msg.clear();
+                self.proto.msg.extend_from_slice(&bytes);
+                wipe(&mut bytes);
+
+                return Poll::Ready(Ok(Some(self.proto.msg.len())));
+            }
+        }
+
+        let wire = self.proto.session.wire;
+        let hdr_len = wire.header_len();
+        loop {
+            if self.hdr_off < hdr_len {
+                let hdr = &mut self.hdr[self.hdr_off..hdr_len];
+                match Pin::new(&mut self.io).poll_read(ctx, hdr) {
+                    Poll::Ready(Ok(0)) if self.hdr_off == 0 => return Poll::Ready(Ok(None)),
+                    Poll::Ready(Ok(0)) => {
+                        let err = Error::UnexpectedEof {
+                            needed: hdr_len,
+                            got: self.hdr_off,
+                        };
+                        let context = ErrorContext::read(self.hdr_off);
+
+                        return Poll::Ready(Err(err.with_context(context)));
+                    }
+                    Poll::Ready(Ok(read)) => self.hdr_off += read,
+                    Poll::Ready(Err(err)) => {
+                        let context = ErrorContext::read(self.hdr_off);
+
+                        return Poll::Ready(Err(Error::from(err).with_context(context)));
+                    }
+                    Poll::Pending => return Poll::Pending,
+                }
+
+                if self.hdr_off == hdr_len {
+                    let (len, _) = wire.decode_header(&self.hdr);
+                    if len > RAW_MAX_LEN {
+                        self.hdr_off = 0;
+
+                        return Poll::Ready(Err(Error::MessageSize {
+                            max: RAW_MAX_LEN,
+                            actual: len,
+                        }));
+                    }
+
+                    if len > self.inp.len() {
+                        self.inp.resize(len, 0);
+                    }
+
+                    self.inp_len = len;
+                    self.inp_off = 0;
+                }
+
+                continue;
+            }
+
+            if self.inp_off < self.inp_len {
+                match Pin::new(&mut self.io)
+                    .poll_read(ctx, &mut self.inp[self.inp_off..self.inp_len])
+                {
+                    Poll::Ready(Ok(0)) => {
+                        let err = Error::UnexpectedEof {
+                            needed: self.inp_len,
+                            got: self.inp_off,
+                        };
+                        let context = ErrorContext::read(hdr_len + self.inp_off);
+
+                        return Poll::Ready(Err(err.with_context(context)));
+                    }
+                    Poll::Ready(Ok(read)) => self.inp_off += read,
+                    Poll::Ready(Err(err)) => {
+                        let context = ErrorContext::read(hdr_len + self.inp_off);
+
+                        return Poll::Ready(Err(Error::from(err).with_context(context)));
+                    }
+                    Poll::Pending => return Poll::Pending,
+                }
+
+                continue;
+            }
+
+            self.hdr_off = 0;
+            self.proto.session.liveness.received();
+            if self.inp_len > self.proto.msg.len() {
+                self.proto.msg.resize(self.inp_len, 0);
+            }
+
+            let context = ErrorContext::read(hdr_len + self.inp_len);
+            let checksum = wire.decode_checksum(&self.hdr);
+            WireFormat::verify(checksum, &self.inp[..self.inp_len])
+                .map_err(|err| err.with_context(context))?;
+
+            let len = self
+                .proto
+                .state
+                .read_message(&self.inp[..self.inp_len], &mut self.proto.msg)
+                .map_err(|err| Error::from(err).with_context(context))?;
+            let len = self.proto.session.padding.unpad(&self.proto.msg[..len])?;
+
+            if len == 0 {
+                self.escaped = true;
+            } else if self.escaped {
+                self.escaped = false;
+
+                self.proto
+                    .session
+                    .handle(&self.proto.msg[..len], &mut self.proto.state)?;
+
+                self.encrypt_pongs()?;
+
+                if let Some(bytes) = self.proto.session.inbox.take_inflated() {
+                    self.proto.msg.clear();
+                    self.proto.msg.extend_from_slice(&bytes);
+
+                    return Poll::Ready(Ok(Some(bytes.len())));
+                }
+            } else {
+                self.proto.session.inbox.flow.received(len);
+
+                return Poll::Ready(Ok(Some(len)));
+            }
+        }
+    }
+
+    fn encrypt_pongs(&mut self) -> Result<()> {
+        while let Some(nonce) = self.proto.session.inbox.pop_pong() {
+            let mut ctl = Vec::new();
+            Control::Pong(nonce).encode(&mut ctl);
+
+            self.encrypt(&[])?;
+            self.encrypt(&ctl)?;
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn encrypt(&mut self, msg: &[u8]) -> Result<()> {
+        self.out_len = write::append(
+            &mut self.proto.state,
+            msg,
+            &mut self.proto.buf,
+            self.out_len,
+            self.proto.session.padding,
+            self.proto.session.wire,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// Identities are carried in the payloads of the Noise handshake messages, so that they only ever
+// get sent encrypted:
+//
+// - the responder's follows its choice in the `e, ee` message,
+// - a targeted initiator's follows its offer in the `e, es, s, ss` message (IK),
+// - any other initiator's makes up the payload of a third `s, se` message (XN), which it announces
+//   by setting `Suite::IDENTIFIED` in its suite id.
+//
+// IDENTITY ;; kind(1) [+ public(32) + signature(64)] [+ certificate(152)]
+//
+// `kind` is 0 when no identity follows, 1 for an identity and 2 for a delegated one, in which case
+// `public` is a subkey and the certificate issued for it by the identity key follows (see
+// certificate.rs). The signed message is role(1) + hash, with role being 0 for the initiator and 1
+// for the responder, and hash being the handshake hash before the message gets written. When the
+// peer has a Noise static key, it must be the one derived from `public` (see static_key.rs).
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Certificate, Error, Result, StaticKey};
+use core::convert::TryFrom;
+use ed25519_dalek::{Keypair, PublicKey, Signature, Signer, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+
+// ========================================== Constants ========================================= \\
+
+const IDENTITY_NONE: u8 = 0;
+const IDENTITY_PLAIN: u8 = 1;
+const IDENTITY_DELEGATED: u8 = 2;
+
+const IDENTITY_LEN: usize = PUBLIC_KEY_LENGTH + SIGNATURE_LENGTH;
+
+// ============================================ Types =========================================== \\
+
+pub(crate) type Identity = (PublicKey, Option<Certificate>);
+
+// ====================================== encode_identity() ===================================== \\
+
+pub(crate) fn encode_identity(
+    keypair: Option<&Keypair>,
+    certificate: Option<&Certificate>,
+    hash: &[u8],
+    initiator: bool,
+    payload: &mut Vec<u8>,
+) {
+    let keypair = match keypair {
+        Some(keypair) => keypair,
+        None => return payload.push(IDENTITY_NONE),
+    };
+
+    if certificate.is_some() {
+        payload.push(IDENTITY_DELEGATED);
+    } else {
+        payload.push(IDENTITY_PLAIN);
+    }
+
+    payload.extend_from_slice(keypair.public.as_bytes());
+    payload.extend_from_slice(&keypair.sign(&message(hash, initiator)).to_bytes());
+
+    if let Some(certificate) = certificate {
+        payload.extend_from_slice(&certificate.encode());
+    }
+}
+
+// ====================================== decode_identity() ===================================== \\
+
+// Decodes and verifies the identity written by the other side at the start of `payload`, returning
+// it along with the rest of the payload.
+pub(crate) fn decode_identity<'payload>(
+    payload: &'payload [u8],
+    hash: &[u8],
+    initiator: bool,
+    remote_static: Option<&[u8]>,
+) -> Result<(Option<Identity>, &'payload [u8])> {
+    let (kind, payload) = payload.split_first().ok_or(Error::InvalidIdentity)?;
+
+    // A peer with a static key must send the identity it was derived from.
+    let len = match *kind {
+        IDENTITY_NONE if remote_static.is_none() => return Ok((None, payload)),
+        IDENTITY_PLAIN => IDENTITY_LEN,
+        IDENTITY_DELEGATED => IDENTITY_LEN + Certificate::LEN,
+        _ => return Err(Error::InvalidIdentity),
+    };
+
+    if payload.len() < len {
+        return Err(Error::InvalidIdentity);
+    }
+
+    let (identity, payload) = payload.split_at(len);
+    let public = PublicKey::from_bytes(&identity[..PUBLIC_KEY_LENGTH])
+        .map_err(|_| Error::InvalidIdentity)?;
+    let signature = Signature::try_from(&identity[PUBLIC_KEY_LENGTH..IDENTITY_LEN])
+        .map_err(|_| Error::InvalidIdentity)?;
+
+    public
+        .verify_strict(&message(hash, !initiator), &signature)
+        .map_err(|_| Error::InvalidIdentity)?;
+
+    if let Some(remote_static) = remote_static {
+        if StaticKey::remote(&public)?[..] != *remote_static {
+            return Err(Error::InvalidIdentity);
+        }
+    }
+
+    if *kind == IDENTITY_PLAIN {
+        return Ok((Some((public, None)), payload));
+    }
+
+    let mut certificate = [0; Certificate::LEN];
+    certificate.copy_from_slice(&identity[IDENTITY_LEN..]);
+
+    let certificate = Certificate::decode(&certificate)?;
+    if *certificate.subkey() != public {
+        return Err(Error::InvalidCertificate);
+    }
+
+    certificate.verify()?;
+
+    Ok((Some((*certificate.issuer(), Some(certificate))), payload))
+}
+
+// ========================================== message() ========================================= \\
+
+fn message(hash: &[u8], initiator: bool) -> Vec<u8> {
+    let mut message = Vec::with_capacity(1 + hash.len());
+    message.push(if initiator { 0 } else { 1 });
+    message.extend_from_slice(hash);
+
+    message
+}
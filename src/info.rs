@@ -0,0 +1,71 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use snow::params::NoiseParams;
+use snow::HandshakeState;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Debug)]
+pub struct HandshakeInfo {
+    hash: Vec<u8>,
+    initiator: bool,
+    params: NoiseParams,
+    remote_static: Option<Vec<u8>>,
+    remote_payload: Vec<u8>,
+}
+
+// ===================================== impl HandshakeInfo ===================================== \\
+
+impl HandshakeInfo {
+    // ==================================== Constructors ==================================== \\
+
+    pub(crate) fn new(params: NoiseParams, state: &HandshakeState, payload: Vec<u8>) -> Self {
+        HandshakeInfo {
+            hash: state.get_handshake_hash().to_vec(),
+            initiator: state.is_initiator(),
+            params,
+            remote_static: state.get_remote_static().map(<[u8]>::to_vec),
+            remote_payload: payload,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn handshake_hash(&self) -> &[u8] {
+        &self.hash
+    }
+
+    #[inline]
+    pub fn is_initiator(&self) -> bool {
+        self.initiator
+    }
+
+    #[inline]
+    pub fn pattern(&self) -> &str {
+        &self.params.name
+    }
+
+    #[inline]
+    pub fn params(&self) -> &NoiseParams {
+        &self.params
+    }
+
+    #[inline]
+    pub fn remote_static(&self) -> Option<&[u8]> {
+        self.remote_static.as_deref()
+    }
+
+    #[inline]
+    pub fn remote_payload(&self) -> &[u8] {
+        &self.remote_payload
+    }
+}
@@ -8,37 +8,114 @@
 
 // =========================================== Imports ========================================== \\
 
-use crate::{Handshake, Read, Result, Write};
-use async_peek::AsyncPeek;
+use crate::config::{HYBRID_ACCEPTED, HYBRID_DECLINED};
+use crate::identify::{decode_identity, encode_identity};
+use crate::pow::{Challenge, STATUS_CHALLENGE, STATUS_READY};
+use crate::{
+    Compression, Config, Error, ErrorContext, Handshake, HandshakePhase, Identity, Metrics,
+    PaddingPolicy, Read, Result, StateDump, Suite, Timeout, Timer, WireFormat, Write,
+};
+use core::fmt::{self, Debug, Display, Formatter};
 use core::future::Future;
 use core::mem;
 use core::pin::Pin;
 use core::task::{Context, Poll};
+use core::time::Duration;
 use futures_io::{AsyncRead, AsyncWrite};
+use snow::params::NoiseParams;
 use snow::HandshakeState;
+use std::io;
 
 // ============================================ Types =========================================== \\
 
 pub struct Initiate<IO> {
     inner: InitiateInner<IO>,
+    params: Option<NoiseParams>,
+    metrics: Metrics,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
 }
 
 enum InitiateInner<IO> {
     Empty,
     State {
         io: IO,
+        config: Config,
+    },
+    Suite {
+        config: Config,
+        offer: [u8; 1 + Suite::MAX_OFFERED],
+        len: usize,
+        off: usize,
+        io: IO,
+    },
+    Choice {
+        config: Config,
+        choice: [u8; 2],
+        len: usize,
+        off: usize,
+        io: IO,
+    },
+    Status {
+        suite: Suite,
+        config: Config,
+        hybrid: bool,
+        status: [u8; 1 + Challenge::LEN],
+        len: usize,
+        off: usize,
+        io: IO,
+    },
+    Solve {
+        suite: Suite,
+        config: Config,
+        hybrid: bool,
+        challenge: Challenge,
+        next: u64,
+        io: IO,
+    },
+    Proof {
+        suite: Suite,
+        config: Config,
+        hybrid: bool,
+        proof: [u8; Challenge::PROOF_LEN],
+        off: usize,
+        io: IO,
     },
     Write {
+        suite: Suite,
+        config: Config,
         write: Write<IO, HandshakeState>,
     },
     Flush {
+        suite: Suite,
+        config: Config,
         buf: Vec<u8>,
         io: IO,
         state: HandshakeState,
     },
     Read {
+        suite: Suite,
+        config: Config,
+        hash: Vec<u8>,
         read: Read<IO, HandshakeState>,
     },
+    Identify {
+        suite: Suite,
+        compression: Compression,
+        wire: WireFormat,
+        payload: Vec<u8>,
+        identity: Option<Identity>,
+        write: Write<IO, HandshakeState>,
+    },
+    Finish {
+        suite: Suite,
+        compression: Compression,
+        wire: WireFormat,
+        payload: Vec<u8>,
+        identity: Option<Identity>,
+        io: IO,
+        state: HandshakeState,
+    },
     Done {
         io: IO,
     },
@@ -50,80 +127,490 @@ impl<IO> Initiate<IO> {
     // ==================================== Constructors ==================================== \\
 
     #[inline]
-    pub(super) fn new(io: IO) -> Self
+    pub(super) fn new(io: IO, config: Config) -> Self
     where
-        IO: AsyncPeek + AsyncRead + AsyncWrite + Unpin,
+        IO: AsyncRead + AsyncWrite + Unpin,
     {
+        let metrics = config.metrics().clone();
         Initiate {
-            inner: InitiateInner::State { io },
+            inner: InitiateInner::State { io, config },
+            params: None,
+            metrics,
+            #[cfg(feature = "tracing")]
+            span: tracing::debug_span!("initiate"),
         }
     }
 
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn phase(&self) -> HandshakePhase
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        self.inner.phase()
+    }
+
+    #[inline]
+    pub fn dump_state(&self) -> StateDump
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        self.inner.dump_state()
+    }
+
     // ===================================== Destructors ==================================== \\
 
+    #[inline]
+    pub fn timeout<Tmr: Timer>(self, duration: Duration) -> Timeout<Self, Tmr> {
+        Timeout::new(self, duration)
+    }
+
     pub fn done(self) -> IO {
         match self.inner {
             InitiateInner::Empty => panic!(),
-            InitiateInner::State { io }
+            InitiateInner::State { io, .. }
+            | InitiateInner::Suite { io, .. }
+            | InitiateInner::Choice { io, .. }
             | InitiateInner::Flush { io, .. }
+            | InitiateInner::Status { io, .. }
+            | InitiateInner::Solve { io, .. }
+            | InitiateInner::Proof { io, .. }
+            | InitiateInner::Finish { io, .. }
             | InitiateInner::Done { io } => io,
-            InitiateInner::Write { write } => write.done().2,
-            InitiateInner::Read { read } => read.done().2,
+            InitiateInner::Write { write, .. } | InitiateInner::Identify { write, .. } => {
+                write.done().2
+            }
+            InitiateInner::Read { read, .. } => read.done().2,
         }
     }
 }
 
-// ========================================= impl Future ======================================== \\
+// ===================================== impl InitiateInner ===================================== \\
 
-impl<IO> Future for Initiate<IO>
+impl<IO> InitiateInner<IO>
 where
-    IO: AsyncPeek + AsyncRead + AsyncWrite + Unpin,
+    IO: AsyncRead + AsyncWrite + Unpin,
 {
-    type Output = Result<Handshake>;
+    fn name(&self) -> &'static str {
+        match self {
+            InitiateInner::Empty => "empty",
+            InitiateInner::State { .. } => "state",
+            InitiateInner::Suite { .. } => "suite",
+            InitiateInner::Choice { .. } => "choice",
+            InitiateInner::Write { .. } => "write",
+            InitiateInner::Flush { .. } => "flush",
+            InitiateInner::Status { .. } => "status",
+            InitiateInner::Solve { .. } => "solve",
+            InitiateInner::Proof { .. } => "proof",
+            InitiateInner::Read { .. } => "read",
+            InitiateInner::Identify { .. } => "identify",
+            InitiateInner::Finish { .. } => "finish",
+            InitiateInner::Done { .. } => "done",
+        }
+    }
 
-    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
-        let inner = &mut self.get_mut().inner;
+    fn dump_state(&self) -> StateDump {
+        let dump = StateDump::new("Initiate", self.name());
+        match self {
+            InitiateInner::Status { off, .. } => dump.with_buffered(*off),
+            InitiateInner::Proof { off, .. } => dump.with_buffered(Challenge::PROOF_LEN - off),
+            InitiateInner::Write { write, .. } => dump.with_buffered(write.dump_state().buffered()),
+            InitiateInner::Read { read, .. } => dump.with_buffered(read.dump_state().buffered()),
+            InitiateInner::Identify { write, .. } => {
+                dump.with_buffered(write.dump_state().buffered())
+            }
+            _ => dump,
+        }
+    }
+
+    fn phase(&self) -> HandshakePhase {
+        match self {
+            InitiateInner::State { .. } | InitiateInner::Suite { .. } => {
+                HandshakePhase::Negotiating
+            }
+            InitiateInner::Write { .. } | InitiateInner::Flush { .. } => HandshakePhase::SendingE,
+            InitiateInner::Choice { .. } | InitiateInner::Status { .. } => {
+                HandshakePhase::AwaitingStatus
+            }
+            InitiateInner::Solve { .. } | InitiateInner::Proof { .. } => {
+                HandshakePhase::SendingProof
+            }
+            InitiateInner::Read { .. } => HandshakePhase::AwaitingEe,
+            InitiateInner::Identify { .. } | InitiateInner::Finish { .. } => {
+                HandshakePhase::Identifying
+            }
+            InitiateInner::Empty | InitiateInner::Done { .. } => HandshakePhase::Done,
+        }
+    }
+
+    // `params` are those of the variant actually being used, which the suite alone doesn't tell.
+    fn poll(
+        &mut self,
+        ctx: &mut Context,
+        phase: &mut HandshakePhase,
+        params: &mut Option<NoiseParams>,
+    ) -> Poll<Result<Handshake>> {
+        let inner = self;
         loop {
+            *phase = inner.phase();
             match mem::take(inner) {
                 InitiateInner::Empty | InitiateInner::Done { .. } => panic!(),
-                InitiateInner::State { io } => {
-                    let state = snow::Builder::new(Handshake::NOISE_PATTERN.parse().unwrap())
-                        .build_initiator()?;
+                // Only targeted initiators (IK) and those that have an identity (XN) get to send
+                // their payload in an encrypted message.
+                InitiateInner::State { io, config }
+                    if !config.payload().is_empty()
+                        && !config.is_targeted()
+                        && config.identity().is_none() =>
+                {
+                    *inner = InitiateInner::Done { io };
 
-                    // -> e     ;; 56 bytes
-                    // <- e, ee ;; 72 bytes
-                    let buf = vec![0; 72];
+                    return Poll::Ready(Err(Error::PlaintextPayload));
+                }
+                InitiateInner::State { io, config } => {
+                    let (offer, len) = config.suite_offer();
 
-                    *inner = InitiateInner::Write {
-                        write: Write::new(Vec::new(), buf, io, state),
+                    *inner = InitiateInner::Suite {
+                        config,
+                        offer,
+                        len,
+                        off: 0,
+                        io,
                     };
                 }
-                InitiateInner::Write { mut write } => {
+                // The responder must tell which suite it chose, and whether it accepts a hybrid
+                // handshake, before the handshake state can be built.
+                InitiateInner::Suite {
+                    config,
+                    len,
+                    off,
+                    io,
+                    ..
+                } if off >= len => {
+                    *inner = InitiateInner::Choice {
+                        choice: [0; 2],
+                        len: 1 + config.is_hybrid() as usize,
+                        off: 0,
+                        config,
+                        io,
+                    };
+                }
+                InitiateInner::Suite {
+                    config,
+                    offer,
+                    len,
+                    mut off,
+                    mut io,
+                } => match Pin::new(&mut io).poll_write(ctx, &offer[off..len])? {
+                    Poll::Ready(0) => {
+                        *inner = InitiateInner::Done { io };
+
+                        return Poll::Ready(Err(io::Error::from(io::ErrorKind::WriteZero).into()));
+                    }
+                    Poll::Ready(wrote) => {
+                        off += wrote;
+
+                        *inner = InitiateInner::Suite {
+                            config,
+                            offer,
+                            len,
+                            off,
+                            io,
+                        };
+                    }
+                    Poll::Pending => {
+                        *inner = InitiateInner::Suite {
+                            config,
+                            offer,
+                            len,
+                            off,
+                            io,
+                        };
+
+                        return Poll::Pending;
+                    }
+                },
+                InitiateInner::Choice {
+                    config,
+                    choice,
+                    len,
+                    off,
+                    io,
+                } if off >= len => {
+                    // The responder may only choose one of the suites that were offered.
+                    let (offer, offered) = config.suite_offer();
+                    let suite = match config.choose_suite(&[choice[0]]) {
+                        Some((suite, _)) if offer[1..offered].contains(&choice[0]) => suite,
+                        _ => {
+                            *inner = InitiateInner::Done { io };
+
+                            return Poll::Ready(Err(Error::UnsupportedSuite(choice[0])));
+                        }
+                    };
+
+                    let hybrid = match (config.is_hybrid(), choice[1]) {
+                        (false, _) | (true, HYBRID_DECLINED) => false,
+                        (true, HYBRID_ACCEPTED) => true,
+                        (true, _) => {
+                            *inner = InitiateInner::Done { io };
+
+                            return Poll::Ready(Err(Error::UnsupportedSuite(choice[0])));
+                        }
+                    };
+
+                    *inner = status(suite, config, hybrid, io);
+                }
+                InitiateInner::Choice {
+                    config,
+                    mut choice,
+                    len,
+                    mut off,
+                    mut io,
+                } => match Pin::new(&mut io).poll_read(ctx, &mut choice[off..len])? {
+                    Poll::Ready(0) => {
+                        *inner = InitiateInner::Done { io };
+
+                        return Poll::Ready(Err(Error::UnexpectedEof {
+                            needed: len,
+                            got: off,
+                        }));
+                    }
+                    Poll::Ready(read) => {
+                        off += read;
+
+                        *inner = InitiateInner::Choice {
+                            config,
+                            choice,
+                            len,
+                            off,
+                            io,
+                        };
+                    }
+                    Poll::Pending => {
+                        *inner = InitiateInner::Choice {
+                            config,
+                            choice,
+                            len,
+                            off,
+                            io,
+                        };
+
+                        return Poll::Pending;
+                    }
+                },
+                InitiateInner::Status {
+                    suite,
+                    config,
+                    hybrid,
+                    status,
+                    len,
+                    off,
+                    io,
+                } if off >= len => match status[0] {
+                    STATUS_READY => *inner = write(suite, config, hybrid, io, params)?,
+                    STATUS_CHALLENGE if len == 1 => {
+                        *inner = InitiateInner::Status {
+                            suite,
+                            config,
+                            hybrid,
+                            status,
+                            len: 1 + Challenge::LEN,
+                            off,
+                            io,
+                        };
+                    }
+                    STATUS_CHALLENGE => {
+                        let mut bytes = [0; Challenge::LEN];
+                        bytes.copy_from_slice(&status[1..]);
+
+                        let challenge = Challenge::decode(&bytes);
+                        if challenge.difficulty() > config.max_pow() {
+                            *inner = InitiateInner::Done { io };
+
+                            return Poll::Ready(Err(Error::PowDifficulty {
+                                max: config.max_pow(),
+                                actual: challenge.difficulty(),
+                            }));
+                        }
+
+                        *inner = InitiateInner::Solve {
+                            suite,
+                            config,
+                            hybrid,
+                            challenge,
+                            next: 0,
+                            io,
+                        };
+                    }
+                    _ => {
+                        *inner = InitiateInner::Done { io };
+
+                        return Poll::Ready(Err(Error::InvalidProof));
+                    }
+                },
+                InitiateInner::Status {
+                    suite,
+                    config,
+                    hybrid,
+                    mut status,
+                    len,
+                    mut off,
+                    mut io,
+                } => match Pin::new(&mut io).poll_read(ctx, &mut status[off..len])? {
+                    Poll::Ready(0) => {
+                        *inner = InitiateInner::Done { io };
+
+                        return Poll::Ready(Err(Error::UnexpectedEof {
+                            needed: len,
+                            got: off,
+                        }));
+                    }
+                    Poll::Ready(read) => {
+                        off += read;
+
+                        *inner = InitiateInner::Status {
+                            suite,
+                            config,
+                            hybrid,
+                            status,
+                            len,
+                            off,
+                            io,
+                        };
+                    }
+                    Poll::Pending => {
+                        *inner = InitiateInner::Status {
+                            suite,
+                            config,
+                            hybrid,
+                            status,
+                            len,
+                            off,
+                            io,
+                        };
+
+                        return Poll::Pending;
+                    }
+                },
+                InitiateInner::Solve {
+                    suite,
+                    config,
+                    hybrid,
+                    challenge,
+                    next,
+                    io,
+                } => match challenge.solve(next, Challenge::ATTEMPTS_PER_POLL) {
+                    Some(proof) => {
+                        *inner = InitiateInner::Proof {
+                            suite,
+                            config,
+                            hybrid,
+                            proof: proof.to_le_bytes(),
+                            off: 0,
+                            io,
+                        };
+                    }
+                    // Yields between batches of attempts so that a hard challenge doesn't block
+                    // the executor.
+                    None => {
+                        *inner = InitiateInner::Solve {
+                            suite,
+                            config,
+                            hybrid,
+                            challenge,
+                            next: next + Challenge::ATTEMPTS_PER_POLL,
+                            io,
+                        };
+
+                        ctx.waker().wake_by_ref();
+                        return Poll::Pending;
+                    }
+                },
+                // The proof gets flushed along with the first handshake message.
+                InitiateInner::Proof {
+                    suite,
+                    config,
+                    hybrid,
+                    proof,
+                    off,
+                    io,
+                } if off >= proof.len() => *inner = write(suite, config, hybrid, io, params)?,
+                InitiateInner::Proof {
+                    suite,
+                    config,
+                    hybrid,
+                    proof,
+                    mut off,
+                    mut io,
+                } => match Pin::new(&mut io).poll_write(ctx, &proof[off..])? {
+                    Poll::Ready(0) => {
+                        *inner = InitiateInner::Done { io };
+
+                        return Poll::Ready(Err(io::Error::from(io::ErrorKind::WriteZero).into()));
+                    }
+                    Poll::Ready(wrote) => {
+                        off += wrote;
+
+                        *inner = InitiateInner::Proof {
+                            suite,
+                            config,
+                            hybrid,
+                            proof,
+                            off,
+                            io,
+                        };
+                    }
+                    Poll::Pending => {
+                        *inner = InitiateInner::Proof {
+                            suite,
+                            config,
+                            hybrid,
+                            proof,
+                            off,
+                            io,
+                        };
+
+                        return Poll::Pending;
+                    }
+                },
+                InitiateInner::Write {
+                    suite,
+                    config,
+                    mut write,
+                } => {
                     if Pin::new(&mut write).poll(ctx)?.is_ready() {
                         let (_, buf, io, state) = write.done();
 
                         *inner = InitiateInner::Flush {
+                            suite,
+                            config,
                             buf,
                             io,
                             state,
                         };
                     } else {
-                        *inner = InitiateInner::Write { write };
+                        *inner = InitiateInner::Write {
+                            suite,
+                            config,
+                            write,
+                        };
 
                         return Poll::Pending;
                     }
                 }
                 InitiateInner::Flush {
+                    suite,
+                    config,
                     buf,
                     mut io,
                     state,
                 } => {
                     if Pin::new(&mut io).poll_flush(ctx)?.is_ready() {
-                        *inner = InitiateInner::Read {
-                            read: Read::new(Vec::new(), buf, io, state),
-                        };
+                        *inner = read(suite, config, buf, io, state);
                     } else {
                         *inner = InitiateInner::Flush {
+                            suite,
+                            config,
                             buf,
                             io,
                             state,
@@ -132,15 +619,170 @@ where
                         return Poll::Pending;
                     }
                 }
-                InitiateInner::Read { mut read } => {
-                    if Pin::new(&mut read).poll(ctx)?.is_ready() {
-                        let (_, _, io, state) = read.done();
+                InitiateInner::Read {
+                    suite,
+                    config,
+                    hash,
+                    mut read,
+                } => {
+                    if let Poll::Ready(len) = Pin::new(&mut read).poll(ctx)? {
+                        let (mut payload, buf, io, state) = read.done();
+                        payload.truncate(len);
+
+                        let (compression, wire) = match payload
+                            .first()
+                            .and_then(|&choice| config.accepts_choice(choice))
+                        {
+                            Some(negotiated) => negotiated,
+                            None => {
+                                *inner = InitiateInner::Done { io };
+
+                                return Poll::Ready(Err(Error::PayloadRejected));
+                            }
+                        };
+
+                        let remote = state.get_remote_static();
+                        let (identity, payload) =
+                            match decode_identity(&payload[1..], &hash, true, remote) {
+                                Ok((identity, rest)) => (identity, rest.to_vec()),
+                                Err(err) => {
+                                    *inner = InitiateInner::Done { io };
+
+                                    return Poll::Ready(Err(err));
+                                }
+                            };
+
+                        if !config.verify(&payload) {
+                            *inner = InitiateInner::Done { io };
+
+                            return Poll::Ready(Err(Error::PayloadRejected));
+                        }
+
+                        // A targeted handshake must end up with the targeted identity.
+                        if let Some(target) = config.remote_identity() {
+                            if identity.as_ref().map(|(identity, _)| identity) != Some(target) {
+                                *inner = InitiateInner::Done { io };
+
+                                return Poll::Ready(Err(Error::InvalidIdentity));
+                            }
+                        }
+
+                        if state.is_handshake_finished() {
+                            *inner = InitiateInner::Done { io };
+
+                            let handshake = Handshake::new(
+                                state,
+                                params.take().unwrap(),
+                                suite,
+                                compression,
+                                wire,
+                                payload,
+                                identity,
+                            );
+                            return Poll::Ready(Ok(handshake));
+                        }
+
+                        let mut local = Vec::new();
+                        encode_identity(
+                            config.identity(),
+                            config.certificate(),
+                            state.get_handshake_hash(),
+                            true,
+                            &mut local,
+                        );
+                        local.extend_from_slice(config.payload());
+
+                        *inner = InitiateInner::Identify {
+                            suite,
+                            compression,
+                            wire,
+                            payload,
+                            identity,
+                            write: Write::new(
+                                local,
+                                buf,
+                                io,
+                                state,
+                                PaddingPolicy::None,
+                                WireFormat::V1,
+                            ),
+                        };
+                    } else {
+                        *inner = InitiateInner::Read {
+                            suite,
+                            config,
+                            hash,
+                            read,
+                        };
+
+                        return Poll::Pending;
+                    }
+                }
+                InitiateInner::Identify {
+                    suite,
+                    compression,
+                    wire,
+                    payload,
+                    identity,
+                    mut write,
+                } => {
+                    if Pin::new(&mut write).poll(ctx)?.is_ready() {
+                        let (_, _, io, state) = write.done();
 
+                        *inner = InitiateInner::Finish {
+                            suite,
+                            compression,
+                            wire,
+                            payload,
+                            identity,
+                            io,
+                            state,
+                        };
+                    } else {
+                        *inner = InitiateInner::Identify {
+                            suite,
+                            compression,
+                            wire,
+                            payload,
+                            identity,
+                            write,
+                        };
+
+                        return Poll::Pending;
+                    }
+                }
+                InitiateInner::Finish {
+                    suite,
+                    compression,
+                    wire,
+                    payload,
+                    identity,
+                    mut io,
+                    state,
+                } => {
+                    if Pin::new(&mut io).poll_flush(ctx)?.is_ready() {
                         *inner = InitiateInner::Done { io };
 
-                        return Poll::Ready(Ok(Handshake { state }));
+                        let handshake = Handshake::new(
+                            state,
+                            params.take().unwrap(),
+                            suite,
+                            compression,
+                            wire,
+                            payload,
+                            identity,
+                        );
+                        return Poll::Ready(Ok(handshake));
                     } else {
-                        *inner = InitiateInner::Read { read };
+                        *inner = InitiateInner::Finish {
+                            suite,
+                            compression,
+                            wire,
+                            payload,
+                            identity,
+                            io,
+                            state,
+                        };
 
                         return Poll::Pending;
                     }
@@ -150,6 +792,164 @@ where
     }
 }
 
+// ========================================= impl Future ======================================== \\
+
+impl<IO> Future for Initiate<IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    type Output = Result<Handshake>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        #[cfg(feature = "tracing")]
+        let _enter = this.span.enter();
+
+        let mut phase = HandshakePhase::Negotiating;
+        match this.inner.poll(ctx, &mut phase, &mut this.params) {
+            Poll::Ready(Ok(mut handshake)) => {
+                debug!(
+                    suite = ?handshake.suite,
+                    compression = ?handshake.compression,
+                    "handshake complete"
+                );
+                this.metrics.handshake_complete(handshake.suite, true);
+                handshake.metrics = this.metrics.clone();
+
+                Poll::Ready(Ok(handshake))
+            }
+            Poll::Ready(Err(err)) => {
+                let err = err.with_context(ErrorContext::handshake(phase));
+                debug!(error = ?err, "handshake failed");
+
+                Poll::Ready(this.metrics.error(Err(err)))
+            }
+            Poll::Pending => {
+                trace!(state = this.inner.name(), "handshake pending");
+
+                Poll::Pending
+            }
+        }
+    }
+}
+
+// ========================================== status() ========================================== \\
+
+fn status<IO>(suite: Suite, config: Config, hybrid: bool, io: IO) -> InitiateInner<IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    // The status byte gets read first, followed by the challenge if there is one.
+    InitiateInner::Status {
+        suite,
+        config,
+        hybrid,
+        status: [0; 1 + Challenge::LEN],
+        len: 1,
+        off: 0,
+        io,
+    }
+}
+
+// =========================================== write() ========================================== \\
+
+// The handshake state only gets built once the responder has answered a hybrid offer and is ready
+// for the first message.
+fn write<IO>(
+    suite: Suite,
+    config: Config,
+    hybrid: bool,
+    io: IO,
+    params: &mut Option<NoiseParams>,
+) -> Result<InitiateInner<IO>>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    let (offer, len) = config.suite_offer();
+    let id = config.suite_id(suite);
+    let (state, variant) = config.build_variant(suite, &offer[..len], id, hybrid, true)?;
+    *params = Some(variant);
+
+    // -> e     ;; 56 bytes
+    // <- e, ee ;; 72 bytes
+    let buf = vec![0; 72];
+
+    // Targeted initiators already have their static key sent, so their identity follows, along with
+    // their payload as the message gets encrypted. Otherwise, both wait for the third message.
+    let mut payload = vec![config.offer()];
+    if config.is_targeted() {
+        encode_identity(
+            config.identity(),
+            config.certificate(),
+            state.get_handshake_hash(),
+            true,
+            &mut payload,
+        );
+
+        payload.extend_from_slice(config.payload());
+    }
+
+    Ok(InitiateInner::Write {
+        suite,
+        config,
+        write: Write::new(payload, buf, io, state, PaddingPolicy::None, WireFormat::V1),
+    })
+}
+
+// =========================================== read() =========================================== \\
+
+fn read<IO>(
+    suite: Suite,
+    config: Config,
+    buf: Vec<u8>,
+    io: IO,
+    state: HandshakeState,
+) -> InitiateInner<IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    // The responder signs the handshake hash as it was before its message got written.
+    let hash = state.get_handshake_hash().to_vec();
+
+    InitiateInner::Read {
+        suite,
+        config,
+        hash,
+        read: Read::new(
+            Vec::new(),
+            buf,
+            io,
+            state,
+            PaddingPolicy::None,
+            WireFormat::V1,
+        ),
+    }
+}
+
+// ========================================= impl Debug ========================================= \\
+
+impl<IO> Debug for Initiate<IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.dump_state(), fmt)
+    }
+}
+
+// ======================================== impl Display ======================================== \\
+
+impl<IO> Display for Initiate<IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.dump_state(), fmt)
+    }
+}
+
 // ======================================== impl Default ======================================== \\
 
 impl<IO> Default for InitiateInner<IO> {
@@ -0,0 +1,335 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::send_all;
+use crate::timeout::Sleep;
+use crate::{Error, Frame, Protocol, Result, Throttle};
+use core::cmp;
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::slice;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use futures_io::AsyncWrite;
+use packets::Packet;
+use std::io;
+use std::time::Instant;
+
+// ============================================ Types =========================================== \\
+
+pub(crate) struct Liveness {
+    interval: Option<Duration>,
+    timeout: Duration,
+    last_send: Instant,
+    last_recv: Instant,
+}
+
+// Keeps sending heartbeats, along with whatever control messages are due, until the peer times out
+// or keepalives get disabled, sleeping on the protocol's timer until the next deadline in-between.
+pub struct Keepalive<'proto, Output> {
+    inner: KeepaliveInner<'proto, Output>,
+    throttle: Throttle,
+}
+
+enum KeepaliveInner<'proto, Output> {
+    Empty,
+    Check {
+        proto: &'proto mut Protocol,
+        out: Output,
+    },
+    Sleep {
+        sleep: Sleep,
+        proto: &'proto mut Protocol,
+        out: Output,
+    },
+    Write {
+        len: usize,
+        off: usize,
+        sent: Vec<(u16, usize)>,
+        proto: &'proto mut Protocol,
+        out: Output,
+    },
+    Flush {
+        proto: &'proto mut Protocol,
+        out: Output,
+    },
+}
+
+// ======================================== impl Liveness ======================================= \\
+
+impl Liveness {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new() -> Self {
+        let now = Instant::now();
+
+        Liveness {
+            interval: None,
+            timeout: Duration::from_secs(0),
+            last_send: now,
+            last_recv: now,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub(crate) fn interval(&self) -> Option<Duration> {
+        self.interval
+    }
+
+    #[inline]
+    pub(crate) fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    #[inline]
+    pub(crate) fn last_send(&self) -> Instant {
+        self.last_send
+    }
+
+    #[inline]
+    pub(crate) fn last_recv(&self) -> Instant {
+        self.last_recv
+    }
+
+    #[inline]
+    pub(crate) fn is_timed_out(&self, now: Instant) -> bool {
+        self.interval.is_some() && now.saturating_duration_since(self.last_recv) >= self.timeout
+    }
+
+    #[inline]
+    pub(crate) fn is_send_due(&self, now: Instant) -> bool {
+        self.interval.map_or(false, |interval| {
+            now.saturating_duration_since(self.last_send) >= interval
+        })
+    }
+
+    #[inline]
+    pub(crate) fn next(&self) -> Option<Instant> {
+        let interval = self.interval?;
+
+        Some(cmp::min(
+            self.last_send + interval,
+            self.last_recv + self.timeout,
+        ))
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub(crate) fn set_interval(&mut self, interval: Option<Duration>) {
+        self.interval = interval;
+    }
+
+    #[inline]
+    pub(crate) fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    #[inline]
+    pub(crate) fn sent(&mut self) {
+        self.last_send = Instant::now();
+    }
+
+    #[inline]
+    pub(crate) fn received(&mut self) {
+        self.last_recv = Instant::now();
+    }
+}
+
+// ======================================= impl Keepalive ======================================= \\
+
+impl<'proto, Output> Keepalive<'proto, Output> {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(super) fn new(proto: &'proto mut Protocol, out: Output) -> Self
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        Keepalive {
+            inner: KeepaliveInner::Check { proto, out },
+            throttle: Throttle::default(),
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Output> Future for Keepalive<'_, Output>
+where
+    Output: AsyncWrite + Unpin,
+{
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let inner = &mut this.inner;
+        loop {
+            match mem::take(inner) {
+                KeepaliveInner::Empty => panic!(),
+                KeepaliveInner::Check { proto, mut out } if !proto.session.pending.is_empty() => {
+                    let res = proto.session.pending.poll_write(ctx, &mut out);
+
+                    *inner = KeepaliveInner::Check { proto, out };
+
+                    if res?.is_pending() {
+                        return Poll::Pending;
+                    }
+                }
+                KeepaliveInner::Check { proto, out } => {
+                    let now = Instant::now();
+                    if proto.session.liveness.is_timed_out(now) {
+                        return Poll::Ready(Err(Error::PeerTimeout));
+                    }
+
+                    if !proto.session.liveness.is_send_due(now)
+                        && !proto.session.inbox.has_pongs()
+                        && !proto.session.inbox.flow.has_update()
+                        && !proto.session.inbox.flow.has_grant()
+                    {
+                        // Nothing is left to drive once keepalives get disabled.
+                        let next = match proto.session.liveness.next() {
+                            Some(next) => next,
+                            None => return Poll::Ready(Ok(())),
+                        };
+
+                        let sleep = proto.session.sleep(next.saturating_duration_since(now))?;
+
+                        *inner = KeepaliveInner::Sleep { sleep, proto, out };
+                        continue;
+                    }
+
+                    if this.throttle.poll(ctx, &mut proto.session, 0)?.is_pending() {
+                        *inner = KeepaliveInner::Check { proto, out };
+
+                        return Poll::Pending;
+                    }
+
+                    let frame = Frame::new(Packet::heartbeat());
+                    let (len, sent) = send_all::encode(slice::from_ref(&frame), proto)?;
+
+                    *inner = KeepaliveInner::Write {
+                        len,
+                        off: 0,
+                        sent,
+                        proto,
+                        out,
+                    };
+                }
+                KeepaliveInner::Sleep {
+                    mut sleep,
+                    proto,
+                    out,
+                } => {
+                    if Pin::new(&mut sleep).poll(ctx).is_ready() {
+                        *inner = KeepaliveInner::Check { proto, out };
+                    } else {
+                        *inner = KeepaliveInner::Sleep { sleep, proto, out };
+
+                        return Poll::Pending;
+                    }
+                }
+                KeepaliveInner::Write {
+                    len,
+                    off,
+                    sent,
+                    proto,
+                    out,
+                } if off >= len => {
+                    for (id, bytes) in sent {
+                        proto.session.packet_written(id, bytes);
+                    }
+
+                    proto.session.pacer.sent(len);
+                    *inner = KeepaliveInner::Flush { proto, out };
+                }
+                KeepaliveInner::Write {
+                    len,
+                    mut off,
+                    sent,
+                    proto,
+                    mut out,
+                } => match Pin::new(&mut out).poll_write(ctx, &proto.buf[off..len])? {
+                    Poll::Ready(0) => {
+                        return Poll::Ready(Err(io::Error::from(io::ErrorKind::WriteZero).into()));
+                    }
+                    Poll::Ready(wrote) => {
+                        off += wrote;
+
+                        *inner = KeepaliveInner::Write {
+                            len,
+                            off,
+                            sent,
+                            proto,
+                            out,
+                        };
+                    }
+                    Poll::Pending => {
+                        *inner = KeepaliveInner::Write {
+                            len,
+                            off,
+                            sent,
+                            proto,
+                            out,
+                        };
+
+                        return Poll::Pending;
+                    }
+                },
+                KeepaliveInner::Flush { proto, mut out } => {
+                    if Pin::new(&mut out).poll_flush(ctx)?.is_ready() {
+                        *inner = KeepaliveInner::Check { proto, out };
+                    } else {
+                        *inner = KeepaliveInner::Flush { proto, out };
+
+                        return Poll::Pending;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ========================================== impl Drop ========================================= \
+
+impl<Output> Drop for Keepalive<'_, Output> {
+    fn drop(&mut self) {
+        if let KeepaliveInner::Write {
+            len,
+            off,
+            sent,
+            proto,
+            ..
+        } = &mut self.inner
+        {
+            // The rest of the frame gets written before anything else, so it counts as sent.
+            if off < len {
+                proto.session.pending.save(&proto.buf[*off..*len]);
+
+                for (id, bytes) in sent.drain(..) {
+                    proto.session.packet_written(id, bytes);
+                }
+            }
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<Output> Default for KeepaliveInner<'_, Output> {
+    #[inline]
+    fn default() -> Self {
+        KeepaliveInner::Empty
+    }
+}
@@ -8,41 +8,210 @@
 
 // =========================================== Imports ========================================== \\
 
+#[macro_use]
+mod trace;
+
+pub mod blocking;
+pub mod testvectors;
+
+mod acceptor;
+mod ack;
+mod batch;
+mod boxed;
+mod budget;
+mod byte_stream;
+mod certificate;
+mod close;
+mod compress;
+mod config;
+mod congestion;
+mod connector;
+mod context;
+mod control;
+mod custom;
+mod datagram;
+mod dispatch;
+mod dump;
+mod duplex;
+mod engine;
+mod eyeballs;
+mod flow;
+mod frame;
+mod framed;
+mod identify;
+mod info;
 mod initiate;
+mod keepalive;
+mod limiter;
+mod manager;
+mod metrics;
+mod mux;
+mod outbox;
+mod pacing;
+mod packet_ref;
+mod packet_stream;
+mod padding;
+mod params;
+mod pending;
+mod phase;
+mod ping;
+mod pool;
+mod pow;
+mod rate;
 mod read;
+mod record;
 mod recv;
+mod recv_custom;
+mod recv_from;
+mod recv_large;
+mod recv_many;
+mod recv_ref;
+mod registry;
+mod rekey;
+mod reliable;
 mod respond;
 mod send;
+mod send_all;
+mod send_large;
+mod send_to;
+mod serve;
+mod session;
+mod shutdown;
+mod socks;
+mod split;
+mod static_key;
+mod stats;
+mod timeout;
+mod transport;
+mod typed;
+mod unknown;
+mod wipe;
+mod wire;
 mod write;
 
+pub use self::acceptor::Acceptor;
+pub use self::ack::{Ack, Nack, RecvWindow, SendWindow};
+pub use self::batch::{BatchPolicy, Batcher};
+pub use self::boxed::{AsyncIo, BoxedIo};
+pub use self::budget::ReadBudget;
+pub use self::byte_stream::ByteStream;
+pub use self::certificate::Certificate;
+pub use self::close::{Close, Reason};
+pub use self::compress::Compression;
+pub use self::config::{Config, Suite};
+pub use self::congestion::{CongestionControl, FixedRate, NewReno};
+pub use self::connector::{Connect, Connector};
+pub use self::context::ErrorContext;
+pub use self::custom::{Custom, SendCustom};
+pub use self::datagram::{DatagramProtocol, DatagramSocket};
+pub use self::dispatch::{Dispatch, Dispatcher, Subscription};
+pub use self::dump::StateDump;
+pub use self::duplex::Duplex;
+pub use self::engine::Engine;
+pub use self::eyeballs::{HappyEyeballs, Race};
+pub use self::frame::{Frame, FrameFlags};
+pub use self::info::HandshakeInfo;
 pub use self::initiate::Initiate;
+pub use self::keepalive::Keepalive;
+pub use self::limiter::HandshakeLimiter;
+pub use self::manager::{GetOrConnect, ReapIdle, SessionManager};
+pub use self::mux::{Accept, MuxStream, Muxer};
+pub use self::outbox::{Drain, Outbox, PriorityClass};
+pub use self::pacing::PacingPolicy;
+pub use self::packet_ref::PacketRef;
+pub use self::packet_stream::PacketStream;
+pub use self::padding::PaddingPolicy;
+pub use self::params::HandshakeParams;
+pub use self::phase::HandshakePhase;
+pub use self::ping::Ping;
+pub use self::pool::BufferPool;
+pub use self::rate::RateLimit;
+pub use self::record::{Direction, Record, RecordedIo, ReplayIo};
 pub use self::recv::Recv;
+pub use self::recv_custom::RecvCustom;
+pub use self::recv_from::RecvFrom;
+pub use self::recv_large::RecvLarge;
+pub use self::recv_many::RecvMany;
+pub use self::recv_ref::RecvRef;
+pub use self::registry::{Decoded, PacketRegistry, RecvDecoded};
+pub use self::rekey::{Rekey, RekeyPolicy};
+pub use self::reliable::{ReliableChannel, ReliableConfig};
 pub use self::respond::Respond;
 pub use self::send::Send;
+pub use self::send_all::SendAll;
+pub use self::send_large::SendLarge;
+pub use self::send_to::SendTo;
+pub use self::serve::{serve, Listener, Serve};
+pub use self::shutdown::Shutdown;
+pub use self::socks::SocksAddr;
+pub use self::split::{Receiver, RecvOwned, SendOwned, Sender};
+pub use self::static_key::StaticKey;
+pub use self::stats::Stats;
+pub use self::timeout::{Timeout, Timer};
+pub use self::transport::Transport;
+pub use self::typed::{RecvAs, SendPacket, TypedPacket};
+pub use self::unknown::UnknownPacketPolicy;
+pub use self::wire::WireFormat;
+pub use ed25519_dalek::{self, Keypair, PublicKey};
 pub use packets::{self, Packet};
 
+#[cfg(feature = "quic")]
+pub use self::duplex::QuicStream;
+#[cfg(feature = "metrics")]
+pub use self::metrics::ProtocolMetrics;
+#[cfg(feature = "tcp")]
+pub use self::transport::{Tcp, TcpIncoming};
+#[cfg(all(feature = "unix", unix))]
+pub use self::transport::{socket_pair, Unix, UnixIncoming};
+
+pub(crate) use self::flow::Flow;
+pub(crate) use self::framed::Framed;
+pub(crate) use self::identify::Identity;
+pub(crate) use self::keepalive::Liveness;
+pub(crate) use self::metrics::Metrics;
+pub(crate) use self::pacing::Pacer;
+pub(crate) use self::rate::{RateLimiter, Throttle};
 pub(crate) use self::read::Read;
+pub(crate) use self::rekey::Rekeyer;
+pub(crate) use self::session::Session;
+pub(crate) use self::wipe::wipe;
 pub(crate) use self::write::Write;
 
-use async_peek::AsyncPeek;
+use core::fmt::{self, Debug, Display, Formatter};
+use core::mem;
+use format::Decode;
 use futures_io::{AsyncRead, AsyncWrite};
 use packets::{MSG_MAX_LEN, NOISE_MAX_LEN};
+use snow::params::NoiseParams;
 use snow::{HandshakeState, TransportState};
 use std::io;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "thiserror")]
 use thiserror::Error;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
 // ============================================ Types =========================================== \\
 
 pub struct Handshake {
     state: HandshakeState,
+    params: NoiseParams,
+    suite: Suite,
+    compression: Compression,
+    wire: WireFormat,
+    payload: Vec<u8>,
+    identity: Option<PublicKey>,
+    certificate: Option<Certificate>,
+    metrics: Metrics,
 }
 
 pub struct Protocol {
     buf: Vec<u8>,
     msg: Vec<u8>,
     state: TransportState,
+    session: Session,
 }
 
 pub type Result<T> = core::result::Result<T, Error>;
@@ -50,16 +219,93 @@ pub type Result<T> = core::result::Result<T, Error>;
 #[derive(Debug)]
 #[cfg_attr(feature = "thiserror", derive(Error))]
 pub enum Error {
+    #[cfg_attr(feature = "thiserror", error("too many packets received while waiting for another message (max={0})"))]
+    BacklogFull(usize),
     #[cfg_attr(feature = "thiserror", error("buffer size is too small (min={min}, actual={actual})"))]
     BufferSize { min: usize, actual: usize },
+    #[cfg_attr(feature = "thiserror", error("too many handshakes in flight (max={0})"))]
+    Busy(usize),
+    #[cfg_attr(feature = "thiserror", error("session closed by the peer ({0})"))]
+    Closed(Reason),
+    #[cfg_attr(feature = "thiserror", error("compression-related error"))]
+    Compression,
+    #[cfg_attr(feature = "thiserror", error("{source} ({context})"))]
+    Context {
+        context: ErrorContext,
+        source: Box<Error>,
+    },
+    #[cfg_attr(feature = "thiserror", error("failed to decrypt a message"))]
+    Decrypt,
+    #[cfg_attr(feature = "thiserror", error("datagram exceeds the mtu (mtu={mtu}, len={len})"))]
+    ExceedsMtu { mtu: usize, len: usize },
+    #[cfg_attr(feature = "thiserror", error("frame corrupted in transit (checksum mismatch)"))]
+    FrameCorrupted,
+    #[cfg_attr(feature = "thiserror", error("invalid acknowledgment"))]
+    InvalidAck,
+    #[cfg_attr(feature = "thiserror", error("invalid delegation certificate"))]
+    InvalidCertificate,
+    #[cfg_attr(feature = "thiserror", error("invalid control message"))]
+    InvalidControl,
+    #[cfg_attr(feature = "thiserror", error("invalid reliable channel frame"))]
+    InvalidFrame,
+    #[cfg_attr(feature = "thiserror", error("invalid identity signature"))]
+    InvalidIdentity,
+    #[cfg_attr(feature = "thiserror", error("invalid cipher suite offer"))]
+    InvalidOffer,
+    #[cfg_attr(feature = "thiserror", error("invalid message padding"))]
+    InvalidPadding,
+    #[cfg_attr(feature = "thiserror", error("invalid proof-of-work"))]
+    InvalidProof,
     #[cfg_attr(feature = "thiserror", error("io-related error ({0})"))]
     Io(io::Error),
     #[cfg_attr(feature = "thiserror", error("message size is too large (max={max}, actual={actual})"))]
     MessageSize { max: usize, actual: usize },
+    #[cfg_attr(feature = "thiserror", error("a send or keepalive had to wait but no timer was set"))]
+    MissingTimer,
     #[cfg_attr(feature = "thiserror", error("noise-related error ({0})"))]
     Noise(snow::Error),
     #[cfg_attr(feature = "thiserror", error("p4ck375-related error ({0})"))]
     P4ck375(packets::Error),
+    #[cfg_attr(feature = "thiserror", error("received a packet with another id (expected={expected}, actual={actual})"))]
+    PacketIdMismatch { expected: u16, actual: u16 },
+    #[cfg_attr(feature = "thiserror", error("handshake payload rejected"))]
+    PayloadRejected,
+    #[cfg_attr(feature = "thiserror", error("connection closed by the peer"))]
+    PeerClosed,
+    #[cfg_attr(feature = "thiserror", error("peer timed out"))]
+    PeerTimeout,
+    #[cfg_attr(feature = "thiserror", error("initiator payload would be sent in plaintext"))]
+    PlaintextPayload,
+    #[cfg_attr(feature = "thiserror", error("proof-of-work is too hard (max={max}, actual={actual})"))]
+    PowDifficulty { max: u8, actual: u8 },
+    #[cfg_attr(feature = "thiserror", error("peer exceeded the receive rate limit"))]
+    RateExceeded,
+    #[cfg_attr(feature = "thiserror", error("too many large messages buffered (streams={streams}, bytes={bytes})"))]
+    ReassemblyFull { streams: usize, bytes: usize },
+    #[cfg_attr(feature = "thiserror", error("packet id is reserved (id={0})"))]
+    ReservedPacketId(u16),
+    #[cfg_attr(feature = "thiserror", error("socks5 request refused (reply={0})"))]
+    Socks(u8),
+    #[cfg_attr(feature = "thiserror", error("operation timed out"))]
+    Timeout,
+    #[cfg_attr(feature = "thiserror", error("connection closed mid-message (needed={needed}, got={got})"))]
+    UnexpectedEof { needed: usize, got: usize },
+    #[cfg_attr(feature = "thiserror", error("received an unexpected packet"))]
+    UnexpectedPacket,
+    #[cfg_attr(feature = "thiserror", error("received an unknown packet (id={id}, len={len})"))]
+    UnknownPacket { id: u16, len: usize },
+    #[cfg_attr(feature = "thiserror", error("unsupported cipher suite (id={0})"))]
+    UnsupportedSuite(u8),
+    #[cfg_attr(feature = "thiserror", error("peer's flow control window is exhausted (credit={credit}, len={len})"))]
+    WindowExhausted { credit: u64, len: usize },
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ErrorKind {
+    Fatal,
+    Transient,
+    ProtocolViolation,
+    PeerClosed,
 }
 
 // ========================================= Interfaces ========================================= \\
@@ -70,6 +316,9 @@ trait NoiseState {
     fn read_message(&mut self, buf: &[u8], msg: &mut [u8]) -> Result<usize>;
 
     fn write_message(&mut self, msg: &[u8], buf: &mut [u8]) -> Result<usize>;
+
+    // Returns the sending and receiving nonces, once the handshake is done.
+    fn nonces(&self) -> Option<(u64, u64)>;
 }
 
 // ======================================= impl Handshake ======================================= \\
@@ -84,50 +333,814 @@ impl Handshake {
     #[inline]
     pub fn initiate<IO>(io: IO) -> Initiate<IO>
     where
-        IO: AsyncPeek + AsyncRead + AsyncWrite + Unpin,
+        IO: AsyncRead + AsyncWrite + Unpin,
     {
-        Initiate::new(io)
+        Self::initiate_with(io, Config::default())
+    }
+
+    #[inline]
+    pub fn initiate_with<IO>(io: IO, config: Config) -> Initiate<IO>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        Initiate::new(io, config)
     }
 
     #[inline]
     pub fn respond<IO>(io: IO) -> Respond<IO>
     where
-        IO: AsyncPeek + AsyncRead + AsyncWrite + Unpin,
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        Self::respond_with(io, Config::default())
+    }
+
+    #[inline]
+    pub fn respond_with<IO>(io: IO, config: Config) -> Respond<IO>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
     {
-        Respond::new(io)
+        Respond::new(io, config)
+    }
+
+    pub(crate) fn new(
+        state: HandshakeState,
+        params: NoiseParams,
+        suite: Suite,
+        compression: Compression,
+        wire: WireFormat,
+        payload: Vec<u8>,
+        identity: Option<Identity>,
+    ) -> Self {
+        let (identity, certificate) = match identity {
+            Some((identity, certificate)) => (Some(identity), certificate),
+            None => (None, None),
+        };
+
+        Handshake {
+            state,
+            params,
+            suite,
+            compression,
+            wire,
+            payload,
+            identity,
+            certificate,
+            metrics: Metrics::default(),
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn suite(&self) -> Suite {
+        self.suite
+    }
+
+    #[inline]
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    #[inline]
+    pub fn wire_format(&self) -> WireFormat {
+        self.wire
+    }
+
+    #[inline]
+    pub fn remote_payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    #[inline]
+    pub fn remote_identity(&self) -> Option<&PublicKey> {
+        self.identity.as_ref()
+    }
+
+    #[inline]
+    pub fn remote_certificate(&self) -> Option<&Certificate> {
+        self.certificate.as_ref()
+    }
+
+    #[inline]
+    pub fn remote_packet(&self) -> Result<Packet> {
+        Ok(Packet::decode(&self.payload)?.0)
+    }
+
+    #[inline]
+    pub fn info(&self) -> HandshakeInfo {
+        HandshakeInfo::new(self.params.clone(), &self.state, self.payload.clone())
     }
 
     // ===================================== Destructors ==================================== \\
 
+    #[inline]
     pub fn done(self) -> Result<Protocol> {
-        Ok(Protocol {
+        Ok(self.done_with_info()?.0)
+    }
+
+    pub fn done_with_info(self) -> Result<(Protocol, HandshakeInfo)> {
+        let info = HandshakeInfo::new(self.params, &self.state, self.payload);
+        let mut session = Session::new();
+        session.inbox.compression = self.compression;
+        session.wire = self.wire;
+        session.identity = self.identity;
+        session.certificate = self.certificate;
+        session.metrics = self.metrics;
+
+        let proto = Protocol {
             buf: vec![0; NOISE_MAX_LEN],
             msg: vec![0; MSG_MAX_LEN],
             state: self.state.into_transport_mode()?,
-        })
+            session,
+        };
+
+        Ok((proto, info))
+    }
+
+    #[inline]
+    pub fn done_datagram(self) -> Result<DatagramProtocol> {
+        Ok(DatagramProtocol::new(
+            self.state.into_stateless_transport_mode()?,
+        ))
     }
 }
 
 // ======================================== impl Protocol ======================================= \\
 
 impl Protocol {
+    // ====================================== Constants ===================================== \\
+
+    pub const LARGE_MAX_LEN: usize = 16 * 1024 * 1024;
+    pub const KEEPALIVE_TIMEOUT_FACTOR: u32 = 3;
+    pub const COMPRESSION_THRESHOLD: usize = 256;
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn rekey_policy(&self) -> RekeyPolicy {
+        self.session.rekeyer.policy()
+    }
+
+    #[inline]
+    pub fn max_large_len(&self) -> usize {
+        self.session.inbox.reassembly.max()
+    }
+
+    #[inline]
+    pub fn keepalive_interval(&self) -> Option<Duration> {
+        self.session.liveness.interval()
+    }
+
+    #[inline]
+    pub fn keepalive_timeout(&self) -> Duration {
+        self.session.liveness.timeout()
+    }
+
+    #[inline]
+    pub fn next_keepalive(&self) -> Option<Instant> {
+        self.session.liveness.next()
+    }
+
+    #[inline]
+    pub fn rtt(&self) -> Option<Duration> {
+        self.session.inbox.rtt()
+    }
+
+    #[inline]
+    pub fn last_sent(&self) -> Instant {
+        self.session.liveness.last_send()
+    }
+
+    #[inline]
+    pub fn last_received(&self) -> Instant {
+        self.session.liveness.last_recv()
+    }
+
+    #[inline]
+    pub fn flow_window(&self) -> Option<u32> {
+        self.session.inbox.flow.window()
+    }
+
+    #[inline]
+    pub fn peer_flow_window(&self) -> Option<u32> {
+        self.session.inbox.flow.limit()
+    }
+
+    #[inline]
+    pub fn send_credit(&self) -> Option<u64> {
+        self.session.inbox.flow.credit()
+    }
+
+    #[inline]
+    pub fn registry(&self) -> Option<&PacketRegistry> {
+        self.session.registry.as_deref()
+    }
+
+    #[inline]
+    pub fn unknown_packet_policy(&self) -> UnknownPacketPolicy {
+        self.session.unknown
+    }
+
+    #[inline]
+    pub fn compression(&self) -> Compression {
+        self.session.inbox.compression
+    }
+
+    #[inline]
+    pub fn compression_threshold(&self) -> usize {
+        self.session.compression_threshold
+    }
+
+    #[inline]
+    pub fn padding_policy(&self) -> PaddingPolicy {
+        self.session.padding
+    }
+
+    #[inline]
+    pub fn wire_format(&self) -> WireFormat {
+        self.session.wire
+    }
+
+    #[inline]
+    pub fn remote_identity(&self) -> Option<&PublicKey> {
+        self.session.identity.as_ref()
+    }
+
+    #[inline]
+    pub fn remote_certificate(&self) -> Option<&Certificate> {
+        self.session.certificate.as_ref()
+    }
+
+    #[inline]
+    pub fn send_rate_limit(&self) -> RateLimit {
+        self.session.send_limit.limit()
+    }
+
+    #[inline]
+    pub fn recv_rate_limit(&self) -> RateLimit {
+        self.session.recv_limit.limit()
+    }
+
+    #[inline]
+    pub fn pending_write_len(&self) -> usize {
+        self.session.pending.len()
+    }
+
+    #[inline]
+    pub fn pacing_policy(&self) -> PacingPolicy {
+        self.session.pacer.policy()
+    }
+
+    #[inline]
+    pub fn read_budget(&self) -> ReadBudget {
+        self.session.read_budget
+    }
+
+    #[inline]
+    pub fn stats(&self) -> Stats {
+        let rekeys = self.session.rekeyer.rekeys() + self.session.inbox.rekeys();
+        let last_activity = self.last_sent().max(self.last_received());
+        let pacer = &self.session.pacer;
+
+        self.session
+            .stats
+            .with_activity(rekeys, last_activity)
+            .with_pacing(pacer.goodput(), pacer.rate())
+    }
+
+    pub fn dump_state(&self) -> StateDump {
+        let state = if self.session.pending.is_empty() {
+            "ready"
+        } else {
+            "pending_write"
+        };
+
+        StateDump::new("Protocol", state)
+            .with_buffered(self.session.pending.len())
+            .with_nonces(self.state.nonces())
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn into_stream<IO>(mut self, io: IO) -> ByteStream<IO>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        self.lease();
+        ByteStream::new(self, io)
+    }
+
+    #[inline]
+    pub fn into_packet_stream<IO>(mut self, io: IO) -> PacketStream<IO>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        self.lease();
+        PacketStream::new(self, io)
+    }
+
+    #[inline]
+    pub fn into_split<IO>(mut self, io: IO) -> (Sender<IO>, Receiver<IO>)
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        self.lease();
+        split::into_split(self, io)
+    }
+
+    #[inline]
+    pub fn into_engine(mut self) -> Engine {
+        self.release_buffers();
+        Engine::new(self)
+    }
+
     // ===================================== Read+Write ===================================== \\
 
+    #[inline]
+    pub fn set_rekey_policy(&mut self, policy: RekeyPolicy) {
+        self.session.rekeyer.set_policy(policy);
+    }
+
+    #[inline]
+    pub fn set_max_large_len(&mut self, max: usize) {
+        self.session.inbox.reassembly.set_max(max);
+    }
+
+    #[inline]
+    pub fn set_flow_window(&mut self, window: Option<u32>) {
+        self.session.inbox.flow.set_window(window);
+    }
+
+    #[inline]
+    pub fn set_buffer_pool(&mut self, pool: BufferPool) {
+        self.session.pool = Some(pool);
+        self.release_buffers();
+    }
+
+    #[inline]
+    pub fn release_buffers(&mut self) {
+        let buf = mem::take(&mut self.buf);
+        let msg = mem::take(&mut self.msg);
+
+        if let Some(pool) = &self.session.pool {
+            pool.put(buf);
+            pool.put(msg);
+        }
+    }
+
+    #[inline]
+    pub fn set_registry(&mut self, registry: Arc<PacketRegistry>) {
+        self.session.registry = Some(registry);
+    }
+
+    #[inline]
+    pub fn set_unknown_packet_policy(&mut self, policy: UnknownPacketPolicy) {
+        self.session.unknown = policy;
+    }
+
+    #[inline]
+    pub fn set_compression_threshold(&mut self, threshold: usize) {
+        self.session.compression_threshold = threshold;
+    }
+
+    #[inline]
+    pub fn set_padding_policy(&mut self, policy: PaddingPolicy) {
+        self.session.padding = policy;
+    }
+
+    #[inline]
+    pub fn set_send_rate_limit(&mut self, limit: RateLimit) {
+        self.session.send_limit.set_limit(limit);
+    }
+
+    #[inline]
+    pub fn set_recv_rate_limit(&mut self, limit: RateLimit) {
+        self.session.recv_limit.set_limit(limit);
+    }
+
+    #[inline]
+    pub fn set_pacing_policy(&mut self, policy: PacingPolicy) {
+        self.session.pacer.set_policy(policy);
+    }
+
+    // Sends delayed by a rate limit or pacing, and keepalives between deadlines, wait on timers
+    // created with `Tmr`, and fail with `Error::MissingTimer` if none was set.
+    #[inline]
+    pub fn set_timer<Tmr>(&mut self)
+    where
+        Tmr: Timer + core::marker::Send + 'static,
+    {
+        self.session.timer = Some(timeout::sleep::<Tmr>);
+    }
+
+    #[inline]
+    pub fn set_read_budget(&mut self, budget: ReadBudget) {
+        self.session.read_budget = budget;
+    }
+
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub fn set_metrics(&mut self, metrics: Arc<dyn ProtocolMetrics>) {
+        self.session.metrics = Metrics::new(metrics);
+    }
+
+    #[inline]
+    pub fn keepalive(&mut self, interval: Duration) {
+        self.session.liveness.set_interval(Some(interval));
+        self.session.liveness.set_timeout(interval * Self::KEEPALIVE_TIMEOUT_FACTOR);
+    }
+
+    #[inline]
+    pub fn set_keepalive_timeout(&mut self, timeout: Duration) {
+        self.session.liveness.set_timeout(timeout);
+    }
+
+    #[inline]
+    pub fn disable_keepalive(&mut self) {
+        self.session.liveness.set_interval(None);
+    }
+
+    #[inline]
+    pub fn drive_keepalive<Output>(&mut self, output: Output) -> Keepalive<Output>
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        self.lease();
+        Keepalive::new(self, output)
+    }
+
+    #[inline]
+    pub fn ping<IO>(&mut self, io: IO) -> Ping<IO>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        self.lease();
+        Ping::new(self, io)
+    }
+
+    #[inline]
+    pub fn rekey_send<Output>(&mut self, output: Output) -> Rekey<Output>
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        self.lease();
+        Rekey::new(self, output)
+    }
+
+    #[inline]
+    pub fn rekey_recv(&mut self) {
+        self.state.rekey_incoming();
+    }
+
+    #[inline]
+    pub fn close<Output>(&mut self, output: Output, reason: Reason) -> Close<Output>
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        self.lease();
+        Close::new(&reason, self, output)
+    }
+
+    #[inline]
+    pub fn shutdown<Output>(&mut self, output: Output) -> Shutdown<Output>
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        Shutdown::new(output)
+    }
+
     #[inline]
     pub fn send<Output>(&mut self, output: Output, packet: Packet) -> Send<Output>
     where
         Output: AsyncWrite + Unpin,
     {
+        self.lease();
         Send::new(packet, self, output)
     }
 
+    #[inline]
+    pub fn send_ref<'proto, Output>(
+        &'proto mut self,
+        output: Output,
+        packet: &'proto Packet,
+    ) -> Send<'proto, Output>
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        self.lease();
+        Send::borrowed(packet, self, output)
+    }
+
+    #[inline]
+    pub fn send_frame<Output>(&mut self, output: Output, frame: Frame) -> Send<Output>
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        let (packet, flags) = frame.into_parts();
+        self.send(output, packet).with_flags(flags)
+    }
+
+    #[inline]
+    pub fn send_all<Output, Packets>(&mut self, output: Output, packets: Packets) -> SendAll<Output>
+    where
+        Output: AsyncWrite + Unpin,
+        Packets: IntoIterator<Item = Packet>,
+    {
+        self.lease();
+        SendAll::new(packets.into_iter().collect(), self, output)
+    }
+
+    #[inline]
+    pub fn send_batch<Output>(&mut self, output: Output, batcher: &mut Batcher) -> SendAll<Output>
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        self.lease();
+        SendAll::new(batcher.take(), self, output)
+    }
+
+    #[inline]
+    pub fn drain_outbox<Output>(&mut self, output: Output, outbox: &Outbox) -> Drain<Output>
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        self.lease();
+        Drain::new(outbox.clone(), self, output)
+    }
+
+    #[inline]
+    pub fn send_custom<Output>(&mut self, output: Output, custom: &Custom) -> SendCustom<Output>
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        self.lease();
+        SendCustom::new(custom, self, output)
+    }
+
+    #[inline]
+    pub fn send_packet<'proto, Output, Typed>(
+        &'proto mut self,
+        output: Output,
+        packet: &Typed,
+    ) -> SendPacket<'proto, Output>
+    where
+        Output: AsyncWrite + Unpin,
+        Typed: TypedPacket,
+    {
+        self.lease();
+        SendPacket::new(packet, self, output)
+    }
+
     #[inline]
     pub fn recv<Input>(&mut self, input: Input) -> Recv<Input>
     where
-        Input: AsyncPeek + AsyncRead + Unpin,
+        Input: AsyncRead + Unpin,
     {
+        self.lease();
         Recv::new(self, input)
     }
+
+    #[inline]
+    pub fn recv_matching<'proto, Input, Match>(
+        &'proto mut self,
+        input: Input,
+        filter: Match,
+    ) -> Recv<'proto, Input>
+    where
+        Input: AsyncRead + Unpin,
+        Match: Fn(u16) -> bool + core::marker::Send + 'proto,
+    {
+        self.lease();
+        Recv::new(self, input).matching(filter)
+    }
+
+    #[inline]
+    pub fn dispatch<'disp, Input>(
+        &mut self,
+        input: Input,
+        dispatcher: &'disp mut Dispatcher,
+    ) -> Dispatch<'_, 'disp, Input>
+    where
+        Input: AsyncRead + Unpin,
+    {
+        self.lease();
+        Dispatch::new(dispatcher, self, input)
+    }
+
+    #[inline]
+    pub fn recv_many<'out, Input>(
+        &mut self,
+        input: Input,
+        packets: &'out mut Vec<Packet>,
+        max: usize,
+    ) -> RecvMany<'_, 'out, Input>
+    where
+        Input: AsyncRead + Unpin,
+    {
+        self.lease();
+        RecvMany::new(packets, max, self, input)
+    }
+
+    #[inline]
+    pub fn recv_custom<Input>(&mut self, input: Input) -> RecvCustom<Input>
+    where
+        Input: AsyncRead + Unpin,
+    {
+        self.lease();
+        RecvCustom::new(self, input)
+    }
+
+    #[inline]
+    pub fn recv_as<Typed, Input>(&mut self, input: Input) -> RecvAs<Input, Typed>
+    where
+        Input: AsyncRead + Unpin,
+        Typed: TypedPacket,
+    {
+        self.lease();
+        RecvAs::new(self, input)
+    }
+
+    #[inline]
+    pub fn recv_decoded<Input>(&mut self, input: Input) -> RecvDecoded<Input>
+    where
+        Input: AsyncRead + Unpin,
+    {
+        self.lease();
+        RecvDecoded::new(self, input)
+    }
+
+    #[inline]
+    pub fn send_large<'data, Output>(
+        &mut self,
+        output: Output,
+        data: &'data [u8],
+    ) -> SendLarge<'_, 'data, Output>
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        self.lease();
+        SendLarge::new(data, self, output)
+    }
+
+    #[inline]
+    pub fn recv_large<Input>(&mut self, input: Input) -> RecvLarge<Input>
+    where
+        Input: AsyncRead + Unpin,
+    {
+        self.lease();
+        RecvLarge::new(self, input)
+    }
+
+    #[inline]
+    pub fn recv_ref<Input>(&mut self, input: Input) -> RecvRef<Input>
+    where
+        Input: AsyncRead + Unpin,
+    {
+        self.lease();
+        RecvRef::new(self, input)
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    #[inline]
+    fn lease(&mut self) {
+        if let Some(pool) = &self.session.pool {
+            if self.buf.capacity() == 0 {
+                self.buf = pool.take();
+            }
+
+            if self.msg.capacity() == 0 {
+                self.msg = pool.take();
+            }
+        }
+    }
+}
+
+// ========================================== impl Drop ========================================= \\
+
+#[cfg(feature = "zeroize")]
+impl Drop for Protocol {
+    #[inline]
+    fn drop(&mut self) {
+        self.buf.zeroize();
+        self.msg.zeroize();
+    }
+}
+
+// ========================================= impl Error ========================================= \\
+
+impl Error {
+    // ====================================== Read-only ===================================== \\
+
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Io(error) => match error.kind() {
+                io::ErrorKind::WouldBlock
+                | io::ErrorKind::Interrupted
+                | io::ErrorKind::TimedOut => ErrorKind::Transient,
+                io::ErrorKind::UnexpectedEof
+                | io::ErrorKind::ConnectionReset
+                | io::ErrorKind::ConnectionAborted
+                | io::ErrorKind::BrokenPipe
+                | io::ErrorKind::WriteZero => ErrorKind::PeerClosed,
+                _ => ErrorKind::Fatal,
+            },
+            Error::Context { source, .. } => source.kind(),
+            Error::Closed(_) | Error::PeerClosed | Error::UnexpectedEof { .. } => {
+                ErrorKind::PeerClosed
+            }
+            Error::Busy(_)
+            | Error::PeerTimeout
+            | Error::Socks(_)
+            | Error::Timeout
+            | Error::WindowExhausted { .. } => ErrorKind::Transient,
+            Error::Compression
+            | Error::InvalidAck
+            | Error::InvalidCertificate
+            | Error::InvalidControl
+            | Error::InvalidFrame
+            | Error::InvalidIdentity
+            | Error::InvalidOffer
+            | Error::InvalidPadding
+            | Error::InvalidProof
+            | Error::MessageSize { .. }
+            | Error::P4ck375(_)
+            | Error::PacketIdMismatch { .. }
+            | Error::PayloadRejected
+            | Error::PowDifficulty { .. }
+            | Error::RateExceeded
+            | Error::ReassemblyFull { .. }
+            | Error::UnexpectedPacket
+            | Error::UnknownPacket { .. }
+            | Error::UnsupportedSuite(_) => ErrorKind::ProtocolViolation,
+            Error::BacklogFull(_)
+            | Error::BufferSize { .. }
+            | Error::Decrypt
+            | Error::ExceedsMtu { .. }
+            | Error::FrameCorrupted
+            | Error::MissingTimer
+            | Error::Noise(_)
+            | Error::PlaintextPayload
+            | Error::ReservedPacketId(_) => ErrorKind::Fatal,
+        }
+    }
+
+    #[inline]
+    pub fn is_retryable(&self) -> bool {
+        self.kind() == ErrorKind::Transient
+    }
+
+    #[inline]
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Error::Context { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::Context { source, .. } => source.root(),
+            error => error,
+        }
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn into_root(self) -> Error {
+        match self {
+            Error::Context { source, .. } => source.into_root(),
+            error => error,
+        }
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    // Only io, noise, frame and eof errors are wrapped, as the other ones are already specific
+    // enough.
+    pub(crate) fn with_context(self, context: ErrorContext) -> Self {
+        match self {
+            Error::Context {
+                context: inner,
+                source,
+            } => Error::Context {
+                context: inner.merge(context),
+                source,
+            },
+            Error::Decrypt
+            | Error::FrameCorrupted
+            | Error::Io(_)
+            | Error::Noise(_)
+            | Error::UnexpectedEof { .. } => Error::Context {
+                context,
+                source: Box::new(self),
+            },
+            error => error,
+        }
+    }
 }
 
 // ======================================= impl NoiseState ====================================== \\
@@ -144,6 +1157,11 @@ impl NoiseState for HandshakeState {
     fn write_message(&mut self, msg: &[u8], buf: &mut [u8]) -> Result<usize> {
         Ok(self.write_message(msg, buf)?)
     }
+
+    #[inline]
+    fn nonces(&self) -> Option<(u64, u64)> {
+        None
+    }
 }
 
 impl NoiseState for TransportState {
@@ -158,6 +1176,11 @@ impl NoiseState for TransportState {
     fn write_message(&mut self, msg: &[u8], buf: &mut [u8]) -> Result<usize> {
         Ok(self.write_message(msg, buf)?)
     }
+
+    #[inline]
+    fn nonces(&self) -> Option<(u64, u64)> {
+        Some((self.sending_nonce(), self.receiving_nonce()))
+    }
 }
 
 impl<State: NoiseState> NoiseState for &mut State {
@@ -172,6 +1195,11 @@ impl<State: NoiseState> NoiseState for &mut State {
     fn write_message(&mut self, msg: &[u8], buf: &mut [u8]) -> Result<usize> {
         State::write_message(*self, msg, buf)
     }
+
+    #[inline]
+    fn nonces(&self) -> Option<(u64, u64)> {
+        State::nonces(*self)
+    }
 }
 
 // ========================================== impl From ========================================= \\
@@ -186,7 +1214,10 @@ impl From<io::Error> for Error {
 impl From<snow::Error> for Error {
     #[inline]
     fn from(error: snow::Error) -> Self {
-        Error::Noise(error)
+        match error {
+            snow::Error::Decrypt => Error::Decrypt,
+            error => Error::Noise(error),
+        }
     }
 }
 
@@ -196,3 +1227,21 @@ impl From<packets::Error> for Error {
         Error::P4ck375(error)
     }
 }
+
+// ========================================= impl Debug ========================================= \\
+
+impl Debug for Protocol {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.dump_state(), fmt)
+    }
+}
+
+// ======================================== impl Display ======================================== \\
+
+impl Display for Protocol {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.dump_state(), fmt)
+    }
+}
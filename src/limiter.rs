@@ -0,0 +1,208 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// A `HandshakeLimiter` is a semaphore shared by every responder built with the same `Config`. A
+// responder only starts reading from its IO once it got a slot, waiting in a FIFO queue for one
+// to be released if none are available, or failing with `Error::Busy` if the queue is full too.
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Error, Result};
+use core::fmt::{self, Debug, Formatter};
+use core::task::{Context, Poll, Waker};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone)]
+pub struct HandshakeLimiter {
+    max: usize,
+    max_queued: usize,
+    shared: Arc<Mutex<Shared>>,
+}
+
+struct Shared {
+    active: usize,
+    queue: VecDeque<(u64, Waker)>,
+    next: u64,
+}
+
+pub(crate) struct Acquire {
+    limiter: HandshakeLimiter,
+    ticket: Option<u64>,
+}
+
+pub(crate) struct Slot {
+    limiter: HandshakeLimiter,
+}
+
+// ==================================== impl HandshakeLimiter =================================== \\
+
+impl HandshakeLimiter {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new(max: usize) -> Self {
+        HandshakeLimiter {
+            max: max.max(1),
+            max_queued: 0,
+            shared: Arc::new(Mutex::new(Shared {
+                active: 0,
+                queue: VecDeque::new(),
+                next: 0,
+            })),
+        }
+    }
+
+    #[inline]
+    pub fn with_max_queued(mut self, max_queued: usize) -> Self {
+        self.max_queued = max_queued;
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    #[inline]
+    pub fn max_queued(&self) -> usize {
+        self.max_queued
+    }
+
+    #[inline]
+    pub fn active(&self) -> usize {
+        self.shared.lock().unwrap().active
+    }
+
+    #[inline]
+    pub fn queued(&self) -> usize {
+        self.shared.lock().unwrap().queue.len()
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub(crate) fn acquire(&self) -> Acquire {
+        Acquire {
+            limiter: self.clone(),
+            ticket: None,
+        }
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    fn release(&self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.active -= 1;
+        shared.wake_next();
+    }
+}
+
+// ========================================= impl Shared ======================================== \\
+
+impl Shared {
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    fn wake_next(&mut self) {
+        if let Some((_, waker)) = self.queue.front() {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+// ======================================== impl Acquire ======================================== \\
+
+impl Acquire {
+    // ===================================== Read+Write ===================================== \\
+
+    pub(crate) fn poll(&mut self, ctx: &mut Context) -> Poll<Result<Slot>> {
+        let limiter = &self.limiter;
+        let mut shared = limiter.shared.lock().unwrap();
+
+        let first = match self.ticket {
+            Some(ticket) => matches!(shared.queue.front(), Some((front, _)) if *front == ticket),
+            None => shared.queue.is_empty(),
+        };
+
+        if first && shared.active < limiter.max {
+            if self.ticket.take().is_some() {
+                shared.queue.pop_front();
+            }
+
+            shared.active += 1;
+
+            return Poll::Ready(Ok(Slot {
+                limiter: limiter.clone(),
+            }));
+        }
+
+        match self.ticket {
+            Some(ticket) => {
+                if let Some((_, waker)) = shared.queue.iter_mut().find(|(id, _)| *id == ticket) {
+                    *waker = ctx.waker().clone();
+                }
+            }
+            None if shared.queue.len() >= limiter.max_queued => {
+                return Poll::Ready(Err(Error::Busy(limiter.max)));
+            }
+            None => {
+                let ticket = shared.next;
+                shared.next += 1;
+                shared.queue.push_back((ticket, ctx.waker().clone()));
+
+                self.ticket = Some(ticket);
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+// ========================================= impl Debug ========================================= \\
+
+impl Debug for HandshakeLimiter {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let shared = self.shared.lock().unwrap();
+        fmt.debug_struct("HandshakeLimiter")
+            .field("max", &self.max)
+            .field("max_queued", &self.max_queued)
+            .field("active", &shared.active)
+            .field("queued", &shared.queue.len())
+            .finish()
+    }
+}
+
+// ========================================== impl Drop ========================================= \\
+
+impl Drop for Acquire {
+    fn drop(&mut self) {
+        let ticket = match self.ticket {
+            Some(ticket) => ticket,
+            None => return,
+        };
+
+        // Hands the turn over to the next responder if this one was about to get a slot.
+        let mut shared = self.limiter.shared.lock().unwrap();
+        shared.queue.retain(|(id, _)| *id != ticket);
+        if shared.active < self.limiter.max {
+            shared.wake_next();
+        }
+    }
+}
+
+impl Drop for Slot {
+    #[inline]
+    fn drop(&mut self) {
+        self.limiter.release();
+    }
+}
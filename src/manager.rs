@@ -0,0 +1,448 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Connector, Error, Handshake, Initiate, Protocol, Reason, Result, Timer};
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use ed25519_dalek::{PublicKey, PUBLIC_KEY_LENGTH};
+use futures_io::{AsyncRead, AsyncWrite};
+use std::collections::HashMap;
+use std::io;
+use std::time::Instant;
+
+// ============================================ Types =========================================== \\
+
+pub struct SessionManager<IO> {
+    connector: Connector,
+    sessions: Sessions<IO>,
+}
+
+pub struct GetOrConnect<'mgr, Addr, Dial, Fut, IO, Tmr> {
+    connector: &'mgr Connector,
+    sessions: Option<&'mgr mut Sessions<IO>>,
+    id: PublicKey,
+    addrs: &'mgr [Addr],
+    next: usize,
+    dial: Dial,
+    error: Option<Error>,
+    inner: GetOrConnectInner<Fut, IO, Tmr>,
+}
+
+pub struct ReapIdle {
+    reaped: usize,
+    close: Pin<Box<dyn Future<Output = ()> + Send>>,
+}
+
+struct Sessions<IO> {
+    entries: HashMap<[u8; PUBLIC_KEY_LENGTH], Entry<IO>>,
+    evicted: Vec<Entry<IO>>,
+    max_sessions: usize,
+    idle_timeout: Duration,
+}
+
+struct Entry<IO> {
+    proto: Protocol,
+    io: IO,
+    last_used: Instant,
+}
+
+enum GetOrConnectInner<Fut, IO, Tmr> {
+    Empty,
+    Next,
+    Dial { fut: Pin<Box<Fut>>, timer: Tmr },
+    Handshake { initiate: Initiate<IO>, timer: Tmr },
+    Done,
+}
+
+// ===================================== impl SessionManager ==================================== \\
+
+impl<IO> SessionManager<IO> {
+    // ====================================== Constants ===================================== \\
+
+    pub const DEFAULT_MAX_SESSIONS: usize = 256;
+    pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new(connector: Connector) -> Self {
+        SessionManager {
+            connector,
+            sessions: Sessions {
+                entries: HashMap::new(),
+                evicted: Vec::new(),
+                max_sessions: Self::DEFAULT_MAX_SESSIONS,
+                idle_timeout: Self::DEFAULT_IDLE_TIMEOUT,
+            },
+        }
+    }
+
+    #[inline]
+    pub fn with_max_sessions(mut self, max_sessions: usize) -> Self {
+        self.sessions.max_sessions = max_sessions.max(1);
+        self
+    }
+
+    #[inline]
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.sessions.idle_timeout = idle_timeout;
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn connector(&self) -> &Connector {
+        &self.connector
+    }
+
+    #[inline]
+    pub fn max_sessions(&self) -> usize {
+        self.sessions.max_sessions
+    }
+
+    #[inline]
+    pub fn idle_timeout(&self) -> Duration {
+        self.sessions.idle_timeout
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.sessions.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.sessions.entries.is_empty()
+    }
+
+    #[inline]
+    pub fn contains(&self, id: &PublicKey) -> bool {
+        self.sessions.entries.contains_key(id.as_bytes())
+    }
+
+    #[inline]
+    pub fn evicted(&self) -> usize {
+        self.sessions.evicted.len()
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub fn get(&mut self, id: &PublicKey) -> Option<(&mut Protocol, &mut IO)> {
+        self.sessions.get(id)
+    }
+
+    #[inline]
+    pub fn insert(&mut self, id: &PublicKey, proto: Protocol, io: IO) -> (&mut Protocol, &mut IO) {
+        self.sessions.insert(id, proto, io)
+    }
+
+    #[inline]
+    pub fn remove(&mut self, id: &PublicKey) -> Option<(Protocol, IO)> {
+        let entry = self.sessions.entries.remove(id.as_bytes())?;
+        Some((entry.proto, entry.io))
+    }
+
+    // Evicts the sessions that have been idle for longer than the idle timeout, returning how many
+    // were. Evicted sessions, including those evicted to make room for new ones, are kept until
+    // either `reap_idle` closes them or `take_evicted` hands them over.
+    #[inline]
+    pub fn evict_idle(&mut self) -> usize {
+        self.sessions.evict_idle()
+    }
+
+    #[inline]
+    pub fn take_evicted(&mut self) -> Vec<(Protocol, IO)> {
+        self.sessions
+            .evicted
+            .drain(..)
+            .map(|entry| (entry.proto, entry.io))
+            .collect()
+    }
+
+    // Removes the sessions that have been idle for longer than the idle timeout, along with those
+    // which were already evicted, and returns a future closing them with `Reason::IDLE_TIMEOUT`,
+    // which can be spawned as a background task.
+    pub fn reap_idle(&mut self) -> ReapIdle
+    where
+        IO: AsyncWrite + Unpin + Send + 'static,
+    {
+        self.sessions.evict_idle();
+
+        ReapIdle::new(mem::take(&mut self.sessions.evicted))
+    }
+
+    pub fn get_or_connect<'mgr, Addr, Dial, Fut, Tmr>(
+        &'mgr mut self,
+        id: &PublicKey,
+        addrs: &'mgr [Addr],
+        dial: Dial,
+    ) -> GetOrConnect<'mgr, Addr, Dial, Fut, IO, Tmr>
+    where
+        Dial: FnMut(&Addr) -> Fut,
+        Fut: Future<Output = io::Result<IO>>,
+        IO: AsyncRead + AsyncWrite + Unpin,
+        Tmr: Timer,
+    {
+        self.sessions.evict_idle();
+
+        let inner = if self.sessions.entries.contains_key(id.as_bytes()) {
+            GetOrConnectInner::Done
+        } else {
+            GetOrConnectInner::Next
+        };
+
+        GetOrConnect {
+            connector: &self.connector,
+            sessions: Some(&mut self.sessions),
+            id: *id,
+            addrs,
+            next: 0,
+            dial,
+            error: None,
+            inner,
+        }
+    }
+}
+
+// ======================================== impl Sessions ======================================= \\
+
+impl<IO> Sessions<IO> {
+    // ===================================== Read+Write ===================================== \\
+
+    fn get(&mut self, id: &PublicKey) -> Option<(&mut Protocol, &mut IO)> {
+        let entry = self.entries.get_mut(id.as_bytes())?;
+        entry.last_used = Instant::now();
+
+        Some((&mut entry.proto, &mut entry.io))
+    }
+
+    fn insert(&mut self, id: &PublicKey, proto: Protocol, io: IO) -> (&mut Protocol, &mut IO) {
+        let key = *id.as_bytes();
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.max_sessions {
+            self.evict_idle();
+        }
+
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.max_sessions {
+            let lru = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| *key);
+
+            if let Some(entry) = lru.and_then(|lru| self.entries.remove(&lru)) {
+                self.evicted.push(entry);
+            }
+        }
+
+        let entry = Entry {
+            proto,
+            io,
+            last_used: Instant::now(),
+        };
+
+        self.entries.insert(key, entry);
+        let entry = self.entries.get_mut(&key).unwrap();
+
+        (&mut entry.proto, &mut entry.io)
+    }
+
+    fn evict_idle(&mut self) -> usize {
+        let idle_timeout = self.idle_timeout;
+        let idle = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.idle() >= idle_timeout)
+            .map(|(key, _)| *key)
+            .collect::<Vec<_>>();
+
+        for key in &idle {
+            if let Some(entry) = self.entries.remove(key) {
+                self.evicted.push(entry);
+            }
+        }
+
+        idle.len()
+    }
+}
+
+// ========================================= impl Entry ========================================= \\
+
+impl<IO> Entry<IO> {
+    // ====================================== Read-only ===================================== \\
+
+    // A session is idle if it has neither been used through the manager, nor sent or received
+    // anything since.
+    #[inline]
+    fn idle(&self) -> Duration {
+        self.last_used
+            .max(self.proto.stats().last_activity())
+            .elapsed()
+    }
+}
+
+// ======================================== impl ReapIdle ======================================= \\
+
+impl ReapIdle {
+    // ==================================== Constructors ==================================== \\
+
+    fn new<IO>(entries: Vec<Entry<IO>>) -> Self
+    where
+        IO: AsyncWrite + Unpin + Send + 'static,
+    {
+        let reaped = entries.len();
+        let close = Box::pin(async move {
+            for mut entry in entries {
+                let reason = Reason::new(Reason::IDLE_TIMEOUT);
+                let _ = entry.proto.close(&mut entry.io, reason).await;
+            }
+        });
+
+        ReapIdle { reaped, close }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn reaped(&self) -> usize {
+        self.reaped
+    }
+}
+
+// ====================================== impl GetOrConnect ===================================== \\
+
+impl<'mgr, Addr, Dial, Fut, IO, Tmr> GetOrConnect<'mgr, Addr, Dial, Fut, IO, Tmr> {
+    // ======================================= Helpers ====================================== \\
+
+    fn fail(&mut self, error: Error) {
+        self.error = Some(error);
+        self.inner = GetOrConnectInner::Next;
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<'mgr, Addr, Dial, Fut, IO, Tmr> Future for GetOrConnect<'mgr, Addr, Dial, Fut, IO, Tmr>
+where
+    Dial: FnMut(&Addr) -> Fut + Unpin,
+    Fut: Future<Output = io::Result<IO>>,
+    IO: AsyncRead + AsyncWrite + Unpin,
+    Tmr: Timer,
+{
+    type Output = Result<(&'mgr mut Protocol, &'mgr mut IO)>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            match mem::take(&mut this.inner) {
+                GetOrConnectInner::Empty => panic!(),
+                GetOrConnectInner::Done => {
+                    let sessions = this.sessions.take().unwrap();
+
+                    return Poll::Ready(Ok(sessions.get(&this.id).unwrap()));
+                }
+                GetOrConnectInner::Next => {
+                    let addr = match this.addrs.get(this.next) {
+                        Some(addr) => addr,
+                        None => {
+                            let error = this.error.take().unwrap_or_else(|| {
+                                io::Error::from(io::ErrorKind::AddrNotAvailable).into()
+                            });
+
+                            return Poll::Ready(Err(error));
+                        }
+                    };
+
+                    this.next += 1;
+                    this.inner = GetOrConnectInner::Dial {
+                        fut: Box::pin((this.dial)(addr)),
+                        timer: Tmr::after(this.connector.timeout()),
+                    };
+                }
+                GetOrConnectInner::Dial { mut fut, mut timer } => match fut.as_mut().poll(ctx) {
+                    Poll::Ready(Ok(io)) => {
+                        this.inner = GetOrConnectInner::Handshake {
+                            initiate: Handshake::initiate_with(io, this.connector.config().clone()),
+                            timer,
+                        };
+                    }
+                    Poll::Ready(Err(error)) => this.fail(error.into()),
+                    Poll::Pending => {
+                        if Pin::new(&mut timer).poll(ctx).is_ready() {
+                            this.fail(Error::Timeout);
+                        } else {
+                            this.inner = GetOrConnectInner::Dial { fut, timer };
+
+                            return Poll::Pending;
+                        }
+                    }
+                },
+                GetOrConnectInner::Handshake {
+                    mut initiate,
+                    mut timer,
+                } => match Pin::new(&mut initiate).poll(ctx) {
+                    Poll::Ready(Ok(handshake)) if handshake.remote_identity() != Some(&this.id) => {
+                        this.fail(Error::InvalidIdentity);
+                    }
+                    Poll::Ready(Ok(handshake)) => {
+                        let io = initiate.done();
+                        let proto = match handshake.done() {
+                            Ok(proto) => proto,
+                            Err(error) => {
+                                this.fail(error);
+                                continue;
+                            }
+                        };
+
+                        let sessions = this.sessions.take().unwrap();
+
+                        return Poll::Ready(Ok(sessions.insert(&this.id, proto, io)));
+                    }
+                    Poll::Ready(Err(error)) => this.fail(error),
+                    Poll::Pending => {
+                        if Pin::new(&mut timer).poll(ctx).is_ready() {
+                            this.fail(Error::Timeout);
+                        } else {
+                            this.inner = GetOrConnectInner::Handshake { initiate, timer };
+
+                            return Poll::Pending;
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl Future for ReapIdle {
+    type Output = usize;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match this.close.as_mut().poll(ctx) {
+            Poll::Ready(()) => Poll::Ready(this.reaped),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<Fut, IO, Tmr> Default for GetOrConnectInner<Fut, IO, Tmr> {
+    #[inline]
+    fn default() -> Self {
+        GetOrConnectInner::Empty
+    }
+}
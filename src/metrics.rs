@@ -0,0 +1,83 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::unknown;
+use crate::{Error, Result, Suite};
+use std::sync::Arc;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Default)]
+pub(crate) struct Metrics {
+    inner: Option<Arc<dyn ProtocolMetrics>>,
+}
+
+// ========================================= Interfaces ========================================= \\
+
+pub trait ProtocolMetrics: Send + Sync {
+    fn on_handshake_complete(&self, _suite: Suite, _initiator: bool) {}
+
+    fn on_packet_sent(&self, _id: u16, _bytes: usize) {}
+
+    fn on_packet_received(&self, _id: u16, _bytes: usize) {}
+
+    fn on_error(&self, _error: &Error) {}
+}
+
+// ======================================== impl Metrics ======================================== \\
+
+impl Metrics {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    pub(crate) fn new(metrics: Arc<dyn ProtocolMetrics>) -> Self {
+        Metrics {
+            inner: Some(metrics),
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    #[inline]
+    pub(crate) fn handshake_complete(&self, suite: Suite, initiator: bool) {
+        if let Some(metrics) = &self.inner {
+            metrics.on_handshake_complete(suite, initiator);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn packet_sent(&self, id: u16, len: usize) {
+        if let Some(metrics) = &self.inner {
+            metrics.on_packet_sent(id, len);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn packet_received(&self, msg: &[u8]) {
+        if let Some(metrics) = &self.inner {
+            metrics.on_packet_received(unknown::id(msg), msg.len());
+        }
+    }
+
+    #[inline]
+    pub(crate) fn error<T>(&self, res: Result<T>) -> Result<T> {
+        if let (Some(metrics), Err(error)) = (&self.inner, &res) {
+            metrics.on_error(error);
+        }
+
+        res
+    }
+}
@@ -0,0 +1,528 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// Every message sent over a multiplexed session is a frame:
+//
+// OPEN   ;; kind(1) + stream(4)
+// DATA   ;; kind(1) + stream(4) + data
+// WINDOW ;; kind(1) + stream(4) + credit(4)
+// CLOSE  ;; kind(1) + stream(4)
+//
+// Streams opened by the initiator have odd ids, the ones opened by the responder have even ids.
+// Every stream starts with a window of `Muxer::INITIAL_WINDOW` bytes in both directions.
+//
+// Every task waiting for a frame is parked, and the IO is polled with a waker that wakes up all the
+// parked tasks (and only them) once it is ready. Frames are then only dispatched to the stream
+// they are for.
+
+// =========================================== Imports ========================================== \\
+
+use crate::byte_stream::into_io;
+use crate::{Error, Framed, Protocol, Result};
+use core::cmp;
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use futures_io::{AsyncRead, AsyncWrite};
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::task::Wake;
+
+// ========================================== Constants ========================================= \\
+
+const OPEN: u8 = 0;
+const DATA: u8 = 1;
+const WINDOW: u8 = 2;
+const CLOSE: u8 = 3;
+
+const FRAME_OVERHEAD: usize = 5;
+const INITIAL_WINDOW: u32 = 256 * 1024;
+
+// ============================================ Types =========================================== \\
+
+pub struct Muxer<IO> {
+    shared: Arc<Mutex<Shared<IO>>>,
+}
+
+pub struct MuxStream<IO> {
+    id: u32,
+    shared: Arc<Mutex<Shared<IO>>>,
+}
+
+pub struct Accept<'mux, IO> {
+    muxer: &'mux Muxer<IO>,
+}
+
+struct Shared<IO> {
+    framed: Framed<IO>,
+    streams: HashMap<u32, StreamState>,
+    next_id: u32,
+    incoming: VecDeque<u32>,
+    accept: Option<Waker>,
+    eof: bool,
+    broken: bool,
+    frame: Vec<u8>,
+    parked: Arc<Parked>,
+}
+
+#[derive(Default)]
+struct Parked {
+    wakers: Mutex<VecDeque<Waker>>,
+}
+
+#[derive(Default)]
+struct StreamState {
+    inp: VecDeque<u8>,
+    recv_window: u32,
+    consumed: u32,
+    send_window: u32,
+    reader: Option<Waker>,
+    writer: Option<Waker>,
+    local_closed: bool,
+    remote_closed: bool,
+}
+
+// ========================================= impl Muxer ========================================= \\
+
+impl<IO> Muxer<IO> {
+    // ====================================== Constants ===================================== \\
+
+    pub const INITIAL_WINDOW: u32 = INITIAL_WINDOW;
+
+    // ==================================== Constructors ==================================== \\
+
+    pub fn new(proto: Protocol, io: IO) -> Self
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        let framed = Framed::new(proto, io);
+        let next_id = if framed.is_initiator() { 1 } else { 2 };
+
+        Muxer {
+            shared: Arc::new(Mutex::new(Shared {
+                framed,
+                streams: HashMap::new(),
+                next_id,
+                incoming: VecDeque::new(),
+                accept: None,
+                eof: false,
+                broken: false,
+                frame: Vec::new(),
+                parked: Arc::new(Parked::default()),
+            })),
+        }
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    pub fn open_stream(&self) -> Result<MuxStream<IO>> {
+        let mut shared = self.shared.lock().unwrap();
+        if shared.broken {
+            return Err(io::Error::from(io::ErrorKind::BrokenPipe).into());
+        }
+
+        let id = shared.next_id;
+        shared.next_id = shared.next_id.wrapping_add(2);
+        shared.streams.insert(id, StreamState::new());
+        shared.write_frame(OPEN, id, &[])?;
+
+        Ok(MuxStream {
+            id,
+            shared: self.shared.clone(),
+        })
+    }
+
+    #[inline]
+    pub fn accept_stream(&self) -> Accept<IO> {
+        Accept { muxer: self }
+    }
+}
+
+// ======================================= impl MuxStream ======================================= \\
+
+impl<IO> MuxStream<IO> {
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+// ========================================= impl Shared ======================================== \\
+
+impl<IO> Shared<IO> {
+    // ===================================== Read+Write ===================================== \\
+
+    fn write_frame(&mut self, kind: u8, id: u32, data: &[u8]) -> Result<()> {
+        self.frame.clear();
+        self.frame.push(kind);
+        self.frame.extend_from_slice(&id.to_le_bytes());
+        self.frame.extend_from_slice(data);
+
+        self.framed.write_msg(&self.frame)
+    }
+
+    fn poll_frame(&mut self, ctx: &mut Context) -> Poll<io::Result<()>>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        if self.broken {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+
+        // Pending writes (eg. window updates) are sent as soon as possible.
+        if let Poll::Ready(Err(err)) = self.framed.poll_flush(ctx) {
+            return Poll::Ready(Err(self.fail(err)));
+        }
+
+        // The task is parked before polling the IO, so that it can't miss a wake-up happening
+        // in between.
+        self.parked.park(ctx.waker());
+        let waker = Waker::from(self.parked.clone());
+        let poll = self.framed.poll_read_msg(&mut Context::from_waker(&waker));
+        if poll.is_ready() {
+            self.parked.unpark(ctx.waker());
+        }
+
+        let len = match poll {
+            Poll::Ready(Ok(Some(len))) => len,
+            Poll::Ready(Ok(None)) => {
+                self.eof = true;
+                for stream in self.streams.values_mut() {
+                    stream.remote_closed = true;
+                }
+
+                self.wake_all();
+                return Poll::Ready(Ok(()));
+            }
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(self.fail(err))),
+            Poll::Pending => return Poll::Pending,
+        };
+
+        match self.dispatch(len) {
+            Ok(()) => Poll::Ready(Ok(())),
+            Err(err) => Poll::Ready(Err(self.fail(err))),
+        }
+    }
+
+    fn dispatch(&mut self, len: usize) -> Result<()> {
+        let msg = &self.framed.msg()[..len];
+        if msg.len() < FRAME_OVERHEAD {
+            return Err(Error::InvalidControl);
+        }
+
+        let id = u32::from_le_bytes([msg[1], msg[2], msg[3], msg[4]]);
+        let data = &msg[FRAME_OVERHEAD..];
+
+        match msg[0] {
+            OPEN if !self.streams.contains_key(&id) => {
+                self.streams.insert(id, StreamState::new());
+                self.incoming.push_back(id);
+                if let Some(waker) = self.accept.take() {
+                    waker.wake();
+                }
+            }
+            DATA => {
+                if let Some(stream) = self.streams.get_mut(&id) {
+                    if data.len() > stream.recv_window as usize {
+                        return Err(Error::InvalidControl);
+                    }
+
+                    stream.recv_window -= data.len() as u32;
+                    stream.inp.extend(data);
+                    stream.wake_reader();
+                }
+            }
+            WINDOW if data.len() == 4 => {
+                if let Some(stream) = self.streams.get_mut(&id) {
+                    let credit = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+                    stream.send_window = stream.send_window.saturating_add(credit);
+                    stream.wake_writer();
+                }
+            }
+            CLOSE => {
+                if let Some(stream) = self.streams.get_mut(&id) {
+                    stream.remote_closed = true;
+                    stream.wake_reader();
+                    stream.wake_writer();
+                }
+            }
+            _ => return Err(Error::InvalidControl),
+        }
+
+        Ok(())
+    }
+
+    fn fail(&mut self, err: Error) -> io::Error {
+        self.broken = true;
+        self.wake_all();
+
+        into_io(err)
+    }
+
+    fn wake_all(&mut self) {
+        self.parked.wake_all();
+        if let Some(waker) = self.accept.take() {
+            waker.wake();
+        }
+
+        for stream in self.streams.values_mut() {
+            stream.wake_reader();
+            stream.wake_writer();
+        }
+    }
+}
+
+// ========================================= impl Parked ======================================== \\
+
+impl Parked {
+    // ===================================== Read+Write ===================================== \\
+
+    fn park(&self, waker: &Waker) {
+        let mut wakers = self.wakers.lock().unwrap();
+        if !wakers.iter().any(|parked| parked.will_wake(waker)) {
+            wakers.push_back(waker.clone());
+        }
+    }
+
+    fn unpark(&self, waker: &Waker) {
+        self.wakers
+            .lock()
+            .unwrap()
+            .retain(|parked| !parked.will_wake(waker));
+    }
+
+    fn wake_all(&self) {
+        let wakers = mem::take(&mut *self.wakers.lock().unwrap());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+// ====================================== impl StreamState ====================================== \\
+
+impl StreamState {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    fn new() -> Self {
+        StreamState {
+            recv_window: INITIAL_WINDOW,
+            send_window: INITIAL_WINDOW,
+            ..StreamState::default()
+        }
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    fn wake_reader(&mut self) {
+        if let Some(waker) = self.reader.take() {
+            waker.wake();
+        }
+    }
+
+    #[inline]
+    fn wake_writer(&mut self) {
+        if let Some(waker) = self.writer.take() {
+            waker.wake();
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<IO> Future for Accept<'_, IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    type Output = Result<Option<MuxStream<IO>>>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let muxer = self.muxer;
+        let mut shared = muxer.shared.lock().unwrap();
+        loop {
+            if let Some(id) = shared.incoming.pop_front() {
+                return Poll::Ready(Ok(Some(MuxStream {
+                    id,
+                    shared: muxer.shared.clone(),
+                })));
+            } else if shared.eof {
+                return Poll::Ready(Ok(None));
+            }
+
+            match shared.poll_frame(ctx) {
+                Poll::Ready(Ok(())) => (),
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err.into())),
+                Poll::Pending => {
+                    shared.accept = Some(ctx.waker().clone());
+
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+// ======================================= impl AsyncRead ======================================= \\
+
+impl<IO> AsyncRead for MuxStream<IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        ctx: &mut Context,
+        out: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let id = self.id;
+        let mut shared = self.shared.lock().unwrap();
+        loop {
+            let stream = shared.streams.get_mut(&id).unwrap();
+            if !stream.inp.is_empty() {
+                let len = cmp::min(out.len(), stream.inp.len());
+                for (dst, src) in out.iter_mut().zip(stream.inp.drain(..len)) {
+                    *dst = src;
+                }
+
+                stream.consumed += len as u32;
+                if stream.consumed >= INITIAL_WINDOW / 2 {
+                    let credit = stream.consumed;
+                    stream.recv_window += credit;
+                    stream.consumed = 0;
+
+                    shared
+                        .write_frame(WINDOW, id, &credit.to_le_bytes())
+                        .map_err(into_io)?;
+                }
+
+                return Poll::Ready(Ok(len));
+            } else if stream.remote_closed {
+                return Poll::Ready(Ok(0));
+            }
+
+            match shared.poll_frame(ctx) {
+                Poll::Ready(Ok(())) => (),
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => {
+                    shared.streams.get_mut(&id).unwrap().reader = Some(ctx.waker().clone());
+
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+// ======================================= impl AsyncWrite ====================================== \\
+
+impl<IO> AsyncWrite for MuxStream<IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(self: Pin<&mut Self>, ctx: &mut Context, data: &[u8]) -> Poll<io::Result<usize>> {
+        let id = self.id;
+        let mut shared = self.shared.lock().unwrap();
+        if data.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        match shared.framed.poll_drain(ctx) {
+            Poll::Ready(Ok(())) => (),
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(shared.fail(err))),
+            Poll::Pending => return Poll::Pending,
+        }
+
+        let max = shared.framed.max_msg_len() - FRAME_OVERHEAD;
+        loop {
+            let stream = shared.streams.get_mut(&id).unwrap();
+            if stream.local_closed {
+                return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+            } else if stream.send_window > 0 {
+                let len = cmp::min(data.len(), max);
+                let len = cmp::min(len, stream.send_window as usize);
+                stream.send_window -= len as u32;
+
+                shared
+                    .write_frame(DATA, id, &data[..len])
+                    .map_err(into_io)?;
+
+                return Poll::Ready(Ok(len));
+            } else if shared.eof {
+                return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+            }
+
+            match shared.poll_frame(ctx) {
+                Poll::Ready(Ok(())) => (),
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => {
+                    shared.streams.get_mut(&id).unwrap().writer = Some(ctx.waker().clone());
+
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<io::Result<()>> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.framed.poll_flush(ctx) {
+            Poll::Ready(Err(err)) => Poll::Ready(Err(shared.fail(err))),
+            poll => poll.map(|_| Ok(())),
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<io::Result<()>> {
+        let id = self.id;
+        {
+            let mut shared = self.shared.lock().unwrap();
+            let stream = shared.streams.get_mut(&id).unwrap();
+            if !stream.local_closed {
+                stream.local_closed = true;
+                shared.write_frame(CLOSE, id, &[]).map_err(into_io)?;
+            }
+        }
+
+        self.poll_flush(ctx)
+    }
+}
+
+// ========================================== impl Wake ========================================= \\
+
+impl Wake for Parked {
+    #[inline]
+    fn wake(self: Arc<Self>) {
+        self.wake_all();
+    }
+
+    #[inline]
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.wake_all();
+    }
+}
+
+// ========================================== impl Drop ========================================= \\
+
+impl<IO> Drop for MuxStream<IO> {
+    fn drop(&mut self) {
+        let mut shared = match self.shared.lock() {
+            Ok(shared) => shared,
+            Err(_) => return,
+        };
+
+        if let Some(stream) = shared.streams.remove(&self.id) {
+            if !stream.local_closed && !shared.broken {
+                let _ = shared.write_frame(CLOSE, self.id, &[]);
+            }
+        }
+    }
+}
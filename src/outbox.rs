@@ -0,0 +1,410 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// An outbox queues packets for a single writer task, which sends them in order of priority class
+// (control, then realtime, then bulk) and in FIFO order within a class. Pending pongs and window
+// updates are always sent before the next packet (see send_all.rs). A packet which doesn't fit in
+// the peer's window is put back at the front of its class and the drain fails.
+
+// =========================================== Imports ========================================== \\
+
+use crate::send_all;
+use crate::{Error, Frame, Protocol, Result, Throttle};
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::slice;
+use core::task::{Context, Poll, Waker};
+use futures_io::AsyncWrite;
+use packets::Packet;
+use std::collections::VecDeque;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PriorityClass {
+    Control,
+    Realtime,
+    Bulk,
+}
+
+#[derive(Clone, Default)]
+pub struct Outbox {
+    shared: Arc<Mutex<Shared>>,
+}
+
+#[derive(Default)]
+struct Shared {
+    queues: [VecDeque<Frame>; 3],
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+pub struct Drain<'proto, Output> {
+    inner: DrainInner<'proto, Output>,
+    outbox: Outbox,
+    throttle: Throttle,
+}
+
+enum DrainInner<'proto, Output> {
+    Empty,
+    Next {
+        wrote: usize,
+        proto: &'proto mut Protocol,
+        out: Output,
+    },
+    Write {
+        wrote: usize,
+        len: usize,
+        off: usize,
+        sent: Vec<(u16, usize)>,
+        proto: &'proto mut Protocol,
+        out: Output,
+    },
+    Flush {
+        wrote: usize,
+        proto: &'proto mut Protocol,
+        out: Output,
+    },
+    Done,
+}
+
+// ===================================== impl PriorityClass ===================================== \\
+
+impl PriorityClass {
+    // ====================================== Constants ===================================== \\
+
+    pub const ALL: [PriorityClass; 3] = [
+        PriorityClass::Control,
+        PriorityClass::Realtime,
+        PriorityClass::Bulk,
+    ];
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn frame_priority(self) -> u8 {
+        match self {
+            PriorityClass::Control => 7,
+            PriorityClass::Realtime => 4,
+            PriorityClass::Bulk => 0,
+        }
+    }
+
+    #[inline]
+    fn idx(self) -> usize {
+        self as usize
+    }
+}
+
+// ========================================= impl Outbox ======================================== \\
+
+impl Outbox {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new() -> Self {
+        Outbox::default()
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        let shared = self.shared.lock().unwrap();
+        shared.queues.iter().map(VecDeque::len).sum()
+    }
+
+    #[inline]
+    pub fn len_of(&self, class: PriorityClass) -> usize {
+        self.shared.lock().unwrap().queues[class.idx()].len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.shared.lock().unwrap().closed
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub fn push(&self, class: PriorityClass, packet: Packet) -> bool {
+        let frame = Frame::new(packet).with_priority(class.frame_priority());
+        self.push_frame(class, frame)
+    }
+
+    pub fn push_frame(&self, class: PriorityClass, frame: Frame) -> bool {
+        let mut shared = self.shared.lock().unwrap();
+        if shared.closed {
+            return false;
+        }
+
+        shared.queues[class.idx()].push_back(frame);
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+
+        true
+    }
+
+    #[inline]
+    pub fn pop(&self) -> Option<Frame> {
+        self.shared.lock().unwrap().pop()
+    }
+
+    // Queued packets are still sent once the outbox is closed.
+    pub fn close(&self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.closed = true;
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn poll_pop(&self, ctx: &mut Context) -> Poll<Option<(usize, Frame)>> {
+        let mut shared = self.shared.lock().unwrap();
+        if let Some(popped) = shared.pop_indexed() {
+            Poll::Ready(Some(popped))
+        } else if shared.closed {
+            Poll::Ready(None)
+        } else {
+            shared.waker = Some(ctx.waker().clone());
+            Poll::Pending
+        }
+    }
+
+    // Puts back a frame that couldn't be sent, ahead of the rest of its class.
+    #[inline]
+    fn requeue(&self, idx: usize, frame: Frame) {
+        self.shared.lock().unwrap().queues[idx].push_front(frame);
+    }
+}
+
+// ========================================= impl Shared ======================================== \\
+
+impl Shared {
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    fn pop(&mut self) -> Option<Frame> {
+        self.pop_indexed().map(|(_, frame)| frame)
+    }
+
+    #[inline]
+    fn pop_indexed(&mut self) -> Option<(usize, Frame)> {
+        self.queues
+            .iter_mut()
+            .enumerate()
+            .find_map(|(idx, queue)| Some((idx, queue.pop_front()?)))
+    }
+}
+
+// ========================================= impl Drain ========================================= \\
+
+impl<'proto, Output> Drain<'proto, Output> {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(super) fn new(outbox: Outbox, proto: &'proto mut Protocol, out: Output) -> Self
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        Drain {
+            inner: DrainInner::Next {
+                wrote: 0,
+                proto,
+                out,
+            },
+            outbox,
+            throttle: Throttle::default(),
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Output> Future for Drain<'_, Output>
+where
+    Output: AsyncWrite + Unpin,
+{
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let inner = &mut this.inner;
+        loop {
+            match mem::take(inner) {
+                DrainInner::Empty | DrainInner::Done => panic!(),
+                DrainInner::Next {
+                    wrote,
+                    proto,
+                    mut out,
+                } if !proto.session.pending.is_empty() => {
+                    let res = proto.session.pending.poll_write(ctx, &mut out);
+
+                    *inner = DrainInner::Next { wrote, proto, out };
+
+                    if res?.is_pending() {
+                        return Poll::Pending;
+                    }
+                }
+                DrainInner::Next { wrote, proto, out } => {
+                    if this.throttle.poll(ctx, &mut proto.session, 0)?.is_pending() {
+                        *inner = DrainInner::Next { wrote, proto, out };
+
+                        return Poll::Pending;
+                    }
+
+                    match this.outbox.poll_pop(ctx) {
+                        Poll::Ready(Some((idx, frame))) => {
+                            let (len, sent) = match send_all::encode(slice::from_ref(&frame), proto)
+                            {
+                                Ok(encoded) => encoded,
+                                Err(err @ Error::WindowExhausted { .. }) => {
+                                    this.outbox.requeue(idx, frame);
+
+                                    return Poll::Ready(Err(err));
+                                }
+                                Err(err) => return Poll::Ready(Err(err)),
+                            };
+
+                            *inner = DrainInner::Write {
+                                wrote,
+                                len,
+                                off: 0,
+                                sent,
+                                proto,
+                                out,
+                            };
+                        }
+                        Poll::Ready(None) => {
+                            *inner = DrainInner::Done;
+
+                            return Poll::Ready(Ok(wrote));
+                        }
+                        Poll::Pending => {
+                            *inner = DrainInner::Next { wrote, proto, out };
+
+                            return Poll::Pending;
+                        }
+                    }
+                }
+                DrainInner::Write {
+                    wrote,
+                    len,
+                    off,
+                    sent,
+                    proto,
+                    out,
+                } if off >= len => {
+                    for (id, bytes) in sent {
+                        proto.session.packet_written(id, bytes);
+                    }
+
+                    proto.session.pacer.sent(len);
+                    *inner = DrainInner::Flush {
+                        wrote: wrote + len,
+                        proto,
+                        out,
+                    };
+                }
+                DrainInner::Write {
+                    wrote,
+                    len,
+                    mut off,
+                    sent,
+                    proto,
+                    mut out,
+                } => match Pin::new(&mut out).poll_write(ctx, &proto.buf[off..len])? {
+                    Poll::Ready(0) => {
+                        *inner = DrainInner::Done;
+
+                        return Poll::Ready(Err(io::Error::from(io::ErrorKind::WriteZero).into()));
+                    }
+                    Poll::Ready(written) => {
+                        off += written;
+
+                        *inner = DrainInner::Write {
+                            wrote,
+                            len,
+                            off,
+                            sent,
+                            proto,
+                            out,
+                        };
+                    }
+                    Poll::Pending => {
+                        *inner = DrainInner::Write {
+                            wrote,
+                            len,
+                            off,
+                            sent,
+                            proto,
+                            out,
+                        };
+
+                        return Poll::Pending;
+                    }
+                },
+                DrainInner::Flush {
+                    wrote,
+                    proto,
+                    mut out,
+                } => {
+                    if Pin::new(&mut out).poll_flush(ctx)?.is_ready() {
+                        *inner = DrainInner::Next { wrote, proto, out };
+                    } else {
+                        *inner = DrainInner::Flush { wrote, proto, out };
+
+                        return Poll::Pending;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ========================================== impl Drop ========================================= \\
+
+impl<Output> Drop for Drain<'_, Output> {
+    fn drop(&mut self) {
+        if let DrainInner::Write {
+            len,
+            off,
+            sent,
+            proto,
+            ..
+        } = &mut self.inner
+        {
+            // The rest of the frame gets written before anything else, so it counts as sent.
+            if off < len {
+                proto.session.pending.save(&proto.buf[*off..*len]);
+
+                for (id, bytes) in sent.drain(..) {
+                    proto.session.packet_written(id, bytes);
+                }
+            }
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<Output> Default for DrainInner<'_, Output> {
+    #[inline]
+    fn default() -> Self {
+        DrainInner::Empty
+    }
+}
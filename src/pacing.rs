@@ -0,0 +1,174 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use core::time::Duration;
+use std::time::Instant;
+
+// ========================================== Constants ========================================= \\
+
+// How long goodput is accumulated before being folded into the estimate.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+// Gaps longer than this are treated as the application being idle rather than the link being
+// slow, so that they don't drag the estimate down.
+const IDLE_INTERVAL: Duration = Duration::from_secs(1);
+const SMOOTHING: f64 = 0.125;
+// When auto-pacing, writes are spaced slightly faster than the estimate so it can grow.
+const AUTO_GAIN: f64 = 1.25;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PacingPolicy {
+    Disabled,
+    Fixed(u64),
+    Auto,
+}
+
+pub(crate) struct Pacer {
+    policy: PacingPolicy,
+    next: Instant,
+    start: Instant,
+    last: Instant,
+    bytes: u64,
+    goodput: Option<f64>,
+}
+
+// ====================================== impl PacingPolicy ===================================== \\
+
+impl PacingPolicy {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub const fn bytes_per_sec(bytes: u64) -> Self {
+        PacingPolicy::Fixed(bytes)
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn is_enabled(self) -> bool {
+        self != PacingPolicy::Disabled
+    }
+}
+
+// ========================================= impl Pacer ========================================= \\
+
+impl Pacer {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new(policy: PacingPolicy) -> Self {
+        let now = Instant::now();
+        Pacer {
+            policy,
+            next: now,
+            start: now,
+            last: now,
+            bytes: 0,
+            goodput: None,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub(crate) fn policy(&self) -> PacingPolicy {
+        self.policy
+    }
+
+    #[inline]
+    pub(crate) fn goodput(&self) -> Option<u64> {
+        self.goodput.map(|goodput| goodput as u64)
+    }
+
+    #[inline]
+    pub(crate) fn rate(&self) -> Option<u64> {
+        self.rate_f64().map(|rate| rate as u64)
+    }
+
+    pub(crate) fn delay(&self) -> Option<Duration> {
+        self.rate_f64()?;
+
+        let now = Instant::now();
+        if self.next > now {
+            Some(self.next - now)
+        } else {
+            None
+        }
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub(crate) fn set_policy(&mut self, policy: PacingPolicy) {
+        self.policy = policy;
+        self.next = Instant::now();
+    }
+
+    pub(crate) fn sent(&mut self, len: usize) {
+        let now = Instant::now();
+        self.sample(now, len);
+
+        if let Some(rate) = self.rate_f64() {
+            let spacing = Duration::from_secs_f64(len as f64 / rate);
+            self.next = self.next.max(now) + spacing;
+        }
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    fn rate_f64(&self) -> Option<f64> {
+        match self.policy {
+            PacingPolicy::Disabled => None,
+            PacingPolicy::Fixed(rate) => Some(rate.max(1) as f64),
+            PacingPolicy::Auto => self.goodput.map(|goodput| (goodput * AUTO_GAIN).max(1.0)),
+        }
+    }
+
+    fn sample(&mut self, now: Instant, len: usize) {
+        if now.duration_since(self.last) >= IDLE_INTERVAL {
+            self.start = now;
+            self.bytes = 0;
+        }
+
+        self.last = now;
+        self.bytes += len as u64;
+
+        let elapsed = now.duration_since(self.start);
+        if elapsed < SAMPLE_INTERVAL {
+            return;
+        }
+
+        let sample = self.bytes as f64 / elapsed.as_secs_f64();
+        self.goodput = Some(match self.goodput {
+            Some(goodput) => goodput + SMOOTHING * (sample - goodput),
+            None => sample,
+        });
+
+        self.start = now;
+        self.bytes = 0;
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl Default for PacingPolicy {
+    #[inline]
+    fn default() -> Self {
+        PacingPolicy::Disabled
+    }
+}
+
+impl Default for Pacer {
+    #[inline]
+    fn default() -> Self {
+        Pacer::new(PacingPolicy::default())
+    }
+}
@@ -0,0 +1,55 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::unknown;
+use crate::Result;
+use packets::Packet;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PacketRef<'proto> {
+    bytes: &'proto [u8],
+}
+
+// ======================================= impl PacketRef ======================================= \\
+
+impl<'proto> PacketRef<'proto> {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new(bytes: &'proto [u8]) -> Self {
+        PacketRef { bytes }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn as_bytes(&self) -> &'proto [u8] {
+        self.bytes
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn to_owned(&self) -> Result<Packet> {
+        unknown::decode(self.bytes)
+    }
+}
@@ -0,0 +1,112 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{send, Error, Framed, Protocol, Result};
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_core::Stream;
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_sink::Sink;
+use packets::Packet;
+
+// ============================================ Types =========================================== \\
+
+pub struct PacketStream<IO> {
+    framed: Framed<IO>,
+    msg: Vec<u8>,
+}
+
+// ====================================== impl PacketStream ===================================== \\
+
+impl<IO> PacketStream<IO> {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(super) fn new(proto: Protocol, io: IO) -> Self {
+        PacketStream {
+            framed: Framed::new(proto, io),
+            msg: Vec::new(),
+        }
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn done(self) -> (Protocol, IO) {
+        self.framed.done()
+    }
+}
+
+// ========================================= impl Stream ======================================== \\
+
+impl<IO> Stream for PacketStream<IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    type Item = Result<Packet>;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<Self::Item>> {
+        let framed = &mut self.get_mut().framed;
+        loop {
+            let len = match framed.poll_read_msg(ctx) {
+                Poll::Ready(Ok(Some(len))) => len,
+                Poll::Ready(Ok(None)) => return Poll::Ready(None),
+                Poll::Ready(Err(err)) => return Poll::Ready(Some(Err(err))),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            if let Err(err) = framed.packet_received(len) {
+                return Poll::Ready(Some(Err(err)));
+            }
+
+            let policy = framed.unknown_packet_policy();
+            if let Some(packet) = policy.decode(&framed.msg()[..len]).transpose() {
+                return Poll::Ready(Some(packet));
+            }
+        }
+    }
+}
+
+// ========================================== impl Sink ========================================= \\
+
+impl<IO> Sink<Packet> for PacketStream<IO>
+where
+    IO: AsyncWrite + Unpin,
+{
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Result<()>> {
+        let framed = &mut self.get_mut().framed;
+        if framed.poll_drain(ctx)?.is_pending() {
+            return Poll::Pending;
+        }
+
+        framed.poll_pace(ctx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, packet: Packet) -> Result<()> {
+        let this = self.get_mut();
+        let bytes = send::encode(&packet, &mut this.msg)?;
+        this.framed.write_msg(&this.msg[..bytes])?;
+        this.framed.packet_sent(&this.msg[..bytes]);
+
+        Ok(())
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Result<()>> {
+        self.get_mut().framed.poll_flush(ctx)
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Result<()>> {
+        self.get_mut().framed.poll_close(ctx)
+    }
+}
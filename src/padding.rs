@@ -0,0 +1,103 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// When a padding policy is set (it must be set on both ends), every message is padded with zeros
+// before being encrypted:
+//
+// MESSAGE ;; payload + zeros(padding) + padding(2)
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Error, Result};
+use packets::MSG_MAX_LEN;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PaddingPolicy {
+    None,
+    PadToMultiple(usize),
+    PadToFixed(usize),
+}
+
+// ===================================== impl PaddingPolicy ===================================== \\
+
+impl PaddingPolicy {
+    // ====================================== Constants ===================================== \\
+
+    pub const OVERHEAD: usize = 2;
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn overhead(self) -> usize {
+        match self {
+            PaddingPolicy::None => 0,
+            _ => Self::OVERHEAD,
+        }
+    }
+
+    #[inline]
+    pub fn max_len(self) -> usize {
+        MSG_MAX_LEN - self.overhead()
+    }
+
+    pub fn padded_len(self, len: usize) -> usize {
+        let len = len + self.overhead();
+        let padded = match self {
+            PaddingPolicy::None => len,
+            PaddingPolicy::PadToMultiple(multiple) if multiple > 1 => {
+                len.saturating_add(multiple - 1) / multiple * multiple
+            }
+            PaddingPolicy::PadToMultiple(_) => len,
+            PaddingPolicy::PadToFixed(fixed) => len.max(fixed),
+        };
+
+        padded.min(MSG_MAX_LEN).max(len)
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    // Writes `msg` and its padding at the start of `padded`, which must be at least
+    // `padded_len(msg.len())` bytes long, and returns how many bytes were written.
+    pub(crate) fn pad(self, msg: &[u8], padded: &mut [u8]) -> usize {
+        let padded_len = self.padded_len(msg.len());
+        let len = padded_len - Self::OVERHEAD;
+
+        padded[..msg.len()].copy_from_slice(msg);
+        for byte in &mut padded[msg.len()..len] {
+            *byte = 0;
+        }
+
+        padded[len..padded_len].copy_from_slice(&((len - msg.len()) as u16).to_le_bytes());
+
+        padded_len
+    }
+
+    pub(crate) fn unpad(self, msg: &[u8]) -> Result<usize> {
+        if self == PaddingPolicy::None {
+            return Ok(msg.len());
+        } else if msg.len() < Self::OVERHEAD {
+            return Err(Error::InvalidPadding);
+        }
+
+        let len = msg.len() - Self::OVERHEAD;
+        let padding = u16::from_le_bytes([msg[len], msg[len + 1]]) as usize;
+
+        len.checked_sub(padding).ok_or(Error::InvalidPadding)
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl Default for PaddingPolicy {
+    #[inline]
+    fn default() -> Self {
+        PaddingPolicy::None
+    }
+}
@@ -0,0 +1,99 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// The params of every variant of every suite's handshake are parsed once, and stored by suite in
+// the following order:
+//
+// NN, XN, IK [, NNhfs, XNhfs, IKhfs] ;; hybrid variants only with the `pq` feature
+
+// =========================================== Imports ========================================== \\
+
+use crate::Suite;
+use snow::params::NoiseParams;
+use std::sync::{Arc, OnceLock};
+
+// ========================================== Constants ========================================= \\
+
+#[cfg(feature = "pq")]
+const HYBRID: [bool; 2] = [false, true];
+#[cfg(not(feature = "pq"))]
+const HYBRID: [bool; 1] = [false];
+
+const VARIANTS: usize = 3 * HYBRID.len();
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Debug)]
+pub struct HandshakeParams {
+    params: Arc<[NoiseParams]>,
+}
+
+// ==================================== impl HandshakeParams ==================================== \\
+
+impl HandshakeParams {
+    // ==================================== Constructors ==================================== \\
+
+    pub fn new() -> Self {
+        let mut params = Vec::with_capacity(Suite::ALL.len() * VARIANTS);
+        for suite in Suite::ALL.iter() {
+            for &hybrid in HYBRID.iter() {
+                params.push(suite.variant_params(false, false, hybrid));
+                params.push(suite.variant_params(false, true, hybrid));
+                params.push(suite.variant_params(true, true, hybrid));
+            }
+        }
+
+        HandshakeParams {
+            params: params.into(),
+        }
+    }
+
+    // Shared by every handshake whose config doesn't have its own params, and by `Suite`'s
+    // getters.
+    pub(crate) fn shared() -> &'static Self {
+        static SHARED: OnceLock<HandshakeParams> = OnceLock::new();
+        SHARED.get_or_init(HandshakeParams::new)
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn get(&self, suite: Suite) -> &NoiseParams {
+        self.variant(suite, false, false, false)
+    }
+
+    pub(crate) fn variant(
+        &self,
+        suite: Suite,
+        targeted: bool,
+        identified: bool,
+        hybrid: bool,
+    ) -> &NoiseParams {
+        let mut idx = suite.id() as usize * VARIANTS;
+        if targeted {
+            idx += 2;
+        } else if identified {
+            idx += 1;
+        }
+
+        if hybrid {
+            idx += 3;
+        }
+
+        &self.params[idx]
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl Default for HandshakeParams {
+    #[inline]
+    fn default() -> Self {
+        HandshakeParams::new()
+    }
+}
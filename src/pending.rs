@@ -0,0 +1,78 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// When a send is dropped (e.g. because it timed out) after its frame was encrypted but before it
+// was fully written, the rest of the frame is copied here, as the write buffer is shared with
+// receives. The next send writes it out before anything else so that the stream stays in sync.
+
+// =========================================== Imports ========================================== \\
+
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::AsyncWrite;
+use std::io;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Default)]
+pub(crate) struct Pending {
+    bytes: Vec<u8>,
+}
+
+// ======================================== impl Pending ======================================== \\
+
+impl Pending {
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub(crate) fn save(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    #[inline]
+    pub(crate) fn take(&mut self) -> Vec<u8> {
+        mem::take(&mut self.bytes)
+    }
+
+    pub(crate) fn poll_write<Output>(
+        &mut self,
+        ctx: &mut Context,
+        out: &mut Output,
+    ) -> Poll<io::Result<()>>
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        while !self.bytes.is_empty() {
+            match Pin::new(&mut *out).poll_write(ctx, &self.bytes)? {
+                Poll::Ready(0) => {
+                    return Poll::Ready(Err(io::Error::from(io::ErrorKind::WriteZero)));
+                }
+                Poll::Ready(wrote) => {
+                    trace!(wrote, left = self.bytes.len() - wrote, "resumed pending write");
+                    self.bytes.drain(..wrote);
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,47 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// The phases a handshake goes through, from either side:
+//
+// INITIATOR                          RESPONDER
+// Negotiating     -> offer        -> Negotiating
+// AwaitingStatus  <- choice(1)    <- SendingStatus
+//                 <- status       <-
+// SendingProof    -> proof        -> AwaitingProof   ;; only if a proof-of-work is required
+// SendingE        -> e            -> AwaitingE
+// AwaitingEe      <- e, ee        <- SendingEe
+// Identifying     -> s, se        -> Identifying     ;; only if the initiator has an identity (XN)
+// Done                               Done
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HandshakePhase {
+    Negotiating,
+    SendingE,
+    AwaitingE,
+    SendingStatus,
+    AwaitingStatus,
+    SendingProof,
+    AwaitingProof,
+    SendingEe,
+    AwaitingEe,
+    Identifying,
+    Done,
+}
+
+// ===================================== impl HandshakePhase ==================================== \\
+
+impl HandshakePhase {
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn is_done(self) -> bool {
+        self == HandshakePhase::Done
+    }
+}
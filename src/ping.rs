@@ -0,0 +1,233 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::control::Control;
+use crate::{wipe, Protocol, Read, Result, Session, Write};
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use futures_io::{AsyncRead, AsyncWrite};
+use snow::TransportState;
+
+// ============================================ Types =========================================== \\
+
+pub struct Ping<'proto, IO> {
+    inner: PingInner<'proto, IO>,
+}
+
+enum PingInner<'proto, IO> {
+    Empty,
+    Write {
+        nonce: u64,
+        write: Write<IO, &'proto mut TransportState, &'proto mut Vec<u8>>,
+        session: &'proto mut Session,
+    },
+    Flush {
+        nonce: u64,
+        msg: &'proto mut Vec<u8>,
+        buf: &'proto mut Vec<u8>,
+        io: IO,
+        state: &'proto mut TransportState,
+        session: &'proto mut Session,
+    },
+    Read {
+        nonce: u64,
+        read: Read<IO, &'proto mut TransportState, &'proto mut Vec<u8>>,
+        session: &'proto mut Session,
+        escaped: bool,
+    },
+}
+
+// ========================================== impl Ping ========================================= \\
+
+impl<'proto, IO> Ping<'proto, IO> {
+    // ==================================== Constructors ==================================== \\
+
+    pub(super) fn new(proto: &'proto mut Protocol, io: IO) -> Self
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        let nonce = proto.session.inbox.next_nonce();
+        Control::Ping(nonce).encode(&mut proto.msg);
+
+        Ping {
+            inner: PingInner::Write {
+                nonce,
+                write: Write::control(
+                    &mut proto.msg,
+                    &mut proto.buf,
+                    io,
+                    &mut proto.state,
+                    proto.session.padding,
+                    proto.session.wire,
+                ),
+                session: &mut proto.session,
+            },
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<IO> Future for Ping<'_, IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    type Output = Result<Duration>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let inner = &mut self.get_mut().inner;
+        loop {
+            match mem::take(inner) {
+                PingInner::Empty => panic!(),
+                PingInner::Write {
+                    nonce,
+                    mut write,
+                    session,
+                } => {
+                    if Pin::new(&mut write).poll(ctx)?.is_ready() {
+                        let (msg, buf, io, state) = write.done();
+                        session.liveness.sent();
+
+                        *inner = PingInner::Flush {
+                            nonce,
+                            msg,
+                            buf,
+                            io,
+                            state,
+                            session,
+                        };
+                    } else {
+                        *inner = PingInner::Write {
+                            nonce,
+                            write,
+                            session,
+                        };
+
+                        return Poll::Pending;
+                    }
+                }
+                PingInner::Flush {
+                    nonce,
+                    msg,
+                    buf,
+                    mut io,
+                    state,
+                    session,
+                } => {
+                    if Pin::new(&mut io).poll_flush(ctx)?.is_ready() {
+                        // This is also where pongs sent while waiting end up.
+                        if !session.inbox.is_pinging(nonce) {
+                            session.inbox.start_ping(nonce);
+                        }
+
+                        *inner = PingInner::Read {
+                            nonce,
+                            read: Read::new(msg, buf, io, state, session.padding, session.wire)
+                                .with_budget(session.budget()),
+                            session,
+                            escaped: false,
+                        };
+                    } else {
+                        *inner = PingInner::Flush {
+                            nonce,
+                            msg,
+                            buf,
+                            io,
+                            state,
+                            session,
+                        };
+
+                        return Poll::Pending;
+                    }
+                }
+                PingInner::Read {
+                    nonce,
+                    mut read,
+                    session,
+                    escaped,
+                } => {
+                    if let Poll::Ready(len) = Pin::new(&mut read).poll(ctx)? {
+                        let budget = read.budget();
+                        let (msg, buf, io, state) = read.done();
+                        session.liveness.received();
+
+                        // Packets received in-between are kept for the next receive.
+                        if len != 0 && !escaped {
+                            session.inbox.flow.received(len);
+                            session.inbox.defer(&msg[..len])?;
+                        } else if escaped {
+                            session.handle(&msg[..len], state)?;
+                            if let Some(mut bytes) = session.inbox.take_inflated() {
+                                session.inbox.defer(&bytes)?;
+                                wipe(&mut bytes);
+                            }
+                        }
+
+                        wipe(&mut msg[..len]);
+
+                        if escaped && !session.inbox.is_pinging(nonce) {
+                            return Poll::Ready(Ok(session.inbox.rtt().unwrap()));
+                        }
+
+                        // The peer's pings get answered right away, as it might be waiting for
+                        // them before answering this one.
+                        if escaped && session.inbox.has_pongs() {
+                            Control::Pong(session.inbox.pop_pong().unwrap()).encode(msg);
+
+                            *inner = PingInner::Write {
+                                nonce,
+                                write: Write::control(
+                                    msg,
+                                    buf,
+                                    io,
+                                    state,
+                                    session.padding,
+                                    session.wire,
+                                ),
+                                session,
+                            };
+
+                            continue;
+                        }
+
+                        *inner = PingInner::Read {
+                            nonce,
+                            read: Read::new(msg, buf, io, state, session.padding, session.wire)
+                                .with_budget(budget),
+                            session,
+                            escaped: !escaped,
+                        };
+                    } else {
+                        *inner = PingInner::Read {
+                            nonce,
+                            read,
+                            session,
+                            escaped,
+                        };
+
+                        return Poll::Pending;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<IO> Default for PingInner<'_, IO> {
+    #[inline]
+    fn default() -> Self {
+        PingInner::Empty
+    }
+}
@@ -0,0 +1,74 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use std::sync::{Arc, Mutex};
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Debug)]
+pub struct BufferPool {
+    idle: Arc<Mutex<Vec<Vec<u8>>>>,
+    max_idle: usize,
+}
+
+// ======================================= impl BufferPool ====================================== \\
+
+impl BufferPool {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new(max_idle: usize) -> Self {
+        BufferPool {
+            idle: Arc::new(Mutex::new(Vec::new())),
+            max_idle,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn idle(&self) -> usize {
+        self.idle.lock().unwrap().len()
+    }
+
+    #[inline]
+    pub fn max_idle(&self) -> usize {
+        self.max_idle
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub(crate) fn take(&self) -> Vec<u8> {
+        let buf = self.idle.lock().unwrap().pop().unwrap_or_default();
+
+        #[cfg(feature = "audit")]
+        debug_assert!(
+            buf.iter().all(|byte| *byte == 0),
+            "pooled buffer was written to"
+        );
+
+        buf
+    }
+
+    pub(crate) fn put(&self, mut buf: Vec<u8>) {
+        if buf.capacity() == 0 {
+            return;
+        }
+
+        // Buffers may hold plaintext from another connection.
+        buf.fill(0);
+
+        let mut idle = self.idle.lock().unwrap();
+        if idle.len() < self.max_idle {
+            idle.push(buf);
+        }
+    }
+}
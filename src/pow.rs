@@ -0,0 +1,158 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// After answering the initiator's offer with the suite it chose, the responder writes a status
+// byte. If it is a challenge, the initiator has to send a proof before its first handshake message,
+// which the responder only reads once the proof is verified:
+//
+// READY     ;; status(1)
+// CHALLENGE ;; status(1) + nonce(16) + difficulty(1)
+// PROOF     ;; proof(8)
+//
+// A proof is valid if BLAKE2s(nonce || proof) starts with at least `difficulty` zero bits.
+
+// =========================================== Imports ========================================== \\
+
+use rand_core::{OsRng, RngCore};
+use snow::params::HashChoice;
+use snow::resolvers::{CryptoResolver, DefaultResolver};
+use snow::types::Hash;
+
+// ========================================== Constants ========================================= \\
+
+pub(crate) const STATUS_READY: u8 = 0;
+pub(crate) const STATUS_CHALLENGE: u8 = 1;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct Challenge {
+    nonce: [u8; 16],
+    difficulty: u8,
+}
+
+// ======================================= impl Challenge ======================================= \\
+
+impl Challenge {
+    // ====================================== Constants ===================================== \\
+
+    pub(crate) const LEN: usize = 17;
+    pub(crate) const PROOF_LEN: usize = 8;
+
+    // How many proofs the initiator tries before yielding back to the executor.
+    pub(crate) const ATTEMPTS_PER_POLL: u64 = 1 << 12;
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new(difficulty: u8) -> Self {
+        Self::with_nonce(nonce(), difficulty)
+    }
+
+    #[inline]
+    pub(crate) fn with_nonce(nonce: [u8; 16], difficulty: u8) -> Self {
+        Challenge { nonce, difficulty }
+    }
+
+    #[inline]
+    pub(crate) fn decode(buf: &[u8; Self::LEN]) -> Self {
+        let mut nonce = [0; 16];
+        nonce.copy_from_slice(&buf[..16]);
+
+        Challenge {
+            nonce,
+            difficulty: buf[16],
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub(crate) fn difficulty(&self) -> u8 {
+        self.difficulty
+    }
+
+    #[inline]
+    pub(crate) fn encode(&self) -> [u8; Self::LEN] {
+        let mut buf = [0; Self::LEN];
+        buf[..16].copy_from_slice(&self.nonce);
+        buf[16] = self.difficulty;
+
+        buf
+    }
+
+    // Tries at most `attempts` proofs, starting at `from`, so that solving a challenge can be
+    // spread over multiple polls.
+    pub(crate) fn solve(&self, from: u64, attempts: u64) -> Option<u64> {
+        let mut hash = hasher();
+        (from..from.saturating_add(attempts)).find(|&proof| self.check(&mut *hash, proof))
+    }
+
+    #[inline]
+    pub(crate) fn verify(&self, proof: u64) -> bool {
+        self.check(&mut *hasher(), proof)
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    fn check(&self, hash: &mut dyn Hash, proof: u64) -> bool {
+        let mut out = [0; 32];
+
+        hash.reset();
+        hash.input(&self.nonce);
+        hash.input(&proof.to_le_bytes());
+        hash.result(&mut out);
+
+        leading_zeros(&out) >= self.difficulty as u32
+    }
+}
+
+// =========================================== nonce() ========================================== \\
+
+fn nonce() -> [u8; 16] {
+    let mut nonce = [0; 16];
+    OsRng.fill_bytes(&mut nonce);
+
+    nonce
+}
+
+// ========================================== hasher() ========================================== \\
+
+#[inline]
+fn hasher() -> Box<dyn Hash> {
+    DefaultResolver.resolve_hash(&HashChoice::Blake2s).unwrap()
+}
+
+// ======================================= leading_zeros() ====================================== \\
+
+#[cfg(not(feature = "audit"))]
+fn leading_zeros(bytes: &[u8]) -> u32 {
+    let mut zeros = 0;
+    for byte in bytes {
+        zeros += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+
+    zeros
+}
+
+// Goes through every byte without branching on their values.
+#[cfg(feature = "audit")]
+fn leading_zeros(bytes: &[u8]) -> u32 {
+    let mut zeros = 0;
+    let mut mask = u32::MAX;
+    for byte in bytes {
+        let byte = *byte as u32;
+        zeros += (byte.leading_zeros() - 24) & mask;
+        mask &= ((byte | byte.wrapping_neg()) >> 31).wrapping_sub(1);
+    }
+
+    zeros
+}
@@ -0,0 +1,218 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::timeout::Sleep;
+use crate::{Result, Session};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use std::time::Instant;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RateLimit {
+    bytes: Option<u64>,
+    packets: Option<u64>,
+}
+
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    bytes: f64,
+    packets: f64,
+    last: Instant,
+}
+
+// Holds the timer of a sender that has to wait for its rate limit or its pacer.
+#[derive(Default)]
+pub(crate) struct Throttle {
+    sleep: Option<Sleep>,
+}
+
+// ======================================= impl RateLimit ======================================= \\
+
+impl RateLimit {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub const fn unlimited() -> Self {
+        RateLimit {
+            bytes: None,
+            packets: None,
+        }
+    }
+
+    #[inline]
+    pub const fn bytes_per_sec(bytes: u64) -> Self {
+        RateLimit {
+            bytes: Some(bytes),
+            packets: None,
+        }
+    }
+
+    #[inline]
+    pub const fn packets_per_sec(packets: u64) -> Self {
+        RateLimit {
+            bytes: None,
+            packets: Some(packets),
+        }
+    }
+
+    #[inline]
+    pub const fn with_bytes_per_sec(mut self, bytes: u64) -> Self {
+        self.bytes = Some(bytes);
+        self
+    }
+
+    #[inline]
+    pub const fn with_packets_per_sec(mut self, packets: u64) -> Self {
+        self.packets = Some(packets);
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn bytes(&self) -> Option<u64> {
+        self.bytes
+    }
+
+    #[inline]
+    pub fn packets(&self) -> Option<u64> {
+        self.packets
+    }
+}
+
+// ====================================== impl RateLimiter ====================================== \\
+
+impl RateLimiter {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        RateLimiter {
+            limit,
+            bytes: limit.bytes.unwrap_or(0) as f64,
+            packets: limit.packets.unwrap_or(0) as f64,
+            last: Instant::now(),
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub(crate) fn limit(&self) -> RateLimit {
+        self.limit
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub(crate) fn set_limit(&mut self, limit: RateLimit) {
+        *self = RateLimiter::new(limit);
+    }
+
+    pub(crate) fn delay(&mut self, len: usize) -> Option<Duration> {
+        self.refill();
+
+        let mut delay = 0.0f64;
+        if let Some(rate) = self.limit.bytes {
+            let rate = rate.max(1) as f64;
+            let needed = (len as f64).min(rate);
+            if self.bytes < needed {
+                delay = delay.max((needed - self.bytes) / rate);
+            }
+        }
+
+        if let Some(rate) = self.limit.packets {
+            let rate = rate.max(1) as f64;
+            if self.packets < 1.0 {
+                delay = delay.max((1.0 - self.packets) / rate);
+            }
+        }
+
+        Some(delay)
+            .filter(|&delay| delay > 0.0)
+            .map(Duration::from_secs_f64)
+    }
+
+    #[inline]
+    pub(crate) fn consume(&mut self, len: usize) {
+        self.bytes -= len as f64;
+        self.packets -= 1.0;
+    }
+
+    #[inline]
+    pub(crate) fn admit(&mut self, len: usize) -> bool {
+        if self.delay(len).is_some() {
+            return false;
+        }
+
+        self.consume(len);
+        true
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last).as_secs_f64();
+        self.last = now;
+
+        if let Some(rate) = self.limit.bytes {
+            self.bytes = (self.bytes + rate as f64 * elapsed).min(rate as f64);
+        }
+
+        if let Some(rate) = self.limit.packets {
+            self.packets = (self.packets + rate as f64 * elapsed).min(rate as f64);
+        }
+    }
+}
+
+// ======================================== impl Throttle ======================================= \\
+
+impl Throttle {
+    // ===================================== Read+Write ===================================== \\
+
+    // Batches wait with a `len` of zero, which only lasts until whatever the previous ones
+    // overdrew has been paid back.
+    pub(crate) fn poll(
+        &mut self,
+        ctx: &mut Context,
+        session: &mut Session,
+        len: usize,
+    ) -> Poll<Result<()>> {
+        loop {
+            if let Some(sleep) = &mut self.sleep {
+                if Pin::new(sleep).poll(ctx).is_pending() {
+                    return Poll::Pending;
+                }
+
+                self.sleep = None;
+            }
+
+            let delay = session.send_limit.delay(len);
+            match delay.max(session.pacer.delay()) {
+                Some(delay) => self.sleep = Some(session.sleep(delay)?),
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl Default for RateLimiter {
+    #[inline]
+    fn default() -> Self {
+        RateLimiter::new(RateLimit::unlimited())
+    }
+}
@@ -8,8 +8,9 @@
 
 // =========================================== Imports ========================================== \\
 
-use crate::{Error, NoiseState, Result};
-use async_peek::AsyncPeek;
+use crate::budget::Budget;
+use crate::{Error, ErrorContext, NoiseState, PaddingPolicy, Result, StateDump, WireFormat};
+use core::fmt::{self, Debug, Display, Formatter};
 use core::future::Future;
 use core::mem;
 use core::pin::Pin;
@@ -18,20 +19,17 @@ use futures_io::AsyncRead;
 use packets::{NOISE_OVERHEAD, RAW_MAX_LEN};
 
 // ============================================ Types =========================================== \\
+
 pub(super) struct Read<Input, State, Buf = Vec<u8>> {
     inner: ReadInner<Input, State, Buf>,
+    padding: PaddingPolicy,
+    wire: WireFormat,
+    budget: Budget,
 }
 
 enum ReadInner<Input, State, Buf> {
     Empty,
-    Peek {
-        msg: Buf,
-        buf: Buf,
-        inp: Input,
-        state: State,
-    },
-    Advance {
-        len: usize,
+    Header {
         off: usize,
         msg: Buf,
         buf: Buf,
@@ -41,6 +39,7 @@ enum ReadInner<Input, State, Buf> {
     Read {
         len: usize,
         off: usize,
+        checksum: Option<u32>,
         msg: Buf,
         buf: Buf,
         inp: Input,
@@ -61,36 +60,74 @@ impl<Input, State, Buf> Read<Input, State, Buf> {
     // ==================================== Constructors ==================================== \\
 
     #[inline]
-    pub(super) fn new(msg: Buf, buf: Buf, inp: Input, state: State) -> Self
+    pub(super) fn new(
+        msg: Buf,
+        buf: Buf,
+        inp: Input,
+        state: State,
+        padding: PaddingPolicy,
+        wire: WireFormat,
+    ) -> Self
     where
-        Input: AsyncPeek + AsyncRead + Unpin,
+        Input: AsyncRead + Unpin,
         State: NoiseState + Unpin,
         Buf: AsRef<[u8]> + AsMut<Vec<u8>> + Unpin,
     {
         Read {
-            inner: ReadInner::Peek {
+            inner: ReadInner::Header {
+                off: 0,
                 msg,
                 buf,
                 inp,
                 state,
             },
+            padding,
+            wire,
+            budget: Budget::default(),
         }
     }
 
+    #[inline]
+    pub(super) fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub(super) fn budget(&self) -> Budget {
+        self.budget
+    }
+
+    #[inline]
+    pub(super) fn has_consumed(&self) -> bool {
+        !matches!(self.inner, ReadInner::Header { off: 0, .. })
+    }
+
+    pub(super) fn dump_state(&self) -> StateDump
+    where
+        State: NoiseState,
+    {
+        let (name, buffered, state) = match &self.inner {
+            ReadInner::Empty => return StateDump::new("Read", "empty"),
+            ReadInner::Header { off, state, .. } => ("header", *off, state),
+            ReadInner::Read { off, state, .. } => ("read", *off, state),
+            ReadInner::Done { state, .. } => ("done", 0, state),
+        };
+
+        StateDump::new("Read", name)
+            .with_buffered(buffered)
+            .with_nonces(state.nonces())
+    }
+
     // ===================================== Destructors ==================================== \\
 
     #[inline]
     pub(super) fn done(self) -> (Buf, Buf, Input, State) {
         match self.inner {
             ReadInner::Empty => panic!(),
-            ReadInner::Peek {
-                msg,
-                buf,
-                inp,
-                state,
-                ..
-            }
-            | ReadInner::Advance {
+            ReadInner::Header {
                 msg,
                 buf,
                 inp,
@@ -119,37 +156,31 @@ impl<Input, State, Buf> Read<Input, State, Buf> {
 
 impl<Input, State, Buf> Future for Read<Input, State, Buf>
 where
-    Input: AsyncPeek + AsyncRead + Unpin,
+    Input: AsyncRead + Unpin,
     State: NoiseState + Unpin,
     Buf: AsRef<[u8]> + AsMut<Vec<u8>> + Unpin,
 {
     type Output = Result<usize>;
 
     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
-        let inner = &mut self.get_mut().inner;
+        let this = self.get_mut();
+        let padding = this.padding;
+        let wire = this.wire;
+        let budget = &mut this.budget;
+        let inner = &mut this.inner;
         loop {
             match mem::take(inner) {
                 ReadInner::Empty => panic!(),
-                ReadInner::Peek {
-                    msg,
+                ReadInner::Header {
+                    off,
+                    mut msg,
                     mut buf,
-                    mut inp,
+                    inp,
                     state,
-                } => match Pin::new(&mut inp).poll_peek(ctx, &mut buf.as_mut()[0..2]) {
-                    Poll::Ready(Ok(2)) => {
-                        let len = u16::from_le_bytes([buf.as_ref()[0], buf.as_ref()[1]]) as usize;
-
-                        *inner = ReadInner::Advance {
-                            len,
-                            off: 0,
-                            msg,
-                            buf,
-                            inp,
-                            state,
-                        };
-                    }
-                    Poll::Ready(Ok(_)) => panic!("peeked != 2"),
-                    Poll::Ready(Err(err)) => {
+                } if off >= wire.header_len() => {
+                    let (len, _) = wire.decode_header(buf.as_ref());
+                    let checksum = wire.decode_checksum(buf.as_ref());
+                    if len > RAW_MAX_LEN {
                         *inner = ReadInner::Done {
                             len: 0,
                             msg,
@@ -158,148 +189,134 @@ where
                             state,
                         };
 
-                        return Poll::Ready(Err(err.into()));
+                        return Err(Error::MessageSize {
+                            max: RAW_MAX_LEN,
+                            actual: len,
+                        })
+                        .into();
                     }
-                    Poll::Pending => {
-                        *inner = ReadInner::Peek {
-                            msg,
-                            buf,
-                            inp,
-                            state,
-                        };
 
-                        return Poll::Pending;
+                    if len.saturating_sub(NOISE_OVERHEAD) > msg.as_ref().len() {
+                        msg.as_mut().resize(len - NOISE_OVERHEAD, 0);
                     }
-                },
-                ReadInner::Advance {
-                    len,
-                    msg,
-                    buf,
-                    inp,
-                    state,
-                    ..
-                } if len > RAW_MAX_LEN => {
-                    *inner = ReadInner::Done {
-                        len: 0,
-                        msg,
-                        buf,
-                        inp,
-                        state,
-                    };
 
-                    return Err(Error::MessageSize {
-                        max: RAW_MAX_LEN,
-                        actual: len,
-                    })
-                    .into();
-                }
-                ReadInner::Advance {
-                    len,
-                    off,
-                    mut msg,
-                    buf,
-                    inp,
-                    state,
-                } if len - NOISE_OVERHEAD > msg.as_ref().len() => {
-                    msg.as_mut().resize(len - NOISE_OVERHEAD, 0);
-
-                    *inner = ReadInner::Advance {
-                        len,
-                        off,
-                        msg,
-                        buf,
-                        inp,
-                        state,
-                    };
-                }
-                ReadInner::Advance {
-                    len,
-                    off,
-                    msg,
-                    mut buf,
-                    inp,
-                    state,
-                } if len > buf.as_ref().len() => {
-                    buf.as_mut().resize(len, 0);
+                    if len > buf.as_ref().len() {
+                        buf.as_mut().resize(len, 0);
+                    }
 
-                    *inner = ReadInner::Advance {
-                        len,
-                        off,
-                        msg,
-                        buf,
-                        inp,
-                        state,
-                    };
-                }
-                ReadInner::Advance {
-                    len,
-                    off,
-                    msg,
-                    buf,
-                    inp,
-                    state,
-                } if off >= 2 => {
                     *inner = ReadInner::Read {
                         len,
                         off: 0,
+                        checksum,
                         msg,
                         buf,
                         inp,
                         state,
                     };
                 }
-                ReadInner::Advance {
-                    len,
+                ReadInner::Header {
                     mut off,
                     msg,
                     mut buf,
                     mut inp,
                     state,
-                } => match Pin::new(&mut inp).poll_read(ctx, &mut buf.as_mut()[off..2]) {
-                    Poll::Ready(Ok(read)) => {
-                        off += read;
-
-                        *inner = ReadInner::Advance {
-                            len,
+                } => {
+                    if budget.poll_yield(ctx) {
+                        *inner = ReadInner::Header {
                             off,
                             msg,
                             buf,
                             inp,
                             state,
                         };
+
+                        return Poll::Pending;
                     }
-                    Poll::Ready(Err(err)) => {
-                        *inner = ReadInner::Done {
-                            len: 0,
-                            msg,
-                            buf,
-                            inp,
-                            state,
-                        };
 
-                        return Poll::Ready(Err(err.into()));
+                    let hdr = wire.header_len();
+                    if buf.as_ref().len() < hdr {
+                        buf.as_mut().resize(hdr, 0);
                     }
-                    Poll::Pending => {
-                        *inner = ReadInner::Advance {
-                            len,
-                            off,
-                            msg,
-                            buf,
-                            inp,
-                            state,
-                        };
 
-                        return Poll::Pending;
+                    match Pin::new(&mut inp).poll_read(ctx, &mut buf.as_mut()[off..hdr]) {
+                        Poll::Ready(Ok(0)) if off == 0 => {
+                            *inner = ReadInner::Done {
+                                len: 0,
+                                msg,
+                                buf,
+                                inp,
+                                state,
+                            };
+
+                            return Poll::Ready(Err(Error::PeerClosed));
+                        }
+                        Poll::Ready(Ok(0)) => {
+                            *inner = ReadInner::Done {
+                                len: 0,
+                                msg,
+                                buf,
+                                inp,
+                                state,
+                            };
+
+                            let err = Error::UnexpectedEof {
+                                needed: hdr,
+                                got: off,
+                            };
+
+                            return Poll::Ready(Err(err.with_context(ErrorContext::read(off))));
+                        }
+                        Poll::Ready(Ok(read)) => {
+                            off += read;
+                            budget.consume(read);
+
+                            *inner = ReadInner::Header {
+                                off,
+                                msg,
+                                buf,
+                                inp,
+                                state,
+                            };
+                        }
+                        Poll::Ready(Err(err)) => {
+                            *inner = ReadInner::Done {
+                                len: 0,
+                                msg,
+                                buf,
+                                inp,
+                                state,
+                            };
+
+                            let err = Error::from(err);
+                            return Poll::Ready(Err(err.with_context(ErrorContext::read(off))));
+                        }
+                        Poll::Pending => {
+                            budget.refill();
+
+                            *inner = ReadInner::Header {
+                                off,
+                                msg,
+                                buf,
+                                inp,
+                                state,
+                            };
+
+                            return Poll::Pending;
+                        }
                     }
-                },
+                }
                 ReadInner::Read {
                     len,
                     off,
+                    checksum,
                     mut msg,
                     buf,
                     inp,
                     mut state,
-                } if off >= len => match state.read_message(&buf.as_ref()[..len], msg.as_mut()) {
+                } if off >= len => match WireFormat::verify(checksum, &buf.as_ref()[..len])
+                    .and_then(|()| state.read_message(&buf.as_ref()[..len], msg.as_mut()))
+                    .and_then(|len| padding.unpad(&msg.as_ref()[..len]))
+                {
                     Ok(len) => {
                         *inner = ReadInner::Done {
                             len,
@@ -320,53 +337,95 @@ where
                             state,
                         };
 
-                        return Poll::Ready(Err(err));
+                        let context = ErrorContext::read(wire.header_len() + len);
+                        return Poll::Ready(Err(err.with_context(context)));
                     }
                 },
                 ReadInner::Read {
                     len,
                     mut off,
+                    checksum,
                     msg,
                     mut buf,
                     mut inp,
                     state,
-                } => match Pin::new(&mut inp).poll_read(ctx, &mut buf.as_mut()[off..len]) {
-                    Poll::Ready(Ok(read)) => {
-                        off += read;
-
+                } => {
+                    if budget.poll_yield(ctx) {
                         *inner = ReadInner::Read {
                             len,
                             off,
-                            msg,
-                            buf,
-                            inp,
-                            state,
-                        };
-                    }
-                    Poll::Ready(Err(err)) => {
-                        *inner = ReadInner::Done {
-                            len: 0,
+                            checksum,
                             msg,
                             buf,
                             inp,
                             state,
                         };
 
-                        return Poll::Ready(Err(err.into()));
+                        return Poll::Pending;
                     }
-                    Poll::Pending => {
-                        *inner = ReadInner::Read {
-                            len,
-                            off,
-                            msg,
-                            buf,
-                            inp,
-                            state,
-                        };
 
-                        return Poll::Pending;
+                    match Pin::new(&mut inp).poll_read(ctx, &mut buf.as_mut()[off..len]) {
+                        Poll::Ready(Ok(0)) => {
+                            *inner = ReadInner::Done {
+                                len: 0,
+                                msg,
+                                buf,
+                                inp,
+                                state,
+                            };
+
+                            let err = Error::UnexpectedEof {
+                                needed: len,
+                                got: off,
+                            };
+                            let context = ErrorContext::read(wire.header_len() + off);
+
+                            return Poll::Ready(Err(err.with_context(context)));
+                        }
+                        Poll::Ready(Ok(read)) => {
+                            off += read;
+                            budget.consume(read);
+
+                            *inner = ReadInner::Read {
+                                len,
+                                off,
+                                checksum,
+                                msg,
+                                buf,
+                                inp,
+                                state,
+                            };
+                        }
+                        Poll::Ready(Err(err)) => {
+                            *inner = ReadInner::Done {
+                                len: 0,
+                                msg,
+                                buf,
+                                inp,
+                                state,
+                            };
+
+                            let err = Error::from(err);
+                            let context = ErrorContext::read(wire.header_len() + off);
+                            return Poll::Ready(Err(err.with_context(context)));
+                        }
+                        Poll::Pending => {
+                            budget.refill();
+
+                            *inner = ReadInner::Read {
+                                len,
+                                off,
+                                checksum,
+                                msg,
+                                buf,
+                                inp,
+                                state,
+                            };
+
+                            return Poll::Pending;
+                        }
                     }
-                },
+                }
                 ReadInner::Done {
                     len,
                     msg,
@@ -389,6 +448,24 @@ where
     }
 }
 
+// ========================================= impl Debug ========================================= \\
+
+impl<Input, State: NoiseState, Buf> Debug for Read<Input, State, Buf> {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.dump_state(), fmt)
+    }
+}
+
+// ======================================== impl Display ======================================== \\
+
+impl<Input, State: NoiseState, Buf> Display for Read<Input, State, Buf> {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.dump_state(), fmt)
+    }
+}
+
 // ======================================== impl Default ======================================== \\
 
 impl<Input, State, Buf> Default for ReadInner<Input, State, Buf> {
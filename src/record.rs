@@ -0,0 +1,332 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// Every chunk of bytes read from or written to a `RecordedIo` is appended to its sink as:
+//
+// RECORD ;; direction(1) + micros(8) + len(4) + data(len)
+//
+// with direction being 0 for bytes read and 1 for bytes written, and micros being the number of
+// microseconds elapsed since the `RecordedIo` was created. Integers are little-endian.
+
+// =========================================== Imports ========================================== \\
+
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::{AsyncRead, AsyncWrite};
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant};
+
+// ========================================== Constants ========================================= \\
+
+const HEADER_LEN: usize = 1 + 8 + 4;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+    Read,
+    Write,
+}
+
+#[derive(Clone, Debug)]
+pub struct Record {
+    direction: Direction,
+    elapsed: Duration,
+    data: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct RecordedIo<IO, W> {
+    io: IO,
+    sink: W,
+    start: Instant,
+}
+
+#[derive(Debug, Default)]
+pub struct ReplayIo {
+    reads: VecDeque<Vec<u8>>,
+    off: usize,
+    written: Vec<u8>,
+}
+
+// ======================================= impl Direction ======================================= \\
+
+impl Direction {
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    fn into_byte(self) -> u8 {
+        match self {
+            Direction::Read => 0,
+            Direction::Write => 1,
+        }
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    #[inline]
+    fn from_byte(byte: u8) -> io::Result<Self> {
+        match byte {
+            0 => Ok(Direction::Read),
+            1 => Ok(Direction::Write),
+            _ => Err(io::ErrorKind::InvalidData.into()),
+        }
+    }
+}
+
+// ========================================= impl Record ======================================== \\
+
+impl Record {
+    // ==================================== Constructors ==================================== \\
+
+    pub fn read_all<R: Read>(mut reader: R) -> io::Result<Vec<Record>> {
+        let mut records = Vec::new();
+        loop {
+            let mut header = [0; HEADER_LEN];
+            match reader.read_exact(&mut header[..1]) {
+                Ok(()) => (),
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(records),
+                Err(err) => return Err(err),
+            }
+
+            reader.read_exact(&mut header[1..])?;
+
+            let direction = Direction::from_byte(header[0])?;
+            let mut micros = [0; 8];
+            micros.copy_from_slice(&header[1..9]);
+            let mut len = [0; 4];
+            len.copy_from_slice(&header[9..]);
+
+            let mut data = vec![0; u32::from_le_bytes(len) as usize];
+            reader.read_exact(&mut data)?;
+
+            records.push(Record {
+                direction,
+                elapsed: Duration::from_micros(u64::from_le_bytes(micros)),
+                data,
+            });
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+// ======================================= impl RecordedIo ====================================== \\
+
+impl<IO, W: Write> RecordedIo<IO, W> {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new(io: IO, sink: W) -> Self {
+        RecordedIo {
+            io,
+            sink,
+            start: Instant::now(),
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn get_ref(&self) -> &IO {
+        &self.io
+    }
+
+    #[inline]
+    pub fn sink(&self) -> &W {
+        &self.sink
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn into_inner(self) -> (IO, W) {
+        (self.io, self.sink)
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    fn record(&mut self, direction: Direction, data: &[u8]) -> io::Result<()> {
+        let micros = u64::try_from(self.start.elapsed().as_micros()).unwrap_or(u64::MAX);
+        let len =
+            u32::try_from(data.len()).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+
+        let mut header = [0; HEADER_LEN];
+        header[0] = direction.into_byte();
+        header[1..9].copy_from_slice(&micros.to_le_bytes());
+        header[9..].copy_from_slice(&len.to_le_bytes());
+
+        self.sink.write_all(&header)?;
+        self.sink.write_all(data)
+    }
+}
+
+// ======================================== impl ReplayIo ======================================= \\
+
+impl ReplayIo {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new<R: Read>(reader: R) -> io::Result<Self> {
+        Ok(ReplayIo::from_records(Record::read_all(reader)?))
+    }
+
+    pub fn from_records<I: IntoIterator<Item = Record>>(records: I) -> Self {
+        ReplayIo {
+            reads: records
+                .into_iter()
+                .filter(|record| record.direction == Direction::Read)
+                .map(Record::into_data)
+                .collect(),
+            off: 0,
+            written: Vec::new(),
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.reads.iter().map(Vec::len).sum::<usize>() - self.off
+    }
+
+    #[inline]
+    pub fn written(&self) -> &[u8] {
+        &self.written
+    }
+}
+
+// ======================================= impl AsyncRead ======================================= \\
+
+impl<IO, W> AsyncRead for RecordedIo<IO, W>
+where
+    IO: AsyncRead + Unpin,
+    W: Write + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        ctx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let read = match Pin::new(&mut this.io).poll_read(ctx, buf)? {
+            Poll::Ready(read) => read,
+            Poll::Pending => return Poll::Pending,
+        };
+
+        if read > 0 {
+            this.record(Direction::Read, &buf[..read])?;
+        }
+
+        Poll::Ready(Ok(read))
+    }
+}
+
+impl AsyncRead for ReplayIo {
+    fn poll_read(self: Pin<&mut Self>, _: &mut Context, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let data = match this.reads.front() {
+            Some(data) => data,
+            None => return Poll::Ready(Ok(0)),
+        };
+
+        let read = buf.len().min(data.len() - this.off);
+        buf[..read].copy_from_slice(&data[this.off..this.off + read]);
+
+        this.off += read;
+        if this.off >= data.len() {
+            this.reads.pop_front();
+            this.off = 0;
+        }
+
+        Poll::Ready(Ok(read))
+    }
+}
+
+// ======================================= impl AsyncWrite ====================================== \\
+
+impl<IO, W> AsyncWrite for RecordedIo<IO, W>
+where
+    IO: AsyncWrite + Unpin,
+    W: Write + Unpin,
+{
+    fn poll_write(self: Pin<&mut Self>, ctx: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let wrote = match Pin::new(&mut this.io).poll_write(ctx, buf)? {
+            Poll::Ready(wrote) => wrote,
+            Poll::Pending => return Poll::Pending,
+        };
+
+        if wrote > 0 {
+            this.record(Direction::Write, &buf[..wrote])?;
+        }
+
+        Poll::Ready(Ok(wrote))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if Pin::new(&mut this.io).poll_flush(ctx)?.is_pending() {
+            return Poll::Pending;
+        }
+
+        Poll::Ready(this.sink.flush())
+    }
+
+    fn poll_close(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if Pin::new(&mut this.io).poll_close(ctx)?.is_pending() {
+            return Poll::Pending;
+        }
+
+        Poll::Ready(this.sink.flush())
+    }
+}
+
+impl AsyncWrite for ReplayIo {
+    #[inline]
+    fn poll_write(self: Pin<&mut Self>, _: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.get_mut().written.extend_from_slice(buf);
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
@@ -8,13 +8,12 @@
 
 // =========================================== Imports ========================================== \\
 
-use crate::{Protocol, Read, Result};
-use async_peek::AsyncPeek;
+use crate::{wipe, Metrics, Protocol, Read, Result, Session, Timeout, Timer};
 use core::future::Future;
 use core::mem;
 use core::pin::Pin;
 use core::task::{Context, Poll};
-use format::Decode;
+use core::time::Duration;
 use futures_io::AsyncRead;
 use packets::Packet;
 use snow::TransportState;
@@ -23,19 +22,24 @@ use snow::TransportState;
 
 pub struct Recv<'proto, Input> {
     inner: RecvInner<'proto, Input>,
+    filter: Option<Filter<'proto>>,
+    metrics: Metrics,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
 }
 
 enum RecvInner<'proto, Input> {
     Empty,
     Read {
         read: Read<Input, &'proto mut TransportState, &'proto mut Vec<u8>>,
+        session: &'proto mut Session,
+        escaped: bool,
     },
-    Decode {
-        len: usize,
-        msg: &'proto mut Vec<u8>,
-    },
+    Done,
 }
 
+type Filter<'proto> = Box<dyn Fn(u16) -> bool + Send + 'proto>;
+
 // ========================================== impl Recv ========================================= \\
 
 impl<'proto, Input> Recv<'proto, Input> {
@@ -43,48 +47,221 @@ impl<'proto, Input> Recv<'proto, Input> {
 
     pub(super) fn new(proto: &'proto mut Protocol, inp: Input) -> Self
     where
-        Input: AsyncPeek + AsyncRead + Unpin,
+        Input: AsyncRead + Unpin,
     {
+        let metrics = proto.session.metrics.clone();
         Recv {
             inner: RecvInner::Read {
-                read: Read::new(&mut proto.msg, &mut proto.buf, inp, &mut proto.state),
+                read: Read::new(
+                    &mut proto.msg,
+                    &mut proto.buf,
+                    inp,
+                    &mut proto.state,
+                    proto.session.padding,
+                    proto.session.wire,
+                )
+                .with_budget(proto.session.budget()),
+                session: &mut proto.session,
+                escaped: false,
             },
+            filter: None,
+            metrics,
+            #[cfg(feature = "tracing")]
+            span: tracing::debug_span!("recv"),
         }
     }
+
+    #[inline]
+    pub(super) fn matching<Match>(mut self, filter: Match) -> Self
+    where
+        Match: Fn(u16) -> bool + Send + 'proto,
+    {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    pub(crate) fn poll_with_id(&mut self, ctx: &mut Context) -> Poll<Result<(u16, Packet)>>
+    where
+        Input: AsyncRead + Unpin,
+    {
+        #[cfg(feature = "tracing")]
+        let _enter = self.span.enter();
+
+        match self.inner.poll(ctx, self.filter.as_deref()) {
+            Poll::Ready(Err(err)) => {
+                debug!(error = ?err, "recv failed");
+
+                Poll::Ready(self.metrics.error(Err(err)))
+            }
+            Poll::Ready(Ok(res)) => Poll::Ready(Ok(res)),
+            Poll::Pending => {
+                trace!(state = self.inner.name(), "recv pending");
+
+                Poll::Pending
+            }
+        }
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn timeout<Tmr: Timer>(self, duration: Duration) -> Timeout<Self, Tmr> {
+        Timeout::new(self, duration)
+    }
 }
 
-// ========================================= impl Future ======================================== \\
+// ======================================= impl RecvInner ======================================= \\
 
-impl<Input> Future for Recv<'_, Input>
+impl<Input> RecvInner<'_, Input>
 where
-    Input: AsyncPeek + AsyncRead + Unpin,
+    Input: AsyncRead + Unpin,
 {
-    type Output = Result<Packet>;
+    #[cfg(feature = "tracing")]
+    fn name(&self) -> &'static str {
+        match self {
+            RecvInner::Empty => "empty",
+            RecvInner::Read { escaped: false, .. } => "read",
+            RecvInner::Read { escaped: true, .. } => "control",
+            RecvInner::Done => "done",
+        }
+    }
 
-    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
-        let inner = &mut self.get_mut().inner;
+    fn poll(
+        &mut self,
+        ctx: &mut Context,
+        filter: Option<&(dyn Fn(u16) -> bool + Send)>,
+    ) -> Poll<Result<(u16, Packet)>> {
+        let inner = self;
         loop {
             match mem::take(inner) {
-                RecvInner::Empty => panic!(),
-                RecvInner::Read { mut read } => {
+                RecvInner::Empty | RecvInner::Done => panic!(),
+                RecvInner::Read {
+                    read,
+                    session,
+                    escaped,
+                } if session.inbox.has_backlog() => {
+                    let bytes = session.inbox.pop_backlog().unwrap();
+                    trace!(len = bytes.len(), "received queued packet");
+
+                    if let Some(packet) = deliver(session, bytes, filter)? {
+                        *inner = RecvInner::Done;
+
+                        return Poll::Ready(Ok(packet));
+                    }
+
+                    *inner = RecvInner::Read {
+                        read,
+                        session,
+                        escaped,
+                    };
+                }
+                RecvInner::Read {
+                    mut read,
+                    session,
+                    escaped,
+                } => {
                     if let Poll::Ready(len) = Pin::new(&mut read).poll(ctx)? {
-                        let (msg, _, _, _) = read.done();
+                        let budget = read.budget();
+                        let (msg, buf, inp, state) = read.done();
+                        session.liveness.received();
+                        trace!(len, escaped, "read message");
+
+                        let packet = if escaped {
+                            session.handle(&msg[..len], state)?;
+                            match session.inbox.take_inflated() {
+                                Some(bytes) => {
+                                    trace!(len = bytes.len(), "received compressed packet");
+                                    deliver(session, bytes, filter)?
+                                }
+                                None => None,
+                            }
+                        } else if len == 0 {
+                            None
+                        } else {
+                            let id = crate::unknown::id(&msg[..len]);
+                            session.inbox.flow.received(len);
+                            session.packet_received(&msg[..len])?;
+                            trace!(id, len, "received packet");
+
+                            if filter.map_or(true, |filter| filter(id)) {
+                                session.unknown.decode(&msg[..len])?.map(|packet| (id, packet))
+                            } else {
+                                trace!(id, "filtered out packet");
+                                None
+                            }
+                        };
+
+                        wipe(&mut msg[..len]);
 
-                        *inner = RecvInner::Decode { len, msg };
+                        if let Some(packet) = packet {
+                            *inner = RecvInner::Done;
+
+                            return Poll::Ready(Ok(packet));
+                        }
+
+                        *inner = RecvInner::Read {
+                            read: Read::new(msg, buf, inp, state, session.padding, session.wire)
+                                .with_budget(budget),
+                            session,
+                            escaped: len == 0 && !escaped,
+                        };
                     } else {
-                        *inner = RecvInner::Read { read };
+                        *inner = RecvInner::Read {
+                            read,
+                            session,
+                            escaped,
+                        };
 
                         return Poll::Pending;
                     }
                 }
-                RecvInner::Decode { len, msg } => {
-                    return Poll::Ready(Ok(Packet::decode(&msg[..len])?.0));
-                }
             }
         }
     }
 }
 
+// ========================================= impl Future ======================================== \\
+
+impl<Input> Future for Recv<'_, Input>
+where
+    Input: AsyncRead + Unpin,
+{
+    type Output = Result<Packet>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        self.get_mut()
+            .poll_with_id(ctx)
+            .map(|res| res.map(|(_, packet)| packet))
+    }
+}
+
+// ========================================== deliver() ========================================= \\
+
+// Handles a packet which didn't come straight out of the read buffer, i.e. one that was inflated
+// or queued by another receive.
+fn deliver(
+    session: &mut Session,
+    mut bytes: Vec<u8>,
+    filter: Option<&(dyn Fn(u16) -> bool + Send)>,
+) -> Result<Option<(u16, Packet)>> {
+    let id = crate::unknown::id(&bytes);
+    session.packet_received(&bytes)?;
+
+    let packet = if filter.map_or(true, |filter| filter(id)) {
+        session.unknown.decode(&bytes)?.map(|packet| (id, packet))
+    } else {
+        trace!(id, "filtered out packet");
+        None
+    };
+
+    wipe(&mut bytes);
+
+    Ok(packet)
+}
+
 // ======================================== impl Default ======================================== \\
 
 impl<Input> Default for RecvInner<'_, Input> {
@@ -0,0 +1,60 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{DatagramProtocol, DatagramSocket, Result};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use packets::Packet;
+use std::net::SocketAddr;
+
+// ============================================ Types =========================================== \\
+
+pub struct RecvFrom<'proto, 'sock, Socket> {
+    proto: &'proto mut DatagramProtocol,
+    socket: &'sock Socket,
+}
+
+// ======================================== impl RecvFrom ======================================= \\
+
+impl<'proto, 'sock, Socket> RecvFrom<'proto, 'sock, Socket> {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(super) fn new(proto: &'proto mut DatagramProtocol, socket: &'sock Socket) -> Self
+    where
+        Socket: DatagramSocket,
+    {
+        RecvFrom { proto, socket }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Socket> Future for RecvFrom<'_, '_, Socket>
+where
+    Socket: DatagramSocket,
+{
+    type Output = Result<(Packet, SocketAddr)>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            let (len, addr) = match this.socket.poll_recv_from(ctx, this.proto.buf_mut())? {
+                Poll::Ready(received) => received,
+                Poll::Pending => return Poll::Pending,
+            };
+
+            if let Some(packet) = this.proto.decrypt(len)? {
+                return Poll::Ready(Ok((packet, addr)));
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{wipe, Protocol, Read, Result, Session};
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::AsyncRead;
+use snow::TransportState;
+
+// ============================================ Types =========================================== \\
+
+pub struct RecvLarge<'proto, Input> {
+    inner: RecvLargeInner<'proto, Input>,
+}
+
+enum RecvLargeInner<'proto, Input> {
+    Empty,
+    Read {
+        read: Read<Input, &'proto mut TransportState, &'proto mut Vec<u8>>,
+        session: &'proto mut Session,
+        escaped: bool,
+    },
+}
+
+// ======================================= impl RecvLarge ======================================= \\
+
+impl<'proto, Input> RecvLarge<'proto, Input> {
+    // ==================================== Constructors ==================================== \\
+
+    pub(super) fn new(proto: &'proto mut Protocol, inp: Input) -> Self
+    where
+        Input: AsyncRead + Unpin,
+    {
+        RecvLarge {
+            inner: RecvLargeInner::Read {
+                read: Read::new(
+                    &mut proto.msg,
+                    &mut proto.buf,
+                    inp,
+                    &mut proto.state,
+                    proto.session.padding,
+                    proto.session.wire,
+                )
+                .with_budget(proto.session.budget()),
+                session: &mut proto.session,
+                escaped: false,
+            },
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Input> Future for RecvLarge<'_, Input>
+where
+    Input: AsyncRead + Unpin,
+{
+    type Output = Result<Vec<u8>>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let inner = &mut self.get_mut().inner;
+        loop {
+            match mem::take(inner) {
+                RecvLargeInner::Empty => panic!(),
+                RecvLargeInner::Read { session, .. } if session.inbox.reassembly.has_done() => {
+                    return Poll::Ready(Ok(session.inbox.reassembly.pop().unwrap()));
+                }
+                RecvLargeInner::Read {
+                    mut read,
+                    session,
+                    escaped,
+                } => {
+                    if let Poll::Ready(len) = Pin::new(&mut read).poll(ctx)? {
+                        let budget = read.budget();
+                        let (msg, buf, inp, state) = read.done();
+                        session.liveness.received();
+
+                        // Packets received in-between are kept for the next receive.
+                        if len != 0 && !escaped {
+                            session.inbox.flow.received(len);
+                            session.inbox.defer(&msg[..len])?;
+                        } else if escaped {
+                            session.handle(&msg[..len], state)?;
+                            if let Some(mut bytes) = session.inbox.take_inflated() {
+                                session.inbox.defer(&bytes)?;
+                                wipe(&mut bytes);
+                            }
+                        }
+
+                        wipe(&mut msg[..len]);
+
+                        *inner = RecvLargeInner::Read {
+                            read: Read::new(msg, buf, inp, state, session.padding, session.wire)
+                                .with_budget(budget),
+                            session,
+                            escaped: !escaped,
+                        };
+                    } else {
+                        *inner = RecvLargeInner::Read {
+                            read,
+                            session,
+                            escaped,
+                        };
+
+                        return Poll::Pending;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<Input> Default for RecvLargeInner<'_, Input> {
+    #[inline]
+    fn default() -> Self {
+        RecvLargeInner::Empty
+    }
+}
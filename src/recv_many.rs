@@ -0,0 +1,211 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{wipe, Protocol, Read, Result, Session};
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::AsyncRead;
+use packets::Packet;
+use snow::TransportState;
+
+// ============================================ Types =========================================== \\
+
+pub struct RecvMany<'proto, 'out, Input> {
+    inner: RecvManyInner<'proto, 'out, Input>,
+}
+
+enum RecvManyInner<'proto, 'out, Input> {
+    Empty,
+    Read {
+        read: Read<Input, &'proto mut TransportState, &'proto mut Vec<u8>>,
+        session: &'proto mut Session,
+        escaped: bool,
+        packets: &'out mut Vec<Packet>,
+        max: usize,
+        count: usize,
+    },
+    Done {
+        count: usize,
+    },
+}
+
+// ======================================== impl RecvMany ======================================= \\
+
+impl<'proto, 'out, Input> RecvMany<'proto, 'out, Input> {
+    // ==================================== Constructors ==================================== \\
+
+    pub(super) fn new(
+        packets: &'out mut Vec<Packet>,
+        max: usize,
+        proto: &'proto mut Protocol,
+        inp: Input,
+    ) -> Self
+    where
+        Input: AsyncRead + Unpin,
+    {
+        if max == 0 {
+            return RecvMany {
+                inner: RecvManyInner::Done { count: 0 },
+            };
+        }
+
+        RecvMany {
+            inner: RecvManyInner::Read {
+                read: Read::new(
+                    &mut proto.msg,
+                    &mut proto.buf,
+                    inp,
+                    &mut proto.state,
+                    proto.session.padding,
+                    proto.session.wire,
+                )
+                .with_budget(proto.session.budget()),
+                session: &mut proto.session,
+                escaped: false,
+                packets,
+                max,
+                count: 0,
+            },
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Input> Future for RecvMany<'_, '_, Input>
+where
+    Input: AsyncRead + Unpin,
+{
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let inner = &mut self.get_mut().inner;
+        loop {
+            match mem::take(inner) {
+                RecvManyInner::Empty => panic!(),
+                RecvManyInner::Done { count } => {
+                    *inner = RecvManyInner::Done { count };
+
+                    return Poll::Ready(Ok(count));
+                }
+                RecvManyInner::Read {
+                    read,
+                    session,
+                    escaped,
+                    packets,
+                    max,
+                    mut count,
+                } if session.inbox.has_backlog() => {
+                    let mut bytes = session.inbox.pop_backlog().unwrap();
+                    session.packet_received(&bytes)?;
+                    if let Some(packet) = session.unknown.decode(&bytes)? {
+                        packets.push(packet);
+                        count += 1;
+                    }
+
+                    wipe(&mut bytes);
+
+                    if count >= max {
+                        *inner = RecvManyInner::Done { count };
+
+                        return Poll::Ready(Ok(count));
+                    }
+
+                    *inner = RecvManyInner::Read {
+                        read,
+                        session,
+                        escaped,
+                        packets,
+                        max,
+                        count,
+                    };
+                }
+                RecvManyInner::Read {
+                    mut read,
+                    session,
+                    escaped,
+                    packets,
+                    max,
+                    mut count,
+                } => {
+                    if let Poll::Ready(len) = Pin::new(&mut read).poll(ctx)? {
+                        let budget = read.budget();
+                        let (msg, buf, inp, state) = read.done();
+                        session.liveness.received();
+
+                        if len == 0 || escaped {
+                            if escaped {
+                                session.handle(&msg[..len], state)?;
+                            }
+
+                            if let Some(bytes) = session.inbox.take_inflated() {
+                                session.packet_received(&bytes)?;
+                                if let Some(packet) = session.unknown.decode(&bytes)? {
+                                    packets.push(packet);
+                                    count += 1;
+                                }
+                            }
+                        } else {
+                            session.inbox.flow.received(len);
+                            session.packet_received(&msg[..len])?;
+                            if let Some(packet) = session.unknown.decode(&msg[..len])? {
+                                packets.push(packet);
+                                count += 1;
+                            }
+                        }
+
+                        let escaped = len == 0 && !escaped;
+                        if count >= max {
+                            *inner = RecvManyInner::Done { count };
+
+                            return Poll::Ready(Ok(count));
+                        }
+
+                        *inner = RecvManyInner::Read {
+                            read: Read::new(msg, buf, inp, state, session.padding, session.wire)
+                                .with_budget(budget),
+                            session,
+                            escaped,
+                            packets,
+                            max,
+                            count,
+                        };
+                    } else if count > 0 && !escaped && !read.has_consumed() {
+                        *inner = RecvManyInner::Done { count };
+
+                        return Poll::Ready(Ok(count));
+                    } else {
+                        *inner = RecvManyInner::Read {
+                            read,
+                            session,
+                            escaped,
+                            packets,
+                            max,
+                            count,
+                        };
+
+                        return Poll::Pending;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<Input> Default for RecvManyInner<'_, '_, Input> {
+    #[inline]
+    fn default() -> Self {
+        RecvManyInner::Empty
+    }
+}
@@ -0,0 +1,156 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{wipe, PacketRef, Protocol, Read, Result, Session};
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::AsyncRead;
+use snow::TransportState;
+
+// ============================================ Types =========================================== \\
+
+pub struct RecvRef<'proto, Input> {
+    inner: RecvRefInner<'proto, Input>,
+}
+
+enum RecvRefInner<'proto, Input> {
+    Empty,
+    Read {
+        read: Read<Input, &'proto mut TransportState, &'proto mut Vec<u8>>,
+        session: &'proto mut Session,
+        escaped: bool,
+    },
+}
+
+// ======================================== impl RecvRef ======================================== \\
+
+impl<'proto, Input> RecvRef<'proto, Input> {
+    // ==================================== Constructors ==================================== \\
+
+    pub(super) fn new(proto: &'proto mut Protocol, inp: Input) -> Self
+    where
+        Input: AsyncRead + Unpin,
+    {
+        RecvRef {
+            inner: RecvRefInner::Read {
+                read: Read::new(
+                    &mut proto.msg,
+                    &mut proto.buf,
+                    inp,
+                    &mut proto.state,
+                    proto.session.padding,
+                    proto.session.wire,
+                )
+                .with_budget(proto.session.budget()),
+                session: &mut proto.session,
+                escaped: false,
+            },
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<'proto, Input> Future for RecvRef<'proto, Input>
+where
+    Input: AsyncRead + Unpin,
+{
+    type Output = Result<PacketRef<'proto>>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let inner = &mut self.get_mut().inner;
+        loop {
+            match mem::take(inner) {
+                RecvRefInner::Empty => panic!(),
+                RecvRefInner::Read { read, session, .. }
+                    if session.inbox.has_backlog() && !read.has_consumed() =>
+                {
+                    let mut bytes = session.inbox.pop_backlog().unwrap();
+                    session.packet_received(&bytes)?;
+
+                    let (msg, _, _, _) = read.done();
+                    msg.clear();
+                    msg.extend_from_slice(&bytes);
+                    wipe(&mut bytes);
+
+                    let msg: &'proto Vec<u8> = msg;
+                    return Poll::Ready(Ok(PacketRef::new(msg)));
+                }
+                RecvRefInner::Read {
+                    mut read,
+                    session,
+                    escaped,
+                } => {
+                    if let Poll::Ready(len) = Pin::new(&mut read).poll(ctx)? {
+                        let budget = read.budget();
+                        let (msg, buf, inp, state) = read.done();
+                        session.liveness.received();
+
+                        if len == 0 || escaped {
+                            if escaped {
+                                session.handle(&msg[..len], state)?;
+                            }
+
+                            if let Some(bytes) = session.inbox.take_inflated() {
+                                session.packet_received(&bytes)?;
+                                msg.clear();
+                                msg.extend_from_slice(&bytes);
+
+                                let msg: &'proto Vec<u8> = msg;
+                                return Poll::Ready(Ok(PacketRef::new(msg)));
+                            }
+
+                            *inner = RecvRefInner::Read {
+                                read: Read::new(
+                                    msg,
+                                    buf,
+                                    inp,
+                                    state,
+                                    session.padding,
+                                    session.wire,
+                                )
+                                .with_budget(budget),
+                                session,
+                                escaped: !escaped,
+                            };
+
+                            continue;
+                        }
+
+                        session.inbox.flow.received(len);
+                        session.packet_received(&msg[..len])?;
+
+                        let msg: &'proto Vec<u8> = msg;
+                        return Poll::Ready(Ok(PacketRef::new(&msg[..len])));
+                    } else {
+                        *inner = RecvRefInner::Read {
+                            read,
+                            session,
+                            escaped,
+                        };
+
+                        return Poll::Pending;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<Input> Default for RecvRefInner<'_, Input> {
+    #[inline]
+    fn default() -> Self {
+        RecvRefInner::Empty
+    }
+}
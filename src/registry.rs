@@ -0,0 +1,175 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Custom, Error, Protocol, RecvCustom, Result};
+use core::any::Any;
+use core::fmt::{self, Debug, Formatter};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::AsyncRead;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Default)]
+pub struct PacketRegistry {
+    decoders: HashMap<u16, Decoder>,
+}
+
+type Decoder = Arc<dyn Fn(&[u8]) -> Result<Box<dyn Any + Send>> + Send + Sync>;
+
+pub struct Decoded {
+    id: u16,
+    value: Box<dyn Any + Send>,
+}
+
+pub struct RecvDecoded<'proto, Input> {
+    registry: Option<Arc<PacketRegistry>>,
+    inner: RecvCustom<'proto, Input>,
+}
+
+// ===================================== impl PacketRegistry ==================================== \\
+
+impl PacketRegistry {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new() -> Self {
+        PacketRegistry::default()
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn is_registered(&self, id: u16) -> bool {
+        self.decoders.contains_key(&id)
+    }
+
+    pub fn decode(&self, custom: &Custom) -> Result<Decoded> {
+        let decoder = self
+            .decoders
+            .get(&custom.id())
+            .ok_or(Error::UnexpectedPacket)?;
+
+        Ok(Decoded {
+            id: custom.id(),
+            value: decoder(custom.payload())?,
+        })
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    pub fn register<Value, Decode>(&mut self, id: u16, decode: Decode) -> Result<()>
+    where
+        Value: Any + Send,
+        Decode: Fn(&[u8]) -> Result<Value> + Send + Sync + 'static,
+    {
+        if Custom::is_reserved(id) {
+            return Err(Error::ReservedPacketId(id));
+        }
+
+        self.decoders.insert(
+            id,
+            Arc::new(move |bytes| Ok(Box::new(decode(bytes)?) as Box<dyn Any + Send>)),
+        );
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn unregister(&mut self, id: u16) -> bool {
+        self.decoders.remove(&id).is_some()
+    }
+}
+
+// ======================================== impl Decoded ======================================== \\
+
+impl Decoded {
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+
+    #[inline]
+    pub fn is<Value: Any>(&self) -> bool {
+        self.value.is::<Value>()
+    }
+
+    #[inline]
+    pub fn downcast_ref<Value: Any>(&self) -> Option<&Value> {
+        self.value.downcast_ref()
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn downcast<Value: Any>(self) -> core::result::Result<Value, Self> {
+        match self.value.downcast() {
+            Ok(value) => Ok(*value),
+            Err(value) => Err(Decoded { id: self.id, value }),
+        }
+    }
+}
+
+// ====================================== impl RecvDecoded ====================================== \\
+
+impl<'proto, Input> RecvDecoded<'proto, Input> {
+    // ==================================== Constructors ==================================== \\
+
+    pub(super) fn new(proto: &'proto mut Protocol, inp: Input) -> Self
+    where
+        Input: AsyncRead + Unpin,
+    {
+        RecvDecoded {
+            registry: proto.session.registry.clone(),
+            inner: RecvCustom::new(proto, inp),
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Input> Future for RecvDecoded<'_, Input>
+where
+    Input: AsyncRead + Unpin,
+{
+    type Output = Result<Decoded>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let custom = match Pin::new(&mut this.inner).poll(ctx)? {
+            Poll::Ready(custom) => custom,
+            Poll::Pending => return Poll::Pending,
+        };
+
+        match &this.registry {
+            Some(registry) => Poll::Ready(registry.decode(&custom)),
+            None => Poll::Ready(Err(Error::UnexpectedPacket)),
+        }
+    }
+}
+
+// ========================================= impl Debug ========================================= \\
+
+impl Debug for PacketRegistry {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_set().entries(self.decoders.keys()).finish()
+    }
+}
+
+impl Debug for Decoded {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Decoded").field("id", &self.id).finish()
+    }
+}
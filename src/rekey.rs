@@ -0,0 +1,209 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::control::Control;
+use crate::{Protocol, Result, Session, Write};
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::AsyncWrite;
+use snow::TransportState;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RekeyPolicy {
+    messages: Option<u64>,
+    bytes: Option<u64>,
+}
+
+pub(crate) struct Rekeyer {
+    policy: RekeyPolicy,
+    messages: u64,
+    bytes: u64,
+    rekeys: u64,
+}
+
+pub struct Rekey<'proto, Output> {
+    inner: RekeyInner<'proto, Output>,
+}
+
+enum RekeyInner<'proto, Output> {
+    Empty,
+    Write {
+        write: Write<Output, &'proto mut TransportState, &'proto mut Vec<u8>>,
+        session: &'proto mut Session,
+    },
+}
+
+// ====================================== impl RekeyPolicy ====================================== \\
+
+impl RekeyPolicy {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub const fn never() -> Self {
+        RekeyPolicy {
+            messages: None,
+            bytes: None,
+        }
+    }
+
+    #[inline]
+    pub const fn after_messages(messages: u64) -> Self {
+        RekeyPolicy {
+            messages: Some(messages),
+            bytes: None,
+        }
+    }
+
+    #[inline]
+    pub const fn after_bytes(bytes: u64) -> Self {
+        RekeyPolicy {
+            messages: None,
+            bytes: Some(bytes),
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn messages(&self) -> Option<u64> {
+        self.messages
+    }
+
+    #[inline]
+    pub fn bytes(&self) -> Option<u64> {
+        self.bytes
+    }
+}
+
+// ======================================== impl Rekeyer ======================================== \\
+
+impl Rekeyer {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new(policy: RekeyPolicy) -> Self {
+        Rekeyer {
+            policy,
+            messages: 0,
+            bytes: 0,
+            rekeys: 0,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub(crate) fn policy(&self) -> RekeyPolicy {
+        self.policy
+    }
+
+    #[inline]
+    pub(crate) fn rekeys(&self) -> u64 {
+        self.rekeys
+    }
+
+    #[inline]
+    pub(crate) fn is_due(&self) -> bool {
+        self.policy
+            .messages
+            .map_or(false, |max| self.messages >= max)
+            || self.policy.bytes.map_or(false, |max| self.bytes >= max)
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub(crate) fn set_policy(&mut self, policy: RekeyPolicy) {
+        self.policy = policy;
+    }
+
+    #[inline]
+    pub(crate) fn record(&mut self, bytes: usize) {
+        self.messages += 1;
+        self.bytes += bytes as u64;
+    }
+
+    #[inline]
+    pub(crate) fn reset(&mut self) {
+        self.messages = 0;
+        self.bytes = 0;
+        self.rekeys += 1;
+    }
+}
+
+// ========================================= impl Rekey ========================================= \\
+
+impl<'proto, Output> Rekey<'proto, Output> {
+    // ==================================== Constructors ==================================== \\
+
+    pub(super) fn new(proto: &'proto mut Protocol, out: Output) -> Self
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        Control::Rekey.encode(&mut proto.msg);
+
+        Rekey {
+            inner: RekeyInner::Write {
+                write: Write::control(
+                    &mut proto.msg,
+                    &mut proto.buf,
+                    out,
+                    &mut proto.state,
+                    proto.session.padding,
+                    proto.session.wire,
+                ),
+                session: &mut proto.session,
+            },
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Output> Future for Rekey<'_, Output>
+where
+    Output: AsyncWrite + Unpin,
+{
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let inner = &mut self.get_mut().inner;
+        match mem::take(inner) {
+            RekeyInner::Empty => panic!(),
+            RekeyInner::Write { mut write, session } => {
+                if Pin::new(&mut write).poll(ctx)?.is_ready() {
+                    let (_, _, _, state) = write.done();
+
+                    state.rekey_outgoing();
+                    session.rekeyer.reset();
+
+                    Poll::Ready(Ok(()))
+                } else {
+                    *inner = RekeyInner::Write { write, session };
+
+                    Poll::Pending
+                }
+            }
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<Output> Default for RekeyInner<'_, Output> {
+    #[inline]
+    fn default() -> Self {
+        RekeyInner::Empty
+    }
+}
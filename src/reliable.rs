@@ -0,0 +1,614 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// A reliable channel carries streams of messages over any lossy transport, as frames that get
+// sent and received by the caller (e.g. through a `DatagramProtocol`):
+//
+// DATA ;; kind(1) + stream(4) + seq(8) + payload
+// ACK  ;; kind(1) + stream(4) + ACK
+// NACK ;; kind(1) + stream(4) + NACK
+//
+// Every stream has its own sequence numbers. Unacknowledged messages are retransmitted once their
+// timeout expires or as soon as they are reported missing, and the timeout doubles after every
+// retransmission. Once the round-trip time has been measured, the configured timeout only serves
+// as a lower bound.
+//
+// All streams share a single congestion controller, which limits the number of bytes in flight
+// and the rate at which messages get sent.
+//
+// Frames opening more streams than the channel's `max_streams`, and messages that are `window` or
+// more past the ones received in order, get dropped, so that a peer can't make the channel hold
+// on to an unbounded amount of state.
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Ack, CongestionControl, Error, Nack, NewReno, RecvWindow, Result, SendWindow, Stats};
+use core::convert::TryInto;
+use core::fmt::{self, Debug, Formatter};
+use core::time::Duration;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Instant;
+
+// ========================================== Constants ========================================= \\
+
+const DATA: u8 = 0;
+const ACK: u8 = 1;
+const NACK: u8 = 2;
+
+const HEADER_LEN: usize = 5;
+const DATA_OVERHEAD: usize = HEADER_LEN + 8;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ReliableConfig {
+    window: usize,
+    rto: Duration,
+    max_rto: Duration,
+    max_retries: u32,
+    max_streams: usize,
+    ordered: bool,
+}
+
+pub struct ReliableChannel {
+    config: ReliableConfig,
+    streams: BTreeMap<u32, Stream>,
+    delivered: VecDeque<(u32, Vec<u8>)>,
+    controller: Box<dyn CongestionControl>,
+    rtt: Rtt,
+    bytes_in_flight: usize,
+    stats: Stats,
+}
+
+#[derive(Debug)]
+struct Stream {
+    config: ReliableConfig,
+    send: SendWindow,
+    queued: VecDeque<Vec<u8>>,
+    unacked: BTreeMap<u64, Unacked>,
+    recv: RecvWindow,
+    next: u64,
+    reorder: BTreeMap<u64, Vec<u8>>,
+    ack: bool,
+    nack: bool,
+}
+
+#[derive(Debug)]
+struct Unacked {
+    payload: Vec<u8>,
+    sent: Instant,
+    due: Instant,
+    rto: Duration,
+    retries: u32,
+    lost: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Rtt {
+    srtt: Option<Duration>,
+    rttvar: Duration,
+}
+
+// ===================================== impl ReliableConfig ==================================== \\
+
+impl ReliableConfig {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub const fn new() -> Self {
+        ReliableConfig {
+            window: 64,
+            rto: Duration::from_millis(200),
+            max_rto: Duration::from_secs(10),
+            max_retries: 8,
+            max_streams: 256,
+            ordered: true,
+        }
+    }
+
+    #[inline]
+    pub const fn with_window(mut self, window: usize) -> Self {
+        self.window = window;
+        self
+    }
+
+    #[inline]
+    pub const fn with_rto(mut self, rto: Duration) -> Self {
+        self.rto = rto;
+        self
+    }
+
+    #[inline]
+    pub const fn with_max_rto(mut self, max_rto: Duration) -> Self {
+        self.max_rto = max_rto;
+        self
+    }
+
+    #[inline]
+    pub const fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    #[inline]
+    pub const fn with_max_streams(mut self, max_streams: usize) -> Self {
+        self.max_streams = max_streams;
+        self
+    }
+
+    #[inline]
+    pub const fn with_ordered(mut self, ordered: bool) -> Self {
+        self.ordered = ordered;
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    #[inline]
+    pub fn rto(&self) -> Duration {
+        self.rto
+    }
+
+    #[inline]
+    pub fn max_rto(&self) -> Duration {
+        self.max_rto
+    }
+
+    #[inline]
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    #[inline]
+    pub fn max_streams(&self) -> usize {
+        self.max_streams
+    }
+
+    #[inline]
+    pub fn is_ordered(&self) -> bool {
+        self.ordered
+    }
+}
+
+// ==================================== impl ReliableChannel ==================================== \\
+
+impl ReliableChannel {
+    // ====================================== Constants ===================================== \\
+
+    pub const DATA_OVERHEAD: usize = DATA_OVERHEAD;
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new(config: ReliableConfig) -> Self {
+        ReliableChannel {
+            config,
+            streams: BTreeMap::new(),
+            delivered: VecDeque::new(),
+            controller: Box::new(NewReno::new()),
+            rtt: Rtt::default(),
+            bytes_in_flight: 0,
+            stats: Stats::new(),
+        }
+    }
+
+    #[inline]
+    pub fn with_congestion<Control>(mut self, controller: Control) -> Self
+    where
+        Control: CongestionControl + 'static,
+    {
+        self.controller = Box::new(controller);
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn config(&self, stream: u32) -> ReliableConfig {
+        self.streams
+            .get(&stream)
+            .map_or(self.config, |stream| stream.config)
+    }
+
+    #[inline]
+    pub fn in_flight(&self) -> usize {
+        self.streams
+            .values()
+            .map(|stream| stream.unacked.len())
+            .sum()
+    }
+
+    #[inline]
+    pub fn queued(&self) -> usize {
+        self.streams
+            .values()
+            .map(|stream| stream.queued.len())
+            .sum()
+    }
+
+    #[inline]
+    pub fn bytes_in_flight(&self) -> usize {
+        self.bytes_in_flight
+    }
+
+    #[inline]
+    pub fn rtt(&self) -> Option<Duration> {
+        self.rtt.srtt
+    }
+
+    #[inline]
+    pub fn stats(&self) -> Stats {
+        let window = self.controller.window();
+        let cwnd = if window < usize::MAX {
+            Some(window as u64)
+        } else {
+            None
+        };
+
+        self.stats.with_congestion(cwnd, self.rtt.srtt)
+    }
+
+    // Whether everything sent has been acknowledged and no acknowledgment is owed to the peer.
+    #[inline]
+    pub fn is_idle(&self) -> bool {
+        self.streams.values().all(|stream| {
+            stream.queued.is_empty() && stream.unacked.is_empty() && !stream.ack && !stream.nack
+        })
+    }
+
+    pub fn next_timeout(&self) -> Option<Instant> {
+        let pacing = match self.controller.delay() {
+            Some(delay) if self.queued() > 0 => Some(Instant::now() + delay),
+            _ => None,
+        };
+
+        self.streams
+            .values()
+            .flat_map(|stream| stream.unacked.values())
+            .map(|unacked| unacked.due)
+            .chain(pacing)
+            .min()
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    pub fn configure(&mut self, stream: u32, config: ReliableConfig) {
+        let stream = self.stream(stream);
+        stream.config = config;
+        stream.send.set_capacity(config.window);
+        stream.recv.set_window(config.window);
+    }
+
+    #[inline]
+    pub fn send<Payload: Into<Vec<u8>>>(&mut self, stream: u32, payload: Payload) {
+        self.stream(stream).queued.push_back(payload.into());
+    }
+
+    #[inline]
+    pub fn recv(&mut self) -> Option<(u32, Vec<u8>)> {
+        self.delivered.pop_front()
+    }
+
+    // Writes the next frame that needs to be sent into `buf`, returning `false` if there is none
+    // for now. Acknowledgments go first, then expired retransmissions, then new messages.
+    pub fn transmit(&mut self, buf: &mut Vec<u8>) -> Result<bool> {
+        let sent = self.next_frame(buf)?;
+        if sent {
+            self.stats.sent(buf.len());
+        }
+
+        Ok(sent)
+    }
+
+    #[inline]
+    pub fn handle(&mut self, frame: &[u8]) -> Result<()> {
+        self.stats.received(frame.len());
+        self.handle_frame(frame)
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    fn next_frame(&mut self, buf: &mut Vec<u8>) -> Result<bool> {
+        buf.clear();
+
+        for (&id, stream) in &mut self.streams {
+            if stream.ack {
+                stream.ack = false;
+                header(buf, ACK, id);
+                stream.recv.ack().encode(buf);
+
+                return Ok(true);
+            }
+
+            if stream.nack {
+                stream.nack = false;
+
+                let nack = stream.recv.nack();
+                if !nack.is_empty() {
+                    header(buf, NACK, id);
+                    nack.encode(buf);
+
+                    return Ok(true);
+                }
+            }
+        }
+
+        if self.controller.delay().is_some() {
+            return Ok(false);
+        }
+
+        let now = Instant::now();
+        for (&id, stream) in &mut self.streams {
+            let max_retries = stream.config.max_retries;
+            let max_rto = stream.config.max_rto;
+            let expired = stream
+                .unacked
+                .iter_mut()
+                .find(|(_, unacked)| unacked.due <= now);
+
+            if let Some((&seq, unacked)) = expired {
+                if unacked.retries >= max_retries {
+                    return Err(Error::PeerTimeout);
+                }
+
+                if unacked.lost {
+                    self.controller.on_loss(unacked.payload.len(), unacked.sent);
+                } else {
+                    self.controller.on_timeout();
+                }
+
+                unacked.retries += 1;
+                unacked.rto = (unacked.rto * 2).min(max_rto);
+                unacked.sent = now;
+                unacked.due = now + unacked.rto;
+                unacked.lost = false;
+                data(buf, id, seq, &unacked.payload);
+                self.controller.on_sent(unacked.payload.len());
+
+                return Ok(true);
+            }
+        }
+
+        let window = self.controller.window();
+        for (&id, stream) in &mut self.streams {
+            let len = match stream.queued.front() {
+                Some(payload) => payload.len(),
+                None => continue,
+            };
+
+            // A single message can always be in flight, however large it is.
+            if self.bytes_in_flight > 0 && self.bytes_in_flight + len > window {
+                break;
+            }
+
+            if let Some(seq) = stream.send.push() {
+                let payload = stream.queued.pop_front().unwrap();
+                data(buf, id, seq, &payload);
+
+                let rto = self.rtt.rto(&stream.config);
+                stream.unacked.insert(
+                    seq,
+                    Unacked {
+                        payload,
+                        sent: now,
+                        due: now + rto,
+                        rto,
+                        retries: 0,
+                        lost: false,
+                    },
+                );
+
+                self.bytes_in_flight += len;
+                self.controller.on_sent(len);
+
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn handle_frame(&mut self, frame: &[u8]) -> Result<()> {
+        if frame.len() < HEADER_LEN {
+            return Err(Error::InvalidFrame);
+        }
+
+        let id = u32::from_le_bytes(frame[1..HEADER_LEN].try_into().unwrap());
+        match frame[0] {
+            DATA if frame.len() >= DATA_OVERHEAD => {
+                let seq = u64::from_le_bytes(frame[HEADER_LEN..DATA_OVERHEAD].try_into().unwrap());
+                let payload = &frame[DATA_OVERHEAD..];
+
+                if !self.streams.contains_key(&id) && self.streams.len() >= self.config.max_streams
+                {
+                    return Ok(());
+                }
+
+                let stream = self.stream(id);
+                stream.ack = true;
+                if !stream.recv.receive(seq) {
+                    return Ok(());
+                }
+
+                if seq > stream.recv.contiguous() {
+                    stream.nack = true;
+                }
+
+                if !stream.config.ordered {
+                    self.delivered.push_back((id, payload.to_vec()));
+                    return Ok(());
+                }
+
+                let stream = self.streams.get_mut(&id).unwrap();
+                stream.reorder.insert(seq, payload.to_vec());
+                while let Some(payload) = stream.reorder.remove(&stream.next) {
+                    stream.next += 1;
+                    self.delivered.push_back((id, payload));
+                }
+            }
+            ACK => {
+                let ack = Ack::decode(&frame[HEADER_LEN..])?;
+                let stream = match self.streams.get_mut(&id) {
+                    Some(stream) => stream,
+                    None => return Ok(()),
+                };
+
+                let now = Instant::now();
+                for seq in stream.send.ack(&ack) {
+                    if let Some(unacked) = stream.unacked.remove(&seq) {
+                        let len = unacked.payload.len();
+                        self.bytes_in_flight -= len;
+
+                        // Retransmitted messages give ambiguous samples.
+                        if unacked.retries == 0 {
+                            self.rtt.sample(now - unacked.sent);
+                        }
+
+                        self.controller.on_ack(len, unacked.sent);
+                    }
+                }
+            }
+            NACK => {
+                let nack = Nack::decode(&frame[HEADER_LEN..])?;
+                let stream = match self.streams.get_mut(&id) {
+                    Some(stream) => stream,
+                    None => return Ok(()),
+                };
+
+                let now = Instant::now();
+
+                // Messages that were (re)sent less than one initial timeout ago might still be on
+                // their way, and the report would then only be stale.
+                let rto = stream.config.rto;
+                for seq in stream.send.nack(&nack) {
+                    if let Some(unacked) = stream.unacked.get_mut(&seq) {
+                        if unacked.sent + rto <= now {
+                            unacked.due = unacked.due.min(now);
+                            unacked.lost = true;
+                        }
+                    }
+                }
+            }
+            _ => return Err(Error::InvalidFrame),
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn stream(&mut self, id: u32) -> &mut Stream {
+        let config = self.config;
+        self.streams
+            .entry(id)
+            .or_insert_with(|| Stream::new(config))
+    }
+}
+
+// ========================================= impl Stream ======================================== \\
+
+impl Stream {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    fn new(config: ReliableConfig) -> Self {
+        Stream {
+            config,
+            send: SendWindow::new(config.window),
+            queued: VecDeque::new(),
+            unacked: BTreeMap::new(),
+            recv: RecvWindow::new().with_window(config.window),
+            next: 0,
+            reorder: BTreeMap::new(),
+            ack: false,
+            nack: false,
+        }
+    }
+}
+
+// ========================================== impl Rtt ========================================== \\
+
+impl Rtt {
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    fn rto(&self, config: &ReliableConfig) -> Duration {
+        match self.srtt {
+            Some(srtt) => (srtt + 4 * self.rttvar).max(config.rto).min(config.max_rto),
+            None => config.rto,
+        }
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    fn sample(&mut self, rtt: Duration) {
+        match self.srtt {
+            Some(srtt) => {
+                let diff = srtt.max(rtt) - srtt.min(rtt);
+                self.rttvar = self.rttvar * 3 / 4 + diff / 4;
+                self.srtt = Some(srtt * 7 / 8 + rtt / 8);
+            }
+            None => {
+                self.srtt = Some(rtt);
+                self.rttvar = rtt / 2;
+            }
+        }
+    }
+}
+
+// ========================================== header() ========================================== \\
+
+#[inline]
+fn header(buf: &mut Vec<u8>, kind: u8, stream: u32) {
+    buf.push(kind);
+    buf.extend_from_slice(&stream.to_le_bytes());
+}
+
+// =========================================== data() =========================================== \\
+
+#[inline]
+fn data(buf: &mut Vec<u8>, stream: u32, seq: u64, payload: &[u8]) {
+    header(buf, DATA, stream);
+    buf.extend_from_slice(&seq.to_le_bytes());
+    buf.extend_from_slice(payload);
+}
+
+// ========================================= impl Debug ========================================= \\
+
+impl Debug for ReliableChannel {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("ReliableChannel")
+            .field("config", &self.config)
+            .field("streams", &self.streams)
+            .field("delivered", &self.delivered.len())
+            .field("window", &self.controller.window())
+            .field("rtt", &self.rtt)
+            .field("bytes_in_flight", &self.bytes_in_flight)
+            .finish()
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl Default for ReliableConfig {
+    #[inline]
+    fn default() -> Self {
+        ReliableConfig::new()
+    }
+}
+
+impl Default for ReliableChannel {
+    #[inline]
+    fn default() -> Self {
+        ReliableChannel::new(ReliableConfig::default())
+    }
+}
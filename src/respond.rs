@@ -8,36 +8,109 @@
 
 // =========================================== Imports ========================================== \\
 
-use crate::{Handshake, Read, Result, Write};
-use async_peek::AsyncPeek;
+use crate::config::{HYBRID_ACCEPTED, HYBRID_DECLINED};
+use crate::identify::{decode_identity, encode_identity};
+use crate::limiter::{Acquire, Slot};
+use crate::pow::{Challenge, STATUS_CHALLENGE, STATUS_READY};
+use crate::{
+    Compression, Config, Error, ErrorContext, Handshake, HandshakePhase, Identity, Metrics,
+    PaddingPolicy, Read, Result, StateDump, Suite, Timeout, Timer, WireFormat, Write,
+};
+use core::fmt::{self, Debug, Display, Formatter};
 use core::future::Future;
 use core::mem;
 use core::pin::Pin;
 use core::task::{Context, Poll};
+use core::time::Duration;
 use futures_io::{AsyncRead, AsyncWrite};
+use snow::params::NoiseParams;
 use snow::HandshakeState;
+use std::io;
 
 // ============================================ Types =========================================== \\
 
 pub struct Respond<IO> {
     inner: RespondInner<IO>,
+    params: Option<NoiseParams>,
+    metrics: Metrics,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    acquire: Option<Acquire>,
+    slot: Option<Slot>,
 }
 
 enum RespondInner<IO> {
     Empty,
     State {
         io: IO,
+        config: Config,
+    },
+    Offer {
+        config: Config,
+        offer: [u8; 1 + Suite::MAX_OFFERED],
+        len: usize,
+        off: usize,
+        io: IO,
+    },
+    Status {
+        suite: Suite,
+        config: Config,
+        offer: [u8; 1 + Suite::MAX_OFFERED],
+        offered: usize,
+        id: u8,
+        hybrid: bool,
+        challenge: Option<Challenge>,
+        status: [u8; 3 + Challenge::LEN],
+        len: usize,
+        off: usize,
+        io: IO,
+    },
+    Proof {
+        suite: Suite,
+        config: Config,
+        offer: [u8; 1 + Suite::MAX_OFFERED],
+        offered: usize,
+        id: u8,
+        hybrid: bool,
+        challenge: Challenge,
+        proof: [u8; Challenge::PROOF_LEN],
+        off: usize,
+        io: IO,
     },
     Read {
+        suite: Suite,
+        config: Config,
+        identifying: bool,
+        hash: Vec<u8>,
         read: Read<IO, HandshakeState>,
     },
     Write {
+        suite: Suite,
+        config: Config,
+        compression: Compression,
+        wire: WireFormat,
+        payload: Vec<u8>,
+        identity: Option<Identity>,
         write: Write<IO, HandshakeState>,
     },
     Flush {
+        suite: Suite,
+        config: Config,
+        compression: Compression,
+        wire: WireFormat,
+        payload: Vec<u8>,
+        identity: Option<Identity>,
         io: IO,
         state: HandshakeState,
     },
+    Identify {
+        suite: Suite,
+        config: Config,
+        compression: Compression,
+        wire: WireFormat,
+        hash: Vec<u8>,
+        read: Read<IO, HandshakeState>,
+    },
     Done {
         io: IO,
     },
@@ -49,85 +122,621 @@ impl<IO> Respond<IO> {
     // ==================================== Constructors ==================================== \\
 
     #[inline]
-    pub(super) fn new(io: IO) -> Self
+    pub(super) fn new(io: IO, config: Config) -> Self
     where
-        IO: AsyncPeek + AsyncRead + AsyncWrite + Unpin,
+        IO: AsyncRead + AsyncWrite + Unpin,
     {
+        let metrics = config.metrics().clone();
+        let acquire = config.limiter().map(|limiter| limiter.acquire());
         Respond {
-            inner: RespondInner::State { io },
+            inner: RespondInner::State { io, config },
+            params: None,
+            metrics,
+            #[cfg(feature = "tracing")]
+            span: tracing::debug_span!("respond"),
+            acquire,
+            slot: None,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn phase(&self) -> HandshakePhase
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        self.inner.phase()
+    }
+
+    #[inline]
+    pub fn dump_state(&self) -> StateDump
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        if self.acquire.is_some() {
+            StateDump::new("Respond", "queued")
+        } else {
+            self.inner.dump_state()
         }
     }
 
     // ===================================== Destructors ==================================== \\
 
+    #[inline]
+    pub fn timeout<Tmr: Timer>(self, duration: Duration) -> Timeout<Self, Tmr> {
+        Timeout::new(self, duration)
+    }
+
     pub fn done(self) -> IO {
         match self.inner {
             RespondInner::Empty => panic!(),
-            RespondInner::State { io }
+            RespondInner::State { io, .. }
+            | RespondInner::Offer { io, .. }
+            | RespondInner::Status { io, .. }
+            | RespondInner::Proof { io, .. }
             | RespondInner::Flush { io, .. }
             | RespondInner::Done { io } => io,
-            RespondInner::Read { read } => read.done().2,
-            RespondInner::Write { write } => write.done().2,
+            RespondInner::Read { read, .. } | RespondInner::Identify { read, .. } => read.done().2,
+            RespondInner::Write { write, .. } => write.done().2,
         }
     }
 }
 
-// ========================================= impl Future ======================================== \\
+// ====================================== impl RespondInner ===================================== \\
 
-impl<IO> Future for Respond<IO>
+impl<IO> RespondInner<IO>
 where
-    IO: AsyncPeek + AsyncRead + AsyncWrite + Unpin,
+    IO: AsyncRead + AsyncWrite + Unpin,
 {
-    type Output = Result<Handshake>;
+    fn name(&self) -> &'static str {
+        match self {
+            RespondInner::Empty => "empty",
+            RespondInner::State { .. } => "state",
+            RespondInner::Offer { .. } => "offer",
+            RespondInner::Status { .. } => "status",
+            RespondInner::Read { .. } => "read",
+            RespondInner::Proof { .. } => "proof",
+            RespondInner::Write { .. } => "write",
+            RespondInner::Flush { .. } => "flush",
+            RespondInner::Identify { .. } => "identify",
+            RespondInner::Done { .. } => "done",
+        }
+    }
 
-    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
-        let inner = &mut self.get_mut().inner;
+    fn dump_state(&self) -> StateDump {
+        let dump = StateDump::new("Respond", self.name());
+        match self {
+            RespondInner::Status { len, off, .. } => dump.with_buffered(len - off),
+            RespondInner::Read { read, .. } => dump.with_buffered(read.dump_state().buffered()),
+            RespondInner::Proof { off, .. } => dump.with_buffered(*off),
+            RespondInner::Write { write, .. } => dump.with_buffered(write.dump_state().buffered()),
+            RespondInner::Identify { read, .. } => dump.with_buffered(read.dump_state().buffered()),
+            _ => dump,
+        }
+    }
+
+    fn phase(&self) -> HandshakePhase {
+        match self {
+            RespondInner::State { .. } | RespondInner::Offer { .. } => HandshakePhase::Negotiating,
+            RespondInner::Status { .. } => HandshakePhase::SendingStatus,
+            RespondInner::Read { .. } => HandshakePhase::AwaitingE,
+            RespondInner::Proof { .. } => HandshakePhase::AwaitingProof,
+            RespondInner::Write { .. } | RespondInner::Flush { .. } => HandshakePhase::SendingEe,
+            RespondInner::Identify { .. } => HandshakePhase::Identifying,
+            RespondInner::Empty | RespondInner::Done { .. } => HandshakePhase::Done,
+        }
+    }
+
+    // `params` are those of the variant actually being used, which the suite alone doesn't tell.
+    fn poll(
+        &mut self,
+        ctx: &mut Context,
+        phase: &mut HandshakePhase,
+        params: &mut Option<NoiseParams>,
+    ) -> Poll<Result<Handshake>> {
+        let inner = self;
         loop {
+            *phase = inner.phase();
             match mem::take(inner) {
                 RespondInner::Empty | RespondInner::Done { .. } => panic!(),
-                RespondInner::State { io } => {
-                    let state = snow::Builder::new(Handshake::NOISE_PATTERN.parse().unwrap())
-                        .build_responder()?;
+                RespondInner::State { io, config } => {
+                    *inner = RespondInner::Offer {
+                        config,
+                        offer: [0; 1 + Suite::MAX_OFFERED],
+                        len: 1,
+                        off: 0,
+                        io,
+                    };
+                }
+                // The count of suites is read first, followed by their ids.
+                RespondInner::Offer {
+                    config,
+                    offer,
+                    len: 1,
+                    off: 1,
+                    io,
+                } => {
+                    let count = offer[0] as usize;
+                    if count == 0 || count > Suite::MAX_OFFERED {
+                        *inner = RespondInner::Done { io };
 
-                    // -> e     ;; 56 bytes
-                    // <- e, ee ;; 72 bytes
-                    let buf = vec![0; 72];
+                        return Poll::Ready(Err(Error::InvalidOffer));
+                    }
 
-                    *inner = RespondInner::Read {
-                        read: Read::new(Vec::new(), buf, io, state),
+                    *inner = RespondInner::Offer {
+                        config,
+                        offer,
+                        len: 1 + count,
+                        off: 1,
+                        io,
                     };
                 }
-                RespondInner::Read { mut read } => {
-                    if Pin::new(&mut read).poll(ctx)?.is_ready() {
-                        let (_, buf, io, state) = read.done();
+                RespondInner::Offer {
+                    config,
+                    offer,
+                    len,
+                    off,
+                    io,
+                } if off >= len => match config.choose_suite(&offer[1..len]) {
+                    Some((suite, id)) => *inner = status(suite, config, offer, len, id, io),
+                    None => {
+                        *inner = RespondInner::Done { io };
+
+                        return Poll::Ready(Err(Error::UnsupportedSuite(offer[1])));
+                    }
+                },
+                RespondInner::Offer {
+                    config,
+                    mut offer,
+                    len,
+                    mut off,
+                    mut io,
+                } => match Pin::new(&mut io).poll_read(ctx, &mut offer[off..len])? {
+                    Poll::Ready(0) => {
+                        *inner = RespondInner::Done { io };
+
+                        return Poll::Ready(Err(Error::UnexpectedEof {
+                            needed: len,
+                            got: off,
+                        }));
+                    }
+                    Poll::Ready(read) => {
+                        off += read;
+
+                        *inner = RespondInner::Offer {
+                            config,
+                            offer,
+                            len,
+                            off,
+                            io,
+                        };
+                    }
+                    Poll::Pending => {
+                        *inner = RespondInner::Offer {
+                            config,
+                            offer,
+                            len,
+                            off,
+                            io,
+                        };
+
+                        return Poll::Pending;
+                    }
+                },
+                RespondInner::Status {
+                    suite,
+                    config,
+                    offer,
+                    offered,
+                    id,
+                    hybrid,
+                    challenge,
+                    status,
+                    len,
+                    off,
+                    mut io,
+                } if off >= len => {
+                    if Pin::new(&mut io).poll_flush(ctx)?.is_pending() {
+                        *inner = RespondInner::Status {
+                            suite,
+                            config,
+                            offer,
+                            offered,
+                            id,
+                            hybrid,
+                            challenge,
+                            status,
+                            len,
+                            off,
+                            io,
+                        };
+
+                        return Poll::Pending;
+                    }
+
+                    // Neither the first handshake message gets read nor the handshake state gets
+                    // built before the proof is verified.
+                    *inner = match challenge {
+                        Some(challenge) => RespondInner::Proof {
+                            suite,
+                            config,
+                            offer,
+                            offered,
+                            id,
+                            hybrid,
+                            challenge,
+                            proof: [0; Challenge::PROOF_LEN],
+                            off: 0,
+                            io,
+                        },
+                        None => read(suite, config, &offer[..offered], id, hybrid, io, params)?,
+                    };
+                }
+                RespondInner::Status {
+                    suite,
+                    config,
+                    offer,
+                    offered,
+                    id,
+                    hybrid,
+                    challenge,
+                    status,
+                    len,
+                    mut off,
+                    mut io,
+                } => match Pin::new(&mut io).poll_write(ctx, &status[off..len])? {
+                    Poll::Ready(0) => {
+                        *inner = RespondInner::Done { io };
+
+                        return Poll::Ready(Err(io::Error::from(io::ErrorKind::WriteZero).into()));
+                    }
+                    Poll::Ready(wrote) => {
+                        off += wrote;
+
+                        *inner = RespondInner::Status {
+                            suite,
+                            config,
+                            offer,
+                            offered,
+                            id,
+                            hybrid,
+                            challenge,
+                            status,
+                            len,
+                            off,
+                            io,
+                        };
+                    }
+                    Poll::Pending => {
+                        *inner = RespondInner::Status {
+                            suite,
+                            config,
+                            offer,
+                            offered,
+                            id,
+                            hybrid,
+                            challenge,
+                            status,
+                            len,
+                            off,
+                            io,
+                        };
+
+                        return Poll::Pending;
+                    }
+                },
+                RespondInner::Proof {
+                    suite,
+                    config,
+                    offer,
+                    offered,
+                    id,
+                    hybrid,
+                    challenge,
+                    proof,
+                    off,
+                    io,
+                } if off >= proof.len() => {
+                    if !challenge.verify(u64::from_le_bytes(proof)) {
+                        *inner = RespondInner::Done { io };
+
+                        return Poll::Ready(Err(Error::InvalidProof));
+                    }
+
+                    *inner = read(suite, config, &offer[..offered], id, hybrid, io, params)?;
+                }
+                RespondInner::Proof {
+                    suite,
+                    config,
+                    offer,
+                    offered,
+                    id,
+                    hybrid,
+                    challenge,
+                    mut proof,
+                    mut off,
+                    mut io,
+                } => match Pin::new(&mut io).poll_read(ctx, &mut proof[off..])? {
+                    Poll::Ready(0) => {
+                        *inner = RespondInner::Done { io };
+
+                        return Poll::Ready(Err(Error::UnexpectedEof {
+                            needed: Challenge::PROOF_LEN,
+                            got: off,
+                        }));
+                    }
+                    Poll::Ready(read) => {
+                        off += read;
+
+                        *inner = RespondInner::Proof {
+                            suite,
+                            config,
+                            offer,
+                            offered,
+                            id,
+                            hybrid,
+                            challenge,
+                            proof,
+                            off,
+                            io,
+                        };
+                    }
+                    Poll::Pending => {
+                        *inner = RespondInner::Proof {
+                            suite,
+                            config,
+                            offer,
+                            offered,
+                            id,
+                            hybrid,
+                            challenge,
+                            proof,
+                            off,
+                            io,
+                        };
+
+                        return Poll::Pending;
+                    }
+                },
+                RespondInner::Read {
+                    suite,
+                    config,
+                    identifying,
+                    hash,
+                    mut read,
+                } => {
+                    if let Poll::Ready(len) = Pin::new(&mut read).poll(ctx)? {
+                        let (mut payload, buf, io, state) = read.done();
+                        payload.truncate(len);
+
+                        if payload.is_empty() {
+                            *inner = RespondInner::Done { io };
+
+                            return Poll::Ready(Err(Error::PayloadRejected));
+                        }
+
+                        let (compression, wire) = config.negotiate(payload.remove(0));
+
+                        // Only targeted initiators have already sent their static key, along with
+                        // their identity.
+                        let mut identity = None;
+                        if let Some(remote) = state.get_remote_static() {
+                            match decode_identity(&payload, &hash, false, Some(remote)) {
+                                Ok((remote, rest)) => {
+                                    identity = remote;
+                                    payload = rest.to_vec();
+                                }
+                                Err(err) => {
+                                    *inner = RespondInner::Done { io };
+
+                                    return Poll::Ready(Err(err));
+                                }
+                            }
+                        }
+
+                        // Initiators that identify themselves in a third message send their payload
+                        // along with their identity.
+                        let accepted = if identifying {
+                            payload.is_empty()
+                        } else {
+                            config.verify(&payload)
+                        };
+
+                        if !accepted {
+                            *inner = RespondInner::Done { io };
+
+                            return Poll::Ready(Err(Error::PayloadRejected));
+                        }
+
+                        let mut local = vec![compression.id() | wire.bit()];
+                        encode_identity(
+                            config.identity(),
+                            config.certificate(),
+                            state.get_handshake_hash(),
+                            false,
+                            &mut local,
+                        );
+                        local.extend_from_slice(config.payload());
 
                         *inner = RespondInner::Write {
-                            write: Write::new(Vec::new(), buf, io, state),
+                            suite,
+                            config,
+                            compression,
+                            wire,
+                            payload,
+                            identity,
+                            write: Write::new(
+                                local,
+                                buf,
+                                io,
+                                state,
+                                PaddingPolicy::None,
+                                WireFormat::V1,
+                            ),
                         };
                     } else {
-                        *inner = RespondInner::Read { read };
+                        *inner = RespondInner::Read {
+                            suite,
+                            config,
+                            identifying,
+                            hash,
+                            read,
+                        };
 
                         return Poll::Pending;
                     }
                 }
-                RespondInner::Write { mut write } => {
+                RespondInner::Write {
+                    suite,
+                    config,
+                    compression,
+                    wire,
+                    payload,
+                    identity,
+                    mut write,
+                } => {
                     if Pin::new(&mut write).poll(ctx)?.is_ready() {
                         let (_, _, io, state) = write.done();
 
-                        *inner = RespondInner::Flush { io, state };
+                        *inner = RespondInner::Flush {
+                            suite,
+                            config,
+                            compression,
+                            wire,
+                            payload,
+                            identity,
+                            io,
+                            state,
+                        };
                     } else {
-                        *inner = RespondInner::Write { write };
+                        *inner = RespondInner::Write {
+                            suite,
+                            config,
+                            compression,
+                            wire,
+                            payload,
+                            identity,
+                            write,
+                        };
 
                         return Poll::Pending;
                     }
                 }
-                RespondInner::Flush { mut io, state } => {
-                    if Pin::new(&mut io).poll_flush(ctx)?.is_ready() {
+                RespondInner::Flush {
+                    suite,
+                    config,
+                    compression,
+                    wire,
+                    payload,
+                    identity,
+                    mut io,
+                    state,
+                } => {
+                    if Pin::new(&mut io).poll_flush(ctx)?.is_pending() {
+                        *inner = RespondInner::Flush {
+                            suite,
+                            config,
+                            compression,
+                            wire,
+                            payload,
+                            identity,
+                            io,
+                            state,
+                        };
+
+                        return Poll::Pending;
+                    }
+
+                    if state.is_handshake_finished() {
+                        *inner = RespondInner::Done { io };
+
+                        let handshake = Handshake::new(
+                            state,
+                            params.take().unwrap(),
+                            suite,
+                            compression,
+                            wire,
+                            payload,
+                            identity,
+                        );
+                        return Poll::Ready(Ok(handshake));
+                    }
+
+                    // -> s, se ;; 64 bytes + identity + payload
+                    let buf = vec![0; 64];
+
+                    *inner = RespondInner::Identify {
+                        suite,
+                        config,
+                        compression,
+                        wire,
+                        hash: state.get_handshake_hash().to_vec(),
+                        read: Read::new(
+                            Vec::new(),
+                            buf,
+                            io,
+                            state,
+                            PaddingPolicy::None,
+                            WireFormat::V1,
+                        ),
+                    };
+                }
+                RespondInner::Identify {
+                    suite,
+                    config,
+                    compression,
+                    wire,
+                    hash,
+                    mut read,
+                } => {
+                    if let Poll::Ready(len) = Pin::new(&mut read).poll(ctx)? {
+                        let (mut local, _, io, state) = read.done();
+                        local.truncate(len);
+
+                        let remote = state.get_remote_static();
+                        let (identity, payload) =
+                            match decode_identity(&local, &hash, false, remote) {
+                                Ok((Some(identity), rest)) => (Some(identity), rest.to_vec()),
+                                Ok(_) => {
+                                    *inner = RespondInner::Done { io };
+
+                                    return Poll::Ready(Err(Error::InvalidIdentity));
+                                }
+                                Err(err) => {
+                                    *inner = RespondInner::Done { io };
+
+                                    return Poll::Ready(Err(err));
+                                }
+                            };
+
+                        if !config.verify(&payload) {
+                            *inner = RespondInner::Done { io };
+
+                            return Poll::Ready(Err(Error::PayloadRejected));
+                        }
+
                         *inner = RespondInner::Done { io };
 
-                        return Poll::Ready(Ok(Handshake { state }));
+                        let handshake = Handshake::new(
+                            state,
+                            params.take().unwrap(),
+                            suite,
+                            compression,
+                            wire,
+                            payload,
+                            identity,
+                        );
+                        return Poll::Ready(Ok(handshake));
                     } else {
-                        *inner = RespondInner::Flush { io, state };
+                        *inner = RespondInner::Identify {
+                            suite,
+                            config,
+                            compression,
+                            wire,
+                            hash,
+                            read,
+                        };
 
                         return Poll::Pending;
                     }
@@ -137,6 +746,201 @@ where
     }
 }
 
+// ========================================= impl Future ======================================== \\
+
+impl<IO> Future for Respond<IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    type Output = Result<Handshake>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        #[cfg(feature = "tracing")]
+        let _enter = this.span.enter();
+
+        // No handshake state gets allocated before a slot is available.
+        if let Some(acquire) = &mut this.acquire {
+            match acquire.poll(ctx) {
+                Poll::Ready(Ok(slot)) => {
+                    this.acquire = None;
+                    this.slot = Some(slot);
+                }
+                Poll::Ready(Err(err)) => {
+                    this.acquire = None;
+                    if let RespondInner::State { io, .. } = mem::take(&mut this.inner) {
+                        this.inner = RespondInner::Done { io };
+                    }
+
+                    debug!(error = ?err, "handshake rejected");
+
+                    return Poll::Ready(this.metrics.error(Err(err)));
+                }
+                Poll::Pending => {
+                    trace!("handshake queued");
+
+                    return Poll::Pending;
+                }
+            }
+        }
+
+        let mut phase = HandshakePhase::Negotiating;
+        match this.inner.poll(ctx, &mut phase, &mut this.params) {
+            Poll::Ready(Ok(mut handshake)) => {
+                debug!(
+                    suite = ?handshake.suite,
+                    compression = ?handshake.compression,
+                    "handshake complete"
+                );
+                this.metrics.handshake_complete(handshake.suite, false);
+                handshake.metrics = this.metrics.clone();
+                this.slot = None;
+
+                Poll::Ready(Ok(handshake))
+            }
+            Poll::Ready(Err(err)) => {
+                let err = err.with_context(ErrorContext::handshake(phase));
+                debug!(error = ?err, "handshake failed");
+                this.slot = None;
+
+                Poll::Ready(this.metrics.error(Err(err)))
+            }
+            Poll::Pending => {
+                trace!(state = this.inner.name(), "handshake pending");
+
+                Poll::Pending
+            }
+        }
+    }
+}
+
+// ========================================== status() ========================================== \\
+
+fn status<IO>(
+    suite: Suite,
+    config: Config,
+    offer: [u8; 1 + Suite::MAX_OFFERED],
+    offered: usize,
+    id: u8,
+    io: IO,
+) -> RespondInner<IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    let hybrid = id & Suite::HYBRID != 0 && config.is_hybrid();
+
+    // The chosen suite and the answer to a hybrid offer come before the status, so that the
+    // initiator knows which handshake to start.
+    let mut status = [0; 3 + Challenge::LEN];
+    status[0] = id;
+
+    let mut len = 1;
+    if id & Suite::HYBRID != 0 {
+        status[len] = if hybrid {
+            HYBRID_ACCEPTED
+        } else {
+            HYBRID_DECLINED
+        };
+
+        len += 1;
+    }
+
+    let challenge = if config.pow() > 0 {
+        let challenge = Challenge::new(config.pow());
+        status[len] = STATUS_CHALLENGE;
+        status[len + 1..len + 1 + Challenge::LEN].copy_from_slice(&challenge.encode());
+        len += 1 + Challenge::LEN;
+
+        Some(challenge)
+    } else {
+        status[len] = STATUS_READY;
+        len += 1;
+
+        None
+    };
+
+    RespondInner::Status {
+        suite,
+        config,
+        offer,
+        offered,
+        id,
+        hybrid,
+        challenge,
+        status,
+        len,
+        off: 0,
+        io,
+    }
+}
+
+// =========================================== read() =========================================== \\
+
+// Nothing gets allocated for a handshake before this point, so that initiators that don't solve
+// their challenge cost the responder no more than a few bytes on its stack.
+fn read<IO>(
+    suite: Suite,
+    config: Config,
+    offer: &[u8],
+    id: u8,
+    hybrid: bool,
+    io: IO,
+    params: &mut Option<NoiseParams>,
+) -> Result<RespondInner<IO>>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    let (state, variant) = config.build_variant(suite, offer, id, hybrid, false)?;
+    *params = Some(variant);
+
+    // -> e     ;; 56 bytes
+    // <- e, ee ;; 72 bytes
+    let buf = vec![0; 72];
+
+    // Initiators that identify themselves without having been targeted (XN) send their payload
+    // along with their identity, as the first message isn't encrypted.
+    let identifying = id & Suite::IDENTIFIED != 0 && id & Suite::TARGETED == 0;
+
+    Ok(RespondInner::Read {
+        suite,
+        config,
+        identifying,
+        hash: state.get_handshake_hash().to_vec(),
+        read: Read::new(
+            Vec::new(),
+            buf,
+            io,
+            state,
+            PaddingPolicy::None,
+            WireFormat::V1,
+        ),
+    })
+}
+
+// ========================================= impl Debug ========================================= \\
+
+impl<IO> Debug for Respond<IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.dump_state(), fmt)
+    }
+}
+
+// ======================================== impl Display ======================================== \\
+
+impl<IO> Display for Respond<IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.dump_state(), fmt)
+    }
+}
+
 // ======================================== impl Default ======================================== \\
 
 impl<IO> Default for RespondInner<IO> {
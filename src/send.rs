@@ -8,91 +8,412 @@
 
 // =========================================== Imports ========================================== \\
 
-use crate::{Protocol, Result, Write};
+use crate::control::Control;
+use crate::{
+    ErrorContext, FrameFlags, Metrics, Protocol, Result, Session, Throttle, Timeout, Timer, Write,
+};
 use core::future::Future;
 use core::mem;
 use core::pin::Pin;
 use core::task::{Context, Poll};
+use core::time::Duration;
 use format::Encode;
 use futures_io::AsyncWrite;
 use packets::{Packet, MSG_MAX_LEN};
 use snow::TransportState;
 
+// ========================================== Constants ========================================= \\
+
+const ENCODE_MIN_LEN: usize = 256;
+
 // ============================================ Types =========================================== \\
 
 pub struct Send<'proto, Output> {
     inner: SendInner<'proto, Output>,
+    flush: bool,
+    flags: FrameFlags,
+    packet: Option<u16>,
+    metrics: Metrics,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
 }
 
 enum SendInner<'proto, Output> {
     Empty,
     Encode {
-        packet: Packet,
+        packet: Outgoing<'proto>,
         buf: &'proto mut Vec<u8>,
         msg: &'proto mut Vec<u8>,
         state: &'proto mut TransportState,
+        session: &'proto mut Session,
         out: Output,
     },
+    Credit {
+        compressed: bool,
+        len: usize,
+        throttle: Throttle,
+        buf: &'proto mut Vec<u8>,
+        msg: &'proto mut Vec<u8>,
+        state: &'proto mut TransportState,
+        session: &'proto mut Session,
+        out: Output,
+    },
+    Control {
+        packet: Outgoing<'proto>,
+        rekey: bool,
+        write: Write<Output, &'proto mut TransportState, &'proto mut Vec<u8>>,
+        session: &'proto mut Session,
+    },
     Write {
+        len: usize,
         write: Write<Output, &'proto mut TransportState, &'proto mut Vec<u8>>,
+        session: &'proto mut Session,
+    },
+    Flush {
+        wrote: usize,
+        out: Output,
     },
 }
 
+// Packets are either moved into the future, or borrowed for as long as it runs.
+enum Outgoing<'proto> {
+    Owned(Packet),
+    Borrowed(&'proto Packet),
+}
+
 // ========================================== impl Send ========================================= \\
 
 impl<'proto, Output> Send<'proto, Output> {
     // ==================================== Constructors ==================================== \\
 
+    #[inline]
     pub(super) fn new(packet: Packet, proto: &'proto mut Protocol, out: Output) -> Self
     where
         Output: AsyncWrite + Unpin,
     {
+        Self::outgoing(Outgoing::Owned(packet), proto, out)
+    }
+
+    #[inline]
+    pub(super) fn borrowed(packet: &'proto Packet, proto: &'proto mut Protocol, out: Output) -> Self
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        Self::outgoing(Outgoing::Borrowed(packet), proto, out)
+    }
+
+    fn outgoing(packet: Outgoing<'proto>, proto: &'proto mut Protocol, out: Output) -> Self
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        let metrics = proto.session.metrics.clone();
         Send {
             inner: SendInner::Encode {
                 packet,
                 buf: &mut proto.buf,
                 msg: &mut proto.msg,
                 state: &mut proto.state,
+                session: &mut proto.session,
                 out,
             },
+            flush: false,
+            flags: FrameFlags::default(),
+            packet: None,
+            metrics,
+            #[cfg(feature = "tracing")]
+            span: tracing::debug_span!("send"),
         }
     }
+
+    #[inline]
+    pub fn and_flush(mut self) -> Self {
+        self.flush = true;
+        self
+    }
+
+    #[inline]
+    pub(super) fn with_flags(mut self, flags: FrameFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn timeout<Tmr: Timer>(self, duration: Duration) -> Timeout<Self, Tmr> {
+        Timeout::new(self, duration)
+    }
 }
 
-// ========================================= impl Future ======================================== \\
+// ======================================= impl SendInner ======================================= \\
 
-impl<Output> Future for Send<'_, Output>
+impl<Output> SendInner<'_, Output>
 where
     Output: AsyncWrite + Unpin,
 {
-    type Output = Result<usize>;
+    #[cfg(feature = "tracing")]
+    fn name(&self) -> &'static str {
+        match self {
+            SendInner::Empty => "empty",
+            SendInner::Encode { .. } => "encode",
+            SendInner::Credit { .. } => "credit",
+            SendInner::Control { .. } => "control",
+            SendInner::Write { .. } => "write",
+            SendInner::Flush { .. } => "flush",
+        }
+    }
 
-    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
-        let inner = &mut self.get_mut().inner;
+    fn poll(
+        &mut self,
+        ctx: &mut Context,
+        flush: bool,
+        flags: FrameFlags,
+        id: &mut Option<u16>,
+    ) -> Poll<Result<usize>> {
+        let inner = self;
         loop {
             match mem::take(inner) {
                 SendInner::Empty => panic!(),
                 SendInner::Encode {
                     packet,
                     buf,
-                    mut msg,
+                    msg,
                     state,
+                    session,
+                    mut out,
+                } if !session.pending.is_empty() => {
+                    let res = session.pending.poll_write(ctx, &mut out);
+
+                    *inner = SendInner::Encode {
+                        packet,
+                        buf,
+                        msg,
+                        state,
+                        session,
+                        out,
+                    };
+
+                    if res?.is_pending() {
+                        return Poll::Pending;
+                    }
+                }
+                SendInner::Encode {
+                    packet,
+                    buf,
+                    msg,
+                    state,
+                    session,
                     out,
-                } => {
-                    msg.resize(MSG_MAX_LEN, 0);
+                } if session.rekeyer.is_due() => {
+                    trace!("sending rekey");
+                    Control::Rekey.encode(msg);
+
+                    *inner = SendInner::Control {
+                        packet,
+                        rekey: true,
+                        write: Write::control(msg, buf, out, state, session.padding, session.wire),
+                        session,
+                    };
+                }
+                SendInner::Encode {
+                    packet,
+                    buf,
+                    msg,
+                    state,
+                    session,
+                    out,
+                } if session.inbox.has_pongs() => {
+                    Control::Pong(session.inbox.pop_pong().unwrap()).encode(msg);
 
-                    let (bytes, _) = packet.encode(&mut msg)?;
+                    *inner = SendInner::Control {
+                        packet,
+                        rekey: false,
+                        write: Write::control(msg, buf, out, state, session.padding, session.wire),
+                        session,
+                    };
+                }
+                SendInner::Encode {
+                    packet,
+                    buf,
+                    msg,
+                    state,
+                    session,
+                    out,
+                } if session.inbox.flow.has_update() => {
+                    Control::Resize(session.inbox.flow.take_update().unwrap()).encode(msg);
+
+                    *inner = SendInner::Control {
+                        packet,
+                        rekey: false,
+                        write: Write::control(msg, buf, out, state, session.padding, session.wire),
+                        session,
+                    };
+                }
+                SendInner::Encode {
+                    packet,
+                    buf,
+                    msg,
+                    state,
+                    session,
+                    out,
+                } if session.inbox.flow.has_grant() => {
+                    Control::Window(session.inbox.flow.take_grant().unwrap()).encode(msg);
+
+                    *inner = SendInner::Control {
+                        packet,
+                        rekey: false,
+                        write: Write::control(msg, buf, out, state, session.padding, session.wire),
+                        session,
+                    };
+                }
+                SendInner::Encode {
+                    packet,
+                    buf,
+                    msg,
+                    state,
+                    session,
+                    out,
+                } => {
+                    let bytes = encode(packet.get(), msg)?;
                     msg.truncate(bytes);
+                    *id = Some(crate::unknown::id(&msg));
+                    trace!(id = crate::unknown::id(&msg), len = bytes, "encoded packet");
+
+                    let compressed = if let Some(payload) = session.compress(&msg)? {
+                        let id = session.inbox.compression.id();
+                        trace!(len = payload.len(), "compressed packet");
+                        Control::Compressed(id, &payload).encode(msg);
 
+                        true
+                    } else {
+                        false
+                    };
+
+                    *inner = SendInner::Credit {
+                        compressed,
+                        len: bytes,
+                        throttle: Throttle::default(),
+                        buf,
+                        msg,
+                        state,
+                        session,
+                        out,
+                    };
+                }
+                SendInner::Credit {
+                    compressed,
+                    len,
+                    mut throttle,
+                    buf,
+                    msg,
+                    state,
+                    session,
+                    out,
+                } => {
+                    // Waiting for credit would only work if something else was receiving
+                    // concurrently, which is why running out of it is an error instead.
+                    session.inbox.flow.check(msg.len())?;
+
+                    if throttle.poll(ctx, session, msg.len())?.is_pending() {
+                        *inner = SendInner::Credit {
+                            compressed,
+                            len,
+                            throttle,
+                            buf,
+                            msg,
+                            state,
+                            session,
+                            out,
+                        };
+
+                        return Poll::Pending;
+                    }
+
+                    session.send_limit.consume(msg.len());
+
+                    let write = if compressed {
+                        Write::control(msg, buf, out, state, session.padding, session.wire)
+                    } else {
+                        Write::new(msg, buf, out, state, session.padding, session.wire)
+                    };
+
+                    let flags = flags.with_compressed(compressed);
                     *inner = SendInner::Write {
-                        write: Write::new(msg, buf, out, state),
+                        len,
+                        write: write.with_flags(flags),
+                        session,
                     };
                 }
-                SendInner::Write { mut write } => {
+                SendInner::Control {
+                    packet,
+                    rekey,
+                    mut write,
+                    session,
+                } => {
+                    if Pin::new(&mut write).poll(ctx)?.is_ready() {
+                        let (msg, buf, out, state) = write.done();
+
+                        if rekey {
+                            state.rekey_outgoing();
+                            session.rekeyer.reset();
+                        }
+
+                        *inner = SendInner::Encode {
+                            packet,
+                            buf,
+                            msg,
+                            state,
+                            session,
+                            out,
+                        };
+                    } else {
+                        *inner = SendInner::Control {
+                            packet,
+                            rekey,
+                            write,
+                            session,
+                        };
+
+                        return Poll::Pending;
+                    }
+                }
+                SendInner::Write {
+                    len,
+                    mut write,
+                    session,
+                } => {
                     if let Poll::Ready(wrote) = Pin::new(&mut write).poll(ctx)? {
+                        let (msg, _, out, _) = write.done();
+                        if let Some(id) = *id {
+                            session.packet_written(id, len);
+                        }
+
+                        session.inbox.flow.sent(msg.len());
+                        session.rekeyer.record(msg.len());
+                        session.liveness.sent();
+                        session.pacer.sent(wrote);
+                        trace!(len = msg.len(), wrote, "sent packet");
+
+                        if !flush {
+                            return Poll::Ready(Ok(wrote));
+                        }
+
+                        *inner = SendInner::Flush { wrote, out };
+                    } else {
+                        *inner = SendInner::Write {
+                            len,
+                            write,
+                            session,
+                        };
+
+                        return Poll::Pending;
+                    }
+                }
+                SendInner::Flush { wrote, mut out } => {
+                    if Pin::new(&mut out).poll_flush(ctx)?.is_ready() {
                         return Poll::Ready(Ok(wrote));
                     } else {
-                        *inner = SendInner::Write { write };
+                        *inner = SendInner::Flush { wrote, out };
 
                         return Poll::Pending;
                     }
@@ -102,6 +423,133 @@ where
     }
 }
 
+// ======================================== impl Outgoing ======================================= \\
+
+impl Outgoing<'_> {
+    #[inline]
+    fn get(&self) -> &Packet {
+        match self {
+            Outgoing::Owned(packet) => packet,
+            Outgoing::Borrowed(packet) => packet,
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Output> Future for Send<'_, Output>
+where
+    Output: AsyncWrite + Unpin,
+{
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        #[cfg(feature = "tracing")]
+        let _enter = this.span.enter();
+
+        let (flush, flags) = (this.flush, this.flags);
+        match this.inner.poll(ctx, flush, flags, &mut this.packet) {
+            Poll::Ready(Err(mut err)) => {
+                if let Some(packet) = this.packet {
+                    err = err.with_context(ErrorContext::packet(packet));
+                }
+
+                debug!(error = ?err, "send failed");
+
+                Poll::Ready(this.metrics.error(Err(err)))
+            }
+            Poll::Ready(Ok(res)) => Poll::Ready(Ok(res)),
+            Poll::Pending => {
+                trace!(state = this.inner.name(), "send pending");
+
+                Poll::Pending
+            }
+        }
+    }
+}
+
+// ========================================== impl Drop ========================================= \\
+
+impl<Output> Drop for Send<'_, Output> {
+    fn drop(&mut self) {
+        match mem::take(&mut self.inner) {
+            SendInner::Control {
+                rekey,
+                write,
+                session,
+                ..
+            } => {
+                if let Some(bytes) = write.unwritten() {
+                    session.pending.save(bytes);
+
+                    if rekey {
+                        let (_, _, _, state) = write.done();
+                        state.rekey_outgoing();
+                        session.rekeyer.reset();
+                    }
+                }
+            }
+            SendInner::Write {
+                len,
+                write,
+                session,
+            } => {
+                // The rest of the frame gets written before anything else, so it counts as sent.
+                if let Some(bytes) = write.unwritten() {
+                    session.pending.save(bytes);
+
+                    if let Some(id) = self.packet {
+                        session.packet_written(id, len);
+                    }
+
+                    let (msg, _, _, _) = write.done();
+                    session.inbox.flow.sent(msg.len());
+                    session.rekeyer.record(msg.len());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// ========================================== encode() ========================================== \\
+
+#[inline]
+pub(crate) fn encode(packet: &Packet, msg: &mut Vec<u8>) -> Result<usize> {
+    msg.clear();
+    append(packet, msg)
+}
+
+// ========================================== append() ========================================== \\
+
+// Encodes the packet after the bytes already in `msg`, which is left holding exactly both. Most
+// packets fit in `ENCODE_MIN_LEN` bytes, and the others are encoded again with as much room as any
+// packet can need, so that failing then isn't because of the room it was given.
+pub(crate) fn append(packet: &Packet, msg: &mut Vec<u8>) -> Result<usize> {
+    let off = msg.len();
+    msg.resize(off + ENCODE_MIN_LEN, 0);
+
+    let res = match packet.encode(&mut msg[off..]) {
+        Err(_) => {
+            msg.resize(off + MSG_MAX_LEN, 0);
+            packet.encode(&mut msg[off..])
+        }
+        res => res,
+    };
+
+    match res {
+        Ok((bytes, _)) => {
+            msg.truncate(off + bytes);
+            Ok(bytes)
+        }
+        Err(err) => {
+            msg.truncate(off);
+            Err(err.into())
+        }
+    }
+}
+
 // ======================================== impl Default ======================================== \\
 
 impl<Output> Default for SendInner<'_, Output> {
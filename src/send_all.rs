@@ -0,0 +1,295 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::control::Control;
+use crate::send;
+use crate::unknown;
+use crate::write;
+use crate::{Frame, Protocol, Result, Throttle};
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::AsyncWrite;
+use packets::Packet;
+use std::io;
+
+// ============================================ Types =========================================== \\
+
+pub struct SendAll<'proto, Output> {
+    inner: SendAllInner<'proto, Output>,
+    throttle: Throttle,
+}
+
+enum SendAllInner<'proto, Output> {
+    Empty,
+    Encode {
+        packets: Vec<Packet>,
+        proto: &'proto mut Protocol,
+        out: Output,
+    },
+    Write {
+        len: usize,
+        off: usize,
+        sent: Vec<(u16, usize)>,
+        proto: &'proto mut Protocol,
+        out: Output,
+    },
+    Done,
+}
+
+// ======================================== impl SendAll ======================================== \\
+
+impl<'proto, Output> SendAll<'proto, Output> {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(super) fn new(packets: Vec<Packet>, proto: &'proto mut Protocol, out: Output) -> Self
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        SendAll {
+            inner: SendAllInner::Encode {
+                packets,
+                proto,
+                out,
+            },
+            throttle: Throttle::default(),
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Output> Future for SendAll<'_, Output>
+where
+    Output: AsyncWrite + Unpin,
+{
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let inner = &mut this.inner;
+        loop {
+            match mem::take(inner) {
+                SendAllInner::Empty | SendAllInner::Done => panic!(),
+                SendAllInner::Encode {
+                    packets,
+                    proto,
+                    mut out,
+                } if !proto.session.pending.is_empty() => {
+                    let res = proto.session.pending.poll_write(ctx, &mut out);
+
+                    *inner = SendAllInner::Encode {
+                        packets,
+                        proto,
+                        out,
+                    };
+
+                    if res?.is_pending() {
+                        return Poll::Pending;
+                    }
+                }
+                SendAllInner::Encode {
+                    packets,
+                    proto,
+                    out,
+                } => {
+                    if this.throttle.poll(ctx, &mut proto.session, 0)?.is_pending() {
+                        *inner = SendAllInner::Encode {
+                            packets,
+                            proto,
+                            out,
+                        };
+
+                        return Poll::Pending;
+                    }
+
+                    let frames = packets.into_iter().map(Frame::new).collect::<Vec<_>>();
+                    let (len, sent) = encode(&frames, proto)?;
+
+                    *inner = SendAllInner::Write {
+                        len,
+                        off: 0,
+                        sent,
+                        proto,
+                        out,
+                    };
+                }
+                SendAllInner::Write {
+                    len,
+                    off,
+                    sent,
+                    proto,
+                    ..
+                } if off >= len => {
+                    for (id, bytes) in sent {
+                        proto.session.packet_written(id, bytes);
+                    }
+
+                    proto.session.pacer.sent(len);
+                    *inner = SendAllInner::Done;
+
+                    return Poll::Ready(Ok(len));
+                }
+                SendAllInner::Write {
+                    len,
+                    mut off,
+                    sent,
+                    proto,
+                    mut out,
+                } => match Pin::new(&mut out).poll_write(ctx, &proto.buf[off..len])? {
+                    Poll::Ready(0) => {
+                        *inner = SendAllInner::Done;
+
+                        return Poll::Ready(Err(io::Error::from(io::ErrorKind::WriteZero).into()));
+                    }
+                    Poll::Ready(wrote) => {
+                        off += wrote;
+
+                        *inner = SendAllInner::Write {
+                            len,
+                            off,
+                            sent,
+                            proto,
+                            out,
+                        };
+                    }
+                    Poll::Pending => {
+                        *inner = SendAllInner::Write {
+                            len,
+                            off,
+                            sent,
+                            proto,
+                            out,
+                        };
+
+                        return Poll::Pending;
+                    }
+                },
+            }
+        }
+    }
+}
+
+// ========================================== impl Drop ========================================= \\
+
+impl<Output> Drop for SendAll<'_, Output> {
+    fn drop(&mut self) {
+        if let SendAllInner::Write {
+            len,
+            off,
+            sent,
+            proto,
+            ..
+        } = &mut self.inner
+        {
+            // The rest of the frames gets written before anything else, so they count as sent.
+            if off < len {
+                proto.session.pending.save(&proto.buf[*off..*len]);
+
+                for (id, bytes) in sent.drain(..) {
+                    proto.session.packet_written(id, bytes);
+                }
+            }
+        }
+    }
+}
+
+// ========================================== encode() ========================================== \\
+
+// Returns how many bytes were encrypted, along with the ID and length of every packet, which only
+// count as sent once written.
+pub(crate) fn encode(frames: &[Frame], proto: &mut Protocol) -> Result<(usize, Vec<(u16, usize)>)> {
+    let Protocol {
+        buf,
+        msg,
+        state,
+        session,
+    } = proto;
+
+    // Every packet gets encoded before anything is encrypted, so that a batch which doesn't fit in
+    // the peer's window is refused as a whole. It doesn't wait for the send limit though, which is
+    // instead paid back before the next one is.
+    msg.clear();
+    let mut ends = Vec::with_capacity(frames.len());
+    for frame in frames {
+        send::append(frame.packet(), msg)?;
+        ends.push(msg.len());
+    }
+
+    session.inbox.flow.check(msg.len())?;
+
+    let mut ctl = Vec::new();
+    let mut len = 0;
+    while session.inbox.has_pongs() {
+        Control::Pong(session.inbox.pop_pong().unwrap()).encode(&mut ctl);
+
+        len = write::append(&mut *state, &[], buf, len, session.padding, session.wire)?;
+        len = write::append(&mut *state, &ctl, buf, len, session.padding, session.wire)?;
+    }
+
+    if let Some(window) = session.inbox.flow.take_update() {
+        Control::Resize(window).encode(&mut ctl);
+
+        len = write::append(&mut *state, &[], buf, len, session.padding, session.wire)?;
+        len = write::append(&mut *state, &ctl, buf, len, session.padding, session.wire)?;
+    }
+
+    if let Some(grant) = session.inbox.flow.take_grant() {
+        Control::Window(grant).encode(&mut ctl);
+
+        len = write::append(&mut *state, &[], buf, len, session.padding, session.wire)?;
+        len = write::append(&mut *state, &ctl, buf, len, session.padding, session.wire)?;
+    }
+
+    let mut sent = Vec::with_capacity(frames.len());
+    let mut start = 0;
+    for (frame, end) in frames.iter().zip(ends) {
+        if session.rekeyer.is_due() {
+            Control::Rekey.encode(&mut ctl);
+
+            len = write::append(&mut *state, &[], buf, len, session.padding, session.wire)?;
+            len = write::append(&mut *state, &ctl, buf, len, session.padding, session.wire)?;
+            state.rekey_outgoing();
+            session.rekeyer.reset();
+        }
+
+        let bytes = &msg[start..end];
+        len = write::append_frame(
+            &mut *state,
+            bytes,
+            buf,
+            len,
+            session.padding,
+            session.wire,
+            frame.flags(),
+        )?;
+        session.send_limit.consume(bytes.len());
+        session.inbox.flow.sent(bytes.len());
+        session.rekeyer.record(bytes.len());
+        sent.push((unknown::id(bytes), bytes.len()));
+
+        start = end;
+    }
+
+    session.liveness.sent();
+
+    Ok((len, sent))
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<Output> Default for SendAllInner<'_, Output> {
+    #[inline]
+    fn default() -> Self {
+        SendAllInner::Empty
+    }
+}
@@ -0,0 +1,261 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::control::{Control, Fragment, FRAGMENT_OVERHEAD};
+use crate::{FrameFlags, Protocol, Result, Session, Write};
+use core::cmp;
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::AsyncWrite;
+use snow::TransportState;
+
+// ============================================ Types =========================================== \\
+
+pub struct SendLarge<'proto, 'data, Output> {
+    inner: SendLargeInner<'proto, 'data, Output>,
+}
+
+enum SendLargeInner<'proto, 'data, Output> {
+    Empty,
+    Encode {
+        data: &'data [u8],
+        stream: u32,
+        seq: u32,
+        wrote: usize,
+        buf: &'proto mut Vec<u8>,
+        msg: &'proto mut Vec<u8>,
+        state: &'proto mut TransportState,
+        session: &'proto mut Session,
+        out: Output,
+    },
+    Rekey {
+        data: &'data [u8],
+        stream: u32,
+        seq: u32,
+        wrote: usize,
+        write: Write<Output, &'proto mut TransportState, &'proto mut Vec<u8>>,
+        session: &'proto mut Session,
+    },
+    Write {
+        data: &'data [u8],
+        stream: u32,
+        seq: u32,
+        wrote: usize,
+        last: bool,
+        write: Write<Output, &'proto mut TransportState, &'proto mut Vec<u8>>,
+        session: &'proto mut Session,
+    },
+}
+
+// ======================================= impl SendLarge ======================================= \\
+
+impl<'proto, 'data, Output> SendLarge<'proto, 'data, Output> {
+    // ==================================== Constructors ==================================== \\
+
+    pub(super) fn new(data: &'data [u8], proto: &'proto mut Protocol, out: Output) -> Self
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        let stream = proto.session.next_large;
+        proto.session.next_large = proto.session.next_large.wrapping_add(1);
+
+        SendLarge {
+            inner: SendLargeInner::Encode {
+                data,
+                stream,
+                seq: 0,
+                wrote: 0,
+                buf: &mut proto.buf,
+                msg: &mut proto.msg,
+                state: &mut proto.state,
+                session: &mut proto.session,
+                out,
+            },
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Output> Future for SendLarge<'_, '_, Output>
+where
+    Output: AsyncWrite + Unpin,
+{
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let inner = &mut self.get_mut().inner;
+        loop {
+            match mem::take(inner) {
+                SendLargeInner::Empty => panic!(),
+                SendLargeInner::Encode {
+                    data,
+                    stream,
+                    seq,
+                    wrote,
+                    buf,
+                    msg,
+                    state,
+                    session,
+                    out,
+                } if session.rekeyer.is_due() => {
+                    Control::Rekey.encode(msg);
+
+                    *inner = SendLargeInner::Rekey {
+                        data,
+                        stream,
+                        seq,
+                        wrote,
+                        write: Write::control(msg, buf, out, state, session.padding, session.wire),
+                        session,
+                    };
+                }
+                SendLargeInner::Encode {
+                    data,
+                    stream,
+                    seq,
+                    wrote,
+                    buf,
+                    msg,
+                    state,
+                    session,
+                    out,
+                } => {
+                    let chunk = session.padding.max_len() - FRAGMENT_OVERHEAD;
+                    if seq == 0 {
+                        // The whole payload has to fit in the peer's window before any of it is
+                        // sent, as a stalled stream could never be completed.
+                        let fragments = cmp::max(1, (data.len() + chunk - 1) / chunk);
+                        session
+                            .inbox
+                            .flow
+                            .check(data.len() + fragments * FRAGMENT_OVERHEAD)?;
+                    }
+
+                    let len = cmp::min(data.len(), chunk);
+                    let last = len == data.len();
+
+                    Control::Fragment(Fragment {
+                        stream,
+                        seq,
+                        last,
+                        payload: &data[..len],
+                    })
+                    .encode(msg);
+
+                    *inner = SendLargeInner::Write {
+                        data: &data[len..],
+                        stream,
+                        seq: seq.wrapping_add(1),
+                        wrote,
+                        last,
+                        write: Write::control(msg, buf, out, state, session.padding, session.wire)
+                            .with_flags(FrameFlags::default().with_fragment(true)),
+                        session,
+                    };
+                }
+                SendLargeInner::Rekey {
+                    data,
+                    stream,
+                    seq,
+                    wrote,
+                    mut write,
+                    session,
+                } => {
+                    if let Poll::Ready(len) = Pin::new(&mut write).poll(ctx)? {
+                        let (msg, buf, out, state) = write.done();
+
+                        state.rekey_outgoing();
+                        session.rekeyer.reset();
+
+                        *inner = SendLargeInner::Encode {
+                            data,
+                            stream,
+                            seq,
+                            wrote: wrote + len,
+                            buf,
+                            msg,
+                            state,
+                            session,
+                            out,
+                        };
+                    } else {
+                        *inner = SendLargeInner::Rekey {
+                            data,
+                            stream,
+                            seq,
+                            wrote,
+                            write,
+                            session,
+                        };
+
+                        return Poll::Pending;
+                    }
+                }
+                SendLargeInner::Write {
+                    data,
+                    stream,
+                    seq,
+                    wrote,
+                    last,
+                    mut write,
+                    session,
+                } => {
+                    if let Poll::Ready(len) = Pin::new(&mut write).poll(ctx)? {
+                        let (msg, buf, out, state) = write.done();
+                        session.stats.sent(msg.len());
+                        session.inbox.flow.sent(msg.len());
+                        session.rekeyer.record(msg.len());
+                        session.liveness.sent();
+
+                        if last {
+                            return Poll::Ready(Ok(wrote + len));
+                        }
+
+                        *inner = SendLargeInner::Encode {
+                            data,
+                            stream,
+                            seq,
+                            wrote: wrote + len,
+                            buf,
+                            msg,
+                            state,
+                            session,
+                            out,
+                        };
+                    } else {
+                        *inner = SendLargeInner::Write {
+                            data,
+                            stream,
+                            seq,
+                            wrote,
+                            last,
+                            write,
+                            session,
+                        };
+
+                        return Poll::Pending;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<Output> Default for SendLargeInner<'_, '_, Output> {
+    #[inline]
+    fn default() -> Self {
+        SendLargeInner::Empty
+    }
+}
@@ -0,0 +1,136 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{DatagramProtocol, DatagramSocket, Result};
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use packets::Packet;
+use std::io;
+use std::net::SocketAddr;
+
+// ============================================ Types =========================================== \\
+
+pub struct SendTo<'proto, 'sock, Socket> {
+    inner: SendToInner<'proto, 'sock, Socket>,
+}
+
+enum SendToInner<'proto, 'sock, Socket> {
+    Empty,
+    Encode {
+        packet: Packet,
+        addr: SocketAddr,
+        proto: &'proto mut DatagramProtocol,
+        socket: &'sock Socket,
+    },
+    Send {
+        len: usize,
+        addr: SocketAddr,
+        proto: &'proto mut DatagramProtocol,
+        socket: &'sock Socket,
+    },
+    Done,
+}
+
+// ========================================= impl SendTo ======================================== \\
+
+impl<'proto, 'sock, Socket> SendTo<'proto, 'sock, Socket> {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(super) fn new(
+        packet: Packet,
+        addr: SocketAddr,
+        proto: &'proto mut DatagramProtocol,
+        socket: &'sock Socket,
+    ) -> Self
+    where
+        Socket: DatagramSocket,
+    {
+        SendTo {
+            inner: SendToInner::Encode {
+                packet,
+                addr,
+                proto,
+                socket,
+            },
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Socket> Future for SendTo<'_, '_, Socket>
+where
+    Socket: DatagramSocket,
+{
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let inner = &mut self.get_mut().inner;
+        loop {
+            match mem::take(inner) {
+                SendToInner::Empty | SendToInner::Done => panic!(),
+                SendToInner::Encode {
+                    packet,
+                    addr,
+                    proto,
+                    socket,
+                } => {
+                    let len = proto.encrypt(packet)?;
+
+                    *inner = SendToInner::Send {
+                        len,
+                        addr,
+                        proto,
+                        socket,
+                    };
+                }
+                SendToInner::Send {
+                    len,
+                    addr,
+                    proto,
+                    socket,
+                } => match socket.poll_send_to(ctx, &proto.buf()[..len], addr)? {
+                    Poll::Ready(sent) if sent < len => {
+                        *inner = SendToInner::Done;
+
+                        return Poll::Ready(Err(io::Error::from(io::ErrorKind::WriteZero).into()));
+                    }
+                    Poll::Ready(_) => {
+                        *inner = SendToInner::Done;
+
+                        return Poll::Ready(Ok(len));
+                    }
+                    Poll::Pending => {
+                        *inner = SendToInner::Send {
+                            len,
+                            addr,
+                            proto,
+                            socket,
+                        };
+
+                        return Poll::Pending;
+                    }
+                },
+            }
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<Socket> Default for SendToInner<'_, '_, Socket> {
+    #[inline]
+    fn default() -> Self {
+        SendToInner::Empty
+    }
+}
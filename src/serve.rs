@@ -0,0 +1,162 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Acceptor, Config, Error, Protocol, Respond, Result, Timeout, Timer};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use ed25519_dalek::PublicKey;
+use futures_io::{AsyncRead, AsyncWrite};
+use std::io;
+
+// ============================================ Types =========================================== \\
+
+pub struct Serve<Lst: Listener, Hdl, Tmr> {
+    listener: Lst,
+    acceptor: Acceptor,
+    handler: Hdl,
+    max_in_flight: usize,
+    in_flight: Vec<Timeout<Respond<Lst::Io>, Tmr>>,
+}
+
+// ========================================= Interfaces ========================================= \\
+
+pub trait Listener {
+    type Io: AsyncRead + AsyncWrite + Unpin;
+
+    fn poll_accept(&mut self, ctx: &mut Context) -> Poll<io::Result<Self::Io>>;
+}
+
+// =========================================== serve() ========================================== \\
+
+#[inline]
+pub fn serve<Lst, Hdl, Tmr>(listener: Lst, config: Config, handler: Hdl) -> Serve<Lst, Hdl, Tmr>
+where
+    Lst: Listener,
+    Hdl: FnMut(PublicKey, Protocol, Lst::Io),
+    Tmr: Timer,
+{
+    Serve {
+        listener,
+        acceptor: Acceptor::new(config),
+        handler,
+        max_in_flight: Serve::<Lst, Hdl, Tmr>::DEFAULT_MAX_IN_FLIGHT,
+        in_flight: Vec::new(),
+    }
+}
+
+// ========================================= impl Serve ========================================= \\
+
+impl<Lst: Listener, Hdl, Tmr> Serve<Lst, Hdl, Tmr> {
+    // ====================================== Constants ===================================== \\
+
+    pub const DEFAULT_MAX_IN_FLIGHT: usize = 64;
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.acceptor = self.acceptor.with_timeout(timeout);
+        self
+    }
+
+    #[inline]
+    pub fn with_max_in_flight(mut self, max: usize) -> Self {
+        self.max_in_flight = max.max(1);
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn acceptor(&self) -> &Acceptor {
+        &self.acceptor
+    }
+
+    #[inline]
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight
+    }
+
+    #[inline]
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn into_listener(self) -> Lst {
+        self.listener
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Lst, Hdl, Tmr> Future for Serve<Lst, Hdl, Tmr>
+where
+    Lst: Listener + Unpin,
+    Hdl: FnMut(PublicKey, Protocol, Lst::Io) + Unpin,
+    Tmr: Timer,
+{
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            while this.in_flight.len() < this.max_in_flight {
+                match this.listener.poll_accept(ctx) {
+                    Poll::Ready(Ok(io)) => {
+                        let respond = this.acceptor.accept_with_timer(io);
+                        this.in_flight.push(respond);
+                    }
+                    Poll::Ready(Err(err)) => return Poll::Ready(Err(err.into())),
+                    Poll::Pending => break,
+                }
+            }
+
+            let mut done = false;
+            let mut idx = 0;
+            while idx < this.in_flight.len() {
+                let res = match Pin::new(&mut this.in_flight[idx]).poll(ctx) {
+                    Poll::Ready(res) => res,
+                    Poll::Pending => {
+                        idx += 1;
+                        continue;
+                    }
+                };
+
+                done = true;
+                let respond = this.in_flight.swap_remove(idx).into_inner();
+
+                let accepted = res.and_then(|handshake| {
+                    let identity = handshake.remote_identity().copied();
+                    let identity = identity.ok_or(Error::InvalidIdentity)?;
+
+                    Ok((identity, handshake.done()?))
+                });
+
+                match accepted {
+                    Ok((identity, proto)) => (this.handler)(identity, proto, respond.done()),
+                    Err(_err) => {
+                        debug!(error = ?_err, "incoming handshake failed");
+                    }
+                }
+            }
+
+            // Completed handshakes free up room for more connections, which need to be polled
+            // at least once to be woken up later.
+            if !done {
+                return Poll::Pending;
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::budget::Budget;
+use crate::control::{Inbox, COMPRESSED_OVERHEAD};
+use crate::pending::Pending;
+use crate::timeout::{After, Sleep};
+use crate::unknown;
+use crate::{
+    BufferPool, Certificate, Compression, Error, Liveness, Metrics, Pacer, PacketRegistry,
+    PaddingPolicy, Protocol, RateLimiter, ReadBudget, RekeyPolicy, Rekeyer, Result, Stats,
+    UnknownPacketPolicy, WireFormat,
+};
+use core::time::Duration;
+use ed25519_dalek::PublicKey;
+use snow::TransportState;
+use std::sync::Arc;
+
+// ============================================ Types =========================================== \\
+
+pub(crate) struct Session {
+    pub(crate) rekeyer: Rekeyer,
+    pub(crate) liveness: Liveness,
+    pub(crate) inbox: Inbox,
+    pub(crate) next_large: u32,
+    pub(crate) registry: Option<Arc<PacketRegistry>>,
+    pub(crate) pool: Option<BufferPool>,
+    pub(crate) unknown: UnknownPacketPolicy,
+    pub(crate) compression_threshold: usize,
+    pub(crate) padding: PaddingPolicy,
+    pub(crate) wire: WireFormat,
+    pub(crate) read_budget: ReadBudget,
+    pub(crate) send_limit: RateLimiter,
+    pub(crate) recv_limit: RateLimiter,
+    pub(crate) pacer: Pacer,
+    pub(crate) timer: Option<After>,
+    pub(crate) pending: Pending,
+    pub(crate) identity: Option<PublicKey>,
+    pub(crate) certificate: Option<Certificate>,
+    pub(crate) metrics: Metrics,
+    pub(crate) stats: Stats,
+}
+
+// ======================================== impl Session ======================================== \\
+
+impl Session {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Session {
+            rekeyer: Rekeyer::new(RekeyPolicy::never()),
+            liveness: Liveness::new(),
+            inbox: Inbox::new(Protocol::LARGE_MAX_LEN),
+            next_large: 0,
+            registry: None,
+            pool: None,
+            unknown: UnknownPacketPolicy::default(),
+            compression_threshold: Protocol::COMPRESSION_THRESHOLD,
+            padding: PaddingPolicy::default(),
+            wire: WireFormat::default(),
+            read_budget: ReadBudget::default(),
+            send_limit: RateLimiter::default(),
+            recv_limit: RateLimiter::default(),
+            pacer: Pacer::default(),
+            timer: None,
+            pending: Pending::default(),
+            identity: None,
+            certificate: None,
+            metrics: Metrics::default(),
+            stats: Stats::new(),
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub(crate) fn budget(&self) -> Budget {
+        Budget::new(self.read_budget)
+    }
+
+    pub(crate) fn compress(&self, msg: &[u8]) -> Result<Option<Vec<u8>>> {
+        let compression = self.inbox.compression;
+        if compression == Compression::None || msg.len() < self.compression_threshold {
+            return Ok(None);
+        }
+
+        let payload = compression.compress(msg)?;
+        if payload.len() + COMPRESSED_OVERHEAD < msg.len() {
+            Ok(Some(payload))
+        } else {
+            Ok(None)
+        }
+    }
+
+    #[inline]
+    pub(crate) fn sleep(&self, delay: Duration) -> Result<Sleep> {
+        match self.timer {
+            Some(after) => Ok(after(delay)),
+            None => Err(Error::MissingTimer),
+        }
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub(crate) fn packet_sent(&mut self, msg: &[u8]) {
+        self.packet_written(unknown::id(msg), msg.len());
+    }
+
+    // Counts a packet that was encoded as `id` and `len` bytes once its frame got written, as it
+    // might have been compressed in-between.
+    #[inline]
+    pub(crate) fn packet_written(&mut self, id: u16, len: usize) {
+        self.metrics.packet_sent(id, len);
+        self.stats.sent(len);
+    }
+
+    #[inline]
+    pub(crate) fn packet_received(&mut self, msg: &[u8]) -> Result<()> {
+        self.metrics.packet_received(msg);
+        self.stats.received(msg.len());
+
+        if self.recv_limit.admit(msg.len()) {
+            Ok(())
+        } else {
+            Err(Error::RateExceeded)
+        }
+    }
+
+    // Control messages count against the receive limit too, except for compressed packets which
+    // only do once they're inflated, like any other packet.
+    pub(crate) fn handle(&mut self, msg: &[u8], state: &mut TransportState) -> Result<()> {
+        if self.inbox.handle(msg, state)? {
+            self.stats.received(msg.len());
+        }
+
+        if self.inbox.has_inflated() || self.recv_limit.admit(msg.len()) {
+            Ok(())
+        } else {
+            Err(Error::RateExceeded)
+        }
+    }
+}
@@ -0,0 +1,92 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::Result;
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::AsyncWrite;
+
+// ============================================ Types =========================================== \\
+
+pub struct Shutdown<Output> {
+    inner: ShutdownInner<Output>,
+}
+
+enum ShutdownInner<Output> {
+    Empty,
+    Flush { out: Output },
+    Close { out: Output },
+    Done,
+}
+
+// ======================================== impl Shutdown ======================================= \\
+
+impl<Output> Shutdown<Output> {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(super) fn new(out: Output) -> Self
+    where
+        Output: AsyncWrite + Unpin,
+    {
+        Shutdown {
+            inner: ShutdownInner::Flush { out },
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Output> Future for Shutdown<Output>
+where
+    Output: AsyncWrite + Unpin,
+{
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let inner = &mut self.get_mut().inner;
+        loop {
+            match mem::take(inner) {
+                ShutdownInner::Empty | ShutdownInner::Done => panic!(),
+                ShutdownInner::Flush { mut out } => {
+                    if Pin::new(&mut out).poll_flush(ctx)?.is_ready() {
+                        *inner = ShutdownInner::Close { out };
+                    } else {
+                        *inner = ShutdownInner::Flush { out };
+
+                        return Poll::Pending;
+                    }
+                }
+                ShutdownInner::Close { mut out } => {
+                    if Pin::new(&mut out).poll_close(ctx)?.is_ready() {
+                        *inner = ShutdownInner::Done;
+
+                        return Poll::Ready(Ok(()));
+                    } else {
+                        *inner = ShutdownInner::Close { out };
+
+                        return Poll::Pending;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<Output> Default for ShutdownInner<Output> {
+    #[inline]
+    fn default() -> Self {
+        ShutdownInner::Empty
+    }
+}
@@ -0,0 +1,380 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// When a `Connector` goes through a SOCKS5 proxy, the dialed connection is first asked to connect
+// to the target (without authentication) before the Noise handshake starts:
+//
+// GREETING ;; version(1) + methods(1) + method(1)
+// METHOD   ;; version(1) + method(1)
+// REQUEST  ;; version(1) + command(1) + reserved(1) + address + port(2)
+// REPLY    ;; version(1) + reply(1) + reserved(1) + address + port(2)
+//
+// with address being kind(1) + ipv4(4), kind(1) + len(1) + domain(len) or kind(1) + ipv6(16),
+// and the port being big-endian.
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Error, Result};
+use core::fmt::{self, Display, Formatter};
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::{AsyncRead, AsyncWrite};
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+
+// ========================================== Constants ========================================= \\
+
+const VERSION: u8 = 5;
+const NO_AUTH: u8 = 0;
+const CONNECT: u8 = 1;
+const SUCCEEDED: u8 = 0;
+
+const IPV4: u8 = 1;
+const DOMAIN: u8 = 3;
+const IPV6: u8 = 4;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SocksAddr {
+    Ip(SocketAddr),
+    Domain(String, u16),
+}
+
+pub(crate) struct Socks5<IO> {
+    inner: Socks5Inner<IO>,
+    target: SocksAddr,
+}
+
+enum Socks5Inner<IO> {
+    Empty,
+    Write {
+        msg: Vec<u8>,
+        off: usize,
+        io: IO,
+        next: Step,
+    },
+    Flush {
+        io: IO,
+        next: Step,
+    },
+    Read {
+        msg: Vec<u8>,
+        off: usize,
+        io: IO,
+        step: Step,
+    },
+    Done {
+        io: IO,
+    },
+}
+
+enum Advance {
+    Write(Vec<u8>, Step),
+    Read(Step),
+    Done,
+}
+
+#[derive(Clone, Copy)]
+enum Step {
+    Method,
+    Reply,
+    DomainLen,
+    Bound(usize),
+}
+
+// ======================================= impl SocksAddr ======================================= \\
+
+impl SocksAddr {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn domain<Domain: Into<String>>(domain: Domain, port: u16) -> Self {
+        SocksAddr::Domain(domain.into(), port)
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn port(&self) -> u16 {
+        match self {
+            SocksAddr::Ip(addr) => addr.port(),
+            SocksAddr::Domain(_, port) => *port,
+        }
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    fn encode(&self, msg: &mut Vec<u8>) -> Result<()> {
+        match self {
+            SocksAddr::Ip(SocketAddr::V4(addr)) => {
+                msg.push(IPV4);
+                msg.extend_from_slice(&addr.ip().octets());
+            }
+            SocksAddr::Ip(SocketAddr::V6(addr)) => {
+                msg.push(IPV6);
+                msg.extend_from_slice(&addr.ip().octets());
+            }
+            SocksAddr::Domain(domain, _) if domain.is_empty() || domain.len() > 255 => {
+                return Err(io::Error::from(io::ErrorKind::InvalidInput).into());
+            }
+            SocksAddr::Domain(domain, _) => {
+                msg.push(DOMAIN);
+                msg.push(domain.len() as u8);
+                msg.extend_from_slice(domain.as_bytes());
+            }
+        }
+
+        msg.extend_from_slice(&self.port().to_be_bytes());
+
+        Ok(())
+    }
+}
+
+// ========================================= impl Socks5 ======================================== \\
+
+impl<IO> Socks5<IO> {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new(io: IO, target: SocksAddr) -> Self {
+        Socks5 {
+            inner: Socks5Inner::Write {
+                msg: vec![VERSION, 1, NO_AUTH],
+                off: 0,
+                io,
+                next: Step::Method,
+            },
+            target,
+        }
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn done(self) -> IO {
+        match self.inner {
+            Socks5Inner::Empty => panic!(),
+            Socks5Inner::Write { io, .. }
+            | Socks5Inner::Flush { io, .. }
+            | Socks5Inner::Read { io, .. }
+            | Socks5Inner::Done { io } => io,
+        }
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    fn advance(&self, msg: &[u8], step: Step) -> Result<Advance> {
+        match step {
+            Step::Method if msg[0] != VERSION || msg[1] != NO_AUTH => Err(invalid()),
+            Step::Method => {
+                let mut msg = vec![VERSION, CONNECT, 0];
+                self.target.encode(&mut msg)?;
+
+                Ok(Advance::Write(msg, Step::Reply))
+            }
+            Step::Reply if msg[0] != VERSION => Err(invalid()),
+            Step::Reply if msg[1] != SUCCEEDED => Err(Error::Socks(msg[1])),
+            Step::Reply => match msg[3] {
+                IPV4 => Ok(Advance::Read(Step::Bound(4 + 2))),
+                IPV6 => Ok(Advance::Read(Step::Bound(16 + 2))),
+                DOMAIN => Ok(Advance::Read(Step::DomainLen)),
+                _ => Err(invalid()),
+            },
+            Step::DomainLen => Ok(Advance::Read(Step::Bound(msg[0] as usize + 2))),
+            Step::Bound(_) => Ok(Advance::Done),
+        }
+    }
+}
+
+// ========================================== impl Step ========================================= \\
+
+impl Step {
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    fn len(self) -> usize {
+        match self {
+            Step::Method => 2,
+            Step::Reply => 4,
+            Step::DomainLen => 1,
+            Step::Bound(len) => len,
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<IO> Future for Socks5<IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            match mem::take(&mut this.inner) {
+                Socks5Inner::Empty | Socks5Inner::Done { .. } => panic!(),
+                Socks5Inner::Write {
+                    msg,
+                    mut off,
+                    mut io,
+                    next,
+                } => match Pin::new(&mut io).poll_write(ctx, &msg[off..])? {
+                    Poll::Ready(0) => {
+                        this.inner = Socks5Inner::Done { io };
+
+                        return Poll::Ready(Err(io::Error::from(io::ErrorKind::WriteZero).into()));
+                    }
+                    Poll::Ready(wrote) => {
+                        off += wrote;
+
+                        if off >= msg.len() {
+                            this.inner = Socks5Inner::Flush { io, next };
+                        } else {
+                            this.inner = Socks5Inner::Write { msg, off, io, next };
+                        }
+                    }
+                    Poll::Pending => {
+                        this.inner = Socks5Inner::Write { msg, off, io, next };
+
+                        return Poll::Pending;
+                    }
+                },
+                Socks5Inner::Flush { mut io, next } => {
+                    if Pin::new(&mut io).poll_flush(ctx)?.is_pending() {
+                        this.inner = Socks5Inner::Flush { io, next };
+
+                        return Poll::Pending;
+                    }
+
+                    this.inner = Socks5Inner::Read {
+                        msg: vec![0; next.len()],
+                        off: 0,
+                        io,
+                        step: next,
+                    };
+                }
+                Socks5Inner::Read { msg, off, io, step } if off >= msg.len() => {
+                    match this.advance(&msg, step) {
+                        Ok(Advance::Write(msg, next)) => {
+                            this.inner = Socks5Inner::Write {
+                                msg,
+                                off: 0,
+                                io,
+                                next,
+                            };
+                        }
+                        Ok(Advance::Read(next)) => {
+                            this.inner = Socks5Inner::Read {
+                                msg: vec![0; next.len()],
+                                off: 0,
+                                io,
+                                step: next,
+                            };
+                        }
+                        Ok(Advance::Done) => {
+                            this.inner = Socks5Inner::Done { io };
+
+                            return Poll::Ready(Ok(()));
+                        }
+                        Err(err) => {
+                            this.inner = Socks5Inner::Done { io };
+
+                            return Poll::Ready(Err(err));
+                        }
+                    }
+                }
+                Socks5Inner::Read {
+                    mut msg,
+                    mut off,
+                    mut io,
+                    step,
+                } => match Pin::new(&mut io).poll_read(ctx, &mut msg[off..])? {
+                    Poll::Ready(0) => {
+                        this.inner = Socks5Inner::Done { io };
+
+                        return Poll::Ready(Err(
+                            io::Error::from(io::ErrorKind::UnexpectedEof).into()
+                        ));
+                    }
+                    Poll::Ready(read) => {
+                        off += read;
+
+                        this.inner = Socks5Inner::Read { msg, off, io, step };
+                    }
+                    Poll::Pending => {
+                        this.inner = Socks5Inner::Read { msg, off, io, step };
+
+                        return Poll::Pending;
+                    }
+                },
+            }
+        }
+    }
+}
+
+// ========================================== invalid() ========================================= \\
+
+#[inline]
+fn invalid() -> Error {
+    io::Error::from(io::ErrorKind::InvalidData).into()
+}
+
+// ========================================== impl From ========================================= \\
+
+impl From<SocketAddr> for SocksAddr {
+    #[inline]
+    fn from(addr: SocketAddr) -> Self {
+        SocksAddr::Ip(addr)
+    }
+}
+
+impl From<(IpAddr, u16)> for SocksAddr {
+    #[inline]
+    fn from(addr: (IpAddr, u16)) -> Self {
+        SocksAddr::Ip(addr.into())
+    }
+}
+
+impl From<(&str, u16)> for SocksAddr {
+    #[inline]
+    fn from((domain, port): (&str, u16)) -> Self {
+        SocksAddr::domain(domain, port)
+    }
+}
+
+impl From<(String, u16)> for SocksAddr {
+    #[inline]
+    fn from((domain, port): (String, u16)) -> Self {
+        SocksAddr::Domain(domain, port)
+    }
+}
+
+// ======================================== impl Display ======================================== \\
+
+impl Display for SocksAddr {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            SocksAddr::Ip(addr) => write!(fmt, "{}", addr),
+            SocksAddr::Domain(domain, port) => write!(fmt, "{}:{}", domain, port),
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<IO> Default for Socks5Inner<IO> {
+    #[inline]
+    fn default() -> Self {
+        Socks5Inner::Empty
+    }
+}
@@ -0,0 +1,138 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{send, Error, Framed, Protocol, Result};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::{AsyncRead, AsyncWrite};
+use packets::Packet;
+use std::sync::{Arc, Mutex};
+
+// ============================================ Types =========================================== \\
+
+pub struct Sender<IO> {
+    shared: Arc<Mutex<Framed<IO>>>,
+}
+
+pub struct Receiver<IO> {
+    shared: Arc<Mutex<Framed<IO>>>,
+}
+
+// Owned sends go through the same steps as `Send`: frames left by a dropped send and pongs go out
+// first, followed by any due rekey or window update, and packets get compressed, checked against
+// the peer's window and delayed by the send limit and pacing.
+pub struct SendOwned<IO> {
+    shared: Arc<Mutex<Framed<IO>>>,
+    packet: Option<Packet>,
+    msg: Vec<u8>,
+}
+
+pub struct RecvOwned<IO> {
+    shared: Arc<Mutex<Framed<IO>>>,
+}
+
+// ========================================= impl Sender ======================================== \\
+
+impl<IO> Sender<IO> {
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub fn send(&self, packet: Packet) -> SendOwned<IO> {
+        SendOwned {
+            shared: self.shared.clone(),
+            packet: Some(packet),
+            msg: Vec::new(),
+        }
+    }
+}
+
+// ======================================== impl Receiver ======================================= \\
+
+impl<IO> Receiver<IO> {
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub fn recv(&self) -> RecvOwned<IO> {
+        RecvOwned {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<IO> Future for SendOwned<IO>
+where
+    IO: AsyncWrite + Unpin,
+{
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut framed = this.shared.lock().unwrap();
+
+        if let Some(packet) = this.packet.take() {
+            // Whatever is left from a previous message needs to be written before encrypting
+            // another one, as they share the same output buffer, and the send limit and pacing
+            // need to let it through.
+            if framed.poll_drain(ctx)?.is_pending() || framed.poll_pace(ctx)?.is_pending() {
+                this.packet = Some(packet);
+
+                return Poll::Pending;
+            }
+
+            let bytes = send::encode(&packet, &mut this.msg)?;
+            framed.write_msg(&this.msg[..bytes])?;
+            framed.packet_sent(&this.msg[..bytes]);
+        }
+
+        framed.poll_flush(ctx)
+    }
+}
+
+impl<IO> Future for RecvOwned<IO>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    type Output = Result<Packet>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let mut framed = self.shared.lock().unwrap();
+        loop {
+            let len = match framed.poll_read_msg(ctx)? {
+                Poll::Ready(Some(len)) => len,
+                Poll::Ready(None) => return Poll::Ready(Err(Error::PeerClosed)),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            framed.packet_received(len)?;
+
+            let policy = framed.unknown_packet_policy();
+            if let Some(packet) = policy.decode(&framed.msg()[..len]).transpose() {
+                return Poll::Ready(packet);
+            }
+        }
+    }
+}
+
+// ======================================== into_split() ======================================== \\
+
+#[inline]
+pub(crate) fn into_split<IO>(proto: Protocol, io: IO) -> (Sender<IO>, Receiver<IO>) {
+    let shared = Arc::new(Mutex::new(Framed::new(proto, io)));
+
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
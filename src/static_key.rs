@@ -0,0 +1,91 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// A node's Noise static key is derived from its ed25519 identity, so that an initiator knowing
+// only the node's id can run an IK handshake against it (see `Config::with_remote_identity`):
+//
+// - the private key is the clamped scalar of the expanded ed25519 secret key,
+// - the public key is the Montgomery form of the ed25519 public key.
+//
+// Both halves are the same point, as the ed25519 public key is that scalar times the base point.
+
+// =========================================== Imports ========================================== \\
+
+use crate::{wipe, Error, Result};
+use core::fmt::{self, Debug, Formatter};
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use ed25519_dalek::{ExpandedSecretKey, Keypair, PublicKey};
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone)]
+pub struct StaticKey {
+    secret: [u8; 32],
+    public: [u8; 32],
+}
+
+// ======================================= impl StaticKey ======================================= \\
+
+impl StaticKey {
+    // ====================================== Constants ===================================== \\
+
+    pub const LEN: usize = 32;
+
+    // ==================================== Constructors ==================================== \\
+
+    pub fn from_identity(keypair: &Keypair) -> Self {
+        let mut expanded = ExpandedSecretKey::from(&keypair.secret).to_bytes();
+
+        let mut secret = [0; 32];
+        secret.copy_from_slice(&expanded[..32]);
+        wipe(&mut expanded[..]);
+
+        StaticKey {
+            secret,
+            // The public key of a keypair is always a valid point.
+            public: Self::remote(&keypair.public).unwrap(),
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    pub fn remote(identity: &PublicKey) -> Result<[u8; 32]> {
+        let point = CompressedEdwardsY(identity.to_bytes())
+            .decompress()
+            .ok_or(Error::InvalidIdentity)?;
+
+        Ok(point.to_montgomery().to_bytes())
+    }
+
+    #[inline]
+    pub fn public(&self) -> &[u8; 32] {
+        &self.public
+    }
+
+    #[inline]
+    pub(crate) fn secret(&self) -> &[u8; 32] {
+        &self.secret
+    }
+}
+
+// ========================================== impl Drop ========================================= \\
+
+impl Drop for StaticKey {
+    #[inline]
+    fn drop(&mut self) {
+        wipe(&mut self.secret[..]);
+    }
+}
+
+// ========================================= impl Debug ========================================= \\
+
+impl Debug for StaticKey {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("StaticKey").field("public", &self.public).finish()
+    }
+}
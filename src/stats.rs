@@ -0,0 +1,145 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use core::time::Duration;
+use std::time::Instant;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug)]
+pub struct Stats {
+    packets_sent: u64,
+    bytes_sent: u64,
+    packets_received: u64,
+    bytes_received: u64,
+    rekeys: u64,
+    handshake: Instant,
+    last_activity: Instant,
+    goodput: Option<u64>,
+    pacing_rate: Option<u64>,
+    cwnd: Option<u64>,
+    rtt: Option<Duration>,
+}
+
+// ========================================= impl Stats ========================================= \\
+
+impl Stats {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(crate) fn new() -> Self {
+        let now = Instant::now();
+        Stats {
+            packets_sent: 0,
+            bytes_sent: 0,
+            packets_received: 0,
+            bytes_received: 0,
+            rekeys: 0,
+            handshake: now,
+            last_activity: now,
+            goodput: None,
+            pacing_rate: None,
+            cwnd: None,
+            rtt: None,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn with_activity(mut self, rekeys: u64, last_activity: Instant) -> Self {
+        self.rekeys = rekeys;
+        self.last_activity = last_activity;
+        self
+    }
+
+    #[inline]
+    pub(crate) fn with_pacing(mut self, goodput: Option<u64>, pacing_rate: Option<u64>) -> Self {
+        self.goodput = goodput;
+        self.pacing_rate = pacing_rate;
+        self
+    }
+
+    #[inline]
+    pub(crate) fn with_congestion(mut self, cwnd: Option<u64>, rtt: Option<Duration>) -> Self {
+        self.cwnd = cwnd;
+        self.rtt = rtt;
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn packets_sent(&self) -> u64 {
+        self.packets_sent
+    }
+
+    #[inline]
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    #[inline]
+    pub fn packets_received(&self) -> u64 {
+        self.packets_received
+    }
+
+    #[inline]
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received
+    }
+
+    #[inline]
+    pub fn rekeys(&self) -> u64 {
+        self.rekeys
+    }
+
+    #[inline]
+    pub fn handshake_completed(&self) -> Instant {
+        self.handshake
+    }
+
+    #[inline]
+    pub fn last_activity(&self) -> Instant {
+        self.last_activity
+    }
+
+    #[inline]
+    pub fn goodput(&self) -> Option<u64> {
+        self.goodput
+    }
+
+    #[inline]
+    pub fn pacing_rate(&self) -> Option<u64> {
+        self.pacing_rate
+    }
+
+    #[inline]
+    pub fn cwnd(&self) -> Option<u64> {
+        self.cwnd
+    }
+
+    #[inline]
+    pub fn rtt(&self) -> Option<Duration> {
+        self.rtt
+    }
+
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub(crate) fn sent(&mut self, bytes: usize) {
+        self.packets_sent += 1;
+        self.bytes_sent += bytes as u64;
+    }
+
+    #[inline]
+    pub(crate) fn received(&mut self, bytes: usize) {
+        self.packets_received += 1;
+        self.bytes_received += bytes as u64;
+    }
+}
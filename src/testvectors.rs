@@ -0,0 +1,332 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// Known-good encodings of the formats defined by this crate on top of Noise, for alternate
+// implementations to check theirs against. Every vector gets encoded and decoded by the same code
+// as the protocol itself, so that any drift of the wire format fails tests/vectors.rs:
+//
+// CONTROLS  ;; every control message kind (see control.rs)
+// HEADERS   ;; a frame with each wire format (see wire.rs)
+// DATAGRAMS ;; the nonce framing of datagrams (see datagram.rs)
+// POW       ;; the status byte, a challenge and its smallest proof (see pow.rs)
+//
+// Frames and datagrams carry 16 bytes (the length of an empty message's tag) standing in for the
+// encrypted message, which depends on the session's keys.
+
+// =========================================== Imports ========================================== \\
+
+use crate::control::{Control, Fragment};
+use crate::pow::{Challenge, STATUS_CHALLENGE, STATUS_READY};
+use crate::{Compression, DatagramProtocol, FrameFlags, WireFormat};
+use core::convert::TryInto;
+use packets::NOISE_OVERHEAD;
+
+// ========================================== Constants ========================================= \\
+
+const NONCE: u64 = 0x0102_0304_0506_0708;
+
+const BODY: [u8; 16] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+];
+
+const DIFFICULTY: u8 = 12;
+
+pub const CONTROLS: &[Vector] = &[
+    Vector {
+        name: "rekey",
+        bytes: &[0x00],
+        encode: || control(Control::Rekey),
+        decode: |bytes| matches!(Control::decode(bytes), Ok(Control::Rekey)),
+    },
+    Vector {
+        name: "fragment",
+        bytes: &[
+            0x01, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x66, 0x72, 0x61, 0x67,
+        ],
+        encode: || {
+            control(Control::Fragment(Fragment {
+                stream: 1,
+                seq: 2,
+                last: true,
+                payload: b"frag",
+            }))
+        },
+        decode: |bytes| match Control::decode(bytes) {
+            Ok(Control::Fragment(fragment)) => {
+                fragment.stream == 1
+                    && fragment.seq == 2
+                    && fragment.last
+                    && fragment.payload == b"frag"
+            }
+            _ => false,
+        },
+    },
+    Vector {
+        name: "ping",
+        bytes: &[0x02, 0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01],
+        encode: || control(Control::Ping(NONCE)),
+        decode: |bytes| matches!(Control::decode(bytes), Ok(Control::Ping(NONCE))),
+    },
+    Vector {
+        name: "pong",
+        bytes: &[0x03, 0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01],
+        encode: || control(Control::Pong(NONCE)),
+        decode: |bytes| matches!(Control::decode(bytes), Ok(Control::Pong(NONCE))),
+    },
+    Vector {
+        name: "close",
+        bytes: &[0x04, 0xe8, 0x03, 0x62, 0x79, 0x65],
+        encode: || control(Control::Close(1000, "bye")),
+        decode: |bytes| matches!(Control::decode(bytes), Ok(Control::Close(1000, "bye"))),
+    },
+    Vector {
+        name: "custom",
+        bytes: &[0x05, 0x34, 0x12, 0x63, 0x75, 0x73, 0x74, 0x6f, 0x6d],
+        encode: || control(Control::Custom(0x1234, b"custom")),
+        decode: |bytes| match Control::decode(bytes) {
+            Ok(Control::Custom(id, payload)) => id == 0x1234 && payload == b"custom",
+            _ => false,
+        },
+    },
+    Vector {
+        name: "window",
+        bytes: &[0x06, 0x00, 0x00, 0x01, 0x00],
+        encode: || control(Control::Window(0x0001_0000)),
+        decode: |bytes| matches!(Control::decode(bytes), Ok(Control::Window(0x0001_0000))),
+    },
+    Vector {
+        name: "compressed",
+        bytes: &[0x07, 0x01, 0x6c, 0x7a, 0x34],
+        encode: || control(Control::Compressed(Compression::Lz4.id(), b"lz4")),
+        decode: |bytes| match Control::decode(bytes) {
+            Ok(Control::Compressed(id, payload)) => {
+                id == Compression::Lz4.id() && payload == b"lz4"
+            }
+            _ => false,
+        },
+    },
+    Vector {
+        name: "resize",
+        bytes: &[0x08, 0x00, 0x10, 0x00, 0x00],
+        encode: || control(Control::Resize(0x1000)),
+        decode: |bytes| matches!(Control::decode(bytes), Ok(Control::Resize(0x1000))),
+    },
+];
+
+pub const HEADERS: &[Vector] = &[
+    Vector {
+        name: "v1",
+        bytes: &[
+            0x10, 0x00, // len
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+            0x0e, 0x0f,
+        ],
+        encode: || frame(WireFormat::V1),
+        decode: |bytes| unframe(WireFormat::V1, bytes),
+    },
+    Vector {
+        name: "v2",
+        bytes: &[
+            0x00, 0x10, // len
+            0x00, 0x17, // flags
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+            0x0e, 0x0f,
+        ],
+        encode: || frame(WireFormat::V2),
+        decode: |bytes| unframe(WireFormat::V2, bytes),
+    },
+    Vector {
+        name: "v3",
+        bytes: &[
+            0x00, 0x10, // len
+            0x00, 0x17, // flags
+            0xce, 0xce, 0xe2, 0x88, // crc32
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+            0x0e, 0x0f,
+        ],
+        encode: || frame(WireFormat::V3),
+        decode: |bytes| unframe(WireFormat::V3, bytes),
+    },
+];
+
+pub const DATAGRAMS: &[Vector] = &[
+    Vector {
+        name: "first",
+        bytes: &[
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // nonce
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+            0x0e, 0x0f,
+        ],
+        encode: || datagram(0),
+        decode: |bytes| undatagram(0, bytes),
+    },
+    Vector {
+        name: "nonce",
+        bytes: &[
+            0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, // nonce
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+            0x0e, 0x0f,
+        ],
+        encode: || datagram(NONCE),
+        decode: |bytes| undatagram(NONCE, bytes),
+    },
+];
+
+pub const POW: &[Vector] = &[
+    Vector {
+        name: "ready",
+        bytes: &[0x00],
+        encode: || vec![STATUS_READY],
+        decode: |bytes| bytes == [STATUS_READY],
+    },
+    Vector {
+        name: "challenge",
+        bytes: &[
+            0x01, // status
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+            0x0e, 0x0f, // nonce
+            0x0c, // difficulty
+        ],
+        encode: || {
+            let mut bytes = vec![STATUS_CHALLENGE];
+            bytes.extend_from_slice(&challenge().encode());
+
+            bytes
+        },
+        decode: |bytes| match bytes.split_first() {
+            Some((&STATUS_CHALLENGE, buf)) => match buf.try_into() {
+                Ok(buf) => Challenge::decode(buf) == challenge(),
+                Err(_) => false,
+            },
+            _ => false,
+        },
+    },
+    Vector {
+        name: "proof",
+        bytes: &[0xe3, 0x15, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        encode: || {
+            let proof = challenge().solve(0, u64::MAX).unwrap();
+            proof.to_le_bytes().to_vec()
+        },
+        decode: |bytes| match bytes.try_into() {
+            Ok(proof) => challenge().verify(u64::from_le_bytes(proof)),
+            Err(_) => false,
+        },
+    },
+];
+
+// ============================================ Types =========================================== \\
+
+pub struct Vector {
+    pub name: &'static str,
+    pub bytes: &'static [u8],
+    encode: fn() -> Vec<u8>,
+    decode: fn(&[u8]) -> bool,
+}
+
+// ========================================= impl Vector ======================================== \\
+
+impl Vector {
+    // ====================================== Encoding ====================================== \\
+
+    // Encodes the value that the vector's bytes are expected to be the encoding of.
+    #[inline]
+    pub fn encode(&self) -> Vec<u8> {
+        (self.encode)()
+    }
+
+    // ====================================== Decoding ====================================== \\
+
+    // Whether `bytes` decode to that same value.
+    #[inline]
+    pub fn decodes(&self, bytes: &[u8]) -> bool {
+        (self.decode)(bytes)
+    }
+}
+
+// ========================================== control() ========================================= \\
+
+fn control(control: Control) -> Vec<u8> {
+    let mut msg = Vec::new();
+    control.encode(&mut msg);
+
+    msg
+}
+
+// =========================================== frame() ========================================== \\
+
+fn frame(wire: WireFormat) -> Vec<u8> {
+    let hdr_len = wire.header_len();
+    let mut buf = vec![0; hdr_len + BODY.len()];
+    buf[hdr_len..].copy_from_slice(&BODY);
+    wire.encode_header(&mut buf, BODY.len(), flags(wire));
+
+    buf
+}
+
+// ========================================== unframe() ========================================= \\
+
+fn unframe(wire: WireFormat, bytes: &[u8]) -> bool {
+    let hdr_len = wire.header_len();
+    if bytes.len() < hdr_len {
+        return false;
+    }
+
+    let (len, flags) = wire.decode_header(bytes);
+    let checksum = wire.decode_checksum(bytes);
+    let msg = &bytes[hdr_len..];
+
+    len == msg.len()
+        && flags == self::flags(wire)
+        && msg == BODY
+        && WireFormat::verify(checksum, msg).is_ok()
+}
+
+// =========================================== flags() ========================================== \\
+
+// V1 doesn't have any room for flags.
+fn flags(wire: WireFormat) -> FrameFlags {
+    match wire {
+        WireFormat::V1 => FrameFlags::default(),
+        WireFormat::V2 | WireFormat::V3 => FrameFlags::default()
+            .with_compressed(true)
+            .with_fragment(true)
+            .with_priority(5),
+    }
+}
+
+// ========================================= datagram() ========================================= \\
+
+fn datagram(nonce: u64) -> Vec<u8> {
+    let mut buf = vec![0; DatagramProtocol::NONCE_LEN + BODY.len()];
+    buf[DatagramProtocol::NONCE_LEN..].copy_from_slice(&BODY);
+    DatagramProtocol::encode_nonce(&mut buf, nonce);
+
+    buf
+}
+
+// ======================================== undatagram() ======================================== \\
+
+// Datagrams too short to hold a tag are dropped, just like by the protocol.
+fn undatagram(nonce: u64, bytes: &[u8]) -> bool {
+    if bytes.len() < DatagramProtocol::NONCE_LEN + NOISE_OVERHEAD {
+        return false;
+    }
+
+    DatagramProtocol::decode_nonce(bytes) == nonce && bytes[DatagramProtocol::NONCE_LEN..] == BODY
+}
+
+// ========================================= challenge() ======================================== \\
+
+#[inline]
+fn challenge() -> Challenge {
+    let mut nonce = [0; 16];
+    nonce.copy_from_slice(&BODY);
+
+    Challenge::with_nonce(nonce, DIFFICULTY)
+}
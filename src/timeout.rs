@@ -0,0 +1,89 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Error, Result};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+
+// ============================================ Types =========================================== \\
+
+pub struct Timeout<Fut, Tmr> {
+    fut: Fut,
+    timer: Tmr,
+}
+
+// A protocol only keeps how to create its timers, so that the futures waiting on them don't need
+// another type parameter.
+pub(crate) type Sleep = Box<dyn Future<Output = ()> + Send + Unpin>;
+pub(crate) type After = fn(Duration) -> Sleep;
+
+// ========================================= Interfaces ========================================= \\
+
+pub trait Timer: Future<Output = ()> + Unpin {
+    fn after(duration: Duration) -> Self;
+}
+
+// ======================================== impl Timeout ======================================== \\
+
+impl<Fut, Tmr> Timeout<Fut, Tmr> {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub(super) fn new(fut: Fut, duration: Duration) -> Self
+    where
+        Tmr: Timer,
+    {
+        Timeout {
+            fut,
+            timer: Tmr::after(duration),
+        }
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn into_inner(self) -> Fut {
+        self.fut
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Fut, Tmr, Output> Future for Timeout<Fut, Tmr>
+where
+    Fut: Future<Output = Result<Output>> + Unpin,
+    Tmr: Timer,
+{
+    type Output = Result<Output>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if let Poll::Ready(output) = Pin::new(&mut this.fut).poll(ctx) {
+            return Poll::Ready(output);
+        }
+
+        match Pin::new(&mut this.timer).poll(ctx) {
+            Poll::Ready(()) => Poll::Ready(Err(Error::Timeout)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+// =========================================== sleep() ========================================== \\
+
+#[inline]
+pub(crate) fn sleep<Tmr>(duration: Duration) -> Sleep
+where
+    Tmr: Timer + Send + 'static,
+{
+    Box::new(Tmr::after(duration))
+}
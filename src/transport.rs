@@ -0,0 +1,286 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use core::future::Future;
+use futures_io::{AsyncRead, AsyncWrite};
+use std::io;
+
+#[cfg(any(feature = "tcp", all(feature = "unix", unix)))]
+use crate::Listener;
+#[cfg(all(feature = "unix", unix))]
+use async_net::unix::{UnixListener, UnixStream};
+#[cfg(feature = "tcp")]
+use async_net::{TcpListener, TcpStream};
+#[cfg(any(feature = "tcp", all(feature = "unix", unix)))]
+use core::pin::Pin;
+#[cfg(any(feature = "tcp", all(feature = "unix", unix)))]
+use core::task::{Context, Poll};
+#[cfg(feature = "tcp")]
+use std::net::SocketAddr;
+#[cfg(all(feature = "unix", unix))]
+use std::path::{Path, PathBuf};
+
+// ============================================ Types =========================================== \\
+
+#[cfg(feature = "tcp")]
+#[derive(Clone, Copy, Debug)]
+pub struct Tcp {
+    addr: SocketAddr,
+}
+
+#[cfg(feature = "tcp")]
+pub struct TcpIncoming {
+    listener: TcpListener,
+    accept: Option<Pin<Box<dyn Future<Output = io::Result<(TcpStream, SocketAddr)>> + Send>>>,
+}
+
+#[cfg(all(feature = "unix", unix))]
+#[derive(Clone, Debug)]
+pub struct Unix {
+    path: PathBuf,
+}
+
+#[cfg(all(feature = "unix", unix))]
+pub struct UnixIncoming {
+    listener: UnixListener,
+    accept: Option<Pin<Box<dyn Future<Output = io::Result<UnixStream>> + Send>>>,
+}
+
+// ========================================= Interfaces ========================================= \\
+
+pub trait Transport {
+    type Io: AsyncRead + AsyncWrite + Unpin;
+    type Dial: Future<Output = io::Result<Self::Io>>;
+
+    fn dial(&mut self) -> Self::Dial;
+}
+
+// ======================================= impl Transport ======================================= \\
+
+impl<Dialer, Fut, IO> Transport for Dialer
+where
+    Dialer: FnMut() -> Fut,
+    Fut: Future<Output = io::Result<IO>>,
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    type Io = IO;
+    type Dial = Fut;
+
+    #[inline]
+    fn dial(&mut self) -> Self::Dial {
+        self()
+    }
+}
+
+#[cfg(feature = "tcp")]
+impl Transport for Tcp {
+    type Io = TcpStream;
+    type Dial = Pin<Box<dyn Future<Output = io::Result<TcpStream>> + Send>>;
+
+    #[inline]
+    fn dial(&mut self) -> Self::Dial {
+        Box::pin(TcpStream::connect(self.addr))
+    }
+}
+
+#[cfg(all(feature = "unix", unix))]
+impl Transport for Unix {
+    type Io = UnixStream;
+    type Dial = Pin<Box<dyn Future<Output = io::Result<UnixStream>> + Send>>;
+
+    #[inline]
+    fn dial(&mut self) -> Self::Dial {
+        Box::pin(UnixStream::connect(self.path.clone()))
+    }
+}
+
+// ======================================== socket_pair() ======================================= \\
+
+// Returns two connected (unnamed) unix sockets, e.g. to run the protocol between a process and one
+// of its children.
+#[cfg(all(feature = "unix", unix))]
+#[inline]
+pub fn socket_pair() -> io::Result<(UnixStream, UnixStream)> {
+    UnixStream::pair()
+}
+
+// ========================================== impl Tcp ========================================== \\
+
+#[cfg(feature = "tcp")]
+impl Tcp {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new(addr: SocketAddr) -> Self {
+        Tcp { addr }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+// ====================================== impl TcpIncoming ====================================== \\
+
+#[cfg(feature = "tcp")]
+impl TcpIncoming {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new(listener: TcpListener) -> Self {
+        TcpIncoming {
+            listener,
+            accept: None,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn listener(&self) -> &TcpListener {
+        &self.listener
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn into_inner(self) -> TcpListener {
+        self.listener
+    }
+}
+
+// ========================================== impl Unix ========================================= \\
+
+#[cfg(all(feature = "unix", unix))]
+impl Unix {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Unix { path: path.into() }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+// ====================================== impl UnixIncoming ===================================== \\
+
+#[cfg(all(feature = "unix", unix))]
+impl UnixIncoming {
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn new(listener: UnixListener) -> Self {
+        UnixIncoming {
+            listener,
+            accept: None,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn listener(&self) -> &UnixListener {
+        &self.listener
+    }
+
+    // ===================================== Destructors ==================================== \\
+
+    #[inline]
+    pub fn into_inner(self) -> UnixListener {
+        self.listener
+    }
+}
+
+// ======================================== impl Listener ======================================= \\
+
+#[cfg(feature = "tcp")]
+impl Listener for TcpIncoming {
+    type Io = TcpStream;
+
+    fn poll_accept(&mut self, ctx: &mut Context) -> Poll<io::Result<Self::Io>> {
+        let listener = &self.listener;
+        let accept = self.accept.get_or_insert_with(|| {
+            let listener = listener.clone();
+            Box::pin(async move { listener.accept().await })
+        });
+
+        match accept.as_mut().poll(ctx) {
+            Poll::Ready(res) => {
+                self.accept = None;
+                Poll::Ready(res.map(|(stream, _)| stream))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(all(feature = "unix", unix))]
+impl Listener for UnixIncoming {
+    type Io = UnixStream;
+
+    fn poll_accept(&mut self, ctx: &mut Context) -> Poll<io::Result<Self::Io>> {
+        let listener = &self.listener;
+        let accept = self.accept.get_or_insert_with(|| {
+            let listener = listener.clone();
+            Box::pin(async move { listener.accept().await.map(|(stream, _)| stream) })
+        });
+
+        match accept.as_mut().poll(ctx) {
+            Poll::Ready(res) => {
+                self.accept = None;
+                Poll::Ready(res)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+// ========================================== impl From ========================================= \\
+
+#[cfg(feature = "tcp")]
+impl From<SocketAddr> for Tcp {
+    #[inline]
+    fn from(addr: SocketAddr) -> Self {
+        Tcp::new(addr)
+    }
+}
+
+#[cfg(feature = "tcp")]
+impl From<TcpListener> for TcpIncoming {
+    #[inline]
+    fn from(listener: TcpListener) -> Self {
+        TcpIncoming::new(listener)
+    }
+}
+
+#[cfg(all(feature = "unix", unix))]
+impl From<PathBuf> for Unix {
+    #[inline]
+    fn from(path: PathBuf) -> Self {
+        Unix::new(path)
+    }
+}
+
+#[cfg(all(feature = "unix", unix))]
+impl From<UnixListener> for UnixIncoming {
+    #[inline]
+    fn from(listener: UnixListener) -> Self {
+        UnixIncoming::new(listener)
+    }
+}
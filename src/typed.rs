@@ -0,0 +1,144 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// Typed packets are sent as custom packets whose id is known at compile time, so that
+// request/response code can send and receive its own types without going through `Custom` or a
+// `PacketRegistry`.
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Custom, Error, Protocol, RecvCustom, Result, SendCustom};
+use core::future::Future;
+use core::marker::PhantomData;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::{AsyncRead, AsyncWrite};
+
+// ============================================ Types =========================================== \\
+
+pub struct SendPacket<'proto, Output> {
+    inner: SendPacketInner<'proto, Output>,
+}
+
+enum SendPacketInner<'proto, Output> {
+    Empty,
+    Invalid(Error),
+    Send(SendCustom<'proto, Output>),
+}
+
+pub struct RecvAs<'proto, Input, Typed> {
+    inner: RecvCustom<'proto, Input>,
+    _packet: PhantomData<fn() -> Typed>,
+}
+
+// ========================================= Interfaces ========================================= \\
+
+pub trait TypedPacket: Sized {
+    const ID: u16;
+
+    fn encode_payload(&self, payload: &mut Vec<u8>);
+
+    fn decode_payload(payload: &[u8]) -> Result<Self>;
+}
+
+// ======================================= impl SendPacket ====================================== \\
+
+impl<'proto, Output> SendPacket<'proto, Output> {
+    // ==================================== Constructors ==================================== \\
+
+    pub(super) fn new<Typed>(packet: &Typed, proto: &'proto mut Protocol, out: Output) -> Self
+    where
+        Output: AsyncWrite + Unpin,
+        Typed: TypedPacket,
+    {
+        let mut payload = Vec::new();
+        packet.encode_payload(&mut payload);
+
+        let inner = match Custom::new(Typed::ID, payload) {
+            Ok(custom) => SendPacketInner::Send(SendCustom::new(&custom, proto, out)),
+            Err(err) => SendPacketInner::Invalid(err),
+        };
+
+        SendPacket { inner }
+    }
+}
+
+// ========================================= impl RecvAs ======================================== \\
+
+impl<'proto, Input, Typed> RecvAs<'proto, Input, Typed> {
+    // ==================================== Constructors ==================================== \\
+
+    pub(super) fn new(proto: &'proto mut Protocol, inp: Input) -> Self
+    where
+        Input: AsyncRead + Unpin,
+    {
+        RecvAs {
+            inner: RecvCustom::new(proto, inp),
+            _packet: PhantomData,
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl<Output> Future for SendPacket<'_, Output>
+where
+    Output: AsyncWrite + Unpin,
+{
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let inner = &mut self.get_mut().inner;
+        match mem::take(inner) {
+            SendPacketInner::Empty => panic!(),
+            SendPacketInner::Invalid(err) => Poll::Ready(Err(err)),
+            SendPacketInner::Send(mut send) => {
+                let poll = Pin::new(&mut send).poll(ctx);
+                if poll.is_pending() {
+                    *inner = SendPacketInner::Send(send);
+                }
+
+                poll
+            }
+        }
+    }
+}
+
+impl<Input, Typed> Future for RecvAs<'_, Input, Typed>
+where
+    Input: AsyncRead + Unpin,
+    Typed: TypedPacket,
+{
+    type Output = Result<Typed>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let custom = match Pin::new(&mut self.get_mut().inner).poll(ctx)? {
+            Poll::Ready(custom) => custom,
+            Poll::Pending => return Poll::Pending,
+        };
+
+        if custom.id() != Typed::ID {
+            return Poll::Ready(Err(Error::PacketIdMismatch {
+                expected: Typed::ID,
+                actual: custom.id(),
+            }));
+        }
+
+        Poll::Ready(Typed::decode_payload(custom.payload()))
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl<Output> Default for SendPacketInner<'_, Output> {
+    #[inline]
+    fn default() -> Self {
+        SendPacketInner::Empty
+    }
+}
@@ -0,0 +1,67 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Error, Result};
+use format::Decode;
+use packets::Packet;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UnknownPacketPolicy {
+    Ignore,
+    Error,
+}
+
+// ================================== impl UnknownPacketPolicy ================================== \\
+
+impl UnknownPacketPolicy {
+    // ===================================== Read+Write ===================================== \\
+
+    #[inline]
+    pub(crate) fn decode(self, msg: &[u8]) -> Result<Option<Packet>> {
+        match decode(msg) {
+            Ok(packet) => Ok(Some(packet)),
+            Err(Error::UnknownPacket { .. }) if self == UnknownPacketPolicy::Ignore => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+// ========================================== decode() ========================================== \\
+
+pub(crate) fn decode(msg: &[u8]) -> Result<Packet> {
+    match Packet::decode(msg) {
+        Ok((packet, _)) => Ok(packet),
+        Err(_) => Err(Error::UnknownPacket {
+            id: id(msg),
+            len: msg.len(),
+        }),
+    }
+}
+
+// ============================================ id() ============================================ \\
+
+pub(crate) fn id(msg: &[u8]) -> u16 {
+    let mut id = [0; 2];
+    let len = msg.len().min(2);
+    id[..len].copy_from_slice(&msg[..len]);
+
+    u16::from_le_bytes(id)
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl Default for UnknownPacketPolicy {
+    #[inline]
+    fn default() -> Self {
+        UnknownPacketPolicy::Error
+    }
+}
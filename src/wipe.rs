@@ -0,0 +1,24 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+// =========================================== wipe() =========================================== \\
+
+#[cfg(feature = "zeroize")]
+#[inline]
+pub(crate) fn wipe<Buf: Zeroize + ?Sized>(buf: &mut Buf) {
+    buf.zeroize();
+}
+
+#[cfg(not(feature = "zeroize"))]
+#[inline]
+pub(crate) fn wipe<Buf: ?Sized>(_: &mut Buf) {}
@@ -0,0 +1,164 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// Every message sent after the handshake is prefixed by a frame header whose layout depends on the
+// wire format negotiated during the handshake (the handshake itself always uses V1):
+//
+// V1 ;; len(2, little-endian)
+// V2 ;; len(2, big-endian) + flags(2, big-endian) ;; see frame.rs
+// V3 ;; len(2, big-endian) + flags(2, big-endian) + crc32(4, big-endian)
+//
+// Support for V2 is advertised by setting the high bit of the initiator's compression offer, and
+// accepted by setting it in the responder's choice (see compress.rs). V3 additionally sets the
+// second highest bit. Packets themselves are encoded the same way with all formats.
+//
+// The V3 checksum covers the encrypted message, which lets the receiver tell a frame corrupted in
+// transit (`Error::FrameCorrupted`) apart from one which doesn't decrypt (`Error::Decrypt`).
+
+// =========================================== Imports ========================================== \\
+
+use crate::{Error, FrameFlags, Result};
+use packets::NOISE_OVERHEAD;
+
+// ============================================ Types =========================================== \\
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WireFormat {
+    V1,
+    V2,
+    V3,
+}
+
+// ======================================= impl WireFormat ====================================== \\
+
+impl WireFormat {
+    // ====================================== Constants ===================================== \\
+
+    pub const ALL: [WireFormat; 3] = [WireFormat::V1, WireFormat::V2, WireFormat::V3];
+
+    pub const MAX_HEADER_LEN: usize = 8;
+
+    pub(crate) const BIT: u8 = 0x80;
+    pub(crate) const CHECKSUM_BIT: u8 = 0x40;
+    pub(crate) const MASK: u8 = Self::BIT | Self::CHECKSUM_BIT;
+
+    // ==================================== Constructors ==================================== \\
+
+    #[inline]
+    pub fn from_version(version: u8) -> Option<Self> {
+        match version {
+            1 => Some(WireFormat::V1),
+            2 => Some(WireFormat::V2),
+            3 => Some(WireFormat::V3),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn negotiate(self, byte: u8) -> Self {
+        match (self, byte & Self::MASK) {
+            (WireFormat::V1, _) => WireFormat::V1,
+            (WireFormat::V3, Self::MASK) => WireFormat::V3,
+            (_, bits) if bits & Self::BIT != 0 => WireFormat::V2,
+            _ => WireFormat::V1,
+        }
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    #[inline]
+    pub fn version(self) -> u8 {
+        match self {
+            WireFormat::V1 => 1,
+            WireFormat::V2 => 2,
+            WireFormat::V3 => 3,
+        }
+    }
+
+    #[inline]
+    pub fn header_len(self) -> usize {
+        match self {
+            WireFormat::V1 => 2,
+            WireFormat::V2 => 4,
+            WireFormat::V3 => 8,
+        }
+    }
+
+    #[inline]
+    pub fn has_checksum(self) -> bool {
+        self == WireFormat::V3
+    }
+
+    #[inline]
+    pub fn overhead(self) -> usize {
+        self.header_len() + NOISE_OVERHEAD
+    }
+
+    #[inline]
+    pub(crate) fn bit(self) -> u8 {
+        match self {
+            WireFormat::V1 => 0,
+            WireFormat::V2 => Self::BIT,
+            WireFormat::V3 => Self::MASK,
+        }
+    }
+
+    // `buf` must contain the header followed by the `len` bytes of the encrypted message.
+    pub(crate) fn encode_header(self, buf: &mut [u8], len: usize, flags: FrameFlags) {
+        match self {
+            WireFormat::V1 => buf[..2].copy_from_slice(&(len as u16).to_le_bytes()),
+            WireFormat::V2 | WireFormat::V3 => {
+                buf[..2].copy_from_slice(&(len as u16).to_be_bytes());
+                buf[2..4].copy_from_slice(&flags.bits().to_be_bytes());
+            }
+        }
+
+        if self.has_checksum() {
+            let checksum = crc32fast::hash(&buf[8..8 + len]);
+            buf[4..8].copy_from_slice(&checksum.to_be_bytes());
+        }
+    }
+
+    pub(crate) fn decode_header(self, hdr: &[u8]) -> (usize, FrameFlags) {
+        match self {
+            WireFormat::V1 => (
+                u16::from_le_bytes([hdr[0], hdr[1]]) as usize,
+                FrameFlags::default(),
+            ),
+            WireFormat::V2 | WireFormat::V3 => (
+                u16::from_be_bytes([hdr[0], hdr[1]]) as usize,
+                FrameFlags::from_bits(u16::from_be_bytes([hdr[2], hdr[3]])),
+            ),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn decode_checksum(self, hdr: &[u8]) -> Option<u32> {
+        match self {
+            WireFormat::V1 | WireFormat::V2 => None,
+            WireFormat::V3 => Some(u32::from_be_bytes([hdr[4], hdr[5], hdr[6], hdr[7]])),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn verify(checksum: Option<u32>, msg: &[u8]) -> Result<()> {
+        match checksum {
+            Some(checksum) if checksum != crc32fast::hash(msg) => Err(Error::FrameCorrupted),
+            _ => Ok(()),
+        }
+    }
+}
+
+// ======================================== impl Default ======================================== \\
+
+impl Default for WireFormat {
+    #[inline]
+    fn default() -> Self {
+        WireFormat::V1
+    }
+}
@@ -8,18 +8,23 @@
 
 // =========================================== Imports ========================================== \\
 
-use crate::{Error, NoiseState, Result};
+use crate::{
+    wipe, Error, ErrorContext, FrameFlags, NoiseState, PaddingPolicy, Result, StateDump, WireFormat,
+};
+use core::fmt::{self, Debug, Display, Formatter};
 use core::future::Future;
 use core::mem;
 use core::pin::Pin;
 use core::task::{Context, Poll};
 use futures_io::AsyncWrite;
-use packets::{MSG_MAX_LEN, MSG_OVERHEAD};
 
 // ============================================ Types =========================================== \\
 
 pub(crate) struct Write<Output, State, Buf = Vec<u8>> {
     inner: WriteInner<Output, State, Buf>,
+    padding: PaddingPolicy,
+    wire: WireFormat,
+    flags: FrameFlags,
 }
 
 enum WriteInner<Output, State, Buf> {
@@ -29,6 +34,7 @@ enum WriteInner<Output, State, Buf> {
         buf: Buf,
         out: Output,
         state: State,
+        escape: bool,
     },
     Write {
         len: usize,
@@ -53,7 +59,14 @@ impl<Output, State, Buf> Write<Output, State, Buf> {
     // ==================================== Constructors ==================================== \\
 
     #[inline]
-    pub(crate) fn new(msg: Buf, buf: Buf, out: Output, state: State) -> Self
+    pub(crate) fn new(
+        msg: Buf,
+        buf: Buf,
+        out: Output,
+        state: State,
+        padding: PaddingPolicy,
+        wire: WireFormat,
+    ) -> Self
     where
         Output: AsyncWrite + Unpin,
         State: NoiseState + Unpin,
@@ -65,10 +78,89 @@ impl<Output, State, Buf> Write<Output, State, Buf> {
                 buf,
                 out,
                 state,
+                escape: false,
             },
+            padding,
+            wire,
+            flags: FrameFlags::default(),
         }
     }
 
+    #[inline]
+    pub(crate) fn control(
+        msg: Buf,
+        buf: Buf,
+        out: Output,
+        state: State,
+        padding: PaddingPolicy,
+        wire: WireFormat,
+    ) -> Self
+    where
+        Output: AsyncWrite + Unpin,
+        State: NoiseState + Unpin,
+        Buf: AsRef<[u8]> + AsMut<Vec<u8>> + Unpin,
+    {
+        Write {
+            inner: WriteInner::Prepare {
+                msg,
+                buf,
+                out,
+                state,
+                escape: true,
+            },
+            padding,
+            wire,
+            flags: FrameFlags::default(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn with_flags(mut self, flags: FrameFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    // ====================================== Read-only ===================================== \\
+
+    // Returns the bytes of an encrypted frame which were not written yet.
+    #[inline]
+    pub(crate) fn unwritten(&self) -> Option<&[u8]>
+    where
+        Buf: AsRef<[u8]>,
+    {
+        match &self.inner {
+            WriteInner::Write {
+                len,
+                offset,
+                buf,
+                ..
+            } if offset < len => Some(&buf.as_ref()[*offset..*len]),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn dump_state(&self) -> StateDump
+    where
+        State: NoiseState,
+        Buf: AsRef<[u8]>,
+    {
+        let (name, buffered, state) = match &self.inner {
+            WriteInner::Empty => return StateDump::new("Write", "empty"),
+            WriteInner::Prepare { msg, state, .. } => ("prepare", msg.as_ref().len(), state),
+            WriteInner::Write {
+                len,
+                offset,
+                state,
+                ..
+            } => ("write", len - offset, state),
+            WriteInner::Done { state, .. } => ("done", 0, state),
+        };
+
+        StateDump::new("Write", name)
+            .with_buffered(buffered)
+            .with_nonces(state.nonces())
+    }
+
     // ===================================== Destructors ==================================== \\
 
     #[inline]
@@ -111,7 +203,11 @@ where
     type Output = Result<usize>;
 
     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
-        let inner = &mut self.get_mut().inner;
+        let this = self.get_mut();
+        let padding = this.padding;
+        let wire = this.wire;
+        let flags = this.flags;
+        let inner = &mut this.inner;
         loop {
             match mem::take(inner) {
                 WriteInner::Empty => panic!(),
@@ -120,9 +216,10 @@ where
                     buf,
                     out,
                     state,
-                } if msg.as_ref().len() > MSG_MAX_LEN => {
+                    ..
+                } if msg.as_ref().len() > padding.max_len() => {
                     let err = Err(Error::MessageSize {
-                        max: MSG_MAX_LEN,
+                        max: padding.max_len(),
                         actual: msg.as_ref().len(),
                     });
 
@@ -141,14 +238,17 @@ where
                     mut buf,
                     out,
                     state,
-                } if msg.as_ref().len() + MSG_OVERHEAD > buf.as_ref().len() => {
-                    buf.as_mut().resize(msg.as_ref().len() + MSG_OVERHEAD, 0);
+                    escape,
+                } if capacity(msg.as_ref().len(), escape, padding, wire) > buf.as_ref().len() => {
+                    buf.as_mut()
+                        .resize(capacity(msg.as_ref().len(), escape, padding, wire), 0);
 
                     *inner = WriteInner::Prepare {
                         msg,
                         buf,
                         out,
                         state,
+                        escape,
                     };
                 }
                 WriteInner::Prepare {
@@ -156,12 +256,19 @@ where
                     mut buf,
                     out,
                     mut state,
-                } => match state.write_message(msg.as_ref(), &mut buf.as_mut()[2..]) {
+                    escape,
+                } => match prepare(
+                    &mut state,
+                    msg.as_ref(),
+                    buf.as_mut(),
+                    escape,
+                    padding,
+                    wire,
+                    flags,
+                ) {
                     Ok(len) => {
-                        buf.as_mut()[0..2].copy_from_slice(&(len as u16).to_le_bytes());
-
                         *inner = WriteInner::Write {
-                            len: len + 2,
+                            len,
                             offset: 0,
                             msg,
                             buf,
@@ -178,7 +285,7 @@ where
                             state,
                         };
 
-                        return Poll::Ready(Err(err));
+                        return Poll::Ready(Err(err.with_context(ErrorContext::write(0))));
                     }
                 },
                 WriteInner::Write {
@@ -228,7 +335,8 @@ where
                             state,
                         };
 
-                        return Poll::Ready(Err(err.into()));
+                        let err = Error::from(err);
+                        return Poll::Ready(Err(err.with_context(ErrorContext::write(offset))));
                     }
                     Poll::Pending => {
                         *inner = WriteInner::Write {
@@ -265,6 +373,137 @@ where
     }
 }
 
+// ========================================= capacity() ========================================= \\
+
+#[inline]
+fn capacity(len: usize, escape: bool, padding: PaddingPolicy, wire: WireFormat) -> usize {
+    let mut capacity = padding.padded_len(len) + wire.overhead() + scratch(len, padding);
+    if escape {
+        capacity += padding.padded_len(0) + wire.overhead();
+    }
+
+    capacity
+}
+
+// ========================================== scratch() ========================================= \\
+
+// Padded messages are written right after their frame before being encrypted, so the buffer needs
+// room for both.
+#[inline]
+fn scratch(len: usize, padding: PaddingPolicy) -> usize {
+    match padding {
+        PaddingPolicy::None => 0,
+        _ => padding.padded_len(len),
+    }
+}
+
+// ========================================== prepare() ========================================= \\
+
+fn prepare<State: NoiseState>(
+    state: &mut State,
+    msg: &[u8],
+    buf: &mut [u8],
+    escape: bool,
+    padding: PaddingPolicy,
+    wire: WireFormat,
+    flags: FrameFlags,
+) -> Result<usize> {
+    let mut len = 0;
+    if escape {
+        len += encrypt(state, &[], buf, padding, wire, flags)?;
+    }
+
+    len += encrypt(state, msg, &mut buf[len..], padding, wire, flags)?;
+
+    Ok(len)
+}
+
+// ========================================== encrypt() ========================================= \\
+
+fn encrypt<State: NoiseState>(
+    state: &mut State,
+    msg: &[u8],
+    buf: &mut [u8],
+    padding: PaddingPolicy,
+    wire: WireFormat,
+    flags: FrameFlags,
+) -> Result<usize> {
+    let hdr = wire.header_len();
+    let len = if padding == PaddingPolicy::None {
+        state.write_message(msg, &mut buf[hdr..])?
+    } else {
+        let (frame, scratch) = buf.split_at_mut(padding.padded_len(msg.len()) + wire.overhead());
+        let padded = padding.pad(msg, scratch);
+
+        let len = state.write_message(&scratch[..padded], &mut frame[hdr..]);
+        wipe(&mut scratch[..padded]);
+
+        len?
+    };
+
+    wire.encode_header(buf, len, flags);
+
+    Ok(hdr + len)
+}
+
+// ========================================== append() ========================================== \\
+
+pub(crate) fn append<State: NoiseState>(
+    state: &mut State,
+    msg: &[u8],
+    buf: &mut Vec<u8>,
+    off: usize,
+    padding: PaddingPolicy,
+    wire: WireFormat,
+) -> Result<usize> {
+    append_frame(state, msg, buf, off, padding, wire, FrameFlags::default())
+}
+
+// ======================================= append_frame() ======================================= \\
+
+pub(crate) fn append_frame<State: NoiseState>(
+    state: &mut State,
+    msg: &[u8],
+    buf: &mut Vec<u8>,
+    off: usize,
+    padding: PaddingPolicy,
+    wire: WireFormat,
+    flags: FrameFlags,
+) -> Result<usize> {
+    let end = off + padding.padded_len(msg.len()) + wire.overhead() + scratch(msg.len(), padding);
+    if end > buf.len() {
+        buf.resize(end, 0);
+    }
+
+    Ok(off + encrypt(state, msg, &mut buf[off..end], padding, wire, flags)?)
+}
+
+// ========================================= impl Debug ========================================= \\
+
+impl<Output, State, Buf> Debug for Write<Output, State, Buf>
+where
+    State: NoiseState,
+    Buf: AsRef<[u8]>,
+{
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.dump_state(), fmt)
+    }
+}
+
+// ======================================== impl Display ======================================== \\
+
+impl<Output, State, Buf> Display for Write<Output, State, Buf>
+where
+    State: NoiseState,
+    Buf: AsRef<[u8]>,
+{
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.dump_state(), fmt)
+    }
+}
+
 // ======================================== impl Default ======================================== \\
 
 impl<Output, State, Buf> Default for WriteInner<Output, State, Buf> {
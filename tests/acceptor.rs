@@ -0,0 +1,77 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{Acceptor, Config, Error, Handshake, Result};
+
+// ===================================== #[test] acceptor() ===================================== \\
+
+#[test]
+fn acceptor() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let acceptor = Acceptor::new(Config::new()).with_pow(4);
+        assert_eq!(acceptor.pow(), 4);
+
+        let initiate = smol::spawn(async move {
+            for _ in 0..2 {
+                let stream = TcpStream::connect(addr).await?;
+                Handshake::initiate(&stream).await?.done()?;
+            }
+
+            Result::Ok(())
+        });
+
+        let respond = async {
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().await?;
+                acceptor.accept(&stream).await?.done()?;
+            }
+
+            Result::Ok(())
+        };
+
+        future::try_zip(initiate, respond).await?;
+        assert_eq!(acceptor.active(), 0);
+
+        Ok(())
+    })
+}
+
+// ================================== #[test] handshake_limit() ================================= \\
+
+#[test]
+fn handshake_limit() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let acceptor = Acceptor::new(Config::new()).with_max_handshakes(1);
+
+        let _first = TcpStream::connect(addr).await?;
+        let (first, _) = listener.accept().await?;
+        let _second = TcpStream::connect(addr).await?;
+        let (second, _) = listener.accept().await?;
+
+        let mut pending = acceptor.accept(&first);
+        assert!(future::poll_once(&mut pending).await.is_none());
+        assert_eq!(acceptor.active(), 1);
+        assert_eq!(acceptor.max_handshakes(), Some(1));
+
+        let res = acceptor.accept(&second).await;
+        assert!(matches!(res, Err(Error::Busy(1))));
+
+        drop(pending);
+        assert_eq!(acceptor.active(), 0);
+
+        Ok(())
+    })
+}
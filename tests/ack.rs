@@ -0,0 +1,97 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use pr070c01::{Ack, Error, Nack, RecvWindow, Result, SendWindow};
+
+// ====================================== #[test] encode() ====================================== \\
+
+#[test]
+fn encode() -> Result<()> {
+    let ack = Ack::new(vec![10..20, 0..5, 4..8, 300..301]);
+    assert_eq!(ack.ranges(), &[0..8, 10..20, 300..301]);
+    assert_eq!(ack.largest(), Some(300));
+    assert!(ack.contains(7));
+    assert!(!ack.contains(8));
+
+    let mut buf = Vec::new();
+    ack.encode(&mut buf);
+    assert_eq!(buf.len(), 8);
+    assert_eq!(Ack::decode(&buf)?, ack);
+
+    let nack = Nack::new(Some(8..10));
+    buf.clear();
+    nack.encode(&mut buf);
+    assert_eq!(Nack::decode(&buf)?, nack);
+
+    buf.push(0);
+    assert!(matches!(Nack::decode(&buf), Err(Error::InvalidAck)));
+    assert!(matches!(Ack::decode(&[1, 0, 0]), Err(Error::InvalidAck)));
+
+    Ok(())
+}
+
+// ====================================== #[test] windows() ===================================== \\
+
+#[test]
+fn windows() -> Result<()> {
+    let mut send = SendWindow::new(4);
+    let seqs = (0..5).filter_map(|_| send.push()).collect::<Vec<_>>();
+    assert_eq!(seqs, vec![0, 1, 2, 3]);
+    assert!(send.is_full());
+
+    let mut recv = RecvWindow::new();
+    assert!(recv.receive(0));
+    assert!(recv.receive(2));
+    assert!(recv.receive(3));
+    assert!(!recv.receive(2));
+    assert_eq!(recv.contiguous(), 1);
+    assert_eq!(recv.largest(), Some(3));
+
+    let mut buf = Vec::new();
+    recv.ack().encode(&mut buf);
+    assert_eq!(send.ack(&Ack::decode(&buf)?), vec![0, 2, 3]);
+    assert_eq!(send.in_flight(), 1);
+    assert_eq!(send.lowest_unacked(), Some(1));
+
+    let nack = recv.nack();
+    assert_eq!(nack.ranges().len(), 1);
+    assert!(nack.contains(1));
+    assert_eq!(send.nack(&nack), vec![1]);
+
+    assert!(recv.receive(1));
+    assert_eq!(recv.contiguous(), 4);
+    assert!(recv.nack().is_empty());
+    assert_eq!(send.ack(&recv.ack()), vec![1]);
+    assert_eq!(send.push(), Some(4));
+
+    Ok(())
+}
+
+// ====================================== #[test] bounded() ===================================== \\
+
+#[test]
+fn bounded() -> Result<()> {
+    let mut send = SendWindow::new(2);
+    assert_eq!(send.push(), Some(0));
+    assert_eq!(send.push(), Some(1));
+    assert_eq!(send.ack(&Ack::new(vec![1..2])), vec![1]);
+    assert_eq!(send.push(), None);
+
+    let mut recv = RecvWindow::new().with_window(2);
+    assert!(!recv.receive(2));
+    assert!(recv.receive(1));
+    assert!(!recv.receive(u64::MAX));
+    assert!(recv.receive(0));
+    assert!(recv.receive(3));
+    assert_eq!(recv.contiguous(), 2);
+    assert_eq!(recv.largest(), Some(3));
+
+    Ok(())
+}
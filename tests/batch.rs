@@ -0,0 +1,74 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{BatchPolicy, Batcher, Handshake, Packet, Result};
+use std::time::Duration;
+
+// ======================================= #[test] batch() ====================================== \\
+
+#[test]
+fn batch() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let proto = Handshake::initiate(&stream).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let proto = Handshake::respond(&stream).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let ((istream, mut iproto), (rstream, mut rproto)) =
+            future::try_zip(initiate, respond).await?;
+
+        let packets = (0..3).map(|_| Packet::heartbeat());
+        iproto.send_all(&istream, packets).await?;
+        for _ in 0..3 {
+            assert!(rproto.recv(&rstream).await?.is_heartbeat());
+        }
+
+        let mut batcher = Batcher::new(BatchPolicy::new(usize::MAX, Duration::from_secs(60)));
+        assert!(!batcher.push(Packet::heartbeat())?);
+        assert!(!batcher.push(Packet::heartbeat())?);
+        assert_eq!(batcher.len(), 2);
+
+        batcher.set_policy(BatchPolicy::new(batcher.bytes(), Duration::from_secs(60)));
+        assert!(batcher.is_due());
+
+        rproto.send_batch(&rstream, &mut batcher).await?;
+        assert!(batcher.is_empty());
+        assert!(iproto.recv(&istream).await?.is_heartbeat());
+        assert!(iproto.recv(&istream).await?.is_heartbeat());
+
+        let packets = (0..4).map(|_| Packet::heartbeat());
+        iproto.send_all(&istream, packets).await?;
+
+        let mut packets = Vec::new();
+        assert_eq!(rproto.recv_many(&rstream, &mut packets, 1).await?, 1);
+        while packets.len() < 4 {
+            let max = 4 - packets.len();
+            assert!(rproto.recv_many(&rstream, &mut packets, max).await? > 0);
+        }
+
+        assert!(packets.iter().all(Packet::is_heartbeat));
+
+        Ok(())
+    })
+}
@@ -0,0 +1,40 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use pr070c01::blocking::Handshake;
+use pr070c01::{Packet, Result};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+// ===================================== #[test] blocking() ===================================== \\
+
+#[test]
+fn blocking() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+
+    let initiate = thread::spawn(move || {
+        let stream = TcpStream::connect(addr)?;
+        let mut proto = Handshake::initiate(stream)?.done()?;
+
+        proto.send(Packet::heartbeat())?;
+        assert!(proto.recv()?.is_heartbeat());
+
+        Result::Ok(())
+    });
+
+    let (stream, _) = listener.accept()?;
+    let mut proto = Handshake::respond(stream)?.done()?;
+
+    assert!(proto.recv()?.is_heartbeat());
+    proto.send(Packet::heartbeat())?;
+
+    initiate.join().unwrap()
+}
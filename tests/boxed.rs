@@ -0,0 +1,83 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::unix::UnixStream;
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{AsyncIo, BoxedIo, Handshake, Packet, Result};
+
+// =========================================== Helpers ========================================== \\
+
+fn assert_send<T: Send>(_: &T) {}
+
+async fn pairs() -> Result<Vec<(BoxedIo, BoxedIo)>> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let connect = TcpStream::connect(listener.local_addr()?);
+    let (tcp, (accepted, _)) = future::try_zip(connect, listener.accept()).await?;
+
+    let (ia, ra) = UnixStream::pair()?;
+
+    Ok(vec![
+        (BoxedIo::new(tcp), BoxedIo::new(accepted)),
+        (BoxedIo::new(ia), BoxedIo::new(ra)),
+    ])
+}
+
+// ====================================== #[test] object() ====================================== \\
+
+#[test]
+fn object() -> Result<()> {
+    smol::block_on(async {
+        let (stream, _) = UnixStream::pair()?;
+        let io: Box<dyn AsyncIo> = Box::new(stream);
+        let io = BoxedIo::new(io);
+        assert_send(&io);
+
+        let _: Box<dyn AsyncIo> = io.into_inner();
+
+        Ok(())
+    })
+}
+
+// ======================================= #[test] boxed() ====================================== \\
+
+#[test]
+fn boxed() -> Result<()> {
+    smol::block_on(async {
+        for (mut iio, mut rio) in pairs().await? {
+            let initiate = Handshake::initiate(&mut iio);
+            assert_send(&initiate);
+
+            let respond = smol::spawn(async move {
+                let mut proto = Handshake::respond(&mut rio).await?.done()?;
+
+                let recv = proto.recv(&mut rio);
+                assert_send(&recv);
+                assert!(recv.await?.is_heartbeat());
+
+                let send = proto.send(&mut rio, Packet::heartbeat());
+                assert_send(&send);
+                send.await?;
+
+                Result::Ok(())
+            });
+
+            let mut proto = initiate.await?.done()?;
+            assert_send(&proto);
+
+            proto.send(&mut iio, Packet::heartbeat()).await?;
+            assert!(proto.recv(&mut iio).await?.is_heartbeat());
+
+            respond.await?;
+        }
+
+        Ok(())
+    })
+}
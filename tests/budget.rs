@@ -0,0 +1,99 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_io::AsyncRead;
+use futures_lite::future;
+use pr070c01::{Handshake, Packet, ReadBudget, Result};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// ============================================ Types =========================================== \\
+
+// Always ready, but only ever returns one byte at a time.
+struct Trickle {
+    bytes: Vec<u8>,
+    off: usize,
+}
+
+// ======================================= impl AsyncRead ======================================= \\
+
+impl AsyncRead for Trickle {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.off >= self.bytes.len() || buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        buf[0] = self.bytes[self.off];
+        self.off += 1;
+
+        Poll::Ready(Ok(1))
+    }
+}
+
+// ====================================== #[test] budget() ====================================== \\
+
+#[test]
+fn budget() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            Handshake::initiate(&stream).await?.done()
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            Handshake::respond(&stream).await?.done()
+        });
+
+        let (mut iproto, mut rproto) = future::try_zip(initiate, respond).await?;
+        assert!(rproto.read_budget().is_unlimited());
+
+        let mut bytes = Vec::new();
+        iproto.send(&mut bytes, Packet::heartbeat()).await?;
+
+        // Without a budget, the whole message gets read within a single poll.
+        let mut trickle = Trickle { bytes, off: 0 };
+        let mut recv = rproto.recv(&mut trickle);
+        assert!(future::poll_once(&mut recv).await.unwrap()?.is_heartbeat());
+
+        let budget = ReadBudget::unlimited().with_max_reads(4);
+        rproto.set_read_budget(budget);
+        assert_eq!(rproto.read_budget().max_reads(), Some(4));
+
+        let mut bytes = Vec::new();
+        iproto.send(&mut bytes, Packet::heartbeat()).await?;
+        let len = bytes.len();
+
+        let mut trickle = Trickle { bytes, off: 0 };
+        let mut recv = rproto.recv(&mut trickle);
+
+        let mut polls = 1;
+        let packet = loop {
+            match future::poll_once(&mut recv).await {
+                Some(packet) => break packet?,
+                None => polls += 1,
+            }
+        };
+
+        assert!(packet.is_heartbeat());
+        assert_eq!(polls, len.div_ceil(4));
+
+        Ok(())
+    })
+}
@@ -0,0 +1,94 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+mod common;
+
+use async_net::{TcpListener, TcpStream};
+use common::keypair;
+use futures_lite::future;
+use pr070c01::{Certificate, Config, Error, Handshake, Packet, Result};
+use std::time::{Duration, SystemTime};
+
+// ==================================== #[test] certificate() =================================== \\
+
+#[test]
+fn certificate() -> Result<()> {
+    let issuer = keypair(1);
+    let subkey = keypair(2).public;
+
+    let now = SystemTime::now();
+    let hour = Duration::from_secs(3600);
+    let certificate = Certificate::issue(&issuer, subkey, now - hour, now + hour, 0b101);
+    assert_eq!(certificate.issuer(), &issuer.public);
+    assert_eq!(certificate.subkey(), &subkey);
+    assert!(certificate.has_capabilities(0b100));
+    assert!(!certificate.has_capabilities(0b010));
+    certificate.verify()?;
+
+    let decoded = Certificate::decode(&certificate.encode())?;
+    assert_eq!(decoded, certificate);
+
+    let res = certificate.verify_at(now + 2 * hour);
+    assert!(matches!(res, Err(Error::InvalidCertificate)));
+
+    let mut tampered = certificate.encode();
+    tampered[80] ^= 0b010;
+    let res = Certificate::decode(&tampered)?.verify();
+    assert!(matches!(res, Err(Error::InvalidCertificate)));
+
+    Ok(())
+}
+
+// ==================================== #[test] delegation() ==================================== \\
+
+#[test]
+fn delegation() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let identity = keypair(1);
+        let subkey = keypair(2);
+        let rkeypair = keypair(3);
+        let ipublic = identity.public;
+        let rpublic = rkeypair.public;
+
+        let certificate = Certificate::issue_for(&identity, subkey.public, Duration::from_secs(60));
+        let config = Config::new().with_delegation(subkey, certificate);
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate_with(&stream, config).await?.done()?;
+            assert_eq!(proto.remote_identity(), Some(&rpublic));
+            assert_eq!(proto.remote_certificate(), None);
+
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            Result::Ok(())
+        });
+
+        let config = Config::new().with_identity(rkeypair);
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let handshake = Handshake::respond_with(&stream, config).await?;
+            assert_eq!(handshake.remote_identity(), Some(&ipublic));
+            assert_eq!(handshake.remote_certificate(), Some(&certificate));
+
+            let mut proto = handshake.done()?;
+            assert_eq!(proto.remote_identity(), Some(&ipublic));
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
@@ -0,0 +1,114 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{Compression, Config, Handshake, Packet, Protocol, Result};
+
+// ===================================== #[test] negotiate() ==================================== \\
+
+#[test]
+fn negotiate() -> Result<()> {
+    let (iproto, rproto) = connect(Config::new(), Config::new())?;
+
+    let preferred = Compression::available()[0];
+    assert_eq!(iproto.compression(), preferred);
+    assert_eq!(rproto.compression(), preferred);
+
+    Ok(())
+}
+
+// ===================================== #[test] disabled() ===================================== \\
+
+#[test]
+fn disabled() -> Result<()> {
+    let config = Config::new().with_compressions(vec![Compression::None]);
+    let (iproto, rproto) = connect(config, Config::new())?;
+
+    assert_eq!(iproto.compression(), Compression::None);
+    assert_eq!(rproto.compression(), Compression::None);
+
+    let config = Config::new().with_compressions(Vec::new());
+    let (iproto, rproto) = connect(Config::new(), config)?;
+
+    assert_eq!(iproto.compression(), Compression::None);
+    assert_eq!(rproto.compression(), Compression::None);
+
+    Ok(())
+}
+
+// ======================================= #[test] mixed() ====================================== \\
+
+#[test]
+fn mixed() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+
+            for threshold in [0, Protocol::COMPRESSION_THRESHOLD].iter().cycle().take(6) {
+                proto.set_compression_threshold(*threshold);
+                proto.send(&stream, Packet::heartbeat()).await?;
+            }
+
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+            assert_eq!(
+                proto.compression_threshold(),
+                Protocol::COMPRESSION_THRESHOLD
+            );
+
+            for _ in 0..6 {
+                assert!(proto.recv(&stream).await?.is_heartbeat());
+            }
+
+            proto.set_compression_threshold(0);
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// ========================================== connect() ========================================= \\
+
+fn connect(iconfig: Config, rconfig: Config) -> Result<(Protocol, Protocol)> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let handshake = Handshake::initiate_with(&stream, iconfig).await?;
+            assert_eq!(handshake.remote_payload(), b"responder");
+
+            handshake.done()
+        });
+
+        let (stream, _) = listener.accept().await?;
+        let rconfig = rconfig.with_payload(&b"responder"[..]);
+        let rproto = Handshake::respond_with(&stream, rconfig).await?.done()?;
+
+        Ok((initiate.await?, rproto))
+    })
+}
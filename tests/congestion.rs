@@ -0,0 +1,102 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use pr070c01::{CongestionControl, FixedRate, NewReno, ReliableChannel, ReliableConfig, Result};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// ===================================== #[test] new_reno() ===================================== \\
+
+#[test]
+fn new_reno() {
+    let mut reno = NewReno::with_mss(1000);
+    assert_eq!(reno.window(), 10_000);
+
+    let sent = Instant::now();
+    reno.on_ack(1000, sent);
+    assert_eq!(reno.window(), 11_000);
+
+    reno.on_loss(1000, sent);
+    assert_eq!(reno.window(), 5500);
+    assert!(reno.is_recovering());
+
+    // Losses of messages sent before the recovery started don't reduce the window again.
+    reno.on_loss(1000, sent);
+    assert_eq!(reno.window(), 5500);
+
+    reno.on_ack(1000, Instant::now());
+    assert!(!reno.is_recovering());
+    assert_eq!(reno.window(), 5500 + 1000 * 1000 / 5500);
+
+    reno.on_timeout();
+    assert_eq!(reno.window(), 2000);
+}
+
+// ==================================== #[test] congestion() ==================================== \\
+
+#[test]
+fn congestion() -> Result<()> {
+    let config = ReliableConfig::new().with_rto(Duration::from_millis(50));
+
+    let mut sender = ReliableChannel::new(config).with_congestion(NewReno::with_mss(100));
+    let mut receiver = ReliableChannel::new(config);
+    for _ in 0..32 {
+        sender.send(0, vec![0; 100]);
+    }
+
+    let mut buf = Vec::new();
+    let mut sent = 0;
+    while sender.transmit(&mut buf)? {
+        sent += 1;
+        receiver.handle(&buf)?;
+    }
+
+    // Only the initial window gets sent before the first acknowledgment.
+    assert_eq!(sent, NewReno::INITIAL_WINDOW);
+    assert_eq!(sender.bytes_in_flight(), NewReno::INITIAL_WINDOW * 100);
+
+    while !sender.is_idle() {
+        while receiver.transmit(&mut buf)? {
+            sender.handle(&buf)?;
+        }
+
+        while sender.transmit(&mut buf)? {
+            receiver.handle(&buf)?;
+        }
+    }
+
+    let stats = sender.stats();
+    assert!(stats.rtt().is_some());
+    assert!(stats.cwnd().unwrap() > (NewReno::INITIAL_WINDOW * 100) as u64);
+    assert_eq!(stats.packets_sent(), 32);
+
+    Ok(())
+}
+
+// ==================================== #[test] fixed_rate() ==================================== \\
+
+#[test]
+fn fixed_rate() -> Result<()> {
+    let mut sender = ReliableChannel::default().with_congestion(FixedRate::new(10_000));
+    for _ in 0..3 {
+        sender.send(0, vec![0; 100]);
+    }
+
+    let mut buf = Vec::new();
+    assert!(sender.transmit(&mut buf)?);
+    assert!(!sender.transmit(&mut buf)?);
+    assert!(sender.next_timeout().is_some());
+    assert_eq!(sender.stats().cwnd(), None);
+
+    thread::sleep(Duration::from_millis(15));
+    assert!(sender.transmit(&mut buf)?);
+
+    Ok(())
+}
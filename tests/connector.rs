@@ -0,0 +1,114 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use futures_lite::future;
+use pr070c01::{Config, Connector, Error, Handshake, Packet, Result, Timer};
+use std::io;
+
+// ============================================ Types =========================================== \\
+
+struct SmolTimer(smol::Timer);
+
+// ========================================= impl Timer ========================================= \\
+
+impl Timer for SmolTimer {
+    #[inline]
+    fn after(duration: Duration) -> Self {
+        SmolTimer(smol::Timer::after(duration))
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl Future for SmolTimer {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(ctx).map(|_| ())
+    }
+}
+
+// ===================================== #[test] connector() ==================================== \\
+
+#[test]
+fn connector() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        let connector = Connector::new(Config::new())
+            .with_attempts(3)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(4));
+        assert_eq!(connector.backoff_for(1), Duration::from_millis(1));
+        assert_eq!(connector.backoff_for(2), Duration::from_millis(2));
+        assert_eq!(connector.backoff_for(8), Duration::from_millis(4));
+
+        let mut dials = 0;
+        let connect = connector.connect::<_, _, _, SmolTimer>(move || {
+            dials += 1;
+            let flaky = dials < 3;
+
+            async move {
+                if flaky {
+                    Err(io::ErrorKind::ConnectionRefused.into())
+                } else {
+                    TcpStream::connect(addr).await
+                }
+            }
+        });
+
+        let initiate = async {
+            let (stream, mut proto) = connect.await?;
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            Result::Ok(())
+        };
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// ================================ #[test] connector_exhausted() =============================== \\
+
+#[test]
+fn connector_exhausted() -> Result<()> {
+    smol::block_on(async {
+        let connector = Connector::new(Config::new())
+            .with_attempts(2)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(1));
+
+        let res = connector
+            .connect::<_, _, TcpStream, SmolTimer>(|| async {
+                Err(io::ErrorKind::ConnectionRefused.into())
+            })
+            .await;
+        assert!(
+            matches!(res, Err(Error::Io(error)) if error.kind() == io::ErrorKind::ConnectionRefused)
+        );
+
+        Ok(())
+    })
+}
@@ -0,0 +1,70 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{Custom, Error, Handshake, Packet, PacketRegistry, Result};
+use std::sync::Arc;
+
+// ====================================== #[test] custom() ====================================== \\
+
+#[test]
+fn custom() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let proto = Handshake::initiate(&stream).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let proto = Handshake::respond(&stream).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let ((istream, mut iproto), (rstream, mut rproto)) =
+            future::try_zip(initiate, respond).await?;
+
+        assert!(matches!(
+            Custom::new(0x00ff, Vec::new()),
+            Err(Error::ReservedPacketId(0x00ff))
+        ));
+
+        let custom = Custom::new(Custom::RESERVED, &b"custom"[..])?;
+        iproto.send_custom(&istream, &custom).await?;
+        iproto.send(&istream, Packet::heartbeat()).await?;
+
+        assert!(rproto.recv(&rstream).await?.is_heartbeat());
+        assert_eq!(rproto.recv_custom(&rstream).await?, custom);
+
+        rproto.send_custom(&rstream, &custom).await?;
+        assert_eq!(iproto.recv_custom(&istream).await?.payload(), b"custom");
+
+        let mut registry = PacketRegistry::new();
+        registry.register(Custom::RESERVED, |bytes| {
+            Ok(String::from_utf8_lossy(bytes).into_owned())
+        })?;
+        assert!(registry.register(0, |_| Ok(())).is_err());
+        rproto.set_registry(Arc::new(registry));
+
+        iproto.send_custom(&istream, &custom).await?;
+        let decoded = rproto.recv_decoded(&rstream).await?;
+        assert_eq!(decoded.id(), Custom::RESERVED);
+        assert_eq!(decoded.downcast::<String>().unwrap(), "custom");
+
+        Ok(())
+    })
+}
@@ -0,0 +1,168 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use core::task::{Context, Poll};
+use futures_lite::{future, ready};
+use pr070c01::{DatagramProtocol, DatagramSocket, Error, Handshake, Packet, Result};
+use smol::Async;
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+
+// ============================================ Types =========================================== \\
+
+struct Udp(Async<UdpSocket>);
+
+// ========================================== impl Udp ========================================== \\
+
+impl Udp {
+    fn bind() -> io::Result<Self> {
+        Ok(Udp(Async::<UdpSocket>::bind(([127, 0, 0, 1], 0))?))
+    }
+
+    fn addr(&self) -> io::Result<SocketAddr> {
+        self.0.get_ref().local_addr()
+    }
+}
+
+// ===================================== impl DatagramSocket ==================================== \\
+
+impl DatagramSocket for Udp {
+    fn poll_send_to(
+        &self,
+        ctx: &mut Context,
+        buf: &[u8],
+        addr: SocketAddr,
+    ) -> Poll<io::Result<usize>> {
+        loop {
+            match self.0.get_ref().send_to(buf, addr) {
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => (),
+                res => return Poll::Ready(res),
+            }
+
+            ready!(self.0.poll_writable(ctx))?;
+        }
+    }
+
+    fn poll_recv_from(
+        &self,
+        ctx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<(usize, SocketAddr)>> {
+        loop {
+            match self.0.get_ref().recv_from(buf) {
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => (),
+                res => return Poll::Ready(res),
+            }
+
+            ready!(self.0.poll_readable(ctx))?;
+        }
+    }
+}
+
+// ===================================== #[test] datagram() ===================================== \\
+
+#[test]
+fn datagram() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            Handshake::initiate(&stream).await?.done_datagram()
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            Handshake::respond(&stream).await?.done_datagram()
+        });
+
+        let (mut iproto, mut rproto) = future::try_zip(initiate, respond).await?;
+
+        let isocket = Udp::bind()?;
+        let rsocket = Udp::bind()?;
+        let relay = Async::<UdpSocket>::bind(([127, 0, 0, 1], 0))?;
+        let relay_addr = relay.get_ref().local_addr()?;
+
+        let mut datagrams = Vec::new();
+        for _ in 0..3 {
+            iproto
+                .send_to(&isocket, relay_addr, Packet::heartbeat())
+                .await?;
+
+            let mut buf = vec![0; DatagramProtocol::MAX_LEN];
+            let (len, _) = relay.recv_from(&mut buf).await?;
+            buf.truncate(len);
+            datagrams.push(buf);
+        }
+
+        assert_eq!(iproto.next_nonce(), 3);
+
+        for idx in &[1, 0, 0, 2] {
+            relay.send_to(&datagrams[*idx], rsocket.addr()?).await?;
+        }
+
+        for _ in 0..3 {
+            let (packet, from) = rproto.recv_from(&rsocket).await?;
+            assert!(packet.is_heartbeat());
+            assert_eq!(from, relay_addr);
+        }
+
+        assert_eq!(rproto.max_received_nonce(), Some(2));
+
+        Ok(())
+    })
+}
+
+// ======================================== #[test] mtu() ======================================= \\
+
+#[test]
+fn mtu() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            Handshake::initiate(&stream).await?.done_datagram()
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            Handshake::respond(&stream).await?.done_datagram()
+        });
+
+        let (mut iproto, mut rproto) = future::try_zip(initiate, respond).await?;
+        assert_eq!(iproto.mtu(), DatagramProtocol::MAX_LEN);
+
+        let isocket = Udp::bind()?;
+        let rsocket = Udp::bind()?;
+
+        iproto.set_mtu(DatagramProtocol::NONCE_LEN);
+        let res = iproto
+            .send_to(&isocket, rsocket.addr()?, Packet::heartbeat())
+            .await;
+        assert!(
+            matches!(res, Err(Error::ExceedsMtu { mtu, .. }) if mtu == DatagramProtocol::NONCE_LEN)
+        );
+        assert_eq!(iproto.next_nonce(), 0);
+
+        iproto.set_mtu(1200);
+        iproto
+            .send_to(&isocket, rsocket.addr()?, Packet::heartbeat())
+            .await?;
+
+        let (packet, _) = rproto.recv_from(&rsocket).await?;
+        assert!(packet.is_heartbeat());
+
+        Ok(())
+    })
+}
@@ -0,0 +1,108 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::{future, StreamExt};
+use pr070c01::{Dispatcher, ErrorKind, Handshake, Packet, Result};
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// ===================================== #[test] dispatch() ===================================== \\
+
+#[test]
+fn dispatch() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+
+            for _ in 0..4 {
+                proto.send(&stream, Packet::heartbeat()).await?;
+            }
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+
+            let seen = AtomicU32::new(u32::MAX);
+            let packet = proto
+                .recv_matching(&stream, |id| {
+                    seen.store(id as u32, Ordering::SeqCst);
+                    true
+                })
+                .await?;
+            assert!(packet.is_heartbeat());
+
+            let id = seen.load(Ordering::SeqCst) as u16;
+            let mut dispatcher = Dispatcher::new();
+            let mut subscription = dispatcher.subscribe(id);
+            assert!(dispatcher.is_routed(id));
+
+            assert!(proto.dispatch(&stream, &mut dispatcher).await?.is_none());
+            assert!(subscription.try_recv().unwrap().is_heartbeat());
+
+            assert!(dispatcher.remove(id));
+            assert!(subscription.next().await.is_none());
+
+            let packet = proto.dispatch(&stream, &mut dispatcher).await?;
+            assert!(packet.unwrap().is_heartbeat());
+
+            // The last heartbeat is filtered out, so the next packet is never received.
+            let res = proto.recv_matching(&stream, |_| false).await;
+            assert_eq!(res.unwrap_err().kind(), ErrorKind::PeerClosed);
+            assert_eq!(proto.stats().packets_received(), 4);
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// ===================================== #[test] handlers() ===================================== \\
+
+#[test]
+fn handlers() {
+    let count = Arc::new(AtomicUsize::new(0));
+    let mut dispatcher = Dispatcher::new();
+
+    let handled = count.clone();
+    dispatcher.on(1, move |packet| {
+        assert!(packet.is_heartbeat());
+        handled.fetch_add(1, Ordering::SeqCst);
+    });
+
+    assert!(dispatcher.dispatch(1, Packet::heartbeat()).is_none());
+    assert!(dispatcher.dispatch(1, Packet::heartbeat()).is_none());
+    assert!(dispatcher.dispatch(2, Packet::heartbeat()).is_some());
+    assert_eq!(count.load(Ordering::SeqCst), 2);
+
+    // Subscriptions which were dropped are unrouted.
+    drop(dispatcher.subscribe(2));
+    assert!(dispatcher.dispatch(2, Packet::heartbeat()).is_some());
+    assert!(!dispatcher.is_routed(2));
+
+    let mut subscription = dispatcher.subscribe(3);
+    assert!(dispatcher.dispatch(3, Packet::heartbeat()).is_none());
+    drop(dispatcher);
+
+    smol::block_on(async {
+        assert!(subscription.next().await.unwrap().is_heartbeat());
+        assert!(subscription.next().await.is_none());
+    });
+}
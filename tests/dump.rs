@@ -0,0 +1,62 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{Handshake, Packet, Result};
+
+// ======================================= #[test] dump() ======================================= \\
+
+#[test]
+fn dump() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let client = TcpStream::connect(addr).await?;
+        let (server, _) = listener.accept().await?;
+
+        let mut initiate = Handshake::initiate(&client);
+        let mut respond = Handshake::respond(&server);
+
+        let dump = initiate.dump_state();
+        assert_eq!(dump.kind(), "Initiate");
+        assert_eq!(dump.state(), "state");
+        assert_eq!(dump.sending_nonce(), None);
+        assert_eq!(respond.to_string(), "Respond(state, buffered=0)");
+
+        assert!(future::poll_once(&mut initiate).await.is_none());
+        assert_eq!(initiate.dump_state().state(), "status");
+
+        let (initiated, responded) = future::try_zip(&mut initiate, &mut respond).await?;
+        assert_eq!(initiate.dump_state().state(), "done");
+
+        let mut iproto = initiated.done()?;
+        let mut rproto = responded.done()?;
+        assert_eq!(iproto.dump_state().state(), "ready");
+        assert_eq!(iproto.dump_state().buffered(), 0);
+
+        let sent = iproto.dump_state().sending_nonce().unwrap();
+        iproto.send(&client, Packet::heartbeat()).await?;
+        assert!(rproto.recv(&server).await?.is_heartbeat());
+
+        let sending = iproto.dump_state().sending_nonce().unwrap();
+        assert!(sending > sent);
+        assert_eq!(rproto.dump_state().receiving_nonce(), Some(sending));
+
+        let debug = format!("{:?}", rproto);
+        assert!(debug.starts_with("Protocol { state: \"ready\""));
+        let display = rproto.to_string();
+        assert!(display.starts_with("Protocol(ready, buffered=0, nonces="));
+        assert!(display.ends_with(&format!("/{})", sending)));
+
+        Ok(())
+    })
+}
@@ -0,0 +1,45 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{Duplex, Handshake, Packet, Result};
+
+// ====================================== #[test] duplex() ====================================== \\
+
+#[test]
+fn duplex() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut io = Duplex::new(stream.clone(), stream);
+            let mut proto = Handshake::initiate(&mut io).await?.done()?;
+            proto.send(&mut io, Packet::heartbeat()).await?;
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut io = Duplex::new(stream.clone(), stream);
+            let mut proto = Handshake::respond(&mut io).await?.done()?;
+            assert!(proto.recv(&mut io).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
@@ -0,0 +1,63 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::{future, AsyncWriteExt};
+use pr070c01::{Engine, Handshake, Packet, Result};
+
+// ====================================== #[test] engine() ====================================== \\
+
+#[test]
+fn engine() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let mut stream = TcpStream::connect(addr).await?;
+            let mut engine = Handshake::initiate(&stream).await?.done()?.into_engine();
+            assert!(engine.is_initiator());
+
+            let mut out = Vec::new();
+            engine.write_packet(Packet::heartbeat(), &mut out)?;
+            engine.write_packet(Packet::heartbeat(), &mut out)?;
+            stream.write_all(&out).await?;
+
+            Result::Ok(engine)
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(Engine::from(proto))
+        });
+
+        let (mut iengine, mut rengine) = future::try_zip(initiate, respond).await?;
+
+        let mut out = Vec::new();
+        let len = rengine.write_packet(Packet::heartbeat(), &mut out)?;
+        rengine.write_packet(Packet::heartbeat(), &mut out)?;
+        assert_eq!(len * 2, out.len());
+
+        let mut packets = Vec::new();
+        for byte in &out {
+            packets.extend(iengine.read(&[*byte])?);
+        }
+
+        assert_eq!(packets.len(), 2);
+        assert!(packets.iter().all(Packet::is_heartbeat));
+        assert_eq!(iengine.pending(), 0);
+
+        Ok(())
+    })
+}
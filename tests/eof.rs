@@ -0,0 +1,68 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{Direction, Error, ErrorKind, Handshake, Packet, Result};
+
+// ======================================== #[test] eof() ======================================= \\
+
+#[test]
+fn eof() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            Handshake::initiate(&stream).await?.done()
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            Handshake::respond(&stream).await?.done()
+        });
+
+        let (mut iproto, mut rproto) = future::try_zip(initiate, respond).await?;
+
+        let mut bytes = Vec::new();
+        iproto.send(&mut bytes, Packet::heartbeat()).await?;
+        let len = bytes.len() - 2;
+
+        // Closed between two messages.
+        let res = rproto.recv(&bytes[..0]).await;
+        assert!(matches!(res, Err(Error::PeerClosed)));
+
+        // Closed in the middle of the header.
+        let err = rproto.recv(&bytes[..1]).await.err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::PeerClosed);
+        assert!(matches!(
+            err.root(),
+            Error::UnexpectedEof { needed: 2, got: 1 }
+        ));
+
+        // Closed in the middle of the message.
+        let err = rproto.recv(&bytes[..2 + 5]).await.err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::PeerClosed);
+        assert!(matches!(
+            err.root(),
+            Error::UnexpectedEof { needed, got: 5 } if *needed == len
+        ));
+
+        let context = err.context().unwrap();
+        assert_eq!(context.direction(), Some(Direction::Read));
+        assert_eq!(context.offset(), Some(2 + 5));
+
+        // Nothing was consumed from the session, so the whole message can still be received.
+        assert!(rproto.recv(&bytes[..]).await?.is_heartbeat());
+
+        Ok(())
+    })
+}
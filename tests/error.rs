@@ -0,0 +1,131 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use futures_lite::AsyncWriteExt;
+use pr070c01::{Direction, Error, ErrorKind, Handshake, HandshakePhase, Result, Suite};
+use std::io;
+
+// ======================================= #[test] kind() ======================================= \\
+
+#[test]
+fn kind() {
+    let io = |kind: io::ErrorKind| Error::Io(kind.into());
+
+    assert_eq!(io(io::ErrorKind::WouldBlock).kind(), ErrorKind::Transient);
+    assert_eq!(io(io::ErrorKind::Interrupted).kind(), ErrorKind::Transient);
+    assert_eq!(
+        io(io::ErrorKind::UnexpectedEof).kind(),
+        ErrorKind::PeerClosed
+    );
+    assert_eq!(io(io::ErrorKind::PermissionDenied).kind(), ErrorKind::Fatal);
+    assert_eq!(Error::Timeout.kind(), ErrorKind::Transient);
+    assert_eq!(Error::InvalidControl.kind(), ErrorKind::ProtocolViolation);
+    assert_eq!(Error::ReservedPacketId(0).kind(), ErrorKind::Fatal);
+
+    assert!(io(io::ErrorKind::WouldBlock).is_retryable());
+    assert!(!Error::InvalidProof.is_retryable());
+}
+
+// ==================================== #[test] peer_closed() =================================== \\
+
+#[test]
+fn peer_closed() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            Handshake::initiate(&stream).await?.done()?;
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let proto = Handshake::respond(&stream).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let (_, (stream, mut proto)) = future::try_zip(initiate, respond).await?;
+
+        let res = proto.recv(&stream).await;
+        assert!(matches!(res, Err(err) if err.kind() == ErrorKind::PeerClosed));
+
+        Ok(())
+    })
+}
+
+// ====================================== #[test] context() ===================================== \\
+
+#[test]
+fn context() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let mut stream = TcpStream::connect(addr).await?;
+            stream
+                .write_all(&[Suite::ChaChaPolyBlake2b.id(), 0])
+                .await?;
+            stream.close().await?;
+
+            Result::Ok(stream)
+        });
+
+        let (stream, _) = listener.accept().await?;
+        let _istream = initiate.await?;
+
+        let err = Handshake::respond(&stream).await.err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::PeerClosed);
+        assert!(matches!(
+            err.root(),
+            Error::UnexpectedEof { needed: 2, got: 1 }
+        ));
+
+        let context = err.context().unwrap();
+        assert_eq!(context.phase(), Some(HandshakePhase::AwaitingE));
+        assert_eq!(context.direction(), Some(Direction::Read));
+        assert_eq!(context.offset(), Some(1));
+        assert_eq!(context.packet_id(), None);
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let proto = Handshake::initiate(&stream).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let respond = async {
+            let (stream, _) = listener.accept().await?;
+            let proto = Handshake::respond(&stream).await?.done()?;
+
+            Result::Ok((stream, proto))
+        };
+
+        let ((mut istream, _iproto), (rstream, mut rproto)) =
+            future::try_zip(initiate, respond).await?;
+        istream.write_all(&[4, 0, 1, 2, 3, 4]).await?;
+
+        let err = rproto.recv(&rstream).await.err().unwrap();
+        assert!(matches!(err.root(), Error::Decrypt));
+
+        let context = err.context().unwrap();
+        assert_eq!(context.phase(), None);
+        assert_eq!(context.direction(), Some(Direction::Read));
+        assert_eq!(context.offset(), Some(2 + 4));
+
+        Ok(())
+    })
+}
@@ -0,0 +1,129 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use futures_lite::future;
+use pr070c01::{Config, Connector, Error, Handshake, Packet, Result, Timer};
+use std::io;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// ============================================ Types =========================================== \\
+
+struct SmolTimer(smol::Timer);
+
+// ========================================= impl Timer ========================================= \\
+
+impl Timer for SmolTimer {
+    #[inline]
+    fn after(duration: Duration) -> Self {
+        SmolTimer(smol::Timer::after(duration))
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl Future for SmolTimer {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(ctx).map(|_| ())
+    }
+}
+
+// ===================================== #[test] eyeballs() ===================================== \\
+
+#[test]
+fn eyeballs() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        // The first (IPv6) candidate never answers, the second one (IPv4) is refused and the third
+        // one is only started because of the failure.
+        let addrs: [SocketAddr; 3] = [
+            "[::1]:1".parse().unwrap(),
+            "127.0.0.1:2".parse().unwrap(),
+            addr,
+        ];
+
+        let started = Arc::new(AtomicUsize::new(0));
+        let connector = Connector::new(Config::new()).with_stagger(Duration::from_millis(10));
+        assert_eq!(connector.stagger(), Duration::from_millis(10));
+
+        let initiate = async {
+            let (stream, mut proto) = connector
+                .connect_any::<_, _, _, SmolTimer>(&addrs, |addr| {
+                    let started = started.clone();
+                    async move {
+                        started.fetch_add(1, Ordering::SeqCst);
+                        match addr.port() {
+                            1 => future::pending().await,
+                            2 => Err(io::ErrorKind::ConnectionRefused.into()),
+                            _ => TcpStream::connect(addr).await,
+                        }
+                    }
+                })
+                .await?;
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            Result::Ok(())
+        };
+
+        future::try_zip(initiate, respond).await?;
+        assert_eq!(started.load(Ordering::SeqCst), 3);
+
+        Ok(())
+    })
+}
+
+// ================================== #[test] eyeballs_failed() ================================= \\
+
+#[test]
+fn eyeballs_failed() -> Result<()> {
+    smol::block_on(async {
+        let addrs: [SocketAddr; 2] = ["127.0.0.1:2".parse().unwrap(), "[::1]:2".parse().unwrap()];
+        let connector = Connector::new(Config::new()).with_attempts(1);
+
+        let res = connector
+            .connect_any::<_, _, TcpStream, SmolTimer>(&addrs, |_| async {
+                Err(io::ErrorKind::ConnectionRefused.into())
+            })
+            .await;
+        assert!(
+            matches!(res, Err(Error::Io(error)) if error.kind() == io::ErrorKind::ConnectionRefused)
+        );
+
+        let res = connector
+            .connect_any::<_, _, TcpStream, SmolTimer>(&[], |_| async {
+                Err(io::ErrorKind::ConnectionRefused.into())
+            })
+            .await;
+        assert!(
+            matches!(res, Err(Error::Io(error)) if error.kind() == io::ErrorKind::InvalidInput)
+        );
+
+        Ok(())
+    })
+}
@@ -0,0 +1,248 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_lite::future;
+use pr070c01::packets::MSG_MAX_LEN;
+use pr070c01::{Handshake, Packet, Result};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::thread;
+use std::time::Duration;
+
+// ========================================== Constants ========================================= \\
+
+const SEEDS: u64 = 16;
+
+// ============================================ Types =========================================== \\
+
+struct FaultyIo<IO> {
+    io: IO,
+    rng: u64,
+    fail_after: Option<usize>,
+    transferred: usize,
+}
+
+// ======================================== impl FaultyIo ======================================= \\
+
+impl<IO> FaultyIo<IO> {
+    // ==================================== Constructors ==================================== \\
+
+    fn new(io: IO, seed: u64) -> Self {
+        FaultyIo {
+            io,
+            rng: seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1,
+            fail_after: None,
+            transferred: 0,
+        }
+    }
+
+    fn fail_after(mut self, len: usize) -> Self {
+        self.fail_after = Some(len);
+        self
+    }
+
+    // ======================================= Helpers ====================================== \\
+
+    fn next(&mut self) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+
+    fn fault(&mut self, ctx: &mut Context) -> io::Result<bool> {
+        if let Some(fail_after) = self.fail_after {
+            if self.transferred >= fail_after {
+                return Err(io::ErrorKind::ConnectionReset.into());
+            }
+        }
+
+        match self.next() % 8 {
+            0 => {
+                ctx.waker().wake_by_ref();
+                Ok(true)
+            }
+            1 => {
+                let waker = ctx.waker().clone();
+                let delay = Duration::from_micros(self.next() % 500);
+                thread::spawn(move || {
+                    thread::sleep(delay);
+                    waker.wake();
+                });
+
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn short(&mut self, len: usize) -> usize {
+        if len <= 1 || self.next() % 2 == 0 {
+            len
+        } else {
+            1 + (self.next() as usize) % (len - 1)
+        }
+    }
+}
+
+// ======================================= impl AsyncRead ======================================= \\
+
+impl<IO: AsyncRead + Unpin> AsyncRead for FaultyIo<IO> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        ctx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if this.fault(ctx)? {
+            return Poll::Pending;
+        }
+
+        let len = this.short(buf.len());
+        match Pin::new(&mut this.io).poll_read(ctx, &mut buf[..len])? {
+            Poll::Ready(read) => {
+                this.transferred += read;
+                Poll::Ready(Ok(read))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+// ======================================= impl AsyncWrite ====================================== \\
+
+impl<IO: AsyncWrite + Unpin> AsyncWrite for FaultyIo<IO> {
+    fn poll_write(self: Pin<&mut Self>, ctx: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if this.fault(ctx)? {
+            return Poll::Pending;
+        }
+
+        let len = this.short(buf.len());
+        match Pin::new(&mut this.io).poll_write(ctx, &buf[..len])? {
+            Poll::Ready(wrote) => {
+                this.transferred += wrote;
+                Poll::Ready(Ok(wrote))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.fault(ctx)? {
+            return Poll::Pending;
+        }
+
+        Pin::new(&mut this.io).poll_flush(ctx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_close(ctx)
+    }
+}
+
+// ====================================== #[test] faulty() ====================================== \\
+
+#[test]
+fn faulty() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        for seed in 0..SEEDS {
+            let data = (0..MSG_MAX_LEN * 2 + seed as usize)
+                .map(|i| i as u8)
+                .collect::<Vec<_>>();
+            let expected = data.clone();
+
+            let initiate = smol::spawn(async move {
+                let stream = TcpStream::connect(addr).await?;
+                let mut io = FaultyIo::new(&stream, seed);
+                let mut proto = Handshake::initiate(&mut io).await?.done()?;
+
+                for _ in 0..8 {
+                    proto.send(&mut io, Packet::heartbeat()).await?;
+                }
+
+                proto.send_large(&mut io, &data).await?;
+
+                Result::Ok(())
+            });
+
+            let (stream, _) = listener.accept().await?;
+            let respond = smol::spawn(async move {
+                let mut io = FaultyIo::new(&stream, !seed);
+                let mut proto = Handshake::respond(&mut io).await?.done()?;
+
+                for _ in 0..8 {
+                    assert!(proto.recv(&mut io).await?.is_heartbeat());
+                }
+
+                assert_eq!(proto.recv_large(&mut io).await?, expected);
+
+                Result::Ok(())
+            });
+
+            future::try_zip(initiate, respond).await?;
+        }
+
+        Ok(())
+    })
+}
+
+// ==================================== #[test] mid_stream() ==================================== \\
+
+#[test]
+fn mid_stream() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        for seed in 0..SEEDS {
+            let fail_after = 16 + (seed as usize) * 16;
+
+            let initiate = smol::spawn(async move {
+                let stream = TcpStream::connect(addr).await?;
+                let mut io = FaultyIo::new(&stream, seed).fail_after(fail_after);
+                let mut proto = Handshake::initiate(&mut io).await?.done()?;
+
+                for _ in 0..1024 {
+                    proto.send(&mut io, Packet::heartbeat()).await?;
+                }
+
+                Result::Ok(())
+            });
+
+            let (stream, _) = listener.accept().await?;
+            let respond = smol::spawn(async move {
+                let mut io = FaultyIo::new(&stream, !seed);
+                let mut proto = Handshake::respond(&mut io).await?.done()?;
+
+                for _ in 0..1024 {
+                    assert!(proto.recv(&mut io).await?.is_heartbeat());
+                }
+
+                Result::Ok(())
+            });
+
+            let initiated = initiate.await;
+            assert!(initiated.is_err());
+
+            let responded = respond.await;
+            assert!(responded.is_err());
+        }
+
+        Ok(())
+    })
+}
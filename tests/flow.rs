@@ -0,0 +1,193 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use futures_lite::io::AsyncWriteExt;
+use pr070c01::{Custom, Error, Handshake, Outbox, Packet, PriorityClass, Protocol, Result};
+use std::io;
+
+// =========================================== Helpers ========================================== \\
+
+async fn connect() -> Result<((TcpStream, Protocol), (TcpStream, Protocol))> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    let initiate = smol::spawn(async move {
+        let stream = TcpStream::connect(addr).await?;
+        let proto = Handshake::initiate(&stream).await?.done()?;
+
+        Result::Ok((stream, proto))
+    });
+
+    let respond = smol::spawn(async move {
+        let (stream, _) = listener.accept().await?;
+        let proto = Handshake::respond(&stream).await?.done()?;
+
+        Result::Ok((stream, proto))
+    });
+
+    future::try_zip(initiate, respond).await
+}
+
+// Sends heartbeats until the peer's window is full, and returns how many were sent.
+async fn exhaust(proto: &mut Protocol, stream: &TcpStream) -> Result<usize> {
+    let mut sent = 0;
+    loop {
+        match proto.send(stream, Packet::heartbeat()).await {
+            Ok(_) => sent += 1,
+            Err(Error::WindowExhausted { .. }) => break,
+            Err(err) => return Err(err),
+        }
+
+        assert!(sent < 100);
+    }
+
+    assert!(sent > 0);
+
+    Ok(sent)
+}
+
+// ======================================= #[test] flow() ======================================= \\
+
+#[test]
+fn flow() -> Result<()> {
+    smol::block_on(async {
+        let ((istream, mut iproto), (rstream, mut rproto)) = connect().await?;
+
+        // The window only limits the initiator once the responder announced it.
+        rproto.set_flow_window(Some(16));
+        assert_eq!(iproto.send_credit(), None);
+
+        rproto.drive_keepalive(&rstream).await?;
+        assert!(iproto.recv(&istream).await?.is_heartbeat());
+        assert_eq!(iproto.peer_flow_window(), Some(16));
+        assert_eq!(iproto.send_credit(), Some(16));
+
+        let sent = exhaust(&mut iproto, &istream).await?;
+        assert!(iproto.send_credit().unwrap() < 16);
+
+        for _ in 0..sent {
+            assert!(rproto.recv(&rstream).await?.is_heartbeat());
+        }
+
+        rproto.drive_keepalive(&rstream).await?;
+        assert!(iproto.recv(&istream).await?.is_heartbeat());
+        assert_eq!(iproto.send_credit(), Some(16));
+
+        iproto.send(&istream, Packet::heartbeat()).await?;
+        assert!(rproto.recv(&rstream).await?.is_heartbeat());
+
+        // Disabling the window lifts the limit on the peer.
+        rproto.set_flow_window(None);
+        rproto.drive_keepalive(&rstream).await?;
+        assert!(iproto.recv(&istream).await?.is_heartbeat());
+        assert_eq!(iproto.send_credit(), None);
+
+        Ok(())
+    })
+}
+
+// ==================================== #[test] every_path() ==================================== \\
+
+#[test]
+fn every_path() -> Result<()> {
+    smol::block_on(async {
+        let ((istream, mut iproto), (rstream, mut rproto)) = connect().await?;
+
+        rproto.set_flow_window(Some(16));
+        rproto.drive_keepalive(&rstream).await?;
+        assert!(iproto.recv(&istream).await?.is_heartbeat());
+
+        let sent = exhaust(&mut iproto, &istream).await?;
+        let credit = iproto.send_credit();
+
+        let custom = Custom::new(Custom::RESERVED, vec![0; 32])?;
+        let res = iproto.send_custom(&istream, &custom).await;
+        assert!(matches!(res, Err(Error::WindowExhausted { .. })));
+
+        let res = iproto.send_large(&istream, &[0; 64]).await;
+        assert!(matches!(res, Err(Error::WindowExhausted { .. })));
+
+        let packets = vec![Packet::heartbeat(), Packet::heartbeat()];
+        let res = iproto.send_all(&istream, packets).await;
+        assert!(matches!(res, Err(Error::WindowExhausted { .. })));
+
+        let outbox = Outbox::new();
+        outbox.push(PriorityClass::Bulk, Packet::heartbeat());
+        outbox.close();
+
+        let res = iproto.drain_outbox(&istream, &outbox).await;
+        assert!(matches!(res, Err(Error::WindowExhausted { .. })));
+        assert_eq!(outbox.len(), 1);
+
+        // Nothing was written by the failed sends.
+        assert_eq!(iproto.send_credit(), credit);
+        for _ in 0..sent {
+            assert!(rproto.recv(&rstream).await?.is_heartbeat());
+        }
+
+        rproto.drive_keepalive(&rstream).await?;
+        assert!(iproto.recv(&istream).await?.is_heartbeat());
+
+        iproto.drain_outbox(&istream, &outbox).await?;
+        assert!(outbox.is_empty());
+        assert!(rproto.recv(&rstream).await?.is_heartbeat());
+
+        Ok(())
+    })
+}
+
+// ==================================== #[test] would_block() =================================== \\
+
+#[test]
+fn would_block() -> Result<()> {
+    smol::block_on(async {
+        let ((istream, mut iproto), (rstream, mut rproto)) = connect().await?;
+
+        rproto.set_flow_window(Some(16));
+        rproto.drive_keepalive(&rstream).await?;
+        assert!(iproto.recv(&istream).await?.is_heartbeat());
+
+        // Writes are cut down to the credit that is left, and fail once there's none.
+        let mut stream = iproto.into_stream(&istream);
+        assert_eq!(stream.write(&[0; 64]).await?, 16);
+
+        let err = stream.write(&[0; 64]).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+
+        Ok(())
+    })
+}
+
+// ======================================= #[test] split() ====================================== \\
+
+#[test]
+fn split() -> Result<()> {
+    smol::block_on(async {
+        let ((istream, mut iproto), (rstream, mut rproto)) = connect().await?;
+
+        rproto.set_flow_window(Some(16));
+        rproto.drive_keepalive(&rstream).await?;
+        assert!(iproto.recv(&istream).await?.is_heartbeat());
+
+        let sent = exhaust(&mut iproto, &istream).await?;
+
+        let (sender, _receiver) = iproto.into_split(&istream);
+        let res = sender.send(Packet::heartbeat()).await;
+        assert!(matches!(res, Err(Error::WindowExhausted { .. })));
+
+        for _ in 0..sent {
+            assert!(rproto.recv(&rstream).await?.is_heartbeat());
+        }
+
+        Ok(())
+    })
+}
@@ -0,0 +1,54 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use futures_lite::io::BufWriter;
+use pr070c01::{Handshake, Packet, Result};
+
+// ======================================= #[test] flush() ====================================== \\
+
+#[test]
+fn flush() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+
+            let mut out = BufWriter::new(&stream);
+            proto
+                .send(&mut out, Packet::heartbeat())
+                .and_flush()
+                .await?;
+            assert!(out.buffer().is_empty());
+
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
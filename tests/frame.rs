@@ -0,0 +1,82 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::{future, AsyncReadExt};
+use pr070c01::{Config, Frame, FrameFlags, Handshake, Packet, Result, WireFormat};
+
+// ======================================= #[test] frame() ====================================== \\
+
+#[test]
+fn frame() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let config = Config::new().with_wire_format(WireFormat::V2);
+            let proto = Handshake::initiate_with(&stream, config).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let config = Config::new().with_wire_format(WireFormat::V2);
+            let proto = Handshake::respond_with(&stream, config).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let ((istream, mut iproto), (rstream, mut rproto)) =
+            future::try_zip(initiate, respond).await?;
+
+        iproto
+            .send_frame(&istream, Packet::heartbeat().into())
+            .await?;
+        assert!(rproto.recv(&rstream).await?.is_heartbeat());
+
+        let frame = Frame::new(Packet::heartbeat()).with_priority(5);
+        assert_eq!(frame.priority(), 5);
+        iproto.send_frame(&istream, frame).await?;
+
+        let mut hdr = [0; 4];
+        (&rstream).read_exact(&mut hdr).await?;
+
+        let flags = FrameFlags::from_bits(u16::from_be_bytes([hdr[2], hdr[3]]));
+        assert_eq!(flags.priority(), 5);
+        assert!(!flags.is_compressed());
+        assert!(!flags.is_fragment());
+
+        Ok(())
+    })
+}
+
+// ======================================= #[test] flags() ====================================== \\
+
+#[test]
+fn flags() {
+    let flags = FrameFlags::default()
+        .with_compressed(true)
+        .with_fragment(true)
+        .with_priority(FrameFlags::MAX_PRIORITY + 1);
+
+    assert!(flags.is_compressed());
+    assert!(flags.is_fragment());
+    assert_eq!(flags.priority(), FrameFlags::MAX_PRIORITY);
+    assert_eq!(FrameFlags::from_bits(flags.bits()), flags);
+
+    let flags = flags.with_compressed(false).with_priority(2);
+    assert!(!flags.is_compressed());
+    assert!(flags.is_fragment());
+    assert_eq!(flags.priority(), 2);
+    assert_eq!(flags.bits(), FrameFlags::FRAGMENT | 2 << 2);
+}
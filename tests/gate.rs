@@ -0,0 +1,160 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_util::task::noop_waker_ref;
+use pr070c01::{Config, Error, Handshake, HandshakePhase, Result, Suite};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// ============================================ Types =========================================== \\
+
+// Counts the allocations made by the current thread, so that the test harness doesn't get in the
+// way.
+struct Counting;
+
+// Reads the bytes it was given and then stays pending, while writes go to a buffer that never has
+// to grow.
+struct Script {
+    input: Vec<u8>,
+    off: usize,
+    output: Vec<u8>,
+}
+
+// =========================================== Statics ========================================== \\
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = Cell::new(0);
+    static LIVE: Cell<isize> = Cell::new(0);
+}
+
+// ====================================== impl GlobalAlloc ====================================== \\
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+        let _ = LIVE.try_with(|live| live.set(live.get() + layout.size() as isize));
+
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let _ = LIVE.try_with(|live| live.set(live.get() - layout.size() as isize));
+
+        System.dealloc(ptr, layout)
+    }
+}
+
+// ======================================= impl AsyncRead ======================================= \\
+
+impl AsyncRead for Script {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        let read = buf.len().min(this.input.len() - this.off);
+        if read == 0 {
+            return Poll::Pending;
+        }
+
+        buf[..read].copy_from_slice(&this.input[this.off..this.off + read]);
+        this.off += read;
+
+        Poll::Ready(Ok(read))
+    }
+}
+
+// ======================================= impl AsyncWrite ====================================== \\
+
+impl AsyncWrite for Script {
+    #[inline]
+    fn poll_write(self: Pin<&mut Self>, _: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let wrote = buf.len().min(this.output.capacity() - this.output.len());
+        this.output.extend_from_slice(&buf[..wrote]);
+
+        Poll::Ready(Ok(wrote))
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+// =============================== #[test] no_state_before_proof() ============================== \\
+
+#[test]
+fn no_state_before_proof() -> Result<()> {
+    // An offer of a single suite followed by half of a proof.
+    let mut input = vec![1, Suite::ChaChaPolyBlake2b.id()];
+    input.extend_from_slice(&[0; 4]);
+
+    let io = Script {
+        input,
+        off: 0,
+        output: Vec::with_capacity(64),
+    };
+
+    let mut respond = Handshake::respond_with(io, Config::new().with_pow(8));
+    let mut ctx = Context::from_waker(noop_waker_ref());
+
+    let allocations = ALLOCATIONS.with(Cell::get);
+    assert!(Pin::new(&mut respond).poll(&mut ctx).is_pending());
+    assert_eq!(respond.phase(), HandshakePhase::AwaitingProof);
+    assert_eq!(ALLOCATIONS.with(Cell::get), allocations);
+
+    // choice(1) + status(1) + nonce(16) + difficulty(1)
+    assert_eq!(respond.done().output.len(), 19);
+
+    Ok(())
+}
+
+// =========================== #[test] nothing_kept_on_invalid_proof() ========================== \\
+
+#[test]
+fn nothing_kept_on_invalid_proof() -> Result<()> {
+    // Zeroes are a valid proof with a probability of 2^-32.
+    let mut input = vec![1, Suite::ChaChaPolyBlake2b.id()];
+    input.extend_from_slice(&[0; 8]);
+
+    let io = Script {
+        input,
+        off: 0,
+        output: Vec::with_capacity(64),
+    };
+
+    let mut respond = Handshake::respond_with(io, Config::new().with_pow(32));
+    let mut ctx = Context::from_waker(noop_waker_ref());
+
+    let live = LIVE.with(Cell::get);
+    let res = Pin::new(&mut respond).poll(&mut ctx);
+    assert!(matches!(res, Poll::Ready(Err(Error::InvalidProof))));
+    assert_eq!(respond.phase(), HandshakePhase::Done);
+
+    drop(res);
+    assert_eq!(LIVE.with(Cell::get), live);
+
+    Ok(())
+}
@@ -10,7 +10,7 @@
 
 use async_net::{TcpListener, TcpStream};
 use futures_lite::future;
-use pr070c01::{Handshake, Packet, Result};
+use pr070c01::{Error, Handshake, Packet, Reason, Result};
 
 // ======================================= #[test] hello() ====================================== \\
 
@@ -43,6 +43,15 @@ fn hello() -> Result<()> {
         rproto.send(&rstream, Packet::heartbeat()).await?;
         assert!(iproto.recv(&istream).await?.is_heartbeat());
 
+        iproto.send(&istream, Packet::heartbeat()).await?;
+        assert!(rproto.recv_ref(&rstream).await?.to_owned()?.is_heartbeat());
+
+        iproto.close(&istream, Reason::new(Reason::NORMAL)).await?;
+        match rproto.recv(&rstream).await {
+            Err(Error::Closed(reason)) => assert_eq!(reason.code(), Reason::NORMAL),
+            _ => panic!(),
+        }
+
         Ok(())
     })
 }
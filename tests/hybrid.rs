@@ -0,0 +1,150 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+#![cfg(feature = "pq")]
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_lite::future;
+use pr070c01::{Config, Handshake, Packet, Result};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// ============================================ Types =========================================== \\
+
+// Flips the second byte read, which is the responder's answer to a hybrid offer, right after the
+// suite it chose.
+struct Flip<IO> {
+    io: IO,
+    read: usize,
+}
+
+// ======================================= impl AsyncRead ======================================= \\
+
+impl<IO: AsyncRead + Unpin> AsyncRead for Flip<IO> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        ctx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let read = match Pin::new(&mut this.io).poll_read(ctx, buf)? {
+            Poll::Ready(read) => read,
+            Poll::Pending => return Poll::Pending,
+        };
+
+        if this.read < 2 && this.read + read >= 2 {
+            buf[1 - this.read] ^= 1;
+        }
+
+        this.read += read;
+
+        Poll::Ready(Ok(read))
+    }
+}
+
+// ======================================= impl AsyncWrite ====================================== \\
+
+impl<IO: AsyncWrite + Unpin> AsyncWrite for Flip<IO> {
+    #[inline]
+    fn poll_write(self: Pin<&mut Self>, ctx: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write(ctx, buf)
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_flush(ctx)
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_close(ctx)
+    }
+}
+
+// ====================================== #[test] hybrid() ====================================== \\
+
+#[test]
+fn hybrid() -> Result<()> {
+    handshake(true, true)
+}
+
+// ===================================== #[test] fallback() ===================================== \\
+
+#[test]
+fn fallback() -> Result<()> {
+    handshake(true, false)?;
+    handshake(false, true)
+}
+
+// ===================================== #[test] tampered() ===================================== \\
+
+#[test]
+fn tampered() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let config = Config::new().with_hybrid(true);
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let io = Flip {
+                io: &stream,
+                read: 0,
+            };
+
+            Handshake::initiate_with(io, config).await.map(|_| ())
+        });
+
+        let config = Config::new().with_hybrid(true);
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            Handshake::respond_with(&stream, config).await.map(|_| ())
+        });
+
+        let (ires, rres) = future::zip(initiate, respond).await;
+        assert!(ires.is_err());
+        assert!(rres.is_err());
+
+        Ok(())
+    })
+}
+
+// ========================================= handshake() ======================================== \\
+
+fn handshake(ihybrid: bool, rhybrid: bool) -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let config = Config::new().with_hybrid(ihybrid);
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate_with(&stream, config).await?.done()?;
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            Result::Ok(())
+        });
+
+        let config = Config::new().with_hybrid(rhybrid).with_pow(4);
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond_with(&stream, config).await?.done()?;
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
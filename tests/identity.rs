@@ -0,0 +1,189 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+mod common;
+
+use async_net::{TcpListener, TcpStream};
+use common::keypair;
+use futures_lite::future;
+use pr070c01::{Config, Handshake, Packet, PublicKey, RecordedIo, Result};
+
+// ===================================== #[test] identity() ===================================== \\
+
+#[test]
+fn identity() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let ikeypair = keypair(1);
+        let rkeypair = keypair(2);
+        let ipublic = ikeypair.public;
+        let rpublic = rkeypair.public;
+
+        let config = Config::new().with_identity(ikeypair);
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let handshake = Handshake::initiate_with(&stream, config).await?;
+            assert_eq!(handshake.remote_identity(), Some(&rpublic));
+
+            let mut proto = handshake.done()?;
+            assert_eq!(proto.remote_identity(), Some(&rpublic));
+
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            Result::Ok(())
+        });
+
+        let config = Config::new().with_identity(rkeypair);
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let handshake = Handshake::respond_with(&stream, config).await?;
+            assert_eq!(handshake.remote_identity(), Some(&ipublic));
+
+            let mut proto = handshake.done()?;
+            assert_eq!(proto.remote_identity(), Some(&ipublic));
+
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// ===================================== #[test] anonymous() ==================================== \\
+
+#[test]
+fn anonymous() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let proto = Handshake::initiate(&stream).await?.done()?;
+            assert_eq!(proto.remote_identity(), None);
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let proto = Handshake::respond(&stream).await?.done()?;
+            assert_eq!(proto.remote_identity(), None);
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// ================================== #[test] initiator_only() ================================== \\
+
+#[test]
+fn initiator_only() -> Result<()> {
+    let ipublic = keypair(1).public;
+
+    let iconfig = Config::new().with_identity(keypair(1));
+    let (iremote, rremote) = identities(iconfig, Config::new())?;
+    assert_eq!(iremote, None);
+    assert_eq!(rremote, Some(ipublic));
+
+    Ok(())
+}
+
+// ================================== #[test] responder_only() ================================== \\
+
+#[test]
+fn responder_only() -> Result<()> {
+    let rpublic = keypair(2).public;
+
+    let rconfig = Config::new().with_identity(keypair(2));
+    let (iremote, rremote) = identities(Config::new(), rconfig)?;
+    assert_eq!(iremote, Some(rpublic));
+    assert_eq!(rremote, None);
+
+    Ok(())
+}
+
+// ===================================== #[test] encrypted() ==================================== \\
+
+#[test]
+fn encrypted() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let ipublic = keypair(1).public;
+        let rpublic = keypair(2).public;
+
+        let config = Config::new().with_identity(keypair(1));
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            Handshake::initiate_with(&stream, config).await?.done()?;
+
+            Result::Ok(())
+        });
+
+        let config = Config::new().with_identity(keypair(2));
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut io = RecordedIo::new(stream, Vec::new());
+            let handshake = Handshake::respond_with(&mut io, config).await?;
+            assert_eq!(handshake.remote_identity(), Some(&ipublic));
+
+            Result::Ok(io.into_inner().1)
+        });
+
+        let ((), recording) = future::try_zip(initiate, respond).await?;
+
+        // Neither identity may be seen by someone watching the connection.
+        for public in &[ipublic, rpublic] {
+            let public = public.as_bytes();
+            assert!(!recording.windows(public.len()).any(|bytes| bytes == public));
+        }
+
+        Ok(())
+    })
+}
+
+// ======================================== identities() ======================================== \\
+
+fn identities(iconfig: Config, rconfig: Config) -> Result<(Option<PublicKey>, Option<PublicKey>)> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate_with(&stream, iconfig).await?.done()?;
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            Result::Ok(proto.remote_identity().copied())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond_with(&stream, rconfig).await?.done()?;
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(proto.remote_identity().copied())
+        });
+
+        future::try_zip(initiate, respond).await
+    })
+}
@@ -0,0 +1,114 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+mod common;
+
+use async_net::{TcpListener, TcpStream};
+use common::keypair;
+use futures_lite::future;
+use pr070c01::{Config, Handshake, HandshakeInfo, Result};
+
+// ======================================= #[test] info() ======================================= \\
+
+#[test]
+fn info() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let (_, info) = Handshake::initiate(&stream).await?.done_with_info()?;
+
+            Result::Ok(info)
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let (_, info) = Handshake::respond(&stream).await?.done_with_info()?;
+
+            Result::Ok(info)
+        });
+
+        let (iinfo, rinfo) = future::try_zip(initiate, respond).await?;
+
+        assert!(iinfo.is_initiator());
+        assert!(!rinfo.is_initiator());
+        assert_eq!(iinfo.handshake_hash(), rinfo.handshake_hash());
+        assert_eq!(iinfo.pattern(), Handshake::NOISE_PATTERN);
+        assert_eq!(rinfo.pattern(), Handshake::NOISE_PATTERN);
+
+        Ok(())
+    })
+}
+
+// ===================================== #[test] targeted() ===================================== \\
+
+#[test]
+fn targeted() -> Result<()> {
+    let rkeypair = keypair(2);
+    let iconfig = Config::new()
+        .with_identity(keypair(1))
+        .with_remote_identity(rkeypair.public);
+    let rconfig = Config::new().with_identity(rkeypair);
+
+    let (iinfo, rinfo) = infos(iconfig, rconfig)?;
+    assert_eq!(iinfo.pattern(), "Noise_IK_25519_ChaChaPoly_BLAKE2b");
+    assert_eq!(rinfo.pattern(), iinfo.pattern());
+    assert!(iinfo.remote_static().is_some());
+    assert!(rinfo.remote_static().is_some());
+
+    Ok(())
+}
+
+// ==================================== #[test] identified() ==================================== \\
+
+#[test]
+fn identified() -> Result<()> {
+    let iconfig = Config::new().with_identity(keypair(1));
+    let rconfig = Config::new();
+
+    let (iinfo, rinfo) = infos(iconfig, rconfig)?;
+    assert_eq!(iinfo.pattern(), "Noise_XN_25519_ChaChaPoly_BLAKE2b");
+    assert_eq!(rinfo.pattern(), iinfo.pattern());
+    assert!(iinfo.remote_static().is_none());
+    assert!(rinfo.remote_static().is_some());
+
+    Ok(())
+}
+
+// =========================================== infos() ========================================== \\
+
+fn infos(iconfig: Config, rconfig: Config) -> Result<(HandshakeInfo, HandshakeInfo)> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let (_, info) = Handshake::initiate_with(&stream, iconfig)
+                .await?
+                .done_with_info()?;
+
+            Result::Ok(info)
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let (_, info) = Handshake::respond_with(&stream, rconfig)
+                .await?
+                .done_with_info()?;
+
+            Result::Ok(info)
+        });
+
+        future::try_zip(initiate, respond).await
+    })
+}
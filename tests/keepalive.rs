@@ -0,0 +1,98 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use futures_lite::future;
+use pr070c01::{Error, Handshake, Result, Timer};
+
+// ============================================ Types =========================================== \\
+
+struct SmolTimer(smol::Timer);
+
+// ========================================= impl Timer ========================================= \\
+
+impl Timer for SmolTimer {
+    #[inline]
+    fn after(duration: Duration) -> Self {
+        SmolTimer(smol::Timer::after(duration))
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl Future for SmolTimer {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(ctx).map(|_| ())
+    }
+}
+
+// ===================================== #[test] keepalive() ==================================== \\
+
+#[test]
+fn keepalive() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let proto = Handshake::initiate(&stream).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let proto = Handshake::respond(&stream).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let ((istream, mut iproto), (rstream, mut rproto)) =
+            future::try_zip(initiate, respond).await?;
+
+        iproto.keepalive(Duration::from_millis(50));
+        iproto.set_keepalive_timeout(Duration::from_millis(500));
+
+        // Keepalives can't be driven without a timer to sleep on.
+        assert!(matches!(
+            iproto.drive_keepalive(&istream).await,
+            Err(Error::MissingTimer)
+        ));
+
+        iproto.set_timer::<SmolTimer>();
+
+        // Heartbeats keep being sent until the peer times out, as it never sends anything.
+        let recv = async {
+            for _ in 0..4 {
+                assert!(rproto.recv(&rstream).await?.is_heartbeat());
+            }
+
+            Result::Ok(())
+        };
+
+        let (res, received) = future::zip(iproto.drive_keepalive(&istream), recv).await;
+        assert!(matches!(res, Err(Error::PeerTimeout)));
+        received?;
+
+        // There's nothing left to drive once keepalives are disabled.
+        iproto.disable_keepalive();
+        iproto.drive_keepalive(&istream).await?;
+
+        Ok(())
+    })
+}
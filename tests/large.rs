@@ -0,0 +1,148 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::packets::MSG_MAX_LEN;
+use pr070c01::{Error, Handshake, Packet, Protocol, Result};
+
+// =========================================== Helpers ========================================== \\
+
+async fn connect() -> Result<((TcpStream, Protocol), (TcpStream, Protocol))> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    let initiate = smol::spawn(async move {
+        let stream = TcpStream::connect(addr).await?;
+        let proto = Handshake::initiate(&stream).await?.done()?;
+
+        Result::Ok((stream, proto))
+    });
+
+    let respond = smol::spawn(async move {
+        let (stream, _) = listener.accept().await?;
+        let proto = Handshake::respond(&stream).await?.done()?;
+
+        Result::Ok((stream, proto))
+    });
+
+    future::try_zip(initiate, respond).await
+}
+
+// ======================================= #[test] large() ====================================== \\
+
+#[test]
+fn large() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let data = (0..MSG_MAX_LEN * 3 + 7)
+            .map(|i| i as u8)
+            .collect::<Vec<_>>();
+        let expected = data.clone();
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+
+            proto.send_large(&stream, &data).await?;
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+
+            assert_eq!(proto.recv_large(&stream).await?, expected);
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// ==================================== #[test] interleaved() =================================== \\
+
+#[test]
+fn interleaved() -> Result<()> {
+    smol::block_on(async {
+        let ((istream, mut iproto), (rstream, mut rproto)) = connect().await?;
+
+        iproto.send(&istream, Packet::heartbeat()).await?;
+        iproto.send_large(&istream, &[1; 64]).await?;
+        iproto.send(&istream, Packet::heartbeat()).await?;
+        iproto.send_large(&istream, &[2; 64]).await?;
+
+        // Packets received while waiting for a large message are returned by the next receives.
+        assert_eq!(rproto.recv_large(&rstream).await?, vec![1; 64]);
+        assert_eq!(rproto.recv_large(&rstream).await?, vec![2; 64]);
+        assert!(rproto.recv(&rstream).await?.is_heartbeat());
+        assert!(rproto.recv(&rstream).await?.is_heartbeat());
+
+        Ok(())
+    })
+}
+
+// ====================================== #[test] streams() ===================================== \\
+
+#[test]
+fn streams() -> Result<()> {
+    smol::block_on(async {
+        let ((istream, mut iproto), (rstream, mut rproto)) = connect().await?;
+
+        // Large messages which aren't received don't pile up.
+        for _ in 0..9 {
+            iproto.send_large(&istream, &[0; 16]).await?;
+        }
+
+        let res = rproto.recv(&rstream).await;
+        assert!(matches!(
+            res,
+            Err(Error::ReassemblyFull {
+                streams: 8,
+                bytes: 128
+            })
+        ));
+
+        Ok(())
+    })
+}
+
+// ===================================== #[test] buffered() ===================================== \\
+
+#[test]
+fn buffered() -> Result<()> {
+    smol::block_on(async {
+        let ((istream, mut iproto), (rstream, mut rproto)) = connect().await?;
+
+        // Every buffered large message counts towards the maximum length.
+        rproto.set_max_large_len(100);
+        iproto.send_large(&istream, &[0; 60]).await?;
+        iproto.send_large(&istream, &[0; 60]).await?;
+
+        let res = rproto.recv(&rstream).await;
+        assert!(matches!(
+            res,
+            Err(Error::ReassemblyFull {
+                streams: 1,
+                bytes: 60
+            })
+        ));
+
+        Ok(())
+    })
+}
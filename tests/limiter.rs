@@ -0,0 +1,89 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{Config, Error, Handshake, HandshakeLimiter, Result};
+
+// =========================================== Helpers ========================================== \\
+
+async fn pair(listener: &TcpListener) -> Result<(TcpStream, TcpStream)> {
+    let connect = TcpStream::connect(listener.local_addr()?);
+    let (initiator, (responder, _)) = future::try_zip(connect, listener.accept()).await?;
+
+    Ok((initiator, responder))
+}
+
+// ======================================= #[test] busy() ======================================= \\
+
+#[test]
+fn busy() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let limiter = HandshakeLimiter::new(1);
+        let config = Config::new().with_limiter(limiter.clone());
+
+        let (_first, rfirst) = pair(&listener).await?;
+        let (_second, rsecond) = pair(&listener).await?;
+
+        let mut first = Handshake::respond_with(&rfirst, config.clone());
+        assert!(future::poll_once(&mut first).await.is_none());
+        assert_eq!(limiter.active(), 1);
+
+        let res = Handshake::respond_with(&rsecond, config).await;
+        assert!(matches!(res, Err(Error::Busy(1))));
+
+        drop(first);
+        assert_eq!(limiter.active(), 0);
+
+        Ok(())
+    })
+}
+
+// ====================================== #[test] queued() ====================================== \\
+
+#[test]
+fn queued() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let limiter = HandshakeLimiter::new(1).with_max_queued(1);
+        let config = Config::new().with_limiter(limiter.clone());
+
+        let (ifirst, rfirst) = pair(&listener).await?;
+        let (isecond, rsecond) = pair(&listener).await?;
+        let (_third, rthird) = pair(&listener).await?;
+
+        let mut first = Handshake::respond_with(&rfirst, config.clone());
+        assert!(future::poll_once(&mut first).await.is_none());
+
+        let mut second = Handshake::respond_with(&rsecond, config.clone());
+        assert!(future::poll_once(&mut second).await.is_none());
+        assert_eq!(second.dump_state().state(), "queued");
+        assert_eq!((limiter.active(), limiter.queued()), (1, 1));
+
+        let res = Handshake::respond_with(&rthird, config).await;
+        assert!(matches!(res, Err(Error::Busy(1))));
+
+        let initiate = smol::spawn(async move {
+            Handshake::initiate(&ifirst).await?.done()?;
+            Handshake::initiate(&isecond).await?.done()?;
+
+            Result::Ok(())
+        });
+
+        first.await?.done()?;
+        second.await?.done()?;
+        initiate.await?;
+
+        assert_eq!((limiter.active(), limiter.queued()), (0, 0));
+
+        Ok(())
+    })
+}
@@ -0,0 +1,240 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+mod common;
+
+use async_net::{TcpListener, TcpStream};
+use common::keypair;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use pr070c01::{Config, Connector, Error, Handshake, Packet, Reason, Result};
+use pr070c01::{SessionManager, Timer};
+use std::io;
+use std::net::SocketAddr;
+
+// ============================================ Types =========================================== \\
+
+struct SmolTimer(smol::Timer);
+
+// ========================================= impl Timer ========================================= \\
+
+impl Timer for SmolTimer {
+    #[inline]
+    fn after(duration: Duration) -> Self {
+        SmolTimer(smol::Timer::after(duration))
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl Future for SmolTimer {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(ctx).map(|_| ())
+    }
+}
+
+// ====================================== #[test] manager() ===================================== \\
+
+#[test]
+fn manager() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let rkeypair = keypair(2);
+        let rpublic = rkeypair.public;
+
+        let config = Config::new().with_identity(rkeypair);
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond_with(&stream, config).await?.done()?;
+
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        let connector = Connector::new(Config::new().with_identity(keypair(1)));
+        let mut manager = SessionManager::<TcpStream>::new(connector).with_max_sessions(4);
+        let addrs = [None, Some(addr)];
+
+        for _ in 0..2 {
+            let (proto, io) = manager
+                .get_or_connect::<_, _, _, SmolTimer>(&rpublic, &addrs, dial)
+                .await?;
+
+            proto.send(&*io, Packet::heartbeat()).await?;
+        }
+
+        assert_eq!(manager.len(), 1);
+        assert!(manager.contains(&rpublic));
+        assert_eq!(manager.evict_idle(), 0);
+
+        respond.await?;
+
+        assert!(manager.remove(&rpublic).is_some());
+        assert!(manager.is_empty());
+
+        Ok(())
+    })
+}
+
+// ===================================== #[test] mismatch() ===================================== \\
+
+#[test]
+fn mismatch() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let config = Config::new().with_identity(keypair(3));
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            Handshake::respond_with(&stream, config).await?;
+
+            Result::Ok(())
+        });
+
+        let connector = Connector::new(Config::new().with_identity(keypair(1)));
+        let mut manager = SessionManager::<TcpStream>::new(connector);
+        let addrs = [Some(addr)];
+
+        let res = manager
+            .get_or_connect::<_, _, _, SmolTimer>(&keypair(2).public, &addrs, dial)
+            .await;
+        assert!(matches!(res, Err(Error::InvalidIdentity)));
+        assert!(manager.is_empty());
+
+        respond.await?;
+
+        Ok(())
+    })
+}
+
+// ===================================== #[test] reap_idle() ==================================== \\
+
+#[test]
+fn reap_idle() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let rkeypair = keypair(2);
+        let rpublic = rkeypair.public;
+
+        let config = Config::new().with_identity(rkeypair);
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond_with(&stream, config).await?.done()?;
+
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+            match proto.recv(&stream).await {
+                Err(Error::Closed(reason)) => assert_eq!(reason.code(), Reason::IDLE_TIMEOUT),
+                res => panic!("{:?}", res.map(|_| ())),
+            }
+
+            Result::Ok(())
+        });
+
+        let connector = Connector::new(Config::new().with_identity(keypair(1)));
+        let mut manager = SessionManager::<TcpStream>::new(connector)
+            .with_idle_timeout(Duration::from_millis(50));
+        let addrs = [Some(addr)];
+
+        let (proto, io) = manager
+            .get_or_connect::<_, _, _, SmolTimer>(&rpublic, &addrs, dial)
+            .await?;
+        proto.send(&*io, Packet::heartbeat()).await?;
+
+        assert_eq!(manager.reap_idle().await, 0);
+        assert_eq!(manager.len(), 1);
+
+        smol::Timer::after(Duration::from_millis(100)).await;
+
+        let reap = manager.reap_idle();
+        assert_eq!(reap.reaped(), 1);
+        assert!(manager.is_empty());
+        assert_eq!(smol::spawn(reap).await, 1);
+
+        respond.await?;
+
+        Ok(())
+    })
+}
+
+// ====================================== #[test] evicted() ===================================== \\
+
+#[test]
+fn evicted() -> Result<()> {
+    smol::block_on(async {
+        let mut addrs = Vec::new();
+        let mut publics = Vec::new();
+        let mut responds = Vec::new();
+        for seed in 2..4 {
+            let listener = TcpListener::bind("127.0.0.1:0").await?;
+            addrs.push([Some(listener.local_addr()?)]);
+
+            let rkeypair = keypair(seed);
+            publics.push(rkeypair.public);
+
+            let config = Config::new().with_identity(rkeypair);
+            responds.push(smol::spawn(async move {
+                let (stream, _) = listener.accept().await?;
+                let mut proto = Handshake::respond_with(&stream, config).await?.done()?;
+
+                proto.recv(&stream).await.map(|_| ())
+            }));
+        }
+
+        let connector = Connector::new(Config::new().with_identity(keypair(1)));
+        let mut manager = SessionManager::<TcpStream>::new(connector).with_max_sessions(1);
+
+        for (public, addrs) in publics.iter().zip(&addrs) {
+            manager
+                .get_or_connect::<_, _, _, SmolTimer>(public, addrs, dial)
+                .await?;
+        }
+
+        // The first session was evicted to make room for the second one, but not dropped.
+        assert_eq!(manager.len(), 1);
+        assert!(manager.contains(&publics[1]));
+        assert_eq!(manager.evicted(), 1);
+
+        let reap = manager.reap_idle();
+        assert_eq!(reap.reaped(), 1);
+        assert_eq!(manager.evicted(), 0);
+        assert_eq!(smol::spawn(reap).await, 1);
+
+        match responds.remove(0).await {
+            Err(Error::Closed(reason)) => assert_eq!(reason.code(), Reason::IDLE_TIMEOUT),
+            res => panic!("{:?}", res),
+        }
+
+        Ok(())
+    })
+}
+
+// =========================================== dial() =========================================== \\
+
+fn dial(addr: &Option<SocketAddr>) -> impl Future<Output = io::Result<TcpStream>> {
+    let addr = *addr;
+    async move {
+        match addr {
+            Some(addr) => TcpStream::connect(addr).await,
+            None => Err(io::ErrorKind::ConnectionRefused.into()),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+#![cfg(feature = "metrics")]
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_io::AsyncWrite;
+use futures_lite::future;
+use pr070c01::{Config, Error, Handshake, Packet, ProtocolMetrics, Result, Suite};
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+// ============================================ Types =========================================== \\
+
+#[derive(Default)]
+struct Counters {
+    handshakes: AtomicUsize,
+    sent: AtomicUsize,
+    sent_bytes: AtomicUsize,
+    received: AtomicUsize,
+    received_bytes: AtomicUsize,
+    errors: AtomicUsize,
+}
+
+struct Stalling {
+    bytes: Vec<u8>,
+    limit: usize,
+}
+
+// ==================================== impl ProtocolMetrics ==================================== \\
+
+impl ProtocolMetrics for Counters {
+    fn on_handshake_complete(&self, _: Suite, _: bool) {
+        self.handshakes.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn on_packet_sent(&self, _: u16, bytes: usize) {
+        self.sent.fetch_add(1, Ordering::SeqCst);
+        self.sent_bytes.fetch_add(bytes, Ordering::SeqCst);
+    }
+
+    fn on_packet_received(&self, _: u16, bytes: usize) {
+        self.received.fetch_add(1, Ordering::SeqCst);
+        self.received_bytes.fetch_add(bytes, Ordering::SeqCst);
+    }
+
+    fn on_error(&self, _: &Error) {
+        self.errors.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+// ======================================= impl AsyncWrite ====================================== \\
+
+impl AsyncWrite for Stalling {
+    fn poll_write(self: Pin<&mut Self>, _: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let len = buf.len().min(this.limit - this.bytes.len());
+        if len == 0 {
+            return Poll::Pending;
+        }
+
+        this.bytes.extend_from_slice(&buf[..len]);
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+// ====================================== #[test] metrics() ===================================== \\
+
+#[test]
+fn metrics() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let icounters = Arc::new(Counters::default());
+        let rcounters = Arc::new(Counters::default());
+
+        let config = Config::new().with_metrics(icounters.clone());
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate_with(&stream, config).await?.done()?;
+
+            proto.send(&stream, Packet::heartbeat()).await?;
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            Result::Ok(())
+        });
+
+        let config = Config::new().with_metrics(rcounters.clone());
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond_with(&stream, config).await?.done()?;
+
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+            assert!(proto.recv(&stream).await.is_err());
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        assert_eq!(icounters.handshakes.load(Ordering::SeqCst), 1);
+        assert_eq!(rcounters.handshakes.load(Ordering::SeqCst), 1);
+
+        assert_eq!(icounters.sent.load(Ordering::SeqCst), 2);
+        assert_eq!(rcounters.received.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            icounters.sent_bytes.load(Ordering::SeqCst),
+            rcounters.received_bytes.load(Ordering::SeqCst),
+        );
+
+        assert_eq!(icounters.errors.load(Ordering::SeqCst), 0);
+        assert_eq!(rcounters.errors.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    })
+}
+
+// ====================================== #[test] written() ===================================== \\
+
+#[test]
+fn written() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let counters = Arc::new(Counters::default());
+
+        let config = Config::new().with_metrics(counters.clone());
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            Handshake::initiate_with(&stream, config).await?.done()
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            Handshake::respond(&stream).await?.done()
+        });
+
+        let (mut proto, _) = future::try_zip(initiate, respond).await?;
+
+        let mut out = Stalling {
+            bytes: Vec::new(),
+            limit: 4,
+        };
+
+        // Packets don't count as sent before their frames are written...
+        let packets = vec![Packet::heartbeat(), Packet::heartbeat()];
+        let mut send = proto.send_all(&mut out, packets);
+        assert!(future::poll_once(&mut send).await.is_none());
+        assert_eq!(counters.sent.load(Ordering::SeqCst), 0);
+
+        // ...unless the send gets dropped, as the rest of its frames is written before anything
+        // else.
+        drop(send);
+        assert_eq!(counters.sent.load(Ordering::SeqCst), 2);
+
+        out.limit = usize::MAX;
+        proto.send_all(&mut out, vec![Packet::heartbeat()]).await?;
+        assert_eq!(counters.sent.load(Ordering::SeqCst), 3);
+
+        Ok(())
+    })
+}
@@ -0,0 +1,141 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::{future, AsyncReadExt, AsyncWriteExt};
+use pr070c01::{Handshake, Muxer, Result};
+
+// ======================================== #[test] mux() ======================================= \\
+
+#[test]
+fn mux() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let proto = Handshake::initiate(&stream).await?.done()?;
+
+            Result::Ok(Muxer::new(proto, stream))
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let proto = Handshake::respond(&stream).await?.done()?;
+
+            Result::Ok(Muxer::new(proto, stream))
+        });
+
+        let (imux, rmux) = future::try_zip(initiate, respond).await?;
+
+        let mut istream1 = imux.open_stream()?;
+        let mut istream2 = imux.open_stream()?;
+        assert_ne!(istream1.id(), istream2.id());
+
+        istream1.write_all(b"hello").await?;
+        istream1.flush().await?;
+
+        let mut rstream1 = rmux.accept_stream().await?.unwrap();
+        let mut rstream2 = rmux.accept_stream().await?.unwrap();
+        assert_eq!(rstream1.id(), istream1.id());
+        assert_eq!(rstream2.id(), istream2.id());
+
+        let mut hello = [0; 5];
+        rstream1.read_exact(&mut hello).await?;
+        assert_eq!(&hello, b"hello");
+
+        let data = vec![42; 4 * Muxer::<TcpStream>::INITIAL_WINDOW as usize];
+        let write = async {
+            istream2.write_all(&data).await?;
+            istream2.close().await?;
+
+            Result::Ok(())
+        };
+
+        let read = async {
+            let mut read = Vec::new();
+            rstream2.read_to_end(&mut read).await?;
+
+            Result::Ok(read)
+        };
+
+        let ((), read) = future::try_zip(write, read).await?;
+        assert_eq!(read, data);
+
+        rstream1.write_all(b"bye").await?;
+        rstream1.close().await?;
+
+        let mut bye = Vec::new();
+        istream1.read_to_end(&mut bye).await?;
+        assert_eq!(bye, b"bye");
+
+        Ok(())
+    })
+}
+
+// ================================ #[test] concurrent_readers() ================================ \\
+
+#[test]
+fn concurrent_readers() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let proto = Handshake::initiate(&stream).await?.done()?;
+
+            Result::Ok(Muxer::new(proto, stream))
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let proto = Handshake::respond(&stream).await?.done()?;
+
+            Result::Ok(Muxer::new(proto, stream))
+        });
+
+        let (imux, rmux) = future::try_zip(initiate, respond).await?;
+
+        let mut istream1 = imux.open_stream()?;
+        let mut istream2 = imux.open_stream()?;
+        istream1.flush().await?;
+
+        let mut rstream1 = rmux.accept_stream().await?.unwrap();
+        let mut rstream2 = rmux.accept_stream().await?.unwrap();
+
+        // Both streams wait for data from different tasks, and the first one to be done stops
+        // polling the muxer.
+        let first = smol::spawn(async move {
+            let mut first = [0; 5];
+            rstream1.read_exact(&mut first).await?;
+
+            Result::Ok(first)
+        });
+
+        let second = smol::spawn(async move {
+            let mut second = [0; 6];
+            rstream2.read_exact(&mut second).await?;
+
+            Result::Ok(second)
+        });
+
+        istream1.write_all(b"first").await?;
+        istream1.flush().await?;
+        assert_eq!(&first.await?, b"first");
+
+        istream2.write_all(b"second").await?;
+        istream2.flush().await?;
+        assert_eq!(&second.await?, b"second");
+
+        Ok(())
+    })
+}
@@ -0,0 +1,88 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{Frame, Handshake, Outbox, Packet, PriorityClass, Result};
+
+// ====================================== #[test] outbox() ====================================== \\
+
+#[test]
+fn outbox() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let proto = Handshake::initiate(&stream).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let proto = Handshake::respond(&stream).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let ((istream, mut iproto), (rstream, mut rproto)) =
+            future::try_zip(initiate, respond).await?;
+
+        let outbox = Outbox::new();
+        outbox.push(PriorityClass::Bulk, Packet::heartbeat());
+
+        let push = async {
+            for class in PriorityClass::ALL.iter().copied() {
+                future::yield_now().await;
+                assert!(outbox.push(class, Packet::heartbeat()));
+            }
+
+            outbox.close();
+        };
+
+        let (wrote, ()) = future::zip(iproto.drain_outbox(&istream, &outbox), push).await;
+        assert!(wrote? > 0);
+        assert!(outbox.is_empty());
+
+        for _ in 0..4 {
+            assert!(rproto.recv(&rstream).await?.is_heartbeat());
+        }
+
+        Ok(())
+    })
+}
+
+// ======================================= #[test] order() ====================================== \\
+
+#[test]
+fn order() {
+    let outbox = Outbox::new();
+    outbox.push(PriorityClass::Bulk, Packet::heartbeat());
+    outbox.push(PriorityClass::Realtime, Packet::heartbeat());
+    outbox.push_frame(
+        PriorityClass::Bulk,
+        Frame::new(Packet::heartbeat()).with_priority(1),
+    );
+    outbox.push(PriorityClass::Control, Packet::heartbeat());
+
+    assert_eq!(outbox.len(), 4);
+    assert_eq!(outbox.len_of(PriorityClass::Bulk), 2);
+
+    let priorities = core::iter::from_fn(|| outbox.pop())
+        .map(|frame| frame.priority())
+        .collect::<Vec<_>>();
+    assert_eq!(priorities, [7, 4, 0, 1]);
+
+    outbox.close();
+    assert!(outbox.is_closed());
+    assert!(!outbox.push(PriorityClass::Control, Packet::heartbeat()));
+}
@@ -0,0 +1,138 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_lite::future;
+use pr070c01::{Handshake, PacingPolicy, Packet, Result, Timer};
+use std::time::{Duration, Instant};
+
+// ============================================ Types =========================================== \\
+
+struct SmolTimer(smol::Timer);
+
+// ========================================= impl Timer ========================================= \\
+
+impl Timer for SmolTimer {
+    #[inline]
+    fn after(duration: Duration) -> Self {
+        SmolTimer(smol::Timer::after(duration))
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl Future for SmolTimer {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(ctx).map(|_| ())
+    }
+}
+
+// ======================================= #[test] fixed() ====================================== \\
+
+#[test]
+fn fixed() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+
+            let policy = PacingPolicy::bytes_per_sec(1000);
+            proto.set_pacing_policy(policy);
+            proto.set_timer::<SmolTimer>();
+            assert_eq!(proto.pacing_policy(), policy);
+            assert_eq!(proto.stats().pacing_rate(), Some(1000));
+
+            // Every frame is at least 18 bytes long (header + tag), so the last one can't be
+            // written before 9 * 18 / 1000 seconds.
+            let start = Instant::now();
+            for _ in 0..10 {
+                proto.send(&stream, Packet::heartbeat()).await?;
+            }
+
+            assert!(start.elapsed() >= Duration::from_millis(150));
+            assert!(proto.stats().goodput().is_some());
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+
+            for _ in 0..10 {
+                assert!(proto.recv(&stream).await?.is_heartbeat());
+            }
+
+            assert_eq!(proto.stats().pacing_rate(), None);
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// ======================================= #[test] auto() ======================================= \\
+
+#[test]
+fn auto() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+            proto.set_pacing_policy(PacingPolicy::Auto);
+            proto.set_timer::<SmolTimer>();
+
+            // No estimate yet, so nothing is paced.
+            assert_eq!(proto.stats().pacing_rate(), None);
+
+            for _ in 0..20 {
+                proto.send(&stream, Packet::heartbeat()).await?;
+                smol::Timer::after(Duration::from_millis(10)).await;
+            }
+
+            let stats = proto.stats();
+            let goodput = stats.goodput().unwrap();
+            assert!(goodput > 0);
+            assert!(stats.pacing_rate().unwrap() >= goodput);
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+
+            for _ in 0..20 {
+                assert!(proto.recv(&stream).await?.is_heartbeat());
+            }
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
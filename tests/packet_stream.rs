@@ -0,0 +1,57 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use futures_util::{SinkExt, StreamExt};
+use pr070c01::{Handshake, Packet, Result};
+
+// =================================== #[test] packet_stream() ================================== \\
+
+#[test]
+fn packet_stream() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let proto = Handshake::initiate(&stream).await?.done()?;
+
+            let mut packets = proto.into_packet_stream(stream);
+            for _ in 0..3 {
+                packets.feed(Packet::heartbeat()).await?;
+            }
+
+            packets.close().await?;
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let proto = Handshake::respond(&stream).await?.done()?;
+
+            let packets = proto.into_packet_stream(stream);
+            let packets = packets.collect::<Vec<_>>().await;
+
+            assert_eq!(packets.len(), 3);
+            for packet in packets {
+                assert!(packet?.is_heartbeat());
+            }
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
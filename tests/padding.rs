@@ -0,0 +1,72 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::{future, AsyncWriteExt};
+use pr070c01::packets::{MSG_MAX_LEN, NOISE_OVERHEAD};
+use pr070c01::{Handshake, Packet, PaddingPolicy, Result};
+
+// ==================================== #[test] padded_len() ==================================== \\
+
+#[test]
+fn padded_len() {
+    assert_eq!(PaddingPolicy::None.padded_len(10), 10);
+    assert_eq!(PaddingPolicy::PadToMultiple(64).padded_len(10), 64);
+    assert_eq!(PaddingPolicy::PadToMultiple(64).padded_len(62), 64);
+    assert_eq!(PaddingPolicy::PadToMultiple(64).padded_len(63), 128);
+    assert_eq!(PaddingPolicy::PadToFixed(256).padded_len(10), 256);
+    assert_eq!(PaddingPolicy::PadToFixed(256).padded_len(300), 302);
+    assert_eq!(
+        PaddingPolicy::PadToFixed(usize::MAX).padded_len(10),
+        MSG_MAX_LEN
+    );
+}
+
+// ====================================== #[test] padding() ===================================== \\
+
+#[test]
+fn padding() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let mut stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+            proto.set_padding_policy(PaddingPolicy::PadToFixed(256));
+            assert_eq!(proto.padding_policy(), PaddingPolicy::PadToFixed(256));
+
+            let mut engine = proto.into_engine();
+            let mut out = Vec::new();
+            let len = engine.write_packet(Packet::heartbeat(), &mut out)?;
+            assert_eq!(len, 2 + 256 + NOISE_OVERHEAD);
+
+            engine.write_packet(Packet::heartbeat(), &mut out)?;
+            stream.write_all(&out).await?;
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+            proto.set_padding_policy(PaddingPolicy::PadToMultiple(64));
+
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
@@ -0,0 +1,99 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+mod common;
+
+use async_net::{TcpListener, TcpStream};
+use common::keypair;
+use futures_lite::future;
+use pr070c01::{Config, Error, Handshake, Packet, Result};
+
+// ====================================== #[test] payload() ===================================== \\
+
+#[test]
+fn payload() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let config = Config::new()
+                .with_identity(keypair(1))
+                .with_packet(Packet::heartbeat())?
+                .with_verifier(|payload| payload == b"responder");
+            let handshake = Handshake::initiate_with(&stream, config).await?;
+
+            Result::Ok(handshake.remote_payload().to_vec())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let config = Config::new().with_payload(&b"responder"[..]);
+            let handshake = Handshake::respond_with(&stream, config).await?;
+
+            Result::Ok(handshake.remote_packet()?)
+        });
+
+        let (ipayload, rpacket) = future::try_zip(initiate, respond).await?;
+
+        assert_eq!(ipayload, b"responder");
+        assert!(rpacket.is_heartbeat());
+
+        Ok(())
+    })
+}
+
+// ================================= #[test] payload_rejected() ================================= \\
+
+#[test]
+fn payload_rejected() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let config = Config::new()
+                .with_identity(keypair(1))
+                .with_payload(&b"initiator"[..]);
+
+            Handshake::initiate_with(&stream, config).await.map(|_| ())
+        });
+
+        let (stream, _) = listener.accept().await?;
+        let config = Config::new().with_verifier(|payload| payload.is_empty());
+        let res = Handshake::respond_with(&stream, config).await;
+        assert!(matches!(res, Err(Error::PayloadRejected)));
+
+        drop(stream);
+        assert!(initiate.await.is_err());
+
+        Ok(())
+    })
+}
+
+// ================================= #[test] plaintext_payload() ================================ \\
+
+#[test]
+fn plaintext_payload() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        // Without an identity, the first handshake message wouldn't be encrypted.
+        let stream = TcpStream::connect(addr).await?;
+        let config = Config::new().with_payload(&b"initiator"[..]);
+        let res = Handshake::initiate_with(&stream, config).await;
+        assert!(matches!(res, Err(Error::PlaintextPayload)));
+
+        Ok(())
+    })
+}
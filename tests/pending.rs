@@ -0,0 +1,149 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_lite::future;
+use pr070c01::{Handshake, Packet, Result};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// ============================================ Types =========================================== \\
+
+struct Stalling {
+    bytes: Vec<u8>,
+    limit: usize,
+}
+
+// ======================================= impl AsyncRead ======================================= \\
+
+impl AsyncRead for Stalling {
+    #[inline]
+    fn poll_read(self: Pin<&mut Self>, _: &mut Context, _: &mut [u8]) -> Poll<io::Result<usize>> {
+        Poll::Pending
+    }
+}
+
+// ======================================= impl AsyncWrite ====================================== \\
+
+impl AsyncWrite for Stalling {
+    fn poll_write(self: Pin<&mut Self>, _: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let len = buf.len().min(this.limit - this.bytes.len());
+        if len == 0 {
+            return Poll::Pending;
+        }
+
+        this.bytes.extend_from_slice(&buf[..len]);
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+// =================================== #[test] partial_write() ================================== \\
+
+#[test]
+fn partial_write() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            Handshake::initiate(&stream).await?.done()
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            Handshake::respond(&stream).await?.done()
+        });
+
+        let (mut iproto, mut rproto) = future::try_zip(initiate, respond).await?;
+
+        let mut out = Stalling {
+            bytes: Vec::new(),
+            limit: 4,
+        };
+
+        let mut send = iproto.send(&mut out, Packet::heartbeat());
+        assert!(future::poll_once(&mut send).await.is_none());
+        drop(send);
+
+        let pending = iproto.pending_write_len();
+        assert!(pending > 0);
+
+        out.limit = usize::MAX;
+        let wrote = iproto.send(&mut out, Packet::heartbeat()).await?;
+        assert_eq!(iproto.pending_write_len(), 0);
+        assert_eq!(out.bytes.len(), 4 + pending + wrote);
+
+        let mut inp = &out.bytes[..];
+        assert!(rproto.recv(&mut inp).await?.is_heartbeat());
+        assert!(rproto.recv(&mut inp).await?.is_heartbeat());
+        assert!(inp.is_empty());
+
+        Ok(())
+    })
+}
+
+// ======================================= #[test] split() ====================================== \\
+
+#[test]
+fn split() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            Handshake::initiate(&stream).await?.done()
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            Handshake::respond(&stream).await?.done()
+        });
+
+        let (mut iproto, mut rproto) = future::try_zip(initiate, respond).await?;
+
+        let mut out = Stalling {
+            bytes: Vec::new(),
+            limit: 4,
+        };
+
+        let mut send = iproto.send(&mut out, Packet::heartbeat());
+        assert!(future::poll_once(&mut send).await.is_none());
+        drop(send);
+
+        // The rest of the dropped frame gets written before the owned send's.
+        let pending = iproto.pending_write_len();
+        out.limit = usize::MAX;
+
+        let (sender, receiver) = iproto.into_split(&mut out);
+        sender.send(Packet::heartbeat()).await?;
+        drop((sender, receiver));
+        assert!(out.bytes.len() > 4 + pending);
+
+        let mut inp = &out.bytes[..];
+        assert!(rproto.recv(&mut inp).await?.is_heartbeat());
+        assert!(rproto.recv(&mut inp).await?.is_heartbeat());
+        assert!(inp.is_empty());
+
+        Ok(())
+    })
+}
@@ -0,0 +1,54 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{Config, Handshake, HandshakePhase, Result};
+
+// ======================================= #[test] phase() ====================================== \\
+
+#[test]
+fn phase() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let client = TcpStream::connect(addr).await?;
+        let (server, _) = listener.accept().await?;
+
+        let mut initiate = Handshake::initiate(&client);
+        let mut respond = Handshake::respond_with(&server, Config::new().with_pow(1));
+        assert_eq!(initiate.phase(), HandshakePhase::Negotiating);
+        assert_eq!(respond.phase(), HandshakePhase::Negotiating);
+
+        assert!(future::poll_once(&mut initiate).await.is_none());
+        assert_eq!(initiate.phase(), HandshakePhase::AwaitingStatus);
+
+        for _ in 0..1000 {
+            if respond.phase() == HandshakePhase::AwaitingProof {
+                break;
+            }
+
+            assert!(future::poll_once(&mut respond).await.is_none());
+            future::yield_now().await;
+        }
+
+        assert_eq!(respond.phase(), HandshakePhase::AwaitingProof);
+
+        let (initiated, responded) = future::try_zip(&mut initiate, &mut respond).await?;
+        assert!(initiate.phase().is_done());
+        assert!(respond.phase().is_done());
+
+        initiated.done()?;
+        responded.done()?;
+
+        Ok(())
+    })
+}
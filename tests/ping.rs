@@ -0,0 +1,166 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use core::time::Duration;
+use futures_lite::future;
+use futures_util::StreamExt;
+use pr070c01::{Handshake, Packet, Result};
+use smol::Timer;
+
+// ======================================= #[test] ping() ======================================= \\
+
+#[test]
+fn ping() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+
+            let rtt = proto.ping(&stream).await?;
+            assert_eq!(proto.rtt(), Some(rtt));
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+
+            // The ping is handled by `recv`, which never completes as no packet is sent.
+            let recv = async {
+                proto.recv(&stream).await?;
+                Result::Ok(false)
+            };
+
+            let timeout = async {
+                Timer::after(Duration::from_millis(100)).await;
+                Result::Ok(true)
+            };
+
+            assert!(future::or(recv, timeout).await?);
+
+            // The pong is sent along with the next outgoing packet.
+            proto.drive_keepalive(&stream).await?;
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// =================================== #[test] simultaneous() =================================== \\
+
+#[test]
+fn simultaneous() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+
+            proto.ping(&stream).await?;
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+
+            proto.ping(&stream).await?;
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// ===================================== #[test] buffered() ===================================== \\
+
+#[test]
+fn buffered() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+
+            // The heartbeat is received before the pong, and kept for the next receive.
+            proto.ping(&stream).await?;
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+
+            proto.send(&stream, Packet::heartbeat()).await?;
+            proto.ping(&stream).await?;
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// =================================== #[test] packet_stream() ================================== \\
+
+#[test]
+fn packet_stream() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+
+            proto.ping(&stream).await?;
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let proto = Handshake::respond(&stream).await?.done()?;
+
+            // The ping gets answered while waiting for the heartbeat.
+            let mut packets = proto.into_packet_stream(stream);
+            assert!(packets.next().await.unwrap()?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
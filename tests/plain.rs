@@ -0,0 +1,97 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_lite::{future, AsyncRead, AsyncWrite};
+use pr070c01::{Error, Handshake, Packet, Result};
+use std::io;
+
+// ============================================ Types =========================================== \\
+
+struct Plain(TcpStream);
+
+// ======================================= impl AsyncRead ======================================= \\
+
+impl AsyncRead for Plain {
+    #[inline]
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_read(ctx, buf)
+    }
+}
+
+// ======================================= impl AsyncWrite ====================================== \\
+
+impl AsyncWrite for Plain {
+    #[inline]
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(ctx, buf)
+    }
+
+    #[inline]
+    fn poll_flush(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(ctx)
+    }
+
+    #[inline]
+    fn poll_close(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_close(ctx)
+    }
+}
+
+// ======================================= #[test] plain() ====================================== \\
+
+#[test]
+fn plain() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let mut stream = Plain(TcpStream::connect(addr).await?);
+            let mut proto = Handshake::initiate(&mut stream).await?.done()?;
+
+            let packets = vec![Packet::heartbeat(), Packet::heartbeat()];
+            proto.send_all(&mut stream, packets).await?;
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let mut stream = Plain(listener.accept().await?.0);
+            let mut proto = Handshake::respond(&mut stream).await?.done()?;
+
+            let mut packets = Vec::new();
+            while packets.len() < 2 {
+                proto.recv_many(&mut stream, &mut packets, 2).await?;
+            }
+
+            assert!(packets.iter().all(Packet::is_heartbeat));
+
+            let res = proto.recv(&mut stream).await;
+            assert!(matches!(res, Err(Error::PeerClosed)));
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
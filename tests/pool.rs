@@ -0,0 +1,56 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{BufferPool, Handshake, Packet, Result};
+
+// ======================================= #[test] pool() ======================================= \\
+
+#[test]
+fn pool() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let proto = Handshake::initiate(&stream).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let proto = Handshake::respond(&stream).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let ((istream, mut iproto), (rstream, mut rproto)) =
+            future::try_zip(initiate, respond).await?;
+
+        let pool = BufferPool::new(4);
+        iproto.set_buffer_pool(pool.clone());
+        rproto.set_buffer_pool(pool.clone());
+        assert_eq!(pool.idle(), 4);
+
+        iproto.send(&istream, Packet::heartbeat()).await?;
+        assert_eq!(pool.idle(), 2);
+        iproto.release_buffers();
+        assert_eq!(pool.idle(), 4);
+
+        assert!(rproto.recv(&rstream).await?.is_heartbeat());
+        rproto.release_buffers();
+        assert_eq!(pool.idle(), 4);
+
+        Ok(())
+    })
+}
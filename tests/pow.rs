@@ -0,0 +1,79 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{Config, Error, Handshake, Packet, Result};
+
+// ======================================== #[test] pow() ======================================= \\
+
+#[test]
+fn pow() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let proto = Handshake::initiate(&stream).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let config = Config::new().with_pow(8);
+            let proto = Handshake::respond_with(&stream, config).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let ((istream, mut iproto), (rstream, mut rproto)) =
+            future::try_zip(initiate, respond).await?;
+
+        iproto.send(&istream, Packet::heartbeat()).await?;
+        assert!(rproto.recv(&rstream).await?.is_heartbeat());
+
+        Ok(())
+    })
+}
+
+// =================================== #[test] pow_too_hard() =================================== \\
+
+#[test]
+fn pow_too_hard() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let config = Config::new().with_pow(32);
+
+            Handshake::respond_with(&stream, config).await.map(|_| ())
+        });
+
+        let stream = TcpStream::connect(addr).await?;
+        let config = Config::new().with_max_pow(16);
+        let res = Handshake::initiate_with(&stream, config).await;
+        assert!(matches!(
+            res,
+            Err(Error::PowDifficulty {
+                max: 16,
+                actual: 32
+            })
+        ));
+
+        drop(stream);
+        assert!(respond.await.is_err());
+
+        Ok(())
+    })
+}
@@ -0,0 +1,209 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_lite::future;
+use pr070c01::{Custom, Error, Handshake, Packet, RateLimit, Result, Timer};
+use std::time::{Duration, Instant};
+
+// ============================================ Types =========================================== \\
+
+struct SmolTimer(smol::Timer);
+
+// ========================================= impl Timer ========================================= \\
+
+impl Timer for SmolTimer {
+    #[inline]
+    fn after(duration: Duration) -> Self {
+        SmolTimer(smol::Timer::after(duration))
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl Future for SmolTimer {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(ctx).map(|_| ())
+    }
+}
+
+// ==================================== #[test] send_limit() ==================================== \\
+
+#[test]
+fn send_limit() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+
+            let limit = RateLimit::packets_per_sec(10);
+            proto.set_send_rate_limit(limit);
+            proto.set_timer::<SmolTimer>();
+            assert_eq!(proto.send_rate_limit(), limit);
+
+            let start = Instant::now();
+            for _ in 0..12 {
+                proto.send(&stream, Packet::heartbeat()).await?;
+            }
+
+            assert!(start.elapsed() >= Duration::from_millis(150));
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+
+            for _ in 0..12 {
+                assert!(proto.recv(&stream).await?.is_heartbeat());
+            }
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// ==================================== #[test] recv_limit() ==================================== \\
+
+#[test]
+fn recv_limit() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+
+            proto.send(&stream, Packet::heartbeat()).await?;
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+            proto.set_recv_rate_limit(RateLimit::packets_per_sec(1).with_bytes_per_sec(1024));
+
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            let res = proto.recv(&stream).await;
+            assert!(matches!(res, Err(Error::RateExceeded)));
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// ====================================== #[test] batches() ===================================== \\
+
+#[test]
+fn batches() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+            proto.set_send_rate_limit(RateLimit::packets_per_sec(10));
+
+            // A batch can overdraw the limit, but the next send waits until it's paid back.
+            let start = Instant::now();
+            let packets = (0..12).map(|_| Packet::heartbeat());
+            proto.send_all(&stream, packets).await?;
+
+            let res = proto.send(&stream, Packet::heartbeat()).await;
+            assert!(matches!(res, Err(Error::MissingTimer)));
+
+            proto.set_timer::<SmolTimer>();
+            let send = proto.send_all(&stream, Some(Packet::heartbeat()));
+            assert!(future::poll_once(send).await.is_none());
+
+            let (sender, _receiver) = proto.into_split(stream);
+            sender.send(Packet::heartbeat()).await?;
+            assert!(start.elapsed() >= Duration::from_millis(250));
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+
+            for _ in 0..13 {
+                assert!(proto.recv(&stream).await?.is_heartbeat());
+            }
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// ===================================== #[test] controls() ===================================== \\
+
+#[test]
+fn controls() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+
+            let custom = Custom::new(Custom::RESERVED, &b"custom"[..])?;
+            proto.send_custom(&stream, &custom).await?;
+            proto.send_custom(&stream, &custom).await?;
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+            proto.set_recv_rate_limit(RateLimit::packets_per_sec(1));
+
+            assert_eq!(proto.recv_custom(&stream).await?.payload(), b"custom");
+
+            let res = proto.recv_custom(&stream).await;
+            assert!(matches!(res, Err(Error::RateExceeded)));
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
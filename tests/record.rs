@@ -0,0 +1,61 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{Direction, Handshake, Packet, Record, RecordedIo, ReplayIo, Result};
+
+// ====================================== #[test] record() ====================================== \\
+
+#[test]
+fn record() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut io = RecordedIo::new(stream, Vec::new());
+            let mut proto = Handshake::respond(&mut io).await?.done()?;
+            assert!(proto.recv(&mut io).await?.is_heartbeat());
+
+            Result::Ok(io.into_inner().1)
+        });
+
+        let ((), recording) = future::try_zip(initiate, respond).await?;
+
+        let records = Record::read_all(&recording[..])?;
+        assert!(records.iter().any(|r| r.direction() == Direction::Read));
+        assert!(records.iter().any(|r| r.direction() == Direction::Write));
+        assert!(records.windows(2).all(|w| w[0].elapsed() <= w[1].elapsed()));
+
+        let written = records
+            .iter()
+            .filter(|r| r.direction() == Direction::Write)
+            .map(Record::data)
+            .collect::<Vec<_>>()
+            .concat();
+
+        let mut replay = ReplayIo::new(&recording[..])?;
+        Handshake::respond(&mut replay).await?.done()?;
+        assert!(replay.remaining() > 0);
+        assert_eq!(replay.written().len(), written.len());
+
+        Ok(())
+    })
+}
@@ -0,0 +1,128 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use pr070c01::{Error, ReliableChannel, ReliableConfig, Result};
+use std::thread;
+use std::time::Duration;
+
+// ===================================== #[test] reliable() ===================================== \\
+
+#[test]
+fn reliable() -> Result<()> {
+    let config = ReliableConfig::new()
+        .with_window(4)
+        .with_rto(Duration::from_millis(10));
+
+    let mut sender = ReliableChannel::new(config);
+    let mut receiver = ReliableChannel::new(config);
+    receiver.configure(2, config.with_ordered(false));
+
+    for idx in 0..16u8 {
+        sender.send(1, vec![idx]);
+    }
+
+    sender.send(2, vec![42]);
+
+    // Every third frame from the sender gets lost.
+    let mut frames = 0;
+    let mut buf = Vec::new();
+    let mut received = Vec::new();
+    while !sender.is_idle() {
+        while sender.transmit(&mut buf)? {
+            frames += 1;
+            if frames % 3 != 0 {
+                receiver.handle(&buf)?;
+            }
+        }
+
+        while receiver.transmit(&mut buf)? {
+            sender.handle(&buf)?;
+        }
+
+        while let Some((stream, payload)) = receiver.recv() {
+            received.push((stream, payload[0]));
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let ordered = received
+        .iter()
+        .filter(|(stream, _)| *stream == 1)
+        .map(|(_, idx)| *idx)
+        .collect::<Vec<_>>();
+    assert_eq!(ordered, (0..16).collect::<Vec<_>>());
+    assert!(received.contains(&(2, 42)));
+    assert!(!receiver.config(2).is_ordered());
+
+    Ok(())
+}
+
+// ==================================== #[test] unreachable() =================================== \\
+
+#[test]
+fn unreachable() -> Result<()> {
+    let config = ReliableConfig::new()
+        .with_rto(Duration::from_millis(1))
+        .with_max_retries(2);
+
+    let mut sender = ReliableChannel::new(config);
+    sender.send(0, vec![0]);
+
+    let mut buf = Vec::new();
+    let res = loop {
+        match sender.transmit(&mut buf) {
+            Ok(_) => thread::sleep(Duration::from_millis(5)),
+            Err(err) => break err,
+        }
+    };
+
+    assert!(matches!(res, Error::PeerTimeout));
+    assert_eq!(sender.in_flight(), 1);
+
+    Ok(())
+}
+
+// ====================================== #[test] limits() ====================================== \\
+
+#[test]
+fn limits() -> Result<()> {
+    let config = ReliableConfig::new().with_window(2).with_max_streams(1);
+    let mut receiver = ReliableChannel::new(config);
+
+    // Messages too far ahead of the ones received in order are dropped.
+    receiver.handle(&data(0, 2, 2))?;
+    receiver.handle(&data(0, u64::MAX, 3))?;
+    receiver.handle(&data(0, 1, 1))?;
+    receiver.handle(&data(0, 0, 0))?;
+
+    // So are those opening more streams than allowed.
+    receiver.handle(&data(1, 0, 4))?;
+
+    let mut received = Vec::new();
+    while let Some((stream, payload)) = receiver.recv() {
+        received.push((stream, payload[0]));
+    }
+
+    assert_eq!(received, vec![(0, 0), (0, 1)]);
+
+    Ok(())
+}
+
+// =========================================== data() =========================================== \\
+
+fn data(stream: u32, seq: u64, payload: u8) -> Vec<u8> {
+    let mut frame = vec![0];
+    frame.extend_from_slice(&stream.to_le_bytes());
+    frame.extend_from_slice(&seq.to_le_bytes());
+    frame.push(payload);
+
+    frame
+}
@@ -0,0 +1,132 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+mod common;
+
+use async_net::{TcpListener, TcpStream};
+use common::keypair;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use pr070c01::{Config, Handshake, Listener, Packet, Result, Timer};
+use std::io;
+use std::net::SocketAddr;
+
+// ============================================ Types =========================================== \\
+
+struct SmolTimer(smol::Timer);
+
+struct SmolListener {
+    listener: TcpListener,
+    accept: Option<Pin<Box<dyn Future<Output = io::Result<(TcpStream, SocketAddr)>> + Send>>>,
+}
+
+// ========================================= impl Timer ========================================= \\
+
+impl Timer for SmolTimer {
+    #[inline]
+    fn after(duration: Duration) -> Self {
+        SmolTimer(smol::Timer::after(duration))
+    }
+}
+
+// ======================================== impl Listener ======================================= \\
+
+impl Listener for SmolListener {
+    type Io = TcpStream;
+
+    fn poll_accept(&mut self, ctx: &mut Context) -> Poll<io::Result<TcpStream>> {
+        let listener = &self.listener;
+        let accept = self.accept.get_or_insert_with(|| {
+            let listener = listener.clone();
+            Box::pin(async move { listener.accept().await })
+        });
+
+        match accept.as_mut().poll(ctx) {
+            Poll::Ready(res) => {
+                self.accept = None;
+                Poll::Ready(res.map(|(stream, _)| stream))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl Future for SmolTimer {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(ctx).map(|_| ())
+    }
+}
+
+// ======================================= #[test] serve() ====================================== \\
+
+#[test]
+fn serve() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let (tx, rx) = smol::channel::unbounded();
+        let listener = SmolListener {
+            listener,
+            accept: None,
+        };
+
+        let config = Config::new().with_identity(keypair(0)).with_pow(4);
+        let serve = pr070c01::serve::<_, _, SmolTimer>(listener, config, move |id, proto, io| {
+            tx.try_send((id, proto, io)).unwrap();
+        })
+        .with_max_in_flight(2)
+        .with_timeout(Duration::from_secs(5));
+        let serve = smol::spawn(serve);
+
+        let anonymous = TcpStream::connect(addr).await?;
+        Handshake::initiate(&anonymous).await?;
+        drop(anonymous);
+
+        let mut clients = Vec::new();
+        for seed in 1..=3 {
+            let config = Config::new().with_identity(keypair(seed));
+            clients.push(smol::spawn(async move {
+                let stream = TcpStream::connect(addr).await?;
+                let mut proto = Handshake::initiate_with(&stream, config).await?.done()?;
+                proto.send(&stream, Packet::heartbeat()).await?;
+
+                Result::Ok(stream)
+            }));
+        }
+
+        let mut ids = Vec::new();
+        for _ in 1..=3 {
+            let (id, mut proto, io) = rx.recv().await.unwrap();
+            assert!(proto.recv(&io).await?.is_heartbeat());
+
+            ids.push(id);
+        }
+
+        for seed in 1..=3 {
+            assert!(ids.contains(&keypair(seed).public));
+        }
+
+        for client in clients {
+            client.await?;
+        }
+
+        serve.cancel().await;
+
+        Ok(())
+    })
+}
@@ -0,0 +1,135 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use futures_lite::{future, AsyncReadExt, AsyncWriteExt};
+use pr070c01::{Config, Connector, Error, Handshake, Packet, Result, SocksAddr, Timer};
+
+// ============================================ Types =========================================== \\
+
+struct SmolTimer(smol::Timer);
+
+// ========================================= impl Timer ========================================= \\
+
+impl Timer for SmolTimer {
+    #[inline]
+    fn after(duration: Duration) -> Self {
+        SmolTimer(smol::Timer::after(duration))
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl Future for SmolTimer {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(ctx).map(|_| ())
+    }
+}
+
+// ======================================= #[test] socks() ====================================== \\
+
+#[test]
+fn socks() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let proxy = smol::spawn(async move {
+            let (mut stream, _) = listener.accept().await?;
+
+            let mut greeting = [0; 3];
+            stream.read_exact(&mut greeting).await?;
+            assert_eq!(greeting, [5, 1, 0]);
+            stream.write_all(&[5, 0]).await?;
+
+            let mut request = [0; 5];
+            stream.read_exact(&mut request).await?;
+            assert_eq!(request, [5, 1, 0, 3, 10]);
+
+            let mut domain = [0; 10 + 2];
+            stream.read_exact(&mut domain).await?;
+            assert_eq!(&domain[..10], b"peer.onion");
+            assert_eq!(&domain[10..], &9999u16.to_be_bytes());
+
+            stream.write_all(&[5, 0, 0, 3, 5]).await?;
+            stream.write_all(b"proxy\x00\x00").await?;
+
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        let connector = Connector::new(Config::new()).via_socks5(("peer.onion", 9999));
+        assert_eq!(
+            connector.socks5(),
+            Some(&SocksAddr::domain("peer.onion", 9999))
+        );
+
+        let initiate = async {
+            let (stream, mut proto) = connector
+                .connect::<_, _, _, SmolTimer>(|| TcpStream::connect(addr))
+                .await?;
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            Result::Ok(())
+        };
+
+        future::try_zip(initiate, proxy).await?;
+
+        Ok(())
+    })
+}
+
+// =================================== #[test] socks_refused() ================================== \\
+
+#[test]
+fn socks_refused() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let proxy = smol::spawn(async move {
+            let (mut stream, _) = listener.accept().await?;
+
+            let mut greeting = [0; 3];
+            stream.read_exact(&mut greeting).await?;
+            stream.write_all(&[5, 0]).await?;
+
+            let mut request = [0; 4 + 4 + 2];
+            stream.read_exact(&mut request).await?;
+            assert_eq!(request[..4], [5, 1, 0, 1]);
+
+            stream.write_all(&[5, 5, 0, 1, 0, 0, 0, 0, 0, 0]).await?;
+
+            Result::Ok(())
+        });
+
+        let connector = Connector::new(Config::new())
+            .with_attempts(1)
+            .via_socks5(addr);
+
+        let res = connector
+            .connect::<_, _, _, SmolTimer>(|| TcpStream::connect(addr))
+            .await;
+        assert!(matches!(res, Err(Error::Socks(5))));
+
+        proxy.await?;
+
+        Ok(())
+    })
+}
@@ -0,0 +1,67 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{Handshake, Packet, Result};
+
+// ======================================= #[test] split() ====================================== \\
+
+#[test]
+fn split() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let proto = Handshake::initiate(&stream).await?.done()?;
+            let (sender, receiver) = proto.into_split(stream);
+
+            let send = smol::spawn(async move {
+                for _ in 0..16 {
+                    sender.send(Packet::heartbeat()).await?;
+                }
+
+                Result::Ok(())
+            });
+
+            for _ in 0..16 {
+                assert!(receiver.recv().await?.is_heartbeat());
+            }
+
+            send.await
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let proto = Handshake::respond(&stream).await?.done()?;
+            let (sender, receiver) = proto.into_split(stream);
+
+            let recv = smol::spawn(async move {
+                for _ in 0..16 {
+                    assert!(receiver.recv().await?.is_heartbeat());
+                }
+
+                Result::Ok(())
+            });
+
+            for _ in 0..16 {
+                sender.send(Packet::heartbeat()).await?;
+            }
+
+            recv.await
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
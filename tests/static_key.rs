@@ -0,0 +1,100 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+mod common;
+
+use async_net::{TcpListener, TcpStream};
+use common::keypair;
+use futures_lite::future;
+use pr070c01::{Config, Handshake, Packet, Result, StaticKey};
+
+// ==================================== #[test] static_key() ==================================== \\
+
+#[test]
+fn static_key() -> Result<()> {
+    let keypair = keypair(1);
+    let key = StaticKey::from_identity(&keypair);
+    assert_eq!(key.public(), &StaticKey::remote(&keypair.public)?);
+    assert_ne!(key.public(), &StaticKey::remote(&self::keypair(2).public)?);
+
+    Ok(())
+}
+
+// ===================================== #[test] targeted() ===================================== \\
+
+#[test]
+fn targeted() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let ikeypair = keypair(1);
+        let rkeypair = keypair(2);
+        let ipublic = ikeypair.public;
+        let rpublic = rkeypair.public;
+
+        let config = Config::new()
+            .with_identity(ikeypair)
+            .with_remote_identity(rpublic);
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate_with(&stream, config).await?.done()?;
+            assert_eq!(proto.remote_identity(), Some(&rpublic));
+
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            Result::Ok(())
+        });
+
+        let config = Config::new().with_identity(rkeypair);
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond_with(&stream, config).await?.done()?;
+            assert_eq!(proto.remote_identity(), Some(&ipublic));
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// =================================== #[test] wrong_target() =================================== \\
+
+#[test]
+fn wrong_target() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let config = Config::new()
+            .with_identity(keypair(1))
+            .with_remote_identity(keypair(3).public);
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            Handshake::initiate_with(&stream, config).await.map(drop)
+        });
+
+        let config = Config::new().with_identity(keypair(2));
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            Handshake::respond_with(&stream, config).await.map(drop)
+        });
+
+        let (ires, rres) = future::zip(initiate, respond).await;
+        assert!(ires.is_err());
+        assert!(rres.is_err());
+
+        Ok(())
+    })
+}
@@ -0,0 +1,170 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use futures_lite::future;
+use pr070c01::{Custom, Handshake, Packet, RateLimit, Result, Timer};
+
+// ============================================ Types =========================================== \\
+
+struct SmolTimer(smol::Timer);
+
+// ========================================= impl Timer ========================================= \\
+
+impl Timer for SmolTimer {
+    #[inline]
+    fn after(duration: Duration) -> Self {
+        SmolTimer(smol::Timer::after(duration))
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl Future for SmolTimer {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(ctx).map(|_| ())
+    }
+}
+
+// ======================================= #[test] stats() ====================================== \\
+
+#[test]
+fn stats() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+
+            let stats = proto.stats();
+            assert_eq!(stats.packets_sent(), 0);
+            assert_eq!(stats.rekeys(), 0);
+
+            proto.send(&stream, Packet::heartbeat()).await?;
+            proto.rekey_send(&stream).await?;
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            let stats = proto.stats();
+            assert_eq!(stats.packets_sent(), 2);
+            assert_eq!(stats.packets_received(), 0);
+            assert_eq!(stats.rekeys(), 1);
+            assert!(stats.last_activity() >= stats.handshake_completed());
+
+            Result::Ok(stats.bytes_sent())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            let stats = proto.stats();
+            assert_eq!(stats.packets_received(), 2);
+            assert_eq!(stats.packets_sent(), 0);
+            assert_eq!(stats.rekeys(), 1);
+
+            Result::Ok(stats.bytes_received())
+        });
+
+        let (sent, received) = future::try_zip(initiate, respond).await?;
+        assert_eq!(sent, received);
+
+        Ok(())
+    })
+}
+
+// =================================== #[test] rate_limited() =================================== \\
+
+#[test]
+fn rate_limited() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+            proto.set_send_rate_limit(RateLimit::packets_per_sec(1));
+            proto.set_timer::<SmolTimer>();
+
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            // Packets only count once written, not when encoded.
+            let send = proto.send(&stream, Packet::heartbeat());
+            assert!(future::poll_once(send).await.is_none());
+            assert_eq!(proto.stats().packets_sent(), 1);
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// ====================================== #[test] custom() ====================================== \\
+
+#[test]
+fn custom() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+
+            let custom = Custom::new(Custom::RESERVED, &b"custom"[..])?;
+            proto.send_custom(&stream, &custom).await?;
+            proto.send_large(&stream, &[0; 4096]).await?;
+            assert_eq!(proto.stats().packets_sent(), 2);
+
+            Result::Ok(proto.stats().bytes_sent())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+
+            // Custom messages and fragments carry data, unlike the other control messages.
+            assert_eq!(proto.recv_custom(&stream).await?.payload(), b"custom");
+            assert_eq!(proto.recv_large(&stream).await?.len(), 4096);
+            assert_eq!(proto.stats().packets_received(), 2);
+
+            Result::Ok(proto.stats().bytes_received())
+        });
+
+        let (sent, received) = future::try_zip(initiate, respond).await?;
+        assert_eq!(sent, received);
+
+        Ok(())
+    })
+}
@@ -0,0 +1,58 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use futures_lite::io::{AsyncReadExt, AsyncWriteExt};
+use pr070c01::packets::MSG_MAX_LEN;
+use pr070c01::{Handshake, Result};
+
+// ====================================== #[test] stream() ====================================== \\
+
+#[test]
+fn stream() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let data = (0..MSG_MAX_LEN * 3 + 7)
+            .map(|i| i as u8)
+            .collect::<Vec<_>>();
+        let expected = data.clone();
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let proto = Handshake::initiate(&stream).await?.done()?;
+
+            let mut stream = proto.into_stream(stream);
+            stream.write_all(&data).await?;
+            stream.flush().await?;
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let proto = Handshake::respond(&stream).await?.done()?;
+
+            let mut stream = proto.into_stream(stream);
+            let mut data = vec![0; expected.len()];
+            stream.read_exact(&mut data).await?;
+
+            assert_eq!(data, expected);
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
@@ -0,0 +1,152 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{Config, Error, Handshake, HandshakeParams, Result, Suite};
+
+// ======================================= #[test] suite() ====================================== \\
+
+#[test]
+fn suite() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let config = Config::new().with_suites([Suite::AesGcmSha256]);
+            let (_, info) = Handshake::initiate_with(&stream, config)
+                .await?
+                .done_with_info()?;
+
+            Result::Ok(info)
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let handshake = Handshake::respond(&stream).await?;
+            assert_eq!(handshake.suite(), Suite::AesGcmSha256);
+
+            Result::Ok(handshake.done_with_info()?.1)
+        });
+
+        let (iinfo, rinfo) = future::try_zip(initiate, respond).await?;
+
+        assert_eq!(iinfo.handshake_hash(), rinfo.handshake_hash());
+        assert_eq!(iinfo.pattern(), Suite::AesGcmSha256.pattern());
+        assert_eq!(rinfo.pattern(), Suite::AesGcmSha256.pattern());
+
+        Ok(())
+    })
+}
+
+// ==================================== #[test] negotiated() ==================================== \\
+
+#[test]
+fn negotiated() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        // The responder picks the first suite of the offer that it accepts, regardless of its own
+        // order.
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let config = Config::new().with_suites([Suite::AesGcmBlake2b, Suite::ChaChaPolySha256]);
+            let handshake = Handshake::initiate_with(&stream, config).await?;
+
+            Result::Ok(handshake.suite())
+        });
+
+        let (stream, _) = listener.accept().await?;
+        let config = Config::new().with_suites([Suite::AesGcmSha256, Suite::ChaChaPolySha256]);
+        let handshake = Handshake::respond_with(&stream, config).await?;
+        assert_eq!(handshake.suite(), Suite::ChaChaPolySha256);
+        assert_eq!(initiate.await?, Suite::ChaChaPolySha256);
+
+        Ok(())
+    })
+}
+
+// ================================= #[test] unsupported_suite() ================================ \\
+
+#[test]
+fn unsupported_suite() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let config = Config::new().with_suites([Suite::AesGcmBlake2b]);
+
+            Handshake::initiate_with(&stream, config).await.map(|_| ())
+        });
+
+        let (stream, _) = listener.accept().await?;
+        let config = Config::new().with_suites([Suite::ChaChaPolyBlake2b]);
+        let res = Handshake::respond_with(&stream, config).await;
+        assert!(matches!(res, Err(Error::UnsupportedSuite(id)) if id == Suite::AesGcmBlake2b.id()));
+
+        drop(stream);
+        assert!(initiate.await.is_err());
+
+        Ok(())
+    })
+}
+
+// ====================================== #[test] params() ====================================== \\
+
+#[test]
+fn params() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let params = HandshakeParams::new();
+        assert_eq!(
+            params.get(Suite::AesGcmSha256).name,
+            Suite::AesGcmSha256.pattern()
+        );
+
+        let config = Config::new().with_params(params);
+        let iconfig = config.clone();
+        let initiate = smol::spawn(async move {
+            for suite in Suite::ALL.iter().copied() {
+                let stream = TcpStream::connect(addr).await?;
+                let config = iconfig.clone().with_suites([suite]);
+                Handshake::initiate_with(&stream, config).await?.done()?;
+            }
+
+            Result::Ok(())
+        });
+
+        for suite in Suite::ALL.iter().copied() {
+            let (stream, _) = listener.accept().await?;
+            let handshake = Handshake::respond_with(&stream, config.clone()).await?;
+            assert_eq!(handshake.suite(), suite);
+        }
+
+        initiate.await?;
+
+        Ok(())
+    })
+}
+
+// =================================== #[test] suite_params() =================================== \\
+
+#[test]
+fn suite_params() {
+    for suite in Suite::ALL.iter().copied() {
+        assert_eq!(suite.params().name, suite.pattern());
+        assert_eq!(suite.params().name, HandshakeParams::new().get(suite).name);
+    }
+}
@@ -0,0 +1,102 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use futures_lite::future;
+use pr070c01::{Error, Handshake, Result, Timer};
+
+// ============================================ Types =========================================== \\
+
+struct SmolTimer(smol::Timer);
+
+// ========================================= impl Timer ========================================= \\
+
+impl Timer for SmolTimer {
+    #[inline]
+    fn after(duration: Duration) -> Self {
+        SmolTimer(smol::Timer::after(duration))
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl Future for SmolTimer {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(ctx).map(|_| ())
+    }
+}
+
+// ================================= #[test] handshake_timeout() ================================ \\
+
+#[test]
+fn handshake_timeout() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let stream = TcpStream::connect(addr).await?;
+        let (_stalled, _) = listener.accept().await?;
+
+        let res = Handshake::initiate(&stream)
+            .timeout::<SmolTimer>(Duration::from_millis(50))
+            .await;
+        assert!(matches!(res, Err(Error::Timeout)));
+
+        Ok(())
+    })
+}
+
+// =================================== #[test] recv_timeout() =================================== \\
+
+#[test]
+fn recv_timeout() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let proto = Handshake::initiate(&stream)
+                .timeout::<SmolTimer>(Duration::from_secs(5))
+                .await?
+                .done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let proto = Handshake::respond(&stream)
+                .timeout::<SmolTimer>(Duration::from_secs(5))
+                .await?
+                .done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let ((_istream, _iproto), (rstream, mut rproto)) =
+            future::try_zip(initiate, respond).await?;
+
+        let res = rproto
+            .recv(&rstream)
+            .timeout::<SmolTimer>(Duration::from_millis(50))
+            .await;
+        assert!(matches!(res, Err(Error::Timeout)));
+
+        Ok(())
+    })
+}
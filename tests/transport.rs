@@ -0,0 +1,135 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+#[cfg(feature = "tcp")]
+mod common;
+
+use async_net::{TcpListener, TcpStream};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use futures_lite::future;
+use pr070c01::{Config, Connector, Handshake, Packet, Result, Timer, Transport};
+use std::io;
+use std::net::SocketAddr;
+
+// ============================================ Types =========================================== \\
+
+struct SmolTimer(smol::Timer);
+
+struct Flaky {
+    addr: SocketAddr,
+    dials: usize,
+}
+
+// ========================================= impl Timer ========================================= \\
+
+impl Timer for SmolTimer {
+    #[inline]
+    fn after(duration: Duration) -> Self {
+        SmolTimer(smol::Timer::after(duration))
+    }
+}
+
+// ======================================= impl Transport ======================================= \\
+
+impl Transport for Flaky {
+    type Io = TcpStream;
+    type Dial = Pin<Box<dyn Future<Output = io::Result<TcpStream>> + Send>>;
+
+    fn dial(&mut self) -> Self::Dial {
+        self.dials += 1;
+        if self.dials < 2 {
+            return Box::pin(async { Err(io::ErrorKind::ConnectionRefused.into()) });
+        }
+
+        Box::pin(TcpStream::connect(self.addr))
+    }
+}
+
+// ========================================= impl Future ======================================== \\
+
+impl Future for SmolTimer {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(ctx).map(|_| ())
+    }
+}
+
+// ===================================== #[test] transport() ==================================== \\
+
+#[test]
+fn transport() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        let connector = Connector::new(Config::new())
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(1));
+
+        let initiate = async {
+            let connect = connector.connect_via::<_, SmolTimer>(Flaky { addr, dials: 0 });
+            let (stream, mut proto) = connect.await?;
+            proto.send(&stream, Packet::heartbeat()).await?;
+
+            Result::Ok(())
+        };
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// ======================================== #[test] tcp() ======================================= \\
+
+#[cfg(feature = "tcp")]
+#[test]
+fn tcp() -> Result<()> {
+    use common::keypair;
+    use pr070c01::{Tcp, TcpIncoming};
+
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let (tx, rx) = smol::channel::unbounded();
+        let incoming = TcpIncoming::new(listener);
+        let config = Config::new().with_identity(keypair(0));
+        let serve = pr070c01::serve::<_, _, SmolTimer>(incoming, config, move |_, proto, io| {
+            tx.try_send((proto, io)).unwrap();
+        });
+        let serve = smol::spawn(serve);
+
+        let connector = Connector::new(Config::new().with_identity(keypair(1)));
+        let (stream, mut proto) = connector
+            .connect_via::<_, SmolTimer>(Tcp::new(addr))
+            .await?;
+        proto.send(&stream, Packet::heartbeat()).await?;
+
+        let (mut proto, io) = rx.recv().await.unwrap();
+        assert!(proto.recv(&io).await?.is_heartbeat());
+
+        serve.cancel().await;
+
+        Ok(())
+    })
+}
@@ -0,0 +1,111 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{Custom, Error, Handshake, Result, TypedPacket};
+
+// ============================================ Types =========================================== \\
+
+#[derive(Debug, Eq, PartialEq)]
+struct Request(u32);
+
+#[derive(Debug, Eq, PartialEq)]
+struct Response(String);
+
+struct Reserved;
+
+// ====================================== impl TypedPacket ====================================== \\
+
+impl TypedPacket for Request {
+    const ID: u16 = Custom::RESERVED;
+
+    fn encode_payload(&self, payload: &mut Vec<u8>) {
+        payload.extend_from_slice(&self.0.to_be_bytes());
+    }
+
+    fn decode_payload(payload: &[u8]) -> Result<Self> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(&payload[..4]);
+
+        Ok(Request(u32::from_be_bytes(bytes)))
+    }
+}
+
+impl TypedPacket for Response {
+    const ID: u16 = Custom::RESERVED + 1;
+
+    fn encode_payload(&self, payload: &mut Vec<u8>) {
+        payload.extend_from_slice(self.0.as_bytes());
+    }
+
+    fn decode_payload(payload: &[u8]) -> Result<Self> {
+        Ok(Response(String::from_utf8_lossy(payload).into_owned()))
+    }
+}
+
+impl TypedPacket for Reserved {
+    const ID: u16 = 0;
+
+    fn encode_payload(&self, _: &mut Vec<u8>) {}
+
+    fn decode_payload(_: &[u8]) -> Result<Self> {
+        Ok(Reserved)
+    }
+}
+
+// ======================================= #[test] typed() ====================================== \\
+
+#[test]
+fn typed() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+
+            proto.send_packet(&stream, &Request(42)).await?;
+            let response = proto.recv_as::<Response, _>(&stream).await?;
+            assert_eq!(response, Response("42".into()));
+
+            proto.send_packet(&stream, &Request(0)).await?;
+
+            assert!(matches!(
+                proto.send_packet(&stream, &Reserved).await,
+                Err(Error::ReservedPacketId(0))
+            ));
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+
+            let Request(n) = proto.recv_as(&stream).await?;
+            proto.send_packet(&stream, &Response(n.to_string())).await?;
+
+            let res = proto.recv_as::<Response, _>(&stream).await;
+            assert!(matches!(
+                res,
+                Err(Error::PacketIdMismatch { expected, actual })
+                    if expected == Custom::RESERVED + 1 && actual == Custom::RESERVED
+            ));
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
@@ -0,0 +1,106 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+#![cfg(all(feature = "unix", unix))]
+
+// =========================================== Imports ========================================== \\
+
+mod common;
+
+use async_net::unix::UnixListener;
+use common::keypair;
+use core::time::Duration;
+use futures_lite::future;
+use pr070c01::{Config, Connector, Handshake, Packet, Result, Timer};
+use pr070c01::{Unix, UnixIncoming};
+use std::fs;
+
+// ============================================ Types =========================================== \\
+
+struct SmolTimer(smol::Timer);
+
+// ========================================= impl Timer ========================================= \\
+
+impl Timer for SmolTimer {
+    #[inline]
+    fn after(duration: Duration) -> Self {
+        SmolTimer(smol::Timer::after(duration))
+    }
+}
+
+// ==================================== #[test] socket_pair() =================================== \\
+
+#[test]
+fn socket_pair() -> Result<()> {
+    smol::block_on(async {
+        let (istream, rstream) = pr070c01::socket_pair()?;
+
+        let initiate = smol::spawn(async move {
+            let config = Config::new().with_identity(keypair(0));
+            let mut proto = Handshake::initiate_with(&istream, config).await?.done()?;
+
+            proto.send(&istream, Packet::heartbeat()).await?;
+            assert!(proto.recv(&istream).await?.is_heartbeat());
+
+            Result::Ok(proto.remote_identity().copied())
+        });
+
+        let respond = smol::spawn(async move {
+            let config = Config::new().with_identity(keypair(1));
+            let mut proto = Handshake::respond_with(&rstream, config).await?.done()?;
+
+            assert!(proto.recv(&rstream).await?.is_heartbeat());
+            proto.send(&rstream, Packet::heartbeat()).await?;
+
+            Result::Ok(proto.remote_identity().copied())
+        });
+
+        let (ridentity, iidentity) = future::try_zip(initiate, respond).await?;
+        assert_eq!(ridentity, Some(keypair(1).public));
+        assert_eq!(iidentity, Some(keypair(0).public));
+
+        Ok(())
+    })
+}
+
+// ======================================= #[test] unix() ======================================= \\
+
+#[test]
+fn unix() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("pr070c01-{}.sock", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    smol::block_on(async {
+        let listener = UnixListener::bind(&path)?;
+
+        let (tx, rx) = smol::channel::unbounded();
+        let incoming = UnixIncoming::new(listener);
+        let config = Config::new().with_identity(keypair(0));
+        let serve = pr070c01::serve::<_, _, SmolTimer>(incoming, config, move |_, proto, io| {
+            tx.try_send((proto, io)).unwrap();
+        });
+        let serve = smol::spawn(serve);
+
+        let connector = Connector::new(Config::new().with_identity(keypair(1)));
+        let (stream, mut proto) = connector
+            .connect_via::<_, SmolTimer>(Unix::new(&path))
+            .await?;
+        proto.send(&stream, Packet::heartbeat()).await?;
+
+        let (mut proto, io) = rx.recv().await.unwrap();
+        assert!(proto.recv(&io).await?.is_heartbeat());
+
+        serve.cancel().await;
+
+        Result::Ok(())
+    })?;
+
+    fs::remove_file(&path)?;
+
+    Ok(())
+}
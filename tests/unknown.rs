@@ -0,0 +1,67 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::{future, AsyncWriteExt};
+use pr070c01::{Error, Handshake, Packet, Protocol, Result, UnknownPacketPolicy};
+
+// ====================================== #[test] unknown() ===================================== \\
+
+#[test]
+fn unknown() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let mut proto = Handshake::initiate(&stream).await?.done()?;
+
+            for _ in 0..2 {
+                proto = send_unknown(proto, &stream).await?;
+                proto.send(&stream, Packet::heartbeat()).await?;
+            }
+
+            Result::Ok(())
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut proto = Handshake::respond(&stream).await?.done()?;
+            assert_eq!(proto.unknown_packet_policy(), UnknownPacketPolicy::Error);
+
+            let res = proto.recv(&stream).await;
+            assert!(matches!(
+                res,
+                Err(Error::UnknownPacket { id: 0xffff, len: 3 })
+            ));
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            proto.set_unknown_packet_policy(UnknownPacketPolicy::Ignore);
+            assert!(proto.recv(&stream).await?.is_heartbeat());
+
+            Result::Ok(())
+        });
+
+        future::try_zip(initiate, respond).await?;
+
+        Ok(())
+    })
+}
+
+// ======================================= send_unknown() ======================================= \\
+
+async fn send_unknown(proto: Protocol, stream: &TcpStream) -> Result<Protocol> {
+    let mut bytes = proto.into_stream(stream);
+    bytes.write_all(&[0xff, 0xff, 0xff]).await?;
+    bytes.flush().await?;
+
+    Ok(bytes.done().0)
+}
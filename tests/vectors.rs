@@ -0,0 +1,58 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use pr070c01::testvectors::{Vector, CONTROLS, DATAGRAMS, HEADERS, POW};
+
+// =========================================== Helpers ========================================== \\
+
+// Every vector has to be encoded to its bytes and decoded back from them, while losing its last
+// byte must make it undecodable.
+fn check(vectors: &[Vector]) {
+    for vector in vectors {
+        assert_eq!(vector.encode(), vector.bytes, "{}", vector.name);
+        assert!(vector.decodes(vector.bytes), "{}", vector.name);
+
+        let truncated = &vector.bytes[..vector.bytes.len() - 1];
+        assert!(!vector.decodes(truncated), "{}", vector.name);
+    }
+}
+
+// ===================================== #[test] controls() ===================================== \\
+
+#[test]
+fn controls() {
+    check(CONTROLS);
+
+    // Every kind has a vector, in order.
+    let kinds = CONTROLS.iter().map(|vector| vector.bytes[0]);
+    assert!(kinds.eq(0..CONTROLS.len() as u8));
+    assert_eq!(CONTROLS.len(), 9);
+}
+
+// ====================================== #[test] headers() ===================================== \\
+
+#[test]
+fn headers() {
+    check(HEADERS);
+}
+
+// ===================================== #[test] datagrams() ==================================== \\
+
+#[test]
+fn datagrams() {
+    check(DATAGRAMS);
+}
+
+// ======================================== #[test] pow() ======================================= \\
+
+#[test]
+fn pow() {
+    check(POW);
+}
@@ -0,0 +1,162 @@
+/**************************************************************************************************
+ *                                                                                                *
+ * This Source Code Form is subject to the terms of the Mozilla Public                            *
+ * License, v. 2.0. If a copy of the MPL was not distributed with this                            *
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.                                       *
+ *                                                                                                *
+ **************************************************************************************************/
+
+// =========================================== Imports ========================================== \\
+
+use async_net::{TcpListener, TcpStream};
+use futures_lite::future;
+use pr070c01::{Config, Engine, Error, Handshake, Packet, Protocol, Result, WireFormat};
+
+// ======================================= #[test] wire() ======================================= \\
+
+#[test]
+fn wire() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let config = Config::new().with_wire_format(WireFormat::V2);
+            let handshake = Handshake::initiate_with(&stream, config).await?;
+            assert_eq!(handshake.wire_format(), WireFormat::V2);
+
+            Result::Ok((stream, handshake.done()?))
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let config = Config::new().with_wire_format(WireFormat::V2);
+            let handshake = Handshake::respond_with(&stream, config).await?;
+            assert_eq!(handshake.wire_format(), WireFormat::V2);
+
+            Result::Ok((stream, handshake.done()?))
+        });
+
+        let ((istream, mut iproto), (rstream, mut rproto)) =
+            future::try_zip(initiate, respond).await?;
+        assert_eq!(iproto.wire_format(), WireFormat::V2);
+        assert_eq!(rproto.wire_format(), WireFormat::V2);
+
+        iproto.send(&istream, Packet::heartbeat()).await?;
+        assert!(rproto.recv(&rstream).await?.is_heartbeat());
+
+        rproto.send(&rstream, Packet::heartbeat()).await?;
+        assert!(iproto.recv(&istream).await?.is_heartbeat());
+
+        let mut out = Vec::new();
+        Engine::from(rproto).write_packet(Packet::heartbeat(), &mut out)?;
+
+        let len = u16::from_be_bytes([out[0], out[1]]) as usize;
+        assert_eq!(len + WireFormat::V2.header_len(), out.len());
+        assert_eq!(out[2..4], [0, 0]);
+
+        let packets = Engine::from(iproto).read(&out)?;
+        assert_eq!(packets.len(), 1);
+        assert!(packets[0].is_heartbeat());
+
+        Ok(())
+    })
+}
+
+// ===================================== #[test] fallback() ===================================== \\
+
+#[test]
+fn fallback() -> Result<()> {
+    smol::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let initiate = smol::spawn(async move {
+            let stream = TcpStream::connect(addr).await?;
+            let config = Config::new().with_wire_format(WireFormat::V2);
+            let proto = Handshake::initiate_with(&stream, config).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let respond = smol::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let proto = Handshake::respond(&stream).await?.done()?;
+
+            Result::Ok((stream, proto))
+        });
+
+        let ((istream, mut iproto), (rstream, mut rproto)) =
+            future::try_zip(initiate, respond).await?;
+        assert_eq!(iproto.wire_format(), WireFormat::V1);
+        assert_eq!(rproto.wire_format(), WireFormat::V1);
+
+        iproto.send(&istream, Packet::heartbeat()).await?;
+        assert!(rproto.recv(&rstream).await?.is_heartbeat());
+
+        Ok(())
+    })
+}
+
+// ===================================== #[test] checksum() ===================================== \\
+
+#[test]
+fn checksum() -> Result<()> {
+    smol::block_on(async {
+        let (iproto, _) = handshake(WireFormat::V3, WireFormat::V2).await?;
+        assert_eq!(iproto.wire_format(), WireFormat::V2);
+
+        let (iproto, rproto) = handshake(WireFormat::V3, WireFormat::V3).await?;
+        assert_eq!(iproto.wire_format(), WireFormat::V3);
+        assert_eq!(rproto.wire_format(), WireFormat::V3);
+
+        let (mut iengine, mut rengine) = (Engine::from(iproto), Engine::from(rproto));
+
+        let mut out = Vec::new();
+        rengine.write_packet(Packet::heartbeat(), &mut out)?;
+
+        let len = u16::from_be_bytes([out[0], out[1]]) as usize;
+        assert_eq!(len + WireFormat::V3.header_len(), out.len());
+
+        // A replayed frame has a valid checksum, but doesn't decrypt.
+        assert_eq!(iengine.read(&out)?.len(), 1);
+        let err = iengine.read(&out).err().unwrap();
+        assert!(matches!(err.root(), Error::Decrypt));
+
+        let (iproto, rproto) = handshake(WireFormat::V3, WireFormat::V3).await?;
+        let (mut iengine, mut rengine) = (Engine::from(iproto), Engine::from(rproto));
+
+        out.clear();
+        rengine.write_packet(Packet::heartbeat(), &mut out)?;
+        out[WireFormat::V3.header_len()] ^= 1;
+
+        let err = iengine.read(&out).err().unwrap();
+        assert!(matches!(err.root(), Error::FrameCorrupted));
+
+        Ok(())
+    })
+}
+
+// ========================================= handshake() ======================================== \\
+
+async fn handshake(iwire: WireFormat, rwire: WireFormat) -> Result<(Protocol, Protocol)> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    let initiate = smol::spawn(async move {
+        let stream = TcpStream::connect(addr).await?;
+        let config = Config::new().with_wire_format(iwire);
+
+        Handshake::initiate_with(&stream, config).await?.done()
+    });
+
+    let respond = smol::spawn(async move {
+        let (stream, _) = listener.accept().await?;
+        let config = Config::new().with_wire_format(rwire);
+
+        Handshake::respond_with(&stream, config).await?.done()
+    });
+
+    future::try_zip(initiate, respond).await
+}